@@ -0,0 +1,515 @@
+// ===========================================================================
+// Index Advisor & Query Guardrails
+// ===========================================================================
+//
+// DataForge owns the events table, so Forge Command can't assume it is
+// indexed for the way the dashboards query it. At startup the advisor
+// inspects the table's columns, indexes and timestamp layout, reports any
+// missing indexes with ready-to-run DDL, and configures the sargable time
+// bounds used by every query. Queries run through `TimedQuery` are timed, and
+// slow ones are logged with their EXPLAIN QUERY PLAN.
+
+use std::collections::VecDeque;
+use std::env;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::SecondsFormat;
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use sqlx::query::{Query, QueryAs};
+use sqlx::sqlite::{Sqlite, SqliteArguments, SqliteConnectOptions, SqlitePool, SqliteRow};
+use sqlx::{Execute, FromRow, Row};
+
+use crate::clock;
+use crate::error::{self, CommandResult, ForgeCommandError, ResultExt};
+use crate::get_db_pool;
+use crate::period::{self, TimestampLayout};
+use crate::store::data_dir;
+
+const DEFAULT_SLOW_QUERY_MS: u64 = 250;
+
+/// Slow queries kept for `get_slow_queries`.
+const SLOW_QUERY_HISTORY: usize = 50;
+
+/// Recent timestamps sampled to detect the column layout.
+const LAYOUT_SAMPLE_SIZE: i64 = 500;
+
+pub(crate) const EXPECTED_COLUMNS: [&str; 7] = [
+    "event_id",
+    "timestamp",
+    "service",
+    "event_type",
+    "severity",
+    "metrics",
+    "metadata",
+];
+
+/// Indexes the dashboard queries rely on, with the access pattern each serves.
+const RECOMMENDED_INDEXES: [(&str, &[&str], &str); 3] = [
+    (
+        "idx_events_service_event_type_timestamp",
+        &["service", "event_type", "timestamp"],
+        "Per-service, per-event-type metrics and time series",
+    ),
+    (
+        "idx_events_service_timestamp",
+        &["service", "timestamp"],
+        "Service-wide filters: health, uptime, error rate and breakdowns",
+    ),
+    (
+        "idx_events_timestamp",
+        &["timestamp"],
+        "Recent events feed, exports and rollup refresh",
+    ),
+];
+
+static SLOW_QUERIES: Mutex<VecDeque<SlowQuery>> = Mutex::new(VecDeque::new());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexInfo {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub unique: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexSuggestion {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub ddl: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexReport {
+    pub events_table_found: bool,
+    pub columns: Vec<String>,
+    pub indexes: Vec<IndexInfo>,
+    pub missing: Vec<IndexSuggestion>,
+    pub timestamp_layout: TimestampLayout,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexTarget {
+    /// Create the indexes in the DataForge database itself.
+    Source,
+    /// Snapshot the DataForge database into the Forge Command data directory
+    /// and index the snapshot, leaving the source untouched.
+    Copy,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IndexApplyResult {
+    pub target: IndexTarget,
+    /// Database the indexes were created in; point DATABASE_URL here to use a copy.
+    pub database_path: Option<String>,
+    pub applied: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlowQuery {
+    pub sql: String,
+    pub duration_ms: u64,
+    pub plan: Vec<String>,
+    pub recorded_at: String,
+}
+
+// ===========================================================================
+// Inspection
+// ===========================================================================
+
+async fn table_columns(pool: &SqlitePool) -> CommandResult<Vec<String>> {
+    Ok(sqlx::query("SELECT name FROM pragma_table_info('events')")
+        .fetch_all(pool)
+        .await?
+        .iter()
+        .map(|row| row.get::<String, _>("name"))
+        .collect())
+}
+
+async fn table_indexes(pool: &SqlitePool) -> CommandResult<Vec<IndexInfo>> {
+    let indexes = sqlx::query("SELECT name, \"unique\" AS is_unique FROM pragma_index_list('events')")
+        .fetch_all(pool)
+        .await?;
+
+    let mut infos = Vec::with_capacity(indexes.len());
+    for index in indexes {
+        let name: String = index.get("name");
+        // Expression columns have no name
+        let columns = sqlx::query("SELECT name FROM pragma_index_info(?) ORDER BY seqno")
+            .bind(&name)
+            .fetch_all(pool)
+            .await?
+            .iter()
+            .map(|row| row.get::<Option<String>, _>("name").unwrap_or_else(|| "<expression>".to_string()))
+            .collect();
+
+        infos.push(IndexInfo {
+            name,
+            columns,
+            unique: index.get::<i64, _>("is_unique") != 0,
+        });
+    }
+
+    Ok(infos)
+}
+
+async fn sample_timestamps(pool: &SqlitePool) -> CommandResult<Vec<String>> {
+    Ok(sqlx::query("SELECT timestamp FROM events ORDER BY rowid DESC LIMIT ?")
+        .bind(LAYOUT_SAMPLE_SIZE)
+        .fetch_all(pool)
+        .await?
+        .iter()
+        .filter_map(|row| row.get::<Option<String>, _>("timestamp"))
+        .collect())
+}
+
+/// Works out how `timestamp` is written from a sample of recent rows. Offsets
+/// other than UTC can't be compared as strings, so they're flagged.
+async fn detect_timestamp_layout(pool: &SqlitePool, warnings: &mut Vec<String>) -> CommandResult<TimestampLayout> {
+    Ok(classify_timestamps(&sample_timestamps(pool).await?, warnings))
+}
+
+fn classify_timestamps(samples: &[String], warnings: &mut Vec<String>) -> TimestampLayout {
+    let iso_t = samples.iter().filter(|ts| ts.as_bytes().get(10) == Some(&b'T')).count();
+    let space = samples.iter().filter(|ts| ts.as_bytes().get(10) == Some(&b' ')).count();
+
+    if iso_t > 0 && space > 0 {
+        warnings.push(format!(
+            "events.timestamp mixes 'T' ({}) and space ({}) separators; time filters assume the majority layout",
+            iso_t, space
+        ));
+    }
+    if iso_t + space < samples.len() {
+        warnings.push(format!(
+            "{} of {} sampled timestamps are not ISO-8601 and will be skipped by time filters",
+            samples.len() - iso_t - space,
+            samples.len()
+        ));
+    }
+
+    let non_utc = samples
+        .iter()
+        .filter(|ts| {
+            let offset = ts.get(19..).unwrap_or("");
+            let offset = offset.trim_start_matches(|c: char| c == '.' || c.is_ascii_digit());
+            !(offset.is_empty() || offset == "Z" || offset == "+00:00")
+        })
+        .count();
+    if non_utc > 0 {
+        warnings.push(format!(
+            "{} sampled timestamps carry a non-UTC offset and will sort incorrectly against UTC bounds",
+            non_utc
+        ));
+    }
+
+    if space > iso_t {
+        TimestampLayout::Space
+    } else {
+        TimestampLayout::IsoT
+    }
+}
+
+/// Detects the timestamp layout of the events behind `source` the first time
+/// it is used, and again whenever the dashboards switch to another source.
+/// An empty table is sampled again on the next use rather than pinned to the
+/// default layout.
+pub async fn ensure_timestamp_layout(source: &str, pool: &SqlitePool) {
+    if period::timestamp_layout_source().as_deref() == Some(source) {
+        return;
+    }
+
+    // A missing events table is reported by the startup check instead
+    if let Ok(samples) = sample_timestamps(pool).await {
+        if !samples.is_empty() {
+            period::set_timestamp_layout(source, classify_timestamps(&samples, &mut Vec::new()));
+        }
+    }
+}
+
+fn suggestion_ddl(name: &str, columns: &[&str]) -> String {
+    format!("CREATE INDEX IF NOT EXISTS {} ON events ({})", name, columns.join(", "))
+}
+
+/// DDL for every recommended index, for event tables Forge Command creates.
+pub(crate) fn recommended_index_ddl() -> Vec<String> {
+    RECOMMENDED_INDEXES
+        .iter()
+        .map(|(name, columns, _)| suggestion_ddl(name, columns))
+        .collect()
+}
+
+pub async fn build_index_report(pool: &SqlitePool) -> CommandResult<IndexReport> {
+    let columns = table_columns(pool).await?;
+    let mut warnings = Vec::new();
+
+    if columns.is_empty() {
+        return Ok(IndexReport {
+            events_table_found: false,
+            columns,
+            indexes: Vec::new(),
+            missing: Vec::new(),
+            timestamp_layout: period::timestamp_layout(),
+            warnings: vec!["No events table found in the configured database".to_string()],
+        });
+    }
+
+    for expected in EXPECTED_COLUMNS {
+        if !columns.iter().any(|column| column == expected) {
+            warnings.push(format!("events table is missing the {} column", expected));
+        }
+    }
+
+    let indexes = table_indexes(pool).await?;
+    let timestamp_layout = detect_timestamp_layout(pool, &mut warnings).await?;
+
+    // An existing index covers a recommendation if it leads with the same columns
+    let missing = RECOMMENDED_INDEXES
+        .iter()
+        .filter(|(_, columns, _)| {
+            !indexes.iter().any(|index| {
+                index.columns.len() >= columns.len() && index.columns.iter().zip(columns.iter()).all(|(a, b)| a == b)
+            })
+        })
+        .map(|(name, columns, reason)| IndexSuggestion {
+            name: name.to_string(),
+            columns: columns.iter().map(|column| column.to_string()).collect(),
+            ddl: suggestion_ddl(name, columns),
+            reason: reason.to_string(),
+        })
+        .collect();
+
+    Ok(IndexReport {
+        events_table_found: true,
+        columns,
+        indexes,
+        missing,
+        timestamp_layout,
+        warnings,
+    })
+}
+
+/// Inspects the events table once at startup and logs anything worth fixing.
+pub async fn run_startup_check() {
+    let report = match get_db_pool().await {
+        Ok(pool) => build_index_report(&pool).await,
+        Err(e) => Err(e),
+    };
+
+    let report = match report {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Index advisor skipped: {}", e);
+            return;
+        }
+    };
+
+    for warning in &report.warnings {
+        eprintln!("Index advisor: {}", warning);
+    }
+    for suggestion in &report.missing {
+        eprintln!("Index advisor: missing index for {}:\n  {};", suggestion.reason, suggestion.ddl);
+    }
+}
+
+// ===========================================================================
+// Slow Query Logging
+// ===========================================================================
+
+fn slow_query_threshold() -> Duration {
+    let ms = env::var("FORGE_COMMAND_SLOW_QUERY_MS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_SLOW_QUERY_MS);
+    Duration::from_millis(ms)
+}
+
+async fn observe(pool: &SqlitePool, sql: &str, elapsed: Duration) {
+    if elapsed < slow_query_threshold() {
+        return;
+    }
+
+    // Unbound parameters are treated as NULL, which doesn't change the plan shape
+    let plan = match sqlx::query(&format!("EXPLAIN QUERY PLAN {}", sql)).fetch_all(pool).await {
+        Ok(rows) => rows.iter().map(|row| row.get::<String, _>("detail")).collect(),
+        Err(e) => vec![format!("EXPLAIN failed: {}", e)],
+    };
+
+    let compact_sql = sql.split_whitespace().collect::<Vec<_>>().join(" ");
+    eprintln!(
+        "Slow query ({} ms): {}\n  plan: {}",
+        elapsed.as_millis(),
+        compact_sql,
+        plan.join(" | ")
+    );
+
+    if let Ok(mut history) = SLOW_QUERIES.lock() {
+        if history.len() == SLOW_QUERY_HISTORY {
+            history.pop_front();
+        }
+        history.push_back(SlowQuery {
+            sql: compact_sql,
+            duration_ms: elapsed.as_millis() as u64,
+            plan,
+            recorded_at: clock::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        });
+    }
+}
+
+/// Timed drop-ins for `fetch_all`/`fetch_one` on events queries.
+pub trait TimedQuery<'q>: Sized {
+    type Output;
+
+    fn fetch_all_timed<'a>(self, pool: &'a SqlitePool) -> BoxFuture<'a, Result<Vec<Self::Output>, sqlx::Error>>
+    where
+        'q: 'a;
+
+    fn fetch_one_timed<'a>(self, pool: &'a SqlitePool) -> BoxFuture<'a, Result<Self::Output, sqlx::Error>>
+    where
+        'q: 'a;
+}
+
+impl<'q> TimedQuery<'q> for Query<'q, Sqlite, SqliteArguments<'q>> {
+    type Output = SqliteRow;
+
+    fn fetch_all_timed<'a>(self, pool: &'a SqlitePool) -> BoxFuture<'a, Result<Vec<SqliteRow>, sqlx::Error>>
+    where
+        'q: 'a,
+    {
+        Box::pin(async move {
+            let sql = self.sql();
+            let started = Instant::now();
+            let result = self.fetch_all(pool).await;
+            observe(pool, sql, started.elapsed()).await;
+            result
+        })
+    }
+
+    fn fetch_one_timed<'a>(self, pool: &'a SqlitePool) -> BoxFuture<'a, Result<SqliteRow, sqlx::Error>>
+    where
+        'q: 'a,
+    {
+        Box::pin(async move {
+            let sql = self.sql();
+            let started = Instant::now();
+            let result = self.fetch_one(pool).await;
+            observe(pool, sql, started.elapsed()).await;
+            result
+        })
+    }
+}
+
+impl<'q, O> TimedQuery<'q> for QueryAs<'q, Sqlite, O, SqliteArguments<'q>>
+where
+    O: Send + Unpin + for<'r> FromRow<'r, SqliteRow> + 'q,
+{
+    type Output = O;
+
+    fn fetch_all_timed<'a>(self, pool: &'a SqlitePool) -> BoxFuture<'a, Result<Vec<O>, sqlx::Error>>
+    where
+        'q: 'a,
+    {
+        Box::pin(async move {
+            let sql = self.sql();
+            let started = Instant::now();
+            let result = self.fetch_all(pool).await;
+            observe(pool, sql, started.elapsed()).await;
+            result
+        })
+    }
+
+    fn fetch_one_timed<'a>(self, pool: &'a SqlitePool) -> BoxFuture<'a, Result<O, sqlx::Error>>
+    where
+        'q: 'a,
+    {
+        Box::pin(async move {
+            let sql = self.sql();
+            let started = Instant::now();
+            let result = self.fetch_one(pool).await;
+            observe(pool, sql, started.elapsed()).await;
+            result
+        })
+    }
+}
+
+// ===========================================================================
+// IPC Commands
+// ===========================================================================
+
+#[tauri::command]
+pub async fn get_index_report() -> CommandResult<IndexReport> {
+    error::command("get_index_report", async move {
+        let pool = get_db_pool().await?;
+        build_index_report(&pool).await
+    })
+    .await
+}
+
+/// Creates the missing recommended indexes, either in the DataForge database
+/// (explicit opt-in, since Forge Command otherwise only reads it) or in an
+/// indexed snapshot under the Forge Command data directory.
+#[tauri::command]
+pub async fn apply_index_suggestions(target: IndexTarget) -> CommandResult<IndexApplyResult> {
+    error::command("apply_index_suggestions", async move {
+        let pool = get_db_pool().await?;
+        let report = build_index_report(&pool).await?;
+        if !report.events_table_found {
+            return Err(ForgeCommandError::SchemaMismatch(
+                "No events table found in the configured database".to_string(),
+            ));
+        }
+
+        let (target_pool, database_path) = match target {
+            IndexTarget::Source => (pool, None),
+            IndexTarget::Copy => {
+                let path = data_dir().join("events-indexed.db");
+                std::fs::create_dir_all(data_dir()).map_err(|e| ForgeCommandError::Io(format!("Failed to create data directory: {}", e)))?;
+                if path.exists() {
+                    std::fs::remove_file(&path).map_err(|e| ForgeCommandError::Io(format!("Failed to replace old snapshot: {}", e)))?;
+                }
+
+                sqlx::query("VACUUM INTO ?")
+                    .bind(path.display().to_string())
+                    .execute(&pool)
+                    .await
+                    .in_query("snapshot_events_database")?;
+
+                let options = SqliteConnectOptions::from_str(&format!("sqlite://{}", path.display()))?;
+                let copy = SqlitePool::connect_with(options)
+                    .await
+                    .map_err(|e| ForgeCommandError::DatabaseUnavailable(format!("Failed to open snapshot: {}", e)))?;
+
+                (copy, Some(path.display().to_string()))
+            }
+        };
+
+        let mut applied = Vec::new();
+        for suggestion in report.missing {
+            sqlx::query(&suggestion.ddl)
+                .execute(&target_pool)
+                .await
+                .in_query(&suggestion.name)?;
+            applied.push(suggestion.ddl);
+        }
+
+        Ok(IndexApplyResult {
+            target,
+            database_path,
+            applied,
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_slow_queries() -> CommandResult<Vec<SlowQuery>> {
+    error::command("get_slow_queries", async move {
+        let history = SLOW_QUERIES.lock().map_err(ForgeCommandError::internal)?;
+        Ok(history.iter().rev().cloned().collect())
+    })
+    .await
+}
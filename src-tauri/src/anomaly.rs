@@ -0,0 +1,465 @@
+// ===========================================================================
+// Anomaly Detection
+// ===========================================================================
+//
+// Detectors that run over any series the backend produces. Each returns
+// markers keyed by the point timestamp so charts can highlight them and the
+// alerts feed can surface them. Every alert raised is also kept in the store,
+// so the feed still shows it once its hour has left the dashboard window.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
+
+use chrono::{Datelike, Duration, NaiveDateTime, SecondsFormat, Timelike};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+
+use crate::clock;
+use crate::error::{self, CommandResult, ForgeCommandError};
+use crate::period::TimeWindow;
+use crate::store::get_store_pool;
+use crate::TimeSeriesPoint;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum AnomalyMethod {
+    /// Z-score of each point against the mean/stddev of the preceding `window` points.
+    RollingZScore { window: usize, threshold: f64 },
+    /// Exponentially weighted mean/variance; `alpha` is the smoothing factor.
+    Ewma { alpha: f64, threshold: f64 },
+    /// Baseline from other points in the same hour of the week, over the
+    /// window and the `SEASONAL_HISTORY_WEEKS` before it.
+    SeasonalHourOfWeek { threshold: f64, min_samples: usize },
+}
+
+impl Default for AnomalyMethod {
+    fn default() -> Self {
+        AnomalyMethod::RollingZScore {
+            window: 24,
+            threshold: 3.0,
+        }
+    }
+}
+
+impl AnomalyMethod {
+    fn threshold(&self) -> f64 {
+        match *self {
+            AnomalyMethod::RollingZScore { threshold, .. }
+            | AnomalyMethod::Ewma { threshold, .. }
+            | AnomalyMethod::SeasonalHourOfWeek { threshold, .. } => threshold,
+        }
+    }
+
+    /// Window a series has to be fetched over to score the points of
+    /// `window`, when that reaches further back than the window itself. Only
+    /// the seasonal baseline does: its peers are the same hour in earlier
+    /// weeks.
+    pub fn history_window(&self, window: &TimeWindow) -> Option<TimeWindow> {
+        match self {
+            AnomalyMethod::SeasonalHourOfWeek { .. } if window.is_bounded() => Some(TimeWindow {
+                start: window.start - Duration::weeks(SEASONAL_HISTORY_WEEKS),
+                end: window.end,
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyDirection {
+    Spike,
+    Drop,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AnomalyMarker {
+    pub timestamp: String,
+    pub value: f64,
+    pub expected: f64,
+    pub lower_bound: f64,
+    pub upper_bound: f64,
+    pub score: f64,
+    pub direction: AnomalyDirection,
+}
+
+/// Points needed before a baseline is trusted.
+const MIN_BASELINE_POINTS: usize = 3;
+
+/// Weeks before the window the seasonal baseline reads, so even a day's
+/// window has a few samples for each of its hours.
+pub const SEASONAL_HISTORY_WEEKS: i64 = 4;
+
+/// Runs `method` over `points` (assumed sorted by timestamp) and returns the anomalous ones.
+pub fn detect(points: &[TimeSeriesPoint], method: AnomalyMethod) -> Vec<AnomalyMarker> {
+    let threshold = method.threshold();
+
+    let baselines: Vec<Option<(f64, f64)>> = match method {
+        AnomalyMethod::RollingZScore { window, .. } => rolling_baselines(points, window.max(1)),
+        AnomalyMethod::Ewma { alpha, .. } => ewma_baselines(points, alpha.clamp(0.01, 1.0)),
+        AnomalyMethod::SeasonalHourOfWeek { min_samples, .. } => {
+            seasonal_baselines(points, min_samples.max(1))
+        }
+    };
+
+    points
+        .iter()
+        .zip(baselines)
+        .filter_map(|(point, baseline)| {
+            let (mean, std_dev) = baseline?;
+            // A perfectly flat baseline has no spread to score against
+            if std_dev <= f64::EPSILON {
+                return None;
+            }
+
+            let score = (point.value - mean) / std_dev;
+            if score.abs() < threshold {
+                return None;
+            }
+
+            Some(AnomalyMarker {
+                timestamp: point.timestamp.clone(),
+                value: point.value,
+                expected: mean,
+                lower_bound: mean - threshold * std_dev,
+                upper_bound: mean + threshold * std_dev,
+                score,
+                direction: if score > 0.0 {
+                    AnomalyDirection::Spike
+                } else {
+                    AnomalyDirection::Drop
+                },
+            })
+        })
+        .collect()
+}
+
+/// Runs `method` over `points`, the series over `window`. When the method
+/// needs history from before the window, the series is fetched again over
+/// [`AnomalyMethod::history_window`] with `fetch` and scored as a whole, and
+/// only the markers on `points` are kept.
+pub async fn detect_with_history<Fut>(
+    points: &[TimeSeriesPoint],
+    window: &TimeWindow,
+    method: AnomalyMethod,
+    fetch: impl FnOnce(TimeWindow) -> Fut,
+) -> CommandResult<Vec<AnomalyMarker>>
+where
+    Fut: Future<Output = CommandResult<Vec<TimeSeriesPoint>>>,
+{
+    let Some(history_window) = method.history_window(window) else {
+        return Ok(detect(points, method));
+    };
+
+    let history = fetch(history_window).await?;
+    Ok(detect_within(points, &history, method))
+}
+
+/// Runs `method` over `history`, a longer stretch of the series `points`
+/// belong to, and keeps only the markers on `points`.
+pub fn detect_within(
+    points: &[TimeSeriesPoint],
+    history: &[TimeSeriesPoint],
+    method: AnomalyMethod,
+) -> Vec<AnomalyMarker> {
+    let in_window: BTreeSet<&str> = points
+        .iter()
+        .map(|point| point.timestamp.as_str())
+        .collect();
+
+    detect(history, method)
+        .into_iter()
+        .filter(|marker| in_window.contains(marker.timestamp.as_str()))
+        .collect()
+}
+
+fn mean_and_std_dev(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+    (mean, variance.sqrt())
+}
+
+fn rolling_baselines(points: &[TimeSeriesPoint], window: usize) -> Vec<Option<(f64, f64)>> {
+    let values: Vec<f64> = points.iter().map(|p| p.value).collect();
+
+    (0..values.len())
+        .map(|i| {
+            let history = &values[i.saturating_sub(window)..i];
+            (history.len() >= MIN_BASELINE_POINTS).then(|| mean_and_std_dev(history))
+        })
+        .collect()
+}
+
+fn ewma_baselines(points: &[TimeSeriesPoint], alpha: f64) -> Vec<Option<(f64, f64)>> {
+    let mut baselines = Vec::with_capacity(points.len());
+    let mut mean = 0.0_f64;
+    let mut variance = 0.0_f64;
+
+    for (i, point) in points.iter().enumerate() {
+        // The baseline for a point only includes what came before it
+        baselines.push((i >= MIN_BASELINE_POINTS).then(|| (mean, variance.sqrt())));
+
+        if i == 0 {
+            mean = point.value;
+        } else {
+            let diff = point.value - mean;
+            mean += alpha * diff;
+            variance = (1.0 - alpha) * (variance + alpha * diff * diff);
+        }
+    }
+
+    baselines
+}
+
+fn seasonal_baselines(points: &[TimeSeriesPoint], min_samples: usize) -> Vec<Option<(f64, f64)>> {
+    let buckets: Vec<Option<u32>> = points.iter().map(|p| hour_of_week(&p.timestamp)).collect();
+
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, _)| {
+            let bucket = buckets[i]?;
+            // Leave the point itself out so a spike can't hide in its own baseline
+            let peers: Vec<f64> = points
+                .iter()
+                .zip(&buckets)
+                .enumerate()
+                .filter(|(j, (_, b))| *j != i && **b == Some(bucket))
+                .map(|(_, (p, _))| p.value)
+                .collect();
+
+            (peers.len() >= min_samples).then(|| mean_and_std_dev(&peers))
+        })
+        .collect()
+}
+
+/// Parses the bucket timestamps the time-series queries produce.
+pub fn parse_bucket_timestamp(timestamp: &str) -> Option<NaiveDateTime> {
+    parse_bucket_format(timestamp).map(|(parsed, _)| parsed)
+}
+
+/// Parses a bucket timestamp along with the strftime format it is written in,
+/// so a derived timestamp can be written the same way.
+pub fn parse_bucket_format(timestamp: &str) -> Option<(NaiveDateTime, &'static str)> {
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S"]
+        .into_iter()
+        .find_map(|format| {
+            NaiveDateTime::parse_from_str(timestamp, format)
+                .ok()
+                .map(|parsed| (parsed, format))
+        })
+}
+
+/// Labels of every hour bucket of `window`, from the one containing its start
+/// up to the one before its end, in the strftime `label_format` the series
+/// uses. Empty for an unbounded window.
+pub fn window_hours(window: &TimeWindow, label_format: &str) -> Vec<String> {
+    if !window.is_bounded() {
+        return Vec::new();
+    }
+
+    let mut hours = Vec::new();
+    let mut hour = window
+        .start
+        .date()
+        .and_hms_opt(window.start.hour(), 0, 0)
+        .unwrap_or(window.start);
+    while hour < window.end {
+        hours.push(hour.format(label_format).to_string());
+        hour += Duration::hours(1);
+    }
+    hours
+}
+
+/// Adds a zero point for every hour of `window` a count or sum series has no
+/// bucket for. Hourly queries skip hours without events, so otherwise traffic
+/// dropping to nothing would never be scored as a drop. A series without any
+/// points stays empty.
+pub fn fill_hourly_gaps(
+    points: Vec<TimeSeriesPoint>,
+    window: &TimeWindow,
+    label_format: &str,
+) -> Vec<TimeSeriesPoint> {
+    if points.is_empty() {
+        return points;
+    }
+
+    let mut filled: BTreeMap<String, f64> = points
+        .into_iter()
+        .map(|point| (point.timestamp, point.value))
+        .collect();
+    for hour in window_hours(window, label_format) {
+        filled.entry(hour).or_insert(0.0);
+    }
+
+    filled
+        .into_iter()
+        .map(|(timestamp, value)| TimeSeriesPoint { timestamp, value })
+        .collect()
+}
+
+fn hour_of_week(timestamp: &str) -> Option<u32> {
+    let parsed = parse_bucket_timestamp(timestamp)?;
+    Some(parsed.weekday().num_days_from_monday() * 24 + parsed.hour())
+}
+
+// ===========================================================================
+// Alerts
+// ===========================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertMetrics {
+    pub actual_value: f64,
+    pub expected_value: f64,
+    pub threshold: f64,
+    pub score: f64,
+    pub unit: String,
+}
+
+/// Alert in the shape of the monitoring agent's alert event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnomalyAlert {
+    pub service: String,
+    pub event_type: String,
+    pub severity: String,
+    pub message: String,
+    pub metrics: AlertMetrics,
+    pub triggered_by: String,
+    pub timestamp: String,
+    pub actions_taken: Vec<String>,
+}
+
+/// Converts markers for one series into alerts. Scores at twice the method
+/// threshold or more are critical, everything else is a warning.
+pub fn to_alerts(
+    service: &str,
+    series_name: &str,
+    unit: &str,
+    method: AnomalyMethod,
+    markers: &[AnomalyMarker],
+) -> Vec<AnomalyAlert> {
+    let threshold = method.threshold();
+
+    markers
+        .iter()
+        .map(|marker| {
+            let (verb, bound) = match marker.direction {
+                AnomalyDirection::Spike => ("spiked above", marker.upper_bound),
+                AnomalyDirection::Drop => ("dropped below", marker.lower_bound),
+            };
+
+            AnomalyAlert {
+                service: service.to_string(),
+                event_type: "alert".to_string(),
+                severity: if marker.score.abs() >= threshold * 2.0 {
+                    "critical".to_string()
+                } else {
+                    "warning".to_string()
+                },
+                message: format!(
+                    "{} {} expected range ({:.2} vs {:.2} expected)",
+                    series_name, verb, marker.value, marker.expected
+                ),
+                metrics: AlertMetrics {
+                    actual_value: marker.value,
+                    expected_value: marker.expected,
+                    threshold: bound,
+                    score: marker.score,
+                    unit: unit.to_string(),
+                },
+                triggered_by: "anomaly_detector".to_string(),
+                timestamp: marker.timestamp.clone(),
+                actions_taken: Vec::new(),
+            }
+        })
+        .collect()
+}
+
+// ===========================================================================
+// Alert Feed
+// ===========================================================================
+
+/// Feed entries older than this are pruned whenever alerts are recorded.
+const ALERT_RETENTION_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertFeedEntry {
+    /// Series the alert was raised on, e.g. "Token cost".
+    pub series: String,
+    /// When the alert was first raised.
+    pub detected_at: String,
+    #[serde(flatten)]
+    pub alert: AnomalyAlert,
+}
+
+/// Adds `alerts` for `series` to the feed. An alert already in the feed for
+/// the same series and hour keeps its `detected_at` but takes the latest
+/// values, since the current hour is rescored as its events arrive.
+pub async fn record_alerts(series: &str, alerts: &[AnomalyAlert]) -> CommandResult<()> {
+    let store = get_store_pool().await?;
+    let detected_at = clock::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+
+    for alert in alerts {
+        sqlx::query(
+            "INSERT INTO anomaly_alerts (service, series, timestamp, severity, detected_at, alert)
+             VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT (service, series, timestamp) DO UPDATE SET
+                severity = excluded.severity,
+                alert = excluded.alert",
+        )
+        .bind(&alert.service)
+        .bind(series)
+        .bind(&alert.timestamp)
+        .bind(&alert.severity)
+        .bind(&detected_at)
+        .bind(serde_json::to_string(alert)?)
+        .execute(&store)
+        .await?;
+    }
+
+    let retention_cutoff = clock::now() - Duration::days(ALERT_RETENTION_DAYS);
+    sqlx::query("DELETE FROM anomaly_alerts WHERE detected_at < ?")
+        .bind(retention_cutoff.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .execute(&store)
+        .await?;
+
+    Ok(())
+}
+
+/// Recorded alerts, newest hour first, optionally for a single service.
+#[tauri::command]
+pub async fn get_alert_feed(
+    limit: i64,
+    service: Option<String>,
+) -> CommandResult<Vec<AlertFeedEntry>> {
+    error::command("get_alert_feed", async move {
+        let store = get_store_pool().await?;
+
+        sqlx::query(
+            "SELECT series, detected_at, alert
+             FROM anomaly_alerts
+             WHERE (? IS NULL OR service = ?)
+             ORDER BY timestamp DESC, alert_id DESC
+             LIMIT ?",
+        )
+        .bind(&service)
+        .bind(&service)
+        .bind(limit)
+        .fetch_all(&store)
+        .await?
+        .into_iter()
+        .map(|row| {
+            let alert = serde_json::from_str(&row.get::<String, _>("alert")).map_err(|e| {
+                ForgeCommandError::Internal(format!("Corrupt alert document: {}", e))
+            })?;
+            Ok(AlertFeedEntry {
+                series: row.get("series"),
+                detected_at: row.get("detected_at"),
+                alert,
+            })
+        })
+        .collect()
+    })
+    .await
+}
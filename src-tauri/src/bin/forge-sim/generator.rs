@@ -0,0 +1,383 @@
+// ===========================================================================
+// Event Generator
+// ===========================================================================
+//
+// Produces events in DataForge's `events` layout. Each service has Poisson
+// arrivals at its configured rate; ForgeAgents tasks and Rake runs emit a
+// start event and, after their duration, a completion event sharing an id.
+// Rake runs are queued first and start after a short wait.
+// Everything random comes from one seeded RNG, so the same seed, rates,
+// scenario and start time give the same events.
+
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::{json, Value};
+
+use crate::scenario::{Conditions, Scenario};
+use crate::Service;
+
+/// (model, USD per 1k prompt tokens, USD per 1k completion tokens, mean latency ms).
+/// The first entry is the most expensive.
+const MODELS: [(&str, f64, f64, f64); 5] = [
+    ("claude-3-opus", 0.015, 0.075, 2400.0),
+    ("gpt-4o", 0.005, 0.015, 1300.0),
+    ("claude-3-5-sonnet", 0.003, 0.015, 1100.0),
+    ("gpt-4o-mini", 0.00015, 0.0006, 600.0),
+    ("llama-3.1-70b", 0.00088, 0.00088, 900.0),
+];
+
+/// Relative traffic per model in steady state.
+const MODEL_WEIGHTS: [f64; 5] = [0.05, 0.25, 0.3, 0.3, 0.1];
+
+const OPERATIONS: [&str; 4] = ["chat", "summarize", "classify", "extract"];
+
+const COLLECTIONS: [&str; 3] = ["docs", "code", "tickets"];
+
+/// (agent id, agent name, mean task duration ms).
+const AGENTS: [(&str, &str, f64); 4] = [
+    ("agent-research", "Research Agent", 45_000.0),
+    ("agent-code", "Code Review Agent", 30_000.0),
+    ("agent-ops", "Ops Agent", 12_000.0),
+    ("agent-writer", "Writer Agent", 20_000.0),
+];
+
+/// (pipeline id, pipeline name, mean records per run, mean run duration ms).
+const PIPELINES: [(&str, &str, f64, f64); 4] = [
+    ("github-sync", "GitHub Sync", 400.0, 90_000.0),
+    ("docs-crawler", "Docs Crawler", 1200.0, 240_000.0),
+    ("slack-archive", "Slack Archive", 2500.0, 150_000.0),
+    ("arxiv-papers", "arXiv Papers", 80.0, 60_000.0),
+];
+
+/// Mean time a Rake run waits in the queue before it starts.
+const MEAN_QUEUE_WAIT_MS: f64 = 20_000.0;
+
+/// Mean age of the newest source record when a Rake run starts.
+const MEAN_SOURCE_LAG_MS: f64 = 300_000.0;
+
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub event_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub service: &'static str,
+    pub event_type: &'static str,
+    pub severity: &'static str,
+    pub metrics: Value,
+    pub metadata: Value,
+}
+
+pub struct Generator {
+    rng: StdRng,
+    scenario: Scenario,
+    incident_start: DateTime<Utc>,
+    /// Events per minute, by `Service::ALL` position.
+    rates: [f64; 4],
+    /// Next arrival per service.
+    next_arrival: [DateTime<Utc>; 4],
+    /// Completions scheduled past the last `advance`.
+    pending: Vec<Event>,
+    tasks: u64,
+    runs: u64,
+}
+
+impl Generator {
+    pub fn new(
+        seed: u64,
+        scenario: Scenario,
+        incident_start: DateTime<Utc>,
+        rates: [f64; 4],
+        start: DateTime<Utc>,
+    ) -> Self {
+        let mut generator = Generator {
+            rng: StdRng::seed_from_u64(seed),
+            scenario,
+            incident_start,
+            rates,
+            next_arrival: [start; 4],
+            pending: Vec::new(),
+            tasks: 0,
+            runs: 0,
+        };
+        for index in 0..Service::ALL.len() {
+            generator.next_arrival[index] = start + generator.gap(index);
+        }
+        generator
+    }
+
+    pub fn conditions(&self, at: DateTime<Utc>) -> Conditions {
+        self.scenario.conditions(at >= self.incident_start)
+    }
+
+    /// Every event timestamped before `until`, oldest first.
+    pub fn advance(&mut self, until: DateTime<Utc>) -> Vec<Event> {
+        for (index, service) in Service::ALL.into_iter().enumerate() {
+            while self.next_arrival[index] < until {
+                let at = self.next_arrival[index];
+                self.arrive(service, at);
+                self.next_arrival[index] = at + self.gap(index);
+            }
+        }
+
+        // Completions landing in an outage are lost with the service
+        let mut ready = Vec::new();
+        let mut waiting = Vec::new();
+        for event in self.pending.drain(..) {
+            if event.timestamp >= until {
+                waiting.push(event);
+            } else if !(event.service == Service::Forgeagents.name()
+                && self
+                    .scenario
+                    .conditions(event.timestamp >= self.incident_start)
+                    .forgeagents_down)
+            {
+                ready.push(event);
+            }
+        }
+        self.pending = waiting;
+
+        ready.sort_by(|a, b| {
+            a.timestamp
+                .cmp(&b.timestamp)
+                .then_with(|| a.event_id.cmp(&b.event_id))
+        });
+        ready
+    }
+
+    /// Exponential gap to the next arrival of a service.
+    fn gap(&mut self, index: usize) -> Duration {
+        let per_second = self.rates[index] / 60.0;
+        if per_second <= 0.0 {
+            return Duration::days(365 * 100);
+        }
+        let seconds = -(1.0 - self.rng.gen::<f64>()).ln() / per_second;
+        Duration::milliseconds((seconds * 1000.0) as i64)
+    }
+
+    /// Log-normal sample with the given mean and spread (sigma of the log).
+    fn log_normal(&mut self, mean: f64, sigma: f64) -> f64 {
+        // Box-Muller
+        let u1: f64 = 1.0 - self.rng.gen::<f64>();
+        let u2: f64 = self.rng.gen();
+        let normal = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+        (mean.ln() - sigma * sigma / 2.0 + sigma * normal).exp()
+    }
+
+    fn event_id(&mut self) -> String {
+        format!("{:032x}", self.rng.gen::<u128>())
+    }
+
+    fn event(
+        &mut self,
+        at: DateTime<Utc>,
+        service: Service,
+        event_type: &'static str,
+        severity: &'static str,
+        metrics: Value,
+        metadata: Value,
+    ) -> Event {
+        Event {
+            event_id: self.event_id(),
+            timestamp: at,
+            service: service.name(),
+            event_type,
+            severity,
+            metrics,
+            metadata,
+        }
+    }
+
+    fn arrive(&mut self, service: Service, at: DateTime<Utc>) {
+        let conditions = self.conditions(at);
+        match service {
+            Service::Dataforge => self.search(at, &conditions),
+            Service::Neuroforge => self.model_request(at, &conditions),
+            Service::Forgeagents if !conditions.forgeagents_down => {
+                self.agent_task(at, &conditions)
+            }
+            Service::Forgeagents => {}
+            Service::Rake => self.ingestion_run(at, &conditions),
+        }
+    }
+
+    fn search(&mut self, at: DateTime<Utc>, conditions: &Conditions) {
+        let duration_ms = self.log_normal(45.0 * conditions.dataforge_latency, 0.5);
+        let collection = COLLECTIONS[self.rng.gen_range(0..COLLECTIONS.len())];
+        let top_k = [5, 10, 20][self.rng.gen_range(0..3)];
+
+        let event = if self.rng.gen_bool(conditions.dataforge_error_rate) {
+            self.event(
+                at,
+                Service::Dataforge,
+                "query_error",
+                "error",
+                json!({ "duration_ms": round(duration_ms, 1) }),
+                json!({ "collection": collection, "error": "search timed out" }),
+            )
+        } else {
+            let similarity = self.rng.gen_range(0.55..0.92);
+            self.event(
+                at,
+                Service::Dataforge,
+                "query",
+                "info",
+                json!({
+                    "duration_ms": round(duration_ms, 1),
+                    "avg_similarity": round(similarity, 3),
+                    "results": top_k,
+                }),
+                json!({ "collection": collection, "top_k": top_k }),
+            )
+        };
+        self.pending.push(event);
+    }
+
+    fn model_request(&mut self, at: DateTime<Utc>, conditions: &Conditions) {
+        let index = if self.rng.gen_bool(conditions.neuroforge_premium_share) {
+            0
+        } else {
+            let mut pick = self.rng.gen::<f64>() * MODEL_WEIGHTS.iter().sum::<f64>();
+            MODEL_WEIGHTS
+                .iter()
+                .position(|weight| {
+                    pick -= weight;
+                    pick < 0.0
+                })
+                .unwrap_or(MODELS.len() - 1)
+        };
+        let (model, prompt_price, completion_price, latency) = MODELS[index];
+
+        let tokens_prompt = self
+            .log_normal(800.0 * conditions.neuroforge_prompt_factor, 0.6)
+            .round() as i64;
+        let tokens_completion = self.log_normal(300.0, 0.6).round() as i64;
+        let cost_usd = tokens_prompt as f64 / 1000.0 * prompt_price
+            + tokens_completion as f64 / 1000.0 * completion_price;
+        let duration_ms = self.log_normal(latency, 0.4);
+        let evaluation_score = self.rng.gen_range(0.65..0.98);
+        let operation = OPERATIONS[self.rng.gen_range(0..OPERATIONS.len())];
+        let user_id = format!("user-{:03}", self.rng.gen_range(1..=40));
+
+        let event = self.event(
+            at,
+            Service::Neuroforge,
+            "model_request",
+            "info",
+            json!({
+                "duration_ms": round(duration_ms, 1),
+                "tokens_prompt": tokens_prompt,
+                "tokens_completion": tokens_completion,
+                "tokens_total": tokens_prompt + tokens_completion,
+                "cost_usd": round(cost_usd, 6),
+                "evaluation_score": round(evaluation_score, 3),
+            }),
+            json!({ "model": model, "operation": operation, "user_id": user_id }),
+        );
+        self.pending.push(event);
+    }
+
+    fn agent_task(&mut self, at: DateTime<Utc>, conditions: &Conditions) {
+        let (agent_id, agent_name, mean_ms) = AGENTS[self.rng.gen_range(0..AGENTS.len())];
+        self.tasks += 1;
+        let task_id = format!("task-{:06}", self.tasks);
+        let duration_ms = self.log_normal(mean_ms, 0.5);
+        let failed = self.rng.gen_bool(conditions.forgeagents_failure_rate);
+
+        let started = self.event(
+            at,
+            Service::Forgeagents,
+            "agent_task_started",
+            "info",
+            json!({}),
+            json!({ "agent_id": agent_id, "agent_name": agent_name, "task_id": task_id }),
+        );
+        let completed = self.event(
+            at + Duration::milliseconds(duration_ms as i64),
+            Service::Forgeagents,
+            "agent_task_completed",
+            if failed { "error" } else { "info" },
+            json!({ "duration_ms": round(duration_ms, 1) }),
+            json!({
+                "agent_id": agent_id,
+                "agent_name": agent_name,
+                "task_id": task_id,
+                "status": if failed { "failed" } else { "success" },
+            }),
+        );
+        self.pending.extend([started, completed]);
+    }
+
+    fn ingestion_run(&mut self, at: DateTime<Utc>, conditions: &Conditions) {
+        let (pipeline_id, pipeline_name, mean_records, mean_ms) =
+            PIPELINES[self.rng.gen_range(0..PIPELINES.len())];
+        self.runs += 1;
+        let run_id = format!("run-{:06}", self.runs);
+        let wait_ms = self.log_normal(MEAN_QUEUE_WAIT_MS, 0.8);
+        let duration_ms = self.log_normal(mean_ms, 0.4);
+        let failed = conditions.rake_broken_pipeline == Some(pipeline_id)
+            || self.rng.gen_bool(conditions.rake_failure_rate);
+
+        let queued = self.event(
+            at,
+            Service::Rake,
+            "ingestion_queued",
+            "info",
+            json!({ "pipeline_id": pipeline_id, "pipeline_name": pipeline_name }),
+            json!({ "run_id": run_id }),
+        );
+        // Runs arrive in the queue; the rest of the run is timed from the start
+        let at = at + Duration::milliseconds(wait_ms as i64);
+        let started = self.event(
+            at,
+            Service::Rake,
+            "ingestion_started",
+            "info",
+            json!({ "pipeline_id": pipeline_id, "pipeline_name": pipeline_name }),
+            json!({ "run_id": run_id }),
+        );
+        let finished_at = at + Duration::milliseconds(duration_ms as i64);
+        let finished = if failed {
+            // Failures usually surface part-way through the run
+            let partial = self.rng.gen_range(0.1..0.6);
+            self.event(
+                at + Duration::milliseconds((duration_ms * partial) as i64),
+                Service::Rake,
+                "ingestion_failed",
+                "error",
+                json!({
+                    "pipeline_id": pipeline_id,
+                    "pipeline_name": pipeline_name,
+                    "duration_ms": round(duration_ms * partial, 1),
+                }),
+                json!({ "run_id": run_id, "error": "source returned 503" }),
+            )
+        } else {
+            let records = self.log_normal(mean_records, 0.3).round() as i64;
+            // Sources are read up to shortly before the run starts
+            let source_lag_ms = self.log_normal(MEAN_SOURCE_LAG_MS, 0.5);
+            let newest_record_at = at - Duration::milliseconds(source_lag_ms as i64);
+            self.event(
+                finished_at,
+                Service::Rake,
+                "ingestion_complete",
+                "info",
+                json!({
+                    "pipeline_id": pipeline_id,
+                    "pipeline_name": pipeline_name,
+                    "records": records,
+                    "duration_ms": round(duration_ms, 1),
+                }),
+                json!({
+                    "run_id": run_id,
+                    "newest_record_at": newest_record_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+                }),
+            )
+        };
+        self.pending.extend([queued, started, finished]);
+    }
+}
+
+fn round(value: f64, digits: i32) -> f64 {
+    let factor = 10f64.powi(digits);
+    (value * factor).round() / factor
+}
@@ -0,0 +1,97 @@
+// ===========================================================================
+// Mock Health Endpoints
+// ===========================================================================
+//
+// One listener per service answering `GET /health` the way the real
+// services do, following the scenario: ForgeAgents answers 503 during its
+// outage and DataForge reports `degraded` while its latency is regressed.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+
+use crate::scenario::Scenario;
+use crate::Service;
+
+#[derive(Clone)]
+struct HealthState {
+    service: Service,
+    scenario: Scenario,
+    incident_start: DateTime<Utc>,
+    started_at: DateTime<Utc>,
+}
+
+async fn health(State(state): State<Arc<HealthState>>) -> (StatusCode, Json<Value>) {
+    let now = Utc::now();
+    let conditions = state.scenario.conditions(now >= state.incident_start);
+
+    let status = match state.service {
+        Service::Forgeagents if conditions.forgeagents_down => "down",
+        Service::Dataforge if conditions.dataforge_latency > 1.0 => "degraded",
+        Service::Rake if conditions.rake_failure_rate > 0.1 => "degraded",
+        _ => "healthy",
+    };
+    let code = if status == "down" {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+
+    (
+        code,
+        Json(json!({
+            "status": status,
+            "service": state.service.name(),
+            "simulated": true,
+            "scenario": state.scenario.name(),
+            "uptime_seconds": (now - state.started_at).num_seconds(),
+        })),
+    )
+}
+
+/// Binds every service's listener, failing fast if a port is taken, then
+/// serves them in the background.
+pub async fn serve(
+    addresses: Vec<(Service, SocketAddr)>,
+    scenario: Scenario,
+    incident_start: DateTime<Utc>,
+) -> std::io::Result<()> {
+    let started_at = Utc::now();
+
+    for (service, address) in addresses {
+        let listener = tokio::net::TcpListener::bind(address).await.map_err(|e| {
+            std::io::Error::new(
+                e.kind(),
+                format!("{} health on {}: {}", service.name(), address, e),
+            )
+        })?;
+        let state = Arc::new(HealthState {
+            service,
+            scenario,
+            incident_start,
+            started_at,
+        });
+        let app = Router::new()
+            .route("/health", get(health))
+            .with_state(state);
+
+        println!(
+            "Mock {} health on http://{}/health",
+            service.name(),
+            address
+        );
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, app).await {
+                eprintln!("Mock {} health stopped: {}", service.name(), e);
+            }
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,336 @@
+// ===========================================================================
+// Forge Ecosystem Simulator
+// ===========================================================================
+//
+// `forge-sim` writes realistic DataForge, NeuroForge, ForgeAgents and Rake
+// events into a SQLite database laid out like `dataforge.db`, so Forge
+// Command can be developed and demoed without the real services:
+//
+//   forge-sim --db /tmp/dataforge.db --scenario rake-failures --health
+//   DATABASE_URL=/tmp/dataforge.db npm run tauri:dev
+//
+// It backfills history first, then keeps writing live events until stopped.
+// Output depends only on the seed, rates, scenario and clock; pass `--now`
+// to freeze the clock and get the same database on every run.
+
+mod generator;
+mod health;
+mod scenario;
+
+use std::collections::BTreeMap;
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use clap::{Parser, ValueEnum};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+
+use generator::{Event, Generator};
+use scenario::Scenario;
+
+const SCHEMA: [&str; 4] = [
+    "CREATE TABLE IF NOT EXISTS events (
+        event_id TEXT PRIMARY KEY,
+        timestamp TEXT NOT NULL,
+        service TEXT NOT NULL,
+        event_type TEXT NOT NULL,
+        severity TEXT NOT NULL,
+        metrics TEXT NOT NULL DEFAULT '{}',
+        metadata TEXT NOT NULL DEFAULT '{}'
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_events_service_event_type_timestamp ON events (service, event_type, timestamp)",
+    "CREATE INDEX IF NOT EXISTS idx_events_service_timestamp ON events (service, timestamp)",
+    "CREATE INDEX IF NOT EXISTS idx_events_timestamp ON events (timestamp)",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum Service {
+    Dataforge,
+    Neuroforge,
+    Forgeagents,
+    Rake,
+}
+
+impl Service {
+    pub const ALL: [Service; 4] = [
+        Service::Dataforge,
+        Service::Neuroforge,
+        Service::Forgeagents,
+        Service::Rake,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Service::Dataforge => "dataforge",
+            Service::Neuroforge => "neuroforge",
+            Service::Forgeagents => "forgeagents",
+            Service::Rake => "rake",
+        }
+    }
+
+    /// Searches, model requests, tasks or pipeline runs per minute.
+    fn default_rate(self) -> f64 {
+        match self {
+            Service::Dataforge => 30.0,
+            Service::Neuroforge => 20.0,
+            Service::Forgeagents => 6.0,
+            Service::Rake => 2.0,
+        }
+    }
+
+    /// The port each service listens on in the real ecosystem.
+    fn default_port(self) -> u16 {
+        match self {
+            Service::Dataforge => 8788,
+            Service::Neuroforge => 8000,
+            Service::Forgeagents => 8787,
+            Service::Rake => 8002,
+        }
+    }
+}
+
+/// `service=value`, for per-service options.
+#[derive(Debug, Clone)]
+struct Setting<T> {
+    service: Service,
+    value: T,
+}
+
+fn parse_setting<T: FromStr>(raw: &str) -> Result<Setting<T>, String> {
+    let (service, value) = raw
+        .split_once('=')
+        .ok_or_else(|| format!("expected service=value, got {:?}", raw))?;
+    Ok(Setting {
+        service: Service::from_str(service, true)?,
+        value: value
+            .parse()
+            .map_err(|_| format!("invalid value {:?} for {}", value, service))?,
+    })
+}
+
+fn parse_timestamp(raw: &str) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(raw)
+        .map(|at| at.with_timezone(&Utc))
+        .map_err(|e| format!("{:?} is not an RFC 3339 timestamp: {}", raw, e))
+}
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "forge-sim",
+    version,
+    about = "Writes simulated Forge ecosystem events for development and demos"
+)]
+struct Args {
+    /// Events database to write [default: DATABASE_URL, or ./dataforge.db]
+    #[arg(long)]
+    db: Option<PathBuf>,
+
+    /// Scenario to play
+    #[arg(long, value_enum, default_value_t = Scenario::Steady)]
+    scenario: Scenario,
+
+    /// Minutes before the current time the scenario's incident began
+    #[arg(long, default_value_t = 60)]
+    incident_minutes_ago: i64,
+
+    /// Seed for every random choice
+    #[arg(long, default_value_t = 42)]
+    seed: u64,
+
+    /// Events per minute for one service, e.g. neuroforge=40 (repeatable)
+    /// [defaults: dataforge=30 neuroforge=20 forgeagents=6 rake=2]
+    #[arg(long = "rate", value_parser = parse_setting::<f64>)]
+    rates: Vec<Setting<f64>>,
+
+    /// Hours of history to write before going live
+    #[arg(long, default_value_t = 24)]
+    backfill_hours: i64,
+
+    /// Seconds to keep writing live events; runs until stopped when omitted
+    #[arg(long)]
+    duration: Option<u64>,
+
+    /// Freeze the clock at this RFC 3339 time: write the backfill and exit
+    #[arg(long, value_parser = parse_timestamp, conflicts_with_all = ["duration", "health"])]
+    now: Option<DateTime<Utc>>,
+
+    /// Delete existing events before writing
+    #[arg(long)]
+    reset: bool,
+
+    /// Serve mock /health endpoints while running
+    #[arg(long)]
+    health: bool,
+
+    /// Address the mock health endpoints bind to
+    #[arg(long, requires = "health", default_value = "127.0.0.1")]
+    health_host: IpAddr,
+
+    /// Health port for one service, e.g. forgeagents=9787 (repeatable)
+    /// [defaults: dataforge=8788 neuroforge=8000 forgeagents=8787 rake=8002]
+    #[arg(long = "health-port", requires = "health", value_parser = parse_setting::<u16>)]
+    health_ports: Vec<Setting<u16>>,
+}
+
+impl Args {
+    fn db_path(&self) -> PathBuf {
+        self.db.clone().unwrap_or_else(|| {
+            std::env::var("DATABASE_URL")
+                .map(|url| {
+                    PathBuf::from(
+                        url.trim_start_matches("sqlite://")
+                            .trim_start_matches("sqlite:")
+                            .split('?')
+                            .next()
+                            .unwrap_or_default(),
+                    )
+                })
+                .unwrap_or_else(|_| PathBuf::from("dataforge.db"))
+        })
+    }
+
+    fn rates(&self) -> Result<[f64; 4], String> {
+        let mut rates = Service::ALL.map(Service::default_rate);
+        for setting in &self.rates {
+            if !setting.value.is_finite() || setting.value < 0.0 {
+                return Err(format!(
+                    "rate for {} must be zero or more",
+                    setting.service.name()
+                ));
+            }
+            rates[setting.service as usize] = setting.value;
+        }
+        Ok(rates)
+    }
+
+    fn health_addresses(&self) -> Vec<(Service, SocketAddr)> {
+        Service::ALL
+            .into_iter()
+            .map(|service| {
+                let port = self
+                    .health_ports
+                    .iter()
+                    .rev()
+                    .find(|setting| setting.service == service)
+                    .map_or(service.default_port(), |setting| setting.value);
+                (service, SocketAddr::new(self.health_host, port))
+            })
+            .collect()
+    }
+}
+
+async fn open_db(path: &PathBuf, reset: bool) -> Result<SqlitePool, sqlx::Error> {
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true);
+    let pool = SqlitePool::connect_with(options).await?;
+
+    for statement in SCHEMA {
+        sqlx::query(statement).execute(&pool).await?;
+    }
+    if reset {
+        sqlx::query("DELETE FROM events").execute(&pool).await?;
+    }
+    Ok(pool)
+}
+
+async fn write_events(pool: &SqlitePool, events: &[Event]) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    for event in events {
+        sqlx::query(
+            "INSERT OR IGNORE INTO events
+                (event_id, timestamp, service, event_type, severity, metrics, metadata)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&event.event_id)
+        // Same layout DataForge writes
+        .bind(event.timestamp.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .bind(event.service)
+        .bind(event.event_type)
+        .bind(event.severity)
+        .bind(event.metrics.to_string())
+        .bind(event.metadata.to_string())
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await
+}
+
+fn summarize(events: &[Event]) -> String {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for event in events {
+        *counts.entry(event.service).or_default() += 1;
+    }
+    counts
+        .iter()
+        .map(|(service, count)| format!("{} {}", service, count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+async fn run(args: Args) -> Result<(), String> {
+    let rates = args.rates()?;
+    let path = args.db_path();
+    let now = args.now.unwrap_or_else(Utc::now);
+    let start = now - Duration::hours(args.backfill_hours.max(0));
+    let incident_start = now - Duration::minutes(args.incident_minutes_ago);
+
+    let pool = open_db(&path, args.reset)
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    if args.health {
+        health::serve(args.health_addresses(), args.scenario, incident_start)
+            .await
+            .map_err(|e| format!("Failed to serve mock health: {}", e))?;
+    }
+
+    let mut generator = Generator::new(args.seed, args.scenario, incident_start, rates, start);
+    let backfill = generator.advance(now);
+    write_events(&pool, &backfill)
+        .await
+        .map_err(|e| format!("Failed to write events: {}", e))?;
+    println!(
+        "Wrote {} events ({}) to {}; scenario {}, incident since {}",
+        backfill.len(),
+        summarize(&backfill),
+        path.display(),
+        args.scenario.name(),
+        incident_start.to_rfc3339_opts(SecondsFormat::Secs, true)
+    );
+
+    if args.now.is_some() || args.duration == Some(0) {
+        return Ok(());
+    }
+
+    let stop_at = args
+        .duration
+        .map(|seconds| Utc::now() + Duration::seconds(seconds as i64));
+    let mut written = 0;
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        let now = Utc::now();
+        let events = generator.advance(now);
+        write_events(&pool, &events)
+            .await
+            .map_err(|e| format!("Failed to write events: {}", e))?;
+        written += events.len();
+
+        if stop_at.is_some_and(|stop_at| now >= stop_at) {
+            println!("Wrote {} live events", written);
+            return Ok(());
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(Args::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("forge-sim: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,96 @@
+// ===========================================================================
+// Scenarios
+// ===========================================================================
+//
+// A scenario is steady-state traffic plus one incident. Before the incident
+// starts every scenario behaves like `steady`; from then on the incident's
+// conditions hold until the simulator stops.
+
+use clap::ValueEnum;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Scenario {
+    /// Normal traffic on every service
+    Steady,
+    /// DataForge searches slow down roughly sixfold and time out more often
+    DataforgeLatency,
+    /// NeuroForge traffic shifts to the priciest model with much longer prompts
+    NeuroforgeCostSpike,
+    /// Rake runs fail often, one pipeline on every run
+    RakeFailures,
+    /// ForgeAgents stops reporting; tasks in flight never complete
+    ForgeagentsOutage,
+}
+
+/// Knobs the generator reads for a point in time.
+#[derive(Debug, Clone, Copy)]
+pub struct Conditions {
+    /// Multiplier on DataForge search latency.
+    pub dataforge_latency: f64,
+    /// Share of DataForge searches that fail.
+    pub dataforge_error_rate: f64,
+    /// Share of NeuroForge requests forced onto the most expensive model.
+    pub neuroforge_premium_share: f64,
+    /// Multiplier on NeuroForge prompt tokens.
+    pub neuroforge_prompt_factor: f64,
+    /// Share of ForgeAgents tasks that fail.
+    pub forgeagents_failure_rate: f64,
+    /// ForgeAgents is down: no events and a failing `/health`.
+    pub forgeagents_down: bool,
+    /// Share of Rake runs that fail.
+    pub rake_failure_rate: f64,
+    /// Pipeline whose every run fails.
+    pub rake_broken_pipeline: Option<&'static str>,
+}
+
+const STEADY: Conditions = Conditions {
+    dataforge_latency: 1.0,
+    dataforge_error_rate: 0.01,
+    neuroforge_premium_share: 0.0,
+    neuroforge_prompt_factor: 1.0,
+    forgeagents_failure_rate: 0.04,
+    forgeagents_down: false,
+    rake_failure_rate: 0.03,
+    rake_broken_pipeline: None,
+};
+
+impl Scenario {
+    pub fn name(self) -> &'static str {
+        match self {
+            Scenario::Steady => "steady",
+            Scenario::DataforgeLatency => "dataforge-latency",
+            Scenario::NeuroforgeCostSpike => "neuroforge-cost-spike",
+            Scenario::RakeFailures => "rake-failures",
+            Scenario::ForgeagentsOutage => "forgeagents-outage",
+        }
+    }
+
+    pub fn conditions(self, incident: bool) -> Conditions {
+        if !incident {
+            return STEADY;
+        }
+
+        match self {
+            Scenario::Steady => STEADY,
+            Scenario::DataforgeLatency => Conditions {
+                dataforge_latency: 6.0,
+                dataforge_error_rate: 0.08,
+                ..STEADY
+            },
+            Scenario::NeuroforgeCostSpike => Conditions {
+                neuroforge_premium_share: 0.7,
+                neuroforge_prompt_factor: 4.0,
+                ..STEADY
+            },
+            Scenario::RakeFailures => Conditions {
+                rake_failure_rate: 0.35,
+                rake_broken_pipeline: Some("slack-archive"),
+                ..STEADY
+            },
+            Scenario::ForgeagentsOutage => Conditions {
+                forgeagents_down: true,
+                ..STEADY
+            },
+        }
+    }
+}
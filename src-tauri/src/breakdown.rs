@@ -0,0 +1,279 @@
+// ===========================================================================
+// Breakdown Queries
+// ===========================================================================
+//
+// Groups a service's events by any metadata/metrics key so the long tail of
+// models, agents and pipelines is visible instead of a hard-wired top 5.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    query::Query,
+    sqlite::{Sqlite, SqliteArguments},
+    Row,
+};
+
+use crate::advisor::TimedQuery;
+use crate::error::{self, CommandResult, ForgeCommandError};
+use crate::get_db_pool;
+use crate::period::TimeWindow;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum JsonColumn {
+    Metadata,
+    Metrics,
+}
+
+impl JsonColumn {
+    pub(crate) fn as_sql(self) -> &'static str {
+        match self {
+            JsonColumn::Metadata => "metadata",
+            JsonColumn::Metrics => "metrics",
+        }
+    }
+}
+
+/// A key inside one of the JSON columns of the events table, e.g. `metadata.model`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonField {
+    pub column: JsonColumn,
+    pub key: String,
+}
+
+impl JsonField {
+    /// JSON path suitable for binding into `json_extract`. Keys are restricted to
+    /// identifier characters so a path can never escape its column.
+    pub(crate) fn json_path(&self) -> CommandResult<String> {
+        let valid = !self.key.is_empty()
+            && self
+                .key
+                .split('.')
+                .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+
+        if valid {
+            Ok(format!("$.{}", self.key))
+        } else {
+            Err(ForgeCommandError::invalid_input(format!(
+                "Invalid {} key: {:?}",
+                self.column.as_sql(),
+                self.key
+            )))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakdownSort {
+    #[default]
+    Count,
+    Sum,
+    Avg,
+    Key,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SortDirection {
+    Asc,
+    #[default]
+    Desc,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreakdownRequest {
+    pub service: String,
+    #[serde(default)]
+    pub event_type: Option<String>,
+    pub group_by: JsonField,
+    /// Numeric field summed/averaged per group alongside the event count.
+    #[serde(default)]
+    pub value: Option<JsonField>,
+    #[serde(default)]
+    pub sort_by: BreakdownSort,
+    #[serde(default)]
+    pub direction: SortDirection,
+    /// Maximum groups to return; `None` returns every group.
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub offset: Option<i64>,
+    /// Roll every group outside the requested page into a single "other" row.
+    #[serde(default)]
+    pub include_other: bool,
+    /// Restrict to events from the last N hours; `None` covers all history.
+    #[serde(default)]
+    pub hours: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BreakdownRow {
+    pub key: String,
+    pub count: i64,
+    pub sum: f64,
+    pub avg: f64,
+    /// Set on the bucket rolling up the groups outside the page, whose key can
+    /// match a real group's.
+    #[serde(default)]
+    pub is_other: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Breakdown {
+    pub total_groups: i64,
+    pub rows: Vec<BreakdownRow>,
+    pub other: Option<BreakdownRow>,
+}
+
+pub const OTHER_BUCKET_KEY: &str = "other";
+
+/// Bind values for the grouped subquery, in placeholder order.
+struct GroupedBinds {
+    group_path: String,
+    value_path: Option<String>,
+    service: String,
+    event_type: Option<String>,
+    since: String,
+    until: String,
+}
+
+impl GroupedBinds {
+    fn apply<'q>(
+        &self,
+        mut query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    ) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+        query = query.bind(self.group_path.clone());
+        // value_expr appears three times (COUNT, SUM, AVG)
+        if let Some(path) = &self.value_path {
+            for _ in 0..3 {
+                query = query.bind(path.clone());
+            }
+        }
+        query
+            .bind(self.service.clone())
+            .bind(self.event_type.clone())
+            .bind(self.event_type.clone())
+            .bind(self.since.clone())
+            .bind(self.until.clone())
+    }
+}
+
+#[tauri::command]
+pub async fn get_breakdown(request: BreakdownRequest) -> CommandResult<Breakdown> {
+    error::command("get_breakdown", async move {
+        let pool = get_db_pool().await?;
+
+        let group_path = request.group_by.json_path()?;
+        let value_path = request.value.as_ref().map(JsonField::json_path).transpose()?;
+        let value_expr = match &request.value {
+            Some(field) => format!("CAST(json_extract({}, ?) AS FLOAT)", field.column.as_sql()),
+            None => "NULL".to_string(),
+        };
+
+        let order_column = match request.sort_by {
+            BreakdownSort::Count => "count",
+            BreakdownSort::Sum => "sum_value",
+            BreakdownSort::Avg => "avg_value",
+            BreakdownSort::Key => "group_key",
+        };
+        let direction = match request.direction {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        };
+
+        let grouped = format!(
+            "SELECT
+                COALESCE(CAST(json_extract({group_column}, ?) AS TEXT), 'unknown') as group_key,
+                COUNT(*) as count,
+                COUNT({value_expr}) as value_count,
+                SUM({value_expr}) as sum_value,
+                AVG({value_expr}) as avg_value
+             FROM events
+             WHERE service = ?
+             AND (? IS NULL OR event_type = ?)
+             AND timestamp > ?
+             AND timestamp <= ?
+             GROUP BY group_key",
+            group_column = request.group_by.column.as_sql(),
+        );
+
+        let window = TimeWindow::last_hours_or_all(request.hours);
+        let binds = GroupedBinds {
+            group_path,
+            value_path,
+            service: request.service.clone(),
+            event_type: request.event_type.clone(),
+            since: window.start_bound(),
+            until: window.end_bound(),
+        };
+
+        let page_sql = format!(
+            "{grouped}
+             ORDER BY {order_column} {direction}, group_key ASC
+             LIMIT ? OFFSET ?"
+        );
+
+        let rows: Vec<(BreakdownRow, i64)> = binds
+            .apply(sqlx::query(&page_sql))
+            .bind(request.limit.unwrap_or(-1))
+            .bind(request.offset.unwrap_or(0))
+            .fetch_all_timed(&pool)
+            .await?
+            .into_iter()
+            .map(|row| {
+                (
+                    BreakdownRow {
+                        key: row.get::<String, _>("group_key"),
+                        count: row.get::<i64, _>("count"),
+                        sum: row.get::<Option<f64>, _>("sum_value").unwrap_or(0.0),
+                        avg: row.get::<Option<f64>, _>("avg_value").unwrap_or(0.0),
+                        is_other: false,
+                    },
+                    row.get::<i64, _>("value_count"),
+                )
+            })
+            .collect();
+
+        let totals_sql = format!(
+            "SELECT
+                COUNT(*) as total_groups,
+                SUM(count) as count,
+                SUM(value_count) as value_count,
+                SUM(sum_value) as sum_value
+             FROM ({grouped})"
+        );
+
+        let totals = binds
+            .apply(sqlx::query(&totals_sql))
+            .fetch_one_timed(&pool)
+            .await?;
+
+        let total_groups = totals.get::<i64, _>("total_groups");
+
+        let other = if request.include_other && (rows.len() as i64) < total_groups {
+            let page_count: i64 = rows.iter().map(|(row, _)| row.count).sum();
+            let page_value_count: i64 = rows.iter().map(|(_, value_count)| value_count).sum();
+            let page_sum: f64 = rows.iter().map(|(row, _)| row.sum).sum();
+
+            let other_value_count = totals.get::<Option<i64>, _>("value_count").unwrap_or(0) - page_value_count;
+            let other_sum = totals.get::<Option<f64>, _>("sum_value").unwrap_or(0.0) - page_sum;
+
+            Some(BreakdownRow {
+                key: OTHER_BUCKET_KEY.to_string(),
+                count: totals.get::<Option<i64>, _>("count").unwrap_or(0) - page_count,
+                sum: other_sum,
+                avg: crate::safe_ratio(other_sum, other_value_count as f64),
+                is_other: true,
+            })
+        } else {
+            None
+        };
+
+        Ok(Breakdown {
+            total_groups,
+            rows: rows.into_iter().map(|(row, _)| row).collect(),
+            other,
+        })
+    })
+    .await
+}
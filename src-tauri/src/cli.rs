@@ -0,0 +1,706 @@
+// ===========================================================================
+// Command-Line Interface
+// ===========================================================================
+//
+// `forge-command <subcommand>` runs the same queries as the dashboard from a
+// terminal, for when there's no webview (tmux over SSH). Output is a table,
+// JSON or CSV, and the exit code reflects service health so scripts can gate
+// on it:
+//
+//   0  success, services healthy
+//   1  a service is down (`status`, or the service passed to `metrics`)
+//   2  usage error
+//   3  the command failed
+//   4  the events database is missing, busy or unreachable
+
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::io::{self, Write};
+use std::time::Duration;
+
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::{CommandResult, ForgeCommandError};
+use crate::event_store::{self, EventSource};
+use crate::otlp::OtlpConfig;
+use crate::period::ComparisonWindow;
+use crate::prometheus::ExporterConfig;
+use crate::report::{self, ReportFormat, ReportType};
+use crate::server::{self, ServeConfig};
+use crate::RecentEvent;
+
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_UNHEALTHY: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_FAILED: i32 = 3;
+pub const EXIT_UNAVAILABLE: i32 = 4;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "forge-command",
+    version,
+    about = "Forge Command - Mission control for Forge services"
+)]
+pub struct Cli {
+    /// Serve the dashboard commands as a local REST API instead of opening a window
+    #[arg(long)]
+    pub serve: bool,
+
+    /// Address for --serve [default: 127.0.0.1:8787, or FORGE_COMMAND_API_BIND]
+    #[arg(long, requires = "serve")]
+    pub bind: Option<String>,
+
+    /// Bearer token for --serve [default: FORGE_COMMAND_API_TOKEN, or generated]
+    #[arg(long, requires = "serve")]
+    pub token: Option<String>,
+
+    /// Also expose Prometheus metrics at /metrics (behind the same token)
+    #[arg(long, requires = "serve")]
+    pub metrics: bool,
+
+    /// Extra metric labels: comma-separated model, agent, pipeline, or none
+    #[arg(long, requires = "metrics", default_value = "none")]
+    pub metrics_labels: String,
+
+    /// Distinct values kept per extra label before folding into "other"
+    #[arg(long, requires = "metrics", default_value_t = 50)]
+    pub metrics_max_label_values: usize,
+
+    /// Also run OTLP receivers (HTTP on 4318, gRPC on 4317, or
+    /// FORGE_COMMAND_OTLP_HTTP/FORGE_COMMAND_OTLP_GRPC)
+    #[arg(long, requires = "serve")]
+    pub otlp: bool,
+
+    /// Where events are read from: auto, dataforge or local
+    /// [default: FORGE_COMMAND_EVENT_SOURCE, or auto]
+    #[arg(long, global = true, value_parser = parse_snake_case::<EventSource>)]
+    pub source: Option<EventSource>,
+
+    /// Output format
+    #[arg(long, short, value_enum, global = true, default_value_t = OutputFormat::Table)]
+    pub output: OutputFormat,
+
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CliCommand {
+    /// Service status and 24h uptime; exits 1 when any service is down
+    Status,
+    /// Recent events
+    Events {
+        #[command(subcommand)]
+        command: EventsCommand,
+    },
+    /// Summary metrics for one service; exits 1 when that service is down
+    Metrics {
+        service: Service,
+        /// Window to summarise: 6h, 7d, 2w or all
+        #[arg(long, default_value = "24h")]
+        range: String,
+        /// Compare against an earlier period
+        #[arg(long, value_enum)]
+        compare: Option<Compare>,
+        /// Rows in the per-model/agent/pipeline tables
+        #[arg(long)]
+        limit: Option<i64>,
+    },
+    /// Ops, cost and health reports
+    Report {
+        #[command(subcommand)]
+        command: ReportCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum EventsCommand {
+    /// Print the latest events, optionally following new ones
+    Tail {
+        /// Only events from this service
+        #[arg(long)]
+        service: Option<String>,
+        /// Events shown initially
+        #[arg(long, short = 'n', default_value_t = 20)]
+        limit: i64,
+        /// Keep polling for new events (JSON output becomes one object per line)
+        #[arg(long, short)]
+        follow: bool,
+        /// Seconds between polls with --follow
+        #[arg(long, default_value_t = 5)]
+        interval: u64,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ReportCommand {
+    /// Generate a report now and print where it was written
+    Generate {
+        /// ops, cost or health
+        #[arg(value_parser = parse_snake_case::<ReportType>)]
+        report_type: ReportType,
+        /// Comma-separated pdf, html, markdown [default: all]
+        #[arg(long, value_delimiter = ',', value_parser = parse_snake_case::<ReportFormat>)]
+        format: Vec<ReportFormat>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Service {
+    Dataforge,
+    Neuroforge,
+    Forgeagents,
+    Rake,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Compare {
+    PreviousPeriod,
+    SamePeriodLastWeek,
+}
+
+impl Compare {
+    fn window(self) -> ComparisonWindow {
+        match self {
+            Compare::PreviousPeriod => ComparisonWindow::PreviousPeriod,
+            Compare::SamePeriodLastWeek => ComparisonWindow::SamePeriodLastWeek,
+        }
+    }
+}
+
+/// Parses a value with the same snake_case names the IPC commands accept.
+fn parse_snake_case<T: DeserializeOwned>(value: &str) -> Result<T, String> {
+    serde_json::from_value(Value::String(value.to_string()))
+        .map_err(|_| format!("unknown value {:?}", value))
+}
+
+/// `24h`, `7d`, `2w`, a bare number of hours, or `all` (no lower bound).
+fn parse_range(range: &str) -> CommandResult<Option<i64>> {
+    if range == "all" {
+        return Ok(None);
+    }
+
+    let (amount, hours_per_unit) = match range.char_indices().last() {
+        Some((index, 'h')) => (&range[..index], 1),
+        Some((index, 'd')) => (&range[..index], 24),
+        Some((index, 'w')) => (&range[..index], 24 * 7),
+        _ => (range, 1),
+    };
+
+    match amount.parse::<i64>() {
+        Ok(amount) if amount > 0 => Ok(Some(amount * hours_per_unit)),
+        _ => Err(ForgeCommandError::invalid_input(format!(
+            "Invalid range {:?} (expected e.g. 24h, 7d, 2w or all)",
+            range
+        ))),
+    }
+}
+
+// ===========================================================================
+// Output
+// ===========================================================================
+
+struct Table {
+    title: Option<String>,
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    fn new(headers: &[&str]) -> Self {
+        Table {
+            title: None,
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.len()).collect();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let line = |cells: &[String]| {
+            cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+
+        let mut out = String::new();
+        if let Some(title) = &self.title {
+            out.push_str(title);
+            out.push('\n');
+        }
+        out.push_str(&line(
+            &self
+                .headers
+                .iter()
+                .map(|h| h.to_uppercase())
+                .collect::<Vec<_>>(),
+        ));
+        out.push('\n');
+        for row in &self.rows {
+            out.push_str(&line(row));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+fn format_number(value: f64) -> String {
+    let formatted = format!("{:.4}", value);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => "-".to_string(),
+        Value::String(s) => s.clone(),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => i.to_string(),
+            None => format_number(n.as_f64().unwrap_or_default()),
+        },
+        other => other.to_string(),
+    }
+}
+
+/// Prints `value` as JSON, or `tables` as aligned text or CSV.
+fn print<T: Serialize>(output: OutputFormat, value: &T, tables: &[Table]) -> CommandResult<()> {
+    match output {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value)?),
+        OutputFormat::Table => {
+            let rendered: Vec<String> = tables.iter().map(Table::render).collect();
+            print!("{}", rendered.join("\n"));
+        }
+        OutputFormat::Csv => {
+            for (index, table) in tables.iter().enumerate() {
+                if index > 0 {
+                    println!();
+                }
+                let mut writer = csv::Writer::from_writer(io::stdout());
+                writer.write_record(&table.headers)?;
+                for row in &table.rows {
+                    writer.write_record(row)?;
+                }
+                writer.flush()?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A key/value table of the top-level scalars, with previous values and
+/// change when a comparison is present, plus one table per list of objects.
+fn metric_tables(value: &Value) -> Vec<Table> {
+    let Some(object) = value.as_object() else {
+        return Vec::new();
+    };
+    let deltas = value
+        .pointer("/comparison/deltas")
+        .and_then(Value::as_object);
+
+    let mut summary = match deltas {
+        Some(_) => Table::new(&["metric", "value", "previous", "change"]),
+        None => Table::new(&["metric", "value"]),
+    };
+    let mut tables = Vec::new();
+
+    for (key, field) in object {
+        match field {
+            Value::Array(items) if items.iter().all(Value::is_object) => {
+                let Some(first) = items.first().and_then(Value::as_object) else {
+                    continue;
+                };
+                let headers: Vec<&str> = first.keys().map(String::as_str).collect();
+                let mut table = Table::new(&headers);
+                table.title = Some(key.clone());
+                for item in items {
+                    table
+                        .rows
+                        .push(headers.iter().map(|h| cell(&item[*h])).collect());
+                }
+                tables.push(table);
+            }
+            Value::Object(_) | Value::Array(_) | Value::Null => {}
+            scalar => {
+                let mut row = vec![key.clone(), cell(scalar)];
+                if let Some(deltas) = deltas {
+                    match deltas.get(key) {
+                        Some(delta) => {
+                            row.push(cell(&delta["previous"]));
+                            row.push(match delta["percent"].as_f64() {
+                                Some(percent) => format!("{:+.1}%", percent),
+                                None => cell(&delta["absolute"]),
+                            });
+                        }
+                        None => row.extend(["-".to_string(), "-".to_string()]),
+                    }
+                }
+                summary.rows.push(row);
+            }
+        }
+    }
+
+    tables.insert(0, summary);
+    tables
+}
+
+fn event_row(event: &RecentEvent) -> Vec<String> {
+    vec![
+        event.timestamp.clone(),
+        event.service.clone(),
+        event.event_type.clone(),
+        event.severity.clone(),
+        event.event_id.clone(),
+    ]
+}
+
+const EVENT_HEADERS: [&str; 5] = ["timestamp", "service", "event_type", "severity", "event_id"];
+
+// ===========================================================================
+// Subcommands
+// ===========================================================================
+
+async fn status(output: OutputFormat) -> CommandResult<i32> {
+    let health = crate::get_system_health().await?;
+
+    let services = [
+        (
+            "dataforge",
+            &health.dataforge_status,
+            health.dataforge_uptime,
+        ),
+        (
+            "neuroforge",
+            &health.neuroforge_status,
+            health.neuroforge_uptime,
+        ),
+        (
+            "forgeagents",
+            &health.forgeagents_status,
+            health.forgeagents_uptime,
+        ),
+        ("rake", &health.rake_status, health.rake_uptime),
+    ];
+
+    let mut table = Table::new(&["service", "status", "uptime_24h"]);
+    for (service, status, uptime) in services {
+        table.rows.push(vec![
+            service.to_string(),
+            status.clone(),
+            format!("{:.2}%", uptime),
+        ]);
+    }
+    print(output, &health, &[table])?;
+
+    let down = services
+        .iter()
+        .any(|(_, status, _)| status.as_str() == "DOWN");
+    Ok(if down { EXIT_UNHEALTHY } else { EXIT_OK })
+}
+
+async fn tail(
+    output: OutputFormat,
+    service: Option<String>,
+    limit: i64,
+    follow: bool,
+    interval: u64,
+) -> CommandResult<i32> {
+    let mut events = crate::get_recent_events(limit, service.clone()).await?;
+    events.reverse();
+
+    if !follow {
+        let mut table = Table::new(&EVENT_HEADERS);
+        table.rows = events.iter().map(event_row).collect();
+        print(output, &events, &[table])?;
+        return Ok(EXIT_OK);
+    }
+
+    // Followed output is streamed: fixed-width rows, CSV rows or JSON lines
+    let mut csv = csv::Writer::from_writer(io::stdout());
+    let emit = |csv: &mut csv::Writer<io::Stdout>, event: &RecentEvent| -> CommandResult<()> {
+        match output {
+            OutputFormat::Json => println!("{}", serde_json::to_string(event)?),
+            OutputFormat::Csv => {
+                csv.write_record(event_row(event))?;
+                csv.flush()?;
+            }
+            OutputFormat::Table => {
+                println!(
+                    "{:<20}  {:<12}  {:<24}  {:<8}  {}",
+                    event.timestamp,
+                    event.service,
+                    event.event_type,
+                    event.severity,
+                    event.event_id
+                );
+                io::stdout().flush()?;
+            }
+        }
+        Ok(())
+    };
+
+    match output {
+        OutputFormat::Csv => csv.write_record(EVENT_HEADERS)?,
+        OutputFormat::Table => println!(
+            "{:<20}  {:<12}  {:<24}  {:<8}  EVENT_ID",
+            "TIMESTAMP", "SERVICE", "EVENT_TYPE", "SEVERITY"
+        ),
+        OutputFormat::Json => {}
+    }
+    for event in &events {
+        emit(&mut csv, event)?;
+    }
+
+    // Events sharing the newest timestamp are remembered so they print once
+    let mut newest = events
+        .last()
+        .map(|event| event.timestamp.clone())
+        .unwrap_or_default();
+    let mut seen: HashSet<String> = events
+        .iter()
+        .filter(|event| event.timestamp == newest)
+        .map(|event| event.event_id.clone())
+        .collect();
+
+    loop {
+        tokio::time::sleep(Duration::from_secs(interval.max(1))).await;
+
+        let mut fresh: Vec<RecentEvent> = crate::get_recent_events(limit.max(100), service.clone())
+            .await?
+            .into_iter()
+            .filter(|event| {
+                event.timestamp > newest
+                    || (event.timestamp == newest && !seen.contains(&event.event_id))
+            })
+            .collect();
+        fresh.reverse();
+
+        for event in &fresh {
+            emit(&mut csv, event)?;
+            if event.timestamp > newest {
+                newest = event.timestamp.clone();
+                seen.clear();
+            }
+            seen.insert(event.event_id.clone());
+        }
+    }
+}
+
+async fn metrics(
+    output: OutputFormat,
+    service: Service,
+    range: &str,
+    compare: Option<Compare>,
+    limit: Option<i64>,
+) -> CommandResult<i32> {
+    let hours = parse_range(range)?;
+    let comparison = compare.map(Compare::window);
+
+    let value = match service {
+        Service::Dataforge => {
+            serde_json::to_value(crate::get_dataforge_metrics(hours, comparison).await?)?
+        }
+        Service::Neuroforge => {
+            serde_json::to_value(crate::get_neuroforge_metrics(limit, hours, comparison).await?)?
+        }
+        Service::Forgeagents => {
+            serde_json::to_value(crate::get_forgeagents_metrics(limit, hours, comparison).await?)?
+        }
+        Service::Rake => {
+            serde_json::to_value(crate::get_rake_metrics(limit, hours, comparison).await?)?
+        }
+    };
+    print(output, &value, &metric_tables(&value))?;
+
+    let health = crate::get_system_health().await?;
+    let status = match service {
+        Service::Dataforge => health.dataforge_status,
+        Service::Neuroforge => health.neuroforge_status,
+        Service::Forgeagents => health.forgeagents_status,
+        Service::Rake => health.rake_status,
+    };
+    Ok(if status == "DOWN" {
+        EXIT_UNHEALTHY
+    } else {
+        EXIT_OK
+    })
+}
+
+async fn generate_report(
+    output: OutputFormat,
+    report_type: ReportType,
+    formats: Vec<ReportFormat>,
+) -> CommandResult<i32> {
+    let formats = if formats.is_empty() {
+        None
+    } else {
+        Some(formats)
+    };
+    let generated = report::generate_report_now(report_type, formats).await?;
+
+    let mut table = Table::new(&["format", "path"]);
+    for file in &generated.files {
+        table
+            .rows
+            .push(vec![file.format.clone(), file.path.clone()]);
+    }
+    print(output, &generated, &[table])?;
+
+    Ok(EXIT_OK)
+}
+
+async fn execute(command: CliCommand, output: OutputFormat) -> CommandResult<i32> {
+    match command {
+        CliCommand::Status => status(output).await,
+        CliCommand::Events {
+            command:
+                EventsCommand::Tail {
+                    service,
+                    limit,
+                    follow,
+                    interval,
+                },
+        } => tail(output, service, limit, follow, interval).await,
+        CliCommand::Metrics {
+            service,
+            range,
+            compare,
+            limit,
+        } => metrics(output, service, &range, compare, limit).await,
+        CliCommand::Report {
+            command:
+                ReportCommand::Generate {
+                    report_type,
+                    format,
+                },
+        } => generate_report(output, report_type, format).await,
+    }
+}
+
+/// Runs the parsed command line and returns the process exit code.
+/// What a launch with a given command line should do.
+#[derive(Debug)]
+pub enum Launch {
+    /// Open the dashboard window.
+    Window,
+    /// Serve the REST API or run a subcommand.
+    Headless(Box<Cli>),
+    /// Print the help, version or a usage error and exit.
+    Usage(clap::Error),
+}
+
+/// Decides between the window and headless use. Only `--serve` or a
+/// subcommand mean headless; anything else the CLI doesn't recognise, such
+/// as macOS `-psn_…` arguments or files and URLs passed by the OS, still
+/// opens the window.
+pub fn launch<I, T>(args: I) -> Launch
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString>,
+{
+    let args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+
+    match Cli::try_parse_from(&args) {
+        Ok(cli) if cli.serve || cli.command.is_some() => Launch::Headless(Box::new(cli)),
+        Ok(_) => Launch::Window,
+        Err(e) if matches!(e.kind(), ErrorKind::DisplayHelp | ErrorKind::DisplayVersion) => Launch::Usage(e),
+        // A typo in a headless invocation is reported, not turned into a window
+        Err(e) => {
+            let command = Cli::command();
+            let headless = args
+                .iter()
+                .skip(1)
+                .any(|arg| arg == "--serve" || command.find_subcommand(arg).is_some());
+            if headless {
+                Launch::Usage(e)
+            } else {
+                Launch::Window
+            }
+        }
+    }
+}
+
+pub fn run(cli: Cli) -> i32 {
+    if let Some(source) = cli.source {
+        event_store::set_event_source(source);
+    }
+
+    if cli.serve && cli.command.is_some() {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--serve can't be combined with a subcommand",
+            )
+            .exit();
+    }
+
+    if cli.serve {
+        let metrics = cli
+            .metrics
+            .then(|| ExporterConfig::new(&cli.metrics_labels, cli.metrics_max_label_values))
+            .transpose();
+        let otlp =
+            OtlpConfig::from_env().map(|otlp| if cli.otlp { otlp.with_defaults() } else { otlp });
+        let config =
+            metrics.and_then(|metrics| ServeConfig::new(cli.bind, cli.token, metrics, otlp?));
+        return match config.and_then(server::run) {
+            Ok(()) => EXIT_OK,
+            Err(e) => {
+                eprintln!("Forge Command API failed: {}", e);
+                EXIT_FAILED
+            }
+        };
+    }
+
+    let Some(command) = cli.command else {
+        let _ = Cli::command().print_help();
+        return EXIT_USAGE;
+    };
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return EXIT_FAILED;
+        }
+    };
+
+    match runtime.block_on(execute(command, cli.output)) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error [{}]: {}", e.code(), e);
+            let unavailable =
+                e.retryable() || matches!(e.kind(), ForgeCommandError::DatabaseMissing { .. });
+            if unavailable {
+                EXIT_UNAVAILABLE
+            } else if matches!(e.kind(), ForgeCommandError::InvalidInput(_)) {
+                EXIT_USAGE
+            } else {
+                EXIT_FAILED
+            }
+        }
+    }
+}
@@ -0,0 +1,35 @@
+// ===========================================================================
+// Clock
+// ===========================================================================
+//
+// The "now" every query window, health cutoff, rollup watermark, job
+// schedule and collector timestamp is anchored to. It follows the system
+// clock unless a test freezes it, so fixtures with fixed timestamps give
+// exact metrics and series.
+
+use std::sync::RwLock;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+static FROZEN: RwLock<Option<DateTime<Utc>>> = RwLock::new(None);
+
+pub fn now() -> DateTime<Utc> {
+    FROZEN
+        .read()
+        .ok()
+        .and_then(|frozen| *frozen)
+        .unwrap_or_else(Utc::now)
+}
+
+/// `now()` as naive UTC, the form time windows use.
+pub fn now_naive() -> NaiveDateTime {
+    now().naive_utc()
+}
+
+/// Pins `now()` to `at`; `None` goes back to the system clock.
+#[cfg(test)]
+pub fn freeze(at: Option<DateTime<Utc>>) {
+    if let Ok(mut frozen) = FROZEN.write() {
+        *frozen = at;
+    }
+}
@@ -0,0 +1,249 @@
+// ===========================================================================
+// Command Errors
+// ===========================================================================
+//
+// Every IPC command fails with a `ForgeCommandError`, serialized as
+// `{ code, message, retryable, context }` so the UI can tell a missing
+// database from a schema change from a busy file and say what to do about it.
+// Codes are stable; messages are for humans and may change.
+
+use std::fmt;
+use std::future::Future;
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+pub type CommandResult<T> = Result<T, ForgeCommandError>;
+
+/// Where an error happened. Inner layers fill in the query, outer layers the
+/// service and command; the first value set for a field wins.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ErrorContext {
+    pub command: Option<String>,
+    pub service: Option<String>,
+    pub query: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum ForgeCommandError {
+    /// The events database file doesn't exist.
+    DatabaseMissing {
+        path: String,
+    },
+    /// The database exists but couldn't be opened.
+    DatabaseUnavailable(String),
+    /// SQLite reported the database as locked or busy.
+    DatabaseBusy(String),
+    /// No connection became available in time.
+    QueryTimeout(String),
+    /// A table or column the query needs is missing.
+    SchemaMismatch(String),
+    QueryFailed(String),
+    InvalidInput(String),
+    NotFound(String),
+    Io(String),
+    Internal(String),
+    /// Any of the above, annotated with where it happened.
+    WithContext {
+        error: Box<ForgeCommandError>,
+        context: ErrorContext,
+    },
+}
+
+impl ForgeCommandError {
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        ForgeCommandError::InvalidInput(message.into())
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        ForgeCommandError::NotFound(message.into())
+    }
+
+    pub fn internal(error: impl fmt::Display) -> Self {
+        ForgeCommandError::Internal(error.to_string())
+    }
+
+    /// The error without its context wrapper.
+    pub fn kind(&self) -> &ForgeCommandError {
+        match self {
+            ForgeCommandError::WithContext { error, .. } => error.kind(),
+            error => error,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self.kind() {
+            ForgeCommandError::DatabaseMissing { .. } => "database_missing",
+            ForgeCommandError::DatabaseUnavailable(_) => "database_unavailable",
+            ForgeCommandError::DatabaseBusy(_) => "database_busy",
+            ForgeCommandError::QueryTimeout(_) => "query_timeout",
+            ForgeCommandError::SchemaMismatch(_) => "schema_mismatch",
+            ForgeCommandError::QueryFailed(_) => "query_failed",
+            ForgeCommandError::InvalidInput(_) => "invalid_input",
+            ForgeCommandError::NotFound(_) => "not_found",
+            ForgeCommandError::Io(_) => "io_error",
+            ForgeCommandError::Internal(_) => "internal",
+            ForgeCommandError::WithContext { .. } => unreachable!("kind() unwraps context"),
+        }
+    }
+
+    /// Whether the same call may succeed if simply tried again.
+    pub fn retryable(&self) -> bool {
+        matches!(
+            self.kind(),
+            ForgeCommandError::DatabaseUnavailable(_)
+                | ForgeCommandError::DatabaseBusy(_)
+                | ForgeCommandError::QueryTimeout(_)
+        )
+    }
+
+    pub fn message(&self) -> String {
+        match self.kind() {
+            ForgeCommandError::DatabaseMissing { path } => format!(
+                "Events database not found at {} (set DATABASE_URL to DataForge's database)",
+                path
+            ),
+            ForgeCommandError::SchemaMismatch(message) => format!(
+                "{} (the events schema differs from what Forge Command expects; see get_schema_report)",
+                message
+            ),
+            ForgeCommandError::DatabaseUnavailable(message)
+            | ForgeCommandError::DatabaseBusy(message)
+            | ForgeCommandError::QueryTimeout(message)
+            | ForgeCommandError::QueryFailed(message)
+            | ForgeCommandError::InvalidInput(message)
+            | ForgeCommandError::NotFound(message)
+            | ForgeCommandError::Io(message)
+            | ForgeCommandError::Internal(message) => message.clone(),
+            ForgeCommandError::WithContext { .. } => unreachable!("kind() unwraps context"),
+        }
+    }
+
+    pub fn context(&self) -> ErrorContext {
+        match self {
+            ForgeCommandError::WithContext { context, .. } => context.clone(),
+            _ => ErrorContext::default(),
+        }
+    }
+
+    fn with_context(self, update: impl FnOnce(&mut ErrorContext)) -> Self {
+        let (error, mut context) = match self {
+            ForgeCommandError::WithContext { error, context } => (error, context),
+            error => (Box::new(error), ErrorContext::default()),
+        };
+        update(&mut context);
+        ForgeCommandError::WithContext { error, context }
+    }
+
+    pub fn in_command(self, command: &str) -> Self {
+        self.with_context(|context| {
+            context.command.get_or_insert_with(|| command.to_string());
+        })
+    }
+
+    pub fn in_service(self, service: &str) -> Self {
+        self.with_context(|context| {
+            context.service.get_or_insert_with(|| service.to_string());
+        })
+    }
+
+    pub fn in_query(self, query: &str) -> Self {
+        self.with_context(|context| {
+            context.query.get_or_insert_with(|| query.to_string());
+        })
+    }
+}
+
+impl fmt::Display for ForgeCommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl std::error::Error for ForgeCommandError {}
+
+impl Serialize for ForgeCommandError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ForgeCommandError", 4)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.message())?;
+        state.serialize_field("retryable", &self.retryable())?;
+        state.serialize_field("context", &self.context())?;
+        state.end()
+    }
+}
+
+impl From<sqlx::Error> for ForgeCommandError {
+    fn from(error: sqlx::Error) -> Self {
+        match &error {
+            sqlx::Error::PoolTimedOut => ForgeCommandError::QueryTimeout(error.to_string()),
+            sqlx::Error::Io(_) | sqlx::Error::Tls(_) => {
+                ForgeCommandError::DatabaseUnavailable(error.to_string())
+            }
+            sqlx::Error::Configuration(_) => ForgeCommandError::InvalidInput(error.to_string()),
+            sqlx::Error::RowNotFound => ForgeCommandError::NotFound(error.to_string()),
+            sqlx::Error::ColumnNotFound(_) => ForgeCommandError::SchemaMismatch(error.to_string()),
+            sqlx::Error::Database(database) => {
+                let message = database.message();
+                if message.contains("no such table") || message.contains("no such column") {
+                    ForgeCommandError::SchemaMismatch(message.to_string())
+                } else if message.contains("database is locked") || message.contains("busy") {
+                    ForgeCommandError::DatabaseBusy(message.to_string())
+                } else if message.contains("unable to open database file") {
+                    ForgeCommandError::DatabaseUnavailable(message.to_string())
+                } else {
+                    ForgeCommandError::QueryFailed(message.to_string())
+                }
+            }
+            _ => ForgeCommandError::QueryFailed(error.to_string()),
+        }
+    }
+}
+
+impl From<std::io::Error> for ForgeCommandError {
+    fn from(error: std::io::Error) -> Self {
+        ForgeCommandError::Io(error.to_string())
+    }
+}
+
+impl From<csv::Error> for ForgeCommandError {
+    fn from(error: csv::Error) -> Self {
+        ForgeCommandError::Io(error.to_string())
+    }
+}
+
+impl From<parquet::errors::ParquetError> for ForgeCommandError {
+    fn from(error: parquet::errors::ParquetError) -> Self {
+        ForgeCommandError::Io(error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for ForgeCommandError {
+    fn from(error: serde_json::Error) -> Self {
+        ForgeCommandError::Internal(error.to_string())
+    }
+}
+
+/// Context helpers for results whose error converts into a `ForgeCommandError`.
+pub trait ResultExt<T> {
+    fn in_query(self, query: &str) -> CommandResult<T>;
+    fn in_service(self, service: &str) -> CommandResult<T>;
+}
+
+impl<T, E: Into<ForgeCommandError>> ResultExt<T> for Result<T, E> {
+    fn in_query(self, query: &str) -> CommandResult<T> {
+        self.map_err(|e| e.into().in_query(query))
+    }
+
+    fn in_service(self, service: &str) -> CommandResult<T> {
+        self.map_err(|e| e.into().in_service(service))
+    }
+}
+
+/// Runs a command body and tags any error with the command's name.
+pub async fn command<T>(
+    name: &str,
+    body: impl Future<Output = CommandResult<T>>,
+) -> CommandResult<T> {
+    body.await.map_err(|e| e.in_command(name))
+}
@@ -0,0 +1,177 @@
+// ===========================================================================
+// Local Event Store
+// ===========================================================================
+//
+// Events sent straight to Forge Command (OTLP, `POST /api/events`) land
+// in a SQLite database it owns, laid out like DataForge's `events` table so
+// every dashboard query runs against it unchanged.
+//
+// `FORGE_COMMAND_EVENT_SOURCE` picks what the dashboards read: `dataforge`,
+// `local`, or `auto` (the default: DataForge, falling back to the local store
+// when DataForge's database doesn't exist).
+
+use std::env;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+use tokio::sync::OnceCell;
+
+use crate::advisor;
+use crate::error::{CommandResult, ForgeCommandError};
+use crate::store::data_dir;
+
+static EVENT_POOL: OnceCell<SqlitePool> = OnceCell::const_new();
+
+pub(crate) const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS events (
+    event_id TEXT PRIMARY KEY,
+    timestamp TEXT NOT NULL,
+    service TEXT NOT NULL,
+    event_type TEXT NOT NULL,
+    severity TEXT NOT NULL,
+    metrics TEXT NOT NULL DEFAULT '{}',
+    metadata TEXT NOT NULL DEFAULT '{}'
+)";
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EventSource {
+    Auto,
+    DataForge,
+    Local,
+}
+
+/// Set by `--source`; takes precedence over the environment.
+static SOURCE_OVERRIDE: OnceLock<EventSource> = OnceLock::new();
+
+pub fn set_event_source(source: EventSource) {
+    let _ = SOURCE_OVERRIDE.set(source);
+}
+
+pub fn event_source() -> CommandResult<EventSource> {
+    if let Some(source) = SOURCE_OVERRIDE.get() {
+        return Ok(*source);
+    }
+
+    match env::var("FORGE_COMMAND_EVENT_SOURCE") {
+        Ok(source) if !source.is_empty() => serde_json::from_value(Value::String(source.clone()))
+            .map_err(|_| {
+                ForgeCommandError::invalid_input(format!(
+                    "Unknown FORGE_COMMAND_EVENT_SOURCE {:?} (use auto, dataforge or local)",
+                    source
+                ))
+            }),
+        _ => Ok(EventSource::Auto),
+    }
+}
+
+/// `FORGE_COMMAND_EVENTS_DB`, or `events.db` next to the Forge Command store.
+pub fn events_db_path() -> PathBuf {
+    env::var("FORGE_COMMAND_EVENTS_DB")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| data_dir().join("events.db"))
+}
+
+async fn open_event_store() -> CommandResult<SqlitePool> {
+    let path = events_db_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| {
+            ForgeCommandError::Io(format!("Failed to create event store directory: {}", e))
+        })?;
+    }
+
+    let options = SqliteConnectOptions::new()
+        .filename(&path)
+        .create_if_missing(true);
+    let pool = SqlitePool::connect_with(options).await.map_err(|e| {
+        ForgeCommandError::DatabaseUnavailable(format!("Failed to open local event store: {}", e))
+    })?;
+
+    for statement in std::iter::once(SCHEMA.to_string()).chain(advisor::recommended_index_ddl()) {
+        sqlx::query(&statement).execute(&pool).await.map_err(|e| {
+            ForgeCommandError::QueryFailed(format!("Failed to initialise local event store: {}", e))
+        })?;
+    }
+
+    Ok(pool)
+}
+
+/// Returns the shared local event store pool, creating it on first use.
+pub async fn get_event_store_pool() -> CommandResult<SqlitePool> {
+    EVENT_POOL.get_or_try_init(open_event_store).await.cloned()
+}
+
+/// An event in the `events` schema, ready to write.
+#[derive(Debug, Clone, Serialize)]
+pub struct StoredEvent {
+    pub event_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub service: String,
+    pub event_type: String,
+    pub severity: String,
+    pub metrics: Map<String, Value>,
+    pub metadata: Map<String, Value>,
+}
+
+impl StoredEvent {
+    /// An event with a random id and empty metrics and metadata.
+    pub fn new(service: &str, event_type: &str, severity: &str, timestamp: DateTime<Utc>) -> Self {
+        StoredEvent {
+            event_id: random_event_id(),
+            timestamp,
+            service: service.to_string(),
+            event_type: event_type.to_string(),
+            severity: severity.to_string(),
+            metrics: Map::new(),
+            metadata: Map::new(),
+        }
+    }
+}
+
+/// Maps common level names (`WARN`, `fatal`, `trace`, ...) onto the events
+/// severities: `error`, `warning`, `info` or `debug`.
+pub fn normalize_severity(level: &str) -> &'static str {
+    match level.to_lowercase().as_str() {
+        "error" | "err" | "fatal" | "critical" | "crit" | "panic" => "error",
+        "warn" | "warning" => "warning",
+        "debug" | "trace" => "debug",
+        _ => "info",
+    }
+}
+
+pub fn random_event_id() -> String {
+    format!("{:032x}", rand::random::<u128>())
+}
+
+/// Writes events in one transaction. Ids already present are skipped, so
+/// retried deliveries don't double count; returns how many were new.
+pub async fn insert_events(events: &[StoredEvent]) -> CommandResult<u64> {
+    let pool = get_event_store_pool().await?;
+    let mut tx = pool.begin().await?;
+    let mut inserted = 0;
+
+    for event in events {
+        inserted += sqlx::query(
+            "INSERT OR IGNORE INTO events
+                (event_id, timestamp, service, event_type, severity, metrics, metadata)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&event.event_id)
+        // Same layout DataForge writes, so time filters match either source
+        .bind(event.timestamp.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .bind(&event.service)
+        .bind(&event.event_type)
+        .bind(&event.severity)
+        .bind(Value::Object(event.metrics.clone()).to_string())
+        .bind(Value::Object(event.metadata.clone()).to_string())
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    }
+
+    tx.commit().await?;
+    Ok(inserted)
+}
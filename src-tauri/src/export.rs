@@ -0,0 +1,758 @@
+// ===========================================================================
+// Data Export
+// ===========================================================================
+//
+// Streams query results from the events table straight to a file chosen by
+// the user, so exports are not limited to whatever a page already fetched.
+// Rows are written as they arrive from SQLite; progress is reported through
+// the `export-progress` event and a running export can be cancelled.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use futures_util::TryStreamExt;
+use parquet::basic::Compression;
+use parquet::data_type::{ByteArray, ByteArrayType, DoubleType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqliteRow, Decode, Row, Sqlite, Type};
+use tauri::{AppHandle, Emitter};
+
+use crate::breakdown::JsonField;
+use crate::clock;
+use crate::error::{self, CommandResult, ForgeCommandError};
+use crate::get_db_pool;
+use crate::period::TimeWindow;
+
+pub const PROGRESS_EVENT: &str = "export-progress";
+
+/// Rows between progress events.
+const PROGRESS_INTERVAL: u64 = 5_000;
+
+/// Row batches queued for the file writer before the query waits on it.
+const WRITE_QUEUE: usize = 4;
+
+/// Rows buffered per Parquet row group.
+const ROW_GROUP_SIZE: usize = 50_000;
+
+/// Cancellation flags for running exports, keyed by export id.
+static ACTIVE_EXPORTS: Mutex<BTreeMap<String, Arc<AtomicBool>>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Csv,
+    Ndjson,
+    Parquet,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportEventsRequest {
+    pub path: String,
+    pub format: ExportFormat,
+    #[serde(default)]
+    pub service: Option<String>,
+    #[serde(default)]
+    pub event_type: Option<String>,
+    #[serde(default)]
+    pub severity: Option<String>,
+    /// Restrict to events from the last N hours; `None` exports all history.
+    #[serde(default)]
+    pub hours: Option<i64>,
+    /// Caller-chosen id used in progress events and for cancellation.
+    #[serde(default)]
+    pub export_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SeriesBucket {
+    Minute,
+    #[default]
+    Hour,
+    Day,
+}
+
+impl SeriesBucket {
+    fn strftime_format(self) -> &'static str {
+        match self {
+            SeriesBucket::Minute => "%Y-%m-%d %H:%M:00",
+            SeriesBucket::Hour => "%Y-%m-%d %H:00:00",
+            SeriesBucket::Day => "%Y-%m-%d 00:00:00",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SeriesAggregate {
+    #[default]
+    Count,
+    Sum,
+    Avg,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportSeriesRequest {
+    pub path: String,
+    pub format: ExportFormat,
+    pub service: String,
+    #[serde(default)]
+    pub event_type: Option<String>,
+    /// Numeric field aggregated per bucket; required unless `aggregate` is `count`.
+    #[serde(default)]
+    pub value: Option<JsonField>,
+    #[serde(default)]
+    pub aggregate: SeriesAggregate,
+    #[serde(default)]
+    pub bucket: SeriesBucket,
+    #[serde(default)]
+    pub hours: Option<i64>,
+    #[serde(default)]
+    pub export_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportProgress {
+    pub export_id: String,
+    pub rows_written: u64,
+    pub total_rows: u64,
+    pub finished: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportStatus {
+    Completed,
+    Cancelled,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportResult {
+    pub export_id: String,
+    pub status: ExportStatus,
+    pub path: String,
+    pub rows_written: u64,
+}
+
+// ===========================================================================
+// Writers
+// ===========================================================================
+
+#[derive(Debug, Clone, Copy)]
+enum ColumnKind {
+    Text,
+    /// Text holding a JSON document; embedded as-is in NDJSON.
+    Json,
+    Integer,
+    Float,
+}
+
+#[derive(Debug)]
+enum ExportValue {
+    Text(Option<String>),
+    Integer(Option<i64>),
+    Float(Option<f64>),
+}
+
+impl ExportValue {
+    fn to_csv_field(&self) -> String {
+        match self {
+            ExportValue::Text(value) => value.clone().unwrap_or_default(),
+            ExportValue::Integer(value) => value.map(|v| v.to_string()).unwrap_or_default(),
+            ExportValue::Float(value) => value.map(|v| v.to_string()).unwrap_or_default(),
+        }
+    }
+
+    fn to_json(&self, kind: ColumnKind) -> serde_json::Value {
+        match (self, kind) {
+            (ExportValue::Text(Some(text)), ColumnKind::Json) => {
+                serde_json::from_str(text).unwrap_or_else(|_| serde_json::Value::String(text.clone()))
+            }
+            (ExportValue::Text(value), _) => value.clone().map(serde_json::Value::String).unwrap_or_default(),
+            (ExportValue::Integer(value), _) => value.map(serde_json::Value::from).unwrap_or_default(),
+            (ExportValue::Float(value), _) => value.map(serde_json::Value::from).unwrap_or_default(),
+        }
+    }
+}
+
+/// Buffered values for one Parquet column, with definition levels for nulls.
+enum ParquetColumn {
+    Text(Vec<ByteArray>, Vec<i16>),
+    Integer(Vec<i64>, Vec<i16>),
+    Float(Vec<f64>, Vec<i16>),
+}
+
+impl ParquetColumn {
+    fn new(kind: ColumnKind) -> Self {
+        match kind {
+            ColumnKind::Text | ColumnKind::Json => ParquetColumn::Text(Vec::new(), Vec::new()),
+            ColumnKind::Integer => ParquetColumn::Integer(Vec::new(), Vec::new()),
+            ColumnKind::Float => ParquetColumn::Float(Vec::new(), Vec::new()),
+        }
+    }
+
+    fn push(&mut self, value: ExportValue) {
+        fn push_level<T>(values: &mut Vec<T>, levels: &mut Vec<i16>, value: Option<T>) {
+            match value {
+                Some(value) => {
+                    values.push(value);
+                    levels.push(1);
+                }
+                None => levels.push(0),
+            }
+        }
+
+        match (self, value) {
+            (ParquetColumn::Text(values, levels), ExportValue::Text(value)) => {
+                push_level(values, levels, value.map(|text| ByteArray::from(text.into_bytes())))
+            }
+            (ParquetColumn::Integer(values, levels), ExportValue::Integer(value)) => push_level(values, levels, value),
+            (ParquetColumn::Float(values, levels), ExportValue::Float(value)) => push_level(values, levels, value),
+            (_, _) => unreachable!("export value does not match its column kind"),
+        }
+    }
+
+    fn clear(&mut self) {
+        match self {
+            ParquetColumn::Text(values, levels) => {
+                values.clear();
+                levels.clear();
+            }
+            ParquetColumn::Integer(values, levels) => {
+                values.clear();
+                levels.clear();
+            }
+            ParquetColumn::Float(values, levels) => {
+                values.clear();
+                levels.clear();
+            }
+        }
+    }
+}
+
+struct ParquetSink {
+    writer: SerializedFileWriter<BufWriter<File>>,
+    columns: Vec<ParquetColumn>,
+    buffered: usize,
+}
+
+impl ParquetSink {
+    fn new(file: BufWriter<File>, columns: &[(&str, ColumnKind)]) -> CommandResult<Self> {
+        let fields: Vec<String> = columns
+            .iter()
+            .map(|(name, kind)| match kind {
+                ColumnKind::Text => format!("OPTIONAL BYTE_ARRAY {} (UTF8);", name),
+                ColumnKind::Json => format!("OPTIONAL BYTE_ARRAY {} (JSON);", name),
+                ColumnKind::Integer => format!("OPTIONAL INT64 {};", name),
+                ColumnKind::Float => format!("OPTIONAL DOUBLE {};", name),
+            })
+            .collect();
+        let schema = parse_message_type(&format!("message export {{ {} }}", fields.join(" ")))
+            .map_err(|e| ForgeCommandError::Internal(format!("Invalid Parquet schema: {}", e)))?;
+
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+
+        let writer = SerializedFileWriter::new(file, Arc::new(schema), Arc::new(properties))
+            .map_err(|e| ForgeCommandError::Io(format!("Failed to start Parquet file: {}", e)))?;
+
+        Ok(ParquetSink {
+            writer,
+            columns: columns.iter().map(|(_, kind)| ParquetColumn::new(*kind)).collect(),
+            buffered: 0,
+        })
+    }
+
+    fn push(&mut self, row: Vec<ExportValue>) -> CommandResult<()> {
+        for (column, value) in self.columns.iter_mut().zip(row) {
+            column.push(value);
+        }
+        self.buffered += 1;
+
+        if self.buffered >= ROW_GROUP_SIZE {
+            self.flush_row_group()?;
+        }
+        Ok(())
+    }
+
+    fn flush_row_group(&mut self) -> CommandResult<()> {
+        if self.buffered == 0 {
+            return Ok(());
+        }
+
+        let mut row_group = self.writer.next_row_group()?;
+        for column in &self.columns {
+            let mut column_writer = row_group
+                .next_column()?
+                .ok_or_else(|| {
+                    ForgeCommandError::internal("Parquet schema has fewer columns than the export")
+                })?;
+
+            match column {
+                ParquetColumn::Text(values, levels) => {
+                    column_writer.typed::<ByteArrayType>().write_batch(values, Some(levels), None)
+                }
+                ParquetColumn::Integer(values, levels) => {
+                    column_writer.typed::<Int64Type>().write_batch(values, Some(levels), None)
+                }
+                ParquetColumn::Float(values, levels) => {
+                    column_writer.typed::<DoubleType>().write_batch(values, Some(levels), None)
+                }
+            }?;
+
+            column_writer.close()?;
+        }
+        row_group.close()?;
+
+        for column in &mut self.columns {
+            column.clear();
+        }
+        self.buffered = 0;
+        Ok(())
+    }
+}
+
+enum ExportWriter {
+    Csv(Box<csv::Writer<BufWriter<File>>>),
+    Ndjson(BufWriter<File>),
+    Parquet(Box<ParquetSink>),
+}
+
+/// Writes rows with a fixed column layout to a file in the requested format.
+struct RowSink {
+    columns: &'static [(&'static str, ColumnKind)],
+    writer: ExportWriter,
+}
+
+impl RowSink {
+    fn create(path: &Path, format: ExportFormat, columns: &'static [(&'static str, ColumnKind)]) -> CommandResult<Self> {
+        let file = BufWriter::new(File::create(path).map_err(|e| ForgeCommandError::Io(format!("Failed to create {}: {}", path.display(), e)))?);
+
+        let writer = match format {
+            ExportFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(file);
+                writer
+                    .write_record(columns.iter().map(|(name, _)| *name))?;
+                ExportWriter::Csv(Box::new(writer))
+            }
+            ExportFormat::Ndjson => ExportWriter::Ndjson(file),
+            ExportFormat::Parquet => ExportWriter::Parquet(Box::new(ParquetSink::new(file, columns)?)),
+        };
+
+        Ok(RowSink { columns, writer })
+    }
+
+    fn write(&mut self, row: Vec<ExportValue>) -> CommandResult<()> {
+        match &mut self.writer {
+            ExportWriter::Csv(writer) => writer
+                .write_record(row.iter().map(ExportValue::to_csv_field))
+                .map_err(ForgeCommandError::internal),
+            ExportWriter::Ndjson(writer) => {
+                let object: serde_json::Map<String, serde_json::Value> = self
+                    .columns
+                    .iter()
+                    .zip(&row)
+                    .map(|((name, kind), value)| (name.to_string(), value.to_json(*kind)))
+                    .collect();
+                serde_json::to_writer(&mut *writer, &object)?;
+                Ok(writer.write_all(b"\n")?)
+            }
+            ExportWriter::Parquet(sink) => sink.push(row),
+        }
+    }
+
+    fn finish(self) -> CommandResult<()> {
+        match self.writer {
+            ExportWriter::Csv(mut writer) => Ok(writer.flush()?),
+            ExportWriter::Ndjson(mut writer) => Ok(writer.flush()?),
+            ExportWriter::Parquet(mut sink) => {
+                sink.flush_row_group()?;
+                sink.writer.close().map_err(|e| ForgeCommandError::Io(format!("Failed to finish Parquet file: {}", e)))?;
+                Ok(())
+            }
+        }
+    }
+}
+
+// ===========================================================================
+// Export Runner
+// ===========================================================================
+
+/// Registers an export for cancellation and removes it again when dropped.
+struct ExportGuard {
+    export_id: String,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl ExportGuard {
+    fn register(export_id: Option<String>) -> CommandResult<Self> {
+        let export_id = export_id.unwrap_or_else(|| format!("export-{}", clock::now().timestamp_millis()));
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let mut active = ACTIVE_EXPORTS.lock().map_err(ForgeCommandError::internal)?;
+        if active.contains_key(&export_id) {
+            return Err(ForgeCommandError::invalid_input(format!(
+                "Export {} is already running",
+                export_id
+            )));
+        }
+        active.insert(export_id.clone(), cancelled.clone());
+
+        Ok(ExportGuard { export_id, cancelled })
+    }
+}
+
+impl Drop for ExportGuard {
+    fn drop(&mut self) {
+        if let Ok(mut active) = ACTIVE_EXPORTS.lock() {
+            active.remove(&self.export_id);
+        }
+    }
+}
+
+/// Where and how an export is written.
+struct ExportTarget<'a> {
+    path: &'a str,
+    format: ExportFormat,
+    columns: &'static [(&'static str, ColumnKind)],
+}
+
+fn partial_path(path: &Path) -> PathBuf {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".partial");
+    PathBuf::from(partial)
+}
+
+/// Work handed to the blocking file writer.
+enum SinkMessage {
+    Rows(Vec<Vec<ExportValue>>),
+    /// Every row has been sent; finish the file and move it into place.
+    Finish,
+}
+
+/// Writes queued rows to a `.partial` file beside `path` on a blocking
+/// thread, renaming it into place on `Finish`. If the queue closes first the
+/// export was cancelled or failed, and the partial file is removed.
+fn write_export(
+    path: &Path,
+    format: ExportFormat,
+    columns: &'static [(&'static str, ColumnKind)],
+    received: &mut tokio::sync::mpsc::Receiver<SinkMessage>,
+) -> CommandResult<()> {
+    let partial = partial_path(path);
+    let written = (|| {
+        let mut sink = RowSink::create(&partial, format, columns)?;
+        while let Some(message) = received.blocking_recv() {
+            match message {
+                SinkMessage::Rows(rows) => {
+                    for row in rows {
+                        sink.write(row)?;
+                    }
+                }
+                SinkMessage::Finish => {
+                    sink.finish()?;
+                    return std::fs::rename(&partial, path).map(|_| true).map_err(|e| {
+                        ForgeCommandError::Io(format!("Failed to move export into place: {}", e))
+                    });
+                }
+            }
+        }
+        Ok(false)
+    })();
+
+    if !matches!(written, Ok(true)) {
+        let _ = std::fs::remove_file(&partial);
+    }
+    written.map(|_| ())
+}
+
+/// Reads one column of a streamed row, failing the export instead of
+/// panicking when SQLite holds a value of an unexpected type.
+fn column<'r, T>(row: &'r SqliteRow, name: &str) -> CommandResult<T>
+where
+    T: Decode<'r, Sqlite> + Type<Sqlite>,
+{
+    row.try_get(name).map_err(|e| {
+        ForgeCommandError::SchemaMismatch(format!("Unexpected value in column {}: {}", name, e))
+    })
+}
+
+/// Streams `rows` into `path`. Output goes to a `.partial` file that is only
+/// renamed into place once complete, so a cancelled or failed export never
+/// leaves a truncated file behind under the requested name.
+async fn run_export<'a, S, F>(
+    progress: &impl Fn(ExportProgress),
+    guard: &ExportGuard,
+    target: ExportTarget<'_>,
+    total_rows: u64,
+    mut rows: S,
+    to_values: F,
+) -> CommandResult<ExportResult>
+where
+    S: futures_util::Stream<Item = Result<SqliteRow, sqlx::Error>> + Unpin + 'a,
+    F: Fn(&SqliteRow) -> CommandResult<Vec<ExportValue>>,
+{
+    let path = PathBuf::from(target.path);
+    let (format, columns) = (target.format, target.columns);
+    let (batches, mut received) = tokio::sync::mpsc::channel::<SinkMessage>(WRITE_QUEUE);
+    let writer = tokio::task::spawn_blocking(move || write_export(&path, format, columns, &mut received));
+    let mut rows_written = 0u64;
+
+    let progress = |rows_written: u64, finished: bool| {
+        progress(ExportProgress {
+            export_id: guard.export_id.clone(),
+            rows_written,
+            total_rows,
+            finished,
+        });
+    };
+
+    let outcome = async {
+        progress(0, false);
+        let mut batch = Vec::new();
+
+        while let Some(row) = rows.try_next().await? {
+            if guard.cancelled.load(Ordering::Relaxed) {
+                return Ok(ExportStatus::Cancelled);
+            }
+
+            batch.push(to_values(&row)?);
+            rows_written += 1;
+
+            if rows_written % PROGRESS_INTERVAL == 0 {
+                // A closed queue means the writer failed; its error is
+                // reported once the writer is joined below
+                if batches.send(SinkMessage::Rows(std::mem::take(&mut batch))).await.is_err() {
+                    return Ok(ExportStatus::Completed);
+                }
+                progress(rows_written, false);
+            }
+        }
+
+        if batches.send(SinkMessage::Rows(batch)).await.is_ok() {
+            let _ = batches.send(SinkMessage::Finish).await;
+        }
+        Ok::<_, ForgeCommandError>(ExportStatus::Completed)
+    }
+    .await;
+
+    // Closing the queue without `Finish` makes the writer discard the partial file
+    drop(batches);
+    let written = writer.await.map_err(ForgeCommandError::internal)?;
+
+    let status = match outcome {
+        Ok(ExportStatus::Completed) => {
+            written?;
+            ExportStatus::Completed
+        }
+        Ok(ExportStatus::Cancelled) => ExportStatus::Cancelled,
+        Err(e) => return Err(e),
+    };
+
+    progress(rows_written, true);
+
+    Ok(ExportResult {
+        export_id: guard.export_id.clone(),
+        status,
+        path: target.path.to_string(),
+        rows_written,
+    })
+}
+
+const EVENT_COLUMNS: &[(&str, ColumnKind)] = &[
+    ("event_id", ColumnKind::Text),
+    ("timestamp", ColumnKind::Text),
+    ("service", ColumnKind::Text),
+    ("event_type", ColumnKind::Text),
+    ("severity", ColumnKind::Text),
+    ("metrics", ColumnKind::Json),
+    ("metadata", ColumnKind::Json),
+];
+
+const SERIES_COLUMNS: &[(&str, ColumnKind)] = &[
+    ("timestamp", ColumnKind::Text),
+    ("count", ColumnKind::Integer),
+    ("value", ColumnKind::Float),
+];
+
+const EVENTS_FILTER: &str = "FROM events
+     WHERE (? IS NULL OR service = ?)
+       AND (? IS NULL OR event_type = ?)
+       AND (? IS NULL OR severity = ?)
+       AND timestamp > ? AND timestamp <= ?";
+
+// ===========================================================================
+// IPC Commands
+// ===========================================================================
+
+fn emit_progress(app: AppHandle) -> impl Fn(ExportProgress) {
+    move |progress| {
+        let _ = app.emit(PROGRESS_EVENT, progress);
+    }
+}
+
+#[tauri::command]
+pub async fn export_events(app: AppHandle, request: ExportEventsRequest) -> CommandResult<ExportResult> {
+    error::command("export_events", write_events(request, emit_progress(app))).await
+}
+
+/// Streams the events matching `request` to its path, passing each progress
+/// update to `progress`.
+pub async fn write_events(
+    request: ExportEventsRequest,
+    progress: impl Fn(ExportProgress),
+) -> CommandResult<ExportResult> {
+    let pool = get_db_pool().await?;
+    let window = TimeWindow::last_hours_or_all(request.hours);
+    let guard = ExportGuard::register(request.export_id.clone())?;
+
+    let total_rows: i64 = sqlx::query(&format!("SELECT COUNT(*) AS total {}", EVENTS_FILTER))
+        .bind(&request.service)
+        .bind(&request.service)
+        .bind(&request.event_type)
+        .bind(&request.event_type)
+        .bind(&request.severity)
+        .bind(&request.severity)
+        .bind(window.start_bound())
+        .bind(window.end_bound())
+        .fetch_one(&pool)
+        .await?
+        .get("total");
+
+    let query = format!(
+        "SELECT event_id, timestamp, service, event_type, severity, metrics, metadata {} ORDER BY timestamp",
+        EVENTS_FILTER
+    );
+    let rows = sqlx::query(&query)
+        .bind(&request.service)
+        .bind(&request.service)
+        .bind(&request.event_type)
+        .bind(&request.event_type)
+        .bind(&request.severity)
+        .bind(&request.severity)
+        .bind(window.start_bound())
+        .bind(window.end_bound())
+        .fetch(&pool);
+
+    let target = ExportTarget {
+        path: &request.path,
+        format: request.format,
+        columns: EVENT_COLUMNS,
+    };
+
+    run_export(&progress, &guard, target, total_rows as u64, rows, |row| {
+        EVENT_COLUMNS
+            .iter()
+            .map(|(name, _)| column(row, name).map(ExportValue::Text))
+            .collect()
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn export_series(app: AppHandle, request: ExportSeriesRequest) -> CommandResult<ExportResult> {
+    error::command("export_series", write_series(request, emit_progress(app))).await
+}
+
+/// Streams the bucketed series described by `request` to its path, passing
+/// each progress update to `progress`.
+pub async fn write_series(
+    request: ExportSeriesRequest,
+    progress: impl Fn(ExportProgress),
+) -> CommandResult<ExportResult> {
+    let pool = get_db_pool().await?;
+    let window = TimeWindow::last_hours_or_all(request.hours);
+
+    let value_sql = match (request.aggregate, &request.value) {
+        (SeriesAggregate::Count, _) => "CAST(COUNT(*) AS FLOAT)".to_string(),
+        (aggregate, Some(field)) => {
+            let function = if aggregate == SeriesAggregate::Sum { "SUM" } else { "AVG" };
+            format!(
+                "CAST({}(json_extract({}, '{}')) AS FLOAT)",
+                function,
+                field.column.as_sql(),
+                field.json_path()?
+            )
+        }
+        (_, None) => {
+            return Err(ForgeCommandError::invalid_input(
+                "A value field is required to sum or average a series",
+            ))
+        }
+    };
+
+    let guard = ExportGuard::register(request.export_id.clone())?;
+
+    let grouped = format!(
+        "SELECT strftime('{}', timestamp) AS bucket, COUNT(*) AS count, {} AS value
+         FROM events
+         WHERE service = ?
+           AND (? IS NULL OR event_type = ?)
+           AND timestamp > ? AND timestamp <= ?
+         GROUP BY bucket",
+        request.bucket.strftime_format(),
+        value_sql
+    );
+
+    let total_rows: i64 = sqlx::query(&format!("SELECT COUNT(*) AS total FROM ({})", grouped))
+        .bind(&request.service)
+        .bind(&request.event_type)
+        .bind(&request.event_type)
+        .bind(window.start_bound())
+        .bind(window.end_bound())
+        .fetch_one(&pool)
+        .await?
+        .get("total");
+
+    let query = format!("{} ORDER BY bucket", grouped);
+    let rows = sqlx::query(&query)
+        .bind(&request.service)
+        .bind(&request.event_type)
+        .bind(&request.event_type)
+        .bind(window.start_bound())
+        .bind(window.end_bound())
+        .fetch(&pool);
+
+    let target = ExportTarget {
+        path: &request.path,
+        format: request.format,
+        columns: SERIES_COLUMNS,
+    };
+
+    run_export(&progress, &guard, target, total_rows as u64, rows, |row| {
+        Ok(vec![
+            ExportValue::Text(column(row, "bucket")?),
+            ExportValue::Integer(column(row, "count")?),
+            ExportValue::Float(column(row, "value")?),
+        ])
+    })
+    .await
+}
+
+/// Requests cancellation of a running export. Returns false if no export with
+/// that id is running.
+#[tauri::command]
+pub async fn cancel_export(export_id: String) -> CommandResult<bool> {
+    error::command("cancel_export", async move { request_cancel(&export_id) }).await
+}
+
+/// Flags a running export for cancellation; it stops before its next row.
+pub fn request_cancel(export_id: &str) -> CommandResult<bool> {
+    let active = ACTIVE_EXPORTS.lock().map_err(ForgeCommandError::internal)?;
+
+    Ok(match active.get(export_id) {
+        Some(cancelled) => {
+            cancelled.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    })
+}
@@ -0,0 +1,210 @@
+// ===========================================================================
+// ForgeAgents Tasks
+// ===========================================================================
+//
+// An agent reports a task as `agent_task_started` followed by
+// `agent_task_completed` or `agent_task_failed` with the same
+// `metadata.task_id`. A completion with `metadata.status` "failed" or "error"
+// is a failure too. A task counts in the window its outcome lands in; tasks
+// started by the end of the window (or in the lookback before it) without an
+// outcome are in flight, or orphaned once they have gone
+// `TASK_TIMEOUT_MINUTES` without one.
+
+use std::collections::BTreeMap;
+
+use chrono::{Duration, NaiveDateTime};
+use sqlx::{sqlite::SqlitePool, Row};
+
+use crate::advisor::TimedQuery;
+use crate::error::{CommandResult, ResultExt};
+use crate::period::{self, TimeWindow};
+use crate::{ingest, safe_ratio};
+
+/// Starts are looked up this far before the window, so tasks that began
+/// before it still pair with their outcome.
+const TASK_LOOKBACK_HOURS: i64 = 24;
+
+/// A started task with no outcome after this long is orphaned.
+const TASK_TIMEOUT_MINUTES: i64 = 60;
+
+/// One task's lifecycle. Outcome fields are `None` while it is unfinished.
+#[derive(Debug, Clone)]
+struct Task {
+    agent_id: String,
+    agent_name: String,
+    started_at: Option<NaiveDateTime>,
+    finished_at: Option<NaiveDateTime>,
+    failed: bool,
+    /// Reported `metrics.duration_ms`, else the gap from start to outcome.
+    duration_ms: Option<f64>,
+}
+
+/// Tasks with a lifecycle event in `window` or the lookback before it.
+/// Starts without a task id can't be paired and are skipped; outcomes
+/// without one still count, each as its own task.
+async fn fetch_tasks(pool: &SqlitePool, window: &TimeWindow) -> CommandResult<Vec<Task>> {
+    let lookback = if window.is_bounded() {
+        period::column_bound(window.start - Duration::hours(TASK_LOOKBACK_HOURS))
+    } else {
+        window.start_bound()
+    };
+
+    let rows = sqlx::query(
+        "SELECT
+            COALESCE(json_extract(metadata, '$.task_id'), event_id) AS task_key,
+            MAX(json_extract(metadata, '$.agent_id')) AS agent_id,
+            MAX(json_extract(metadata, '$.agent_name')) AS agent_name,
+            MIN(CASE WHEN event_type = 'agent_task_started' THEN timestamp END) AS started_at,
+            MIN(CASE WHEN event_type != 'agent_task_started' THEN timestamp END) AS finished_at,
+            MAX(CASE WHEN event_type = 'agent_task_failed'
+                       OR (event_type = 'agent_task_completed'
+                           AND json_extract(metadata, '$.status') IN ('failed', 'error'))
+                THEN 1 ELSE 0 END) AS failed,
+            MAX(CASE WHEN event_type != 'agent_task_started'
+                THEN CAST(json_extract(metrics, '$.duration_ms') AS FLOAT) END) AS reported_ms
+         FROM events
+         WHERE service = 'forgeagents'
+         AND event_type IN ('agent_task_started', 'agent_task_completed', 'agent_task_failed')
+         AND (event_type != 'agent_task_started' OR json_extract(metadata, '$.task_id') IS NOT NULL)
+         AND timestamp > ?
+         AND timestamp <= ?
+         GROUP BY task_key",
+    )
+    .bind(lookback)
+    .bind(window.end_bound())
+    .fetch_all_timed(pool)
+    .await
+    .in_query("forgeagents_tasks")?;
+
+    let parse = |raw: Option<String>| {
+        raw.and_then(|raw| ingest::parse_timestamp(&raw).ok())
+            .map(|at| at.naive_utc())
+    };
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let started_at = parse(row.get("started_at"));
+            let finished_at = parse(row.get("finished_at"));
+            let paired_ms = started_at
+                .zip(finished_at)
+                .map(|(start, end)| (end - start).num_milliseconds().max(0) as f64);
+
+            Task {
+                agent_id: row
+                    .get::<Option<String>, _>("agent_id")
+                    .unwrap_or_else(|| "unknown".to_string()),
+                agent_name: row
+                    .get::<Option<String>, _>("agent_name")
+                    .unwrap_or_else(|| "Unknown Agent".to_string()),
+                started_at,
+                finished_at,
+                failed: row.get::<i64, _>("failed") == 1,
+                duration_ms: row.get::<Option<f64>, _>("reported_ms").or(paired_ms),
+            }
+        })
+        .collect())
+}
+
+/// Task totals for one agent over a window.
+#[derive(Debug, Clone)]
+pub struct AgentStats {
+    pub agent_id: String,
+    pub agent_name: String,
+    pub completed: i64,
+    pub failed: i64,
+    pub in_flight: i64,
+    pub orphaned: i64,
+    /// Sum and count of the finished tasks with a known duration, so
+    /// averages can be combined across agents.
+    pub total_duration_ms: f64,
+    pub timed_tasks: i64,
+    /// From the agent's latest task event: `active` (a task in flight),
+    /// `stalled` (an orphaned task), `idle` or `failed` (the last outcome).
+    pub status: String,
+    pub last_seen: NaiveDateTime,
+}
+
+impl AgentStats {
+    pub fn finished(&self) -> i64 {
+        self.completed + self.failed
+    }
+
+    pub fn success_rate(&self) -> f64 {
+        safe_ratio(self.completed as f64 * 100.0, self.finished() as f64)
+    }
+
+    pub fn avg_duration_ms(&self) -> f64 {
+        safe_ratio(self.total_duration_ms, self.timed_tasks as f64)
+    }
+}
+
+/// Per-agent task totals for `window`, most finished tasks first.
+pub async fn fetch_agent_stats(
+    pool: &SqlitePool,
+    window: &TimeWindow,
+) -> CommandResult<Vec<AgentStats>> {
+    let in_window = |at: NaiveDateTime| at > window.start && at <= window.end;
+    let timeout = Duration::minutes(TASK_TIMEOUT_MINUTES);
+
+    let mut agents: BTreeMap<String, AgentStats> = BTreeMap::new();
+    for task in fetch_tasks(pool, window).await? {
+        // The task's latest event in the window, and the status it implies
+        let latest = match (task.started_at, task.finished_at) {
+            (_, Some(finished_at)) if in_window(finished_at) => {
+                (finished_at, if task.failed { "failed" } else { "idle" })
+            }
+            // Still running at the end of the window, wherever it started
+            (Some(started_at), None) if started_at <= window.end => {
+                if window.end - started_at >= timeout {
+                    (started_at, "stalled")
+                } else {
+                    (started_at, "active")
+                }
+            }
+            _ => continue,
+        };
+
+        let agent = agents
+            .entry(task.agent_id.clone())
+            .or_insert_with(|| AgentStats {
+                agent_id: task.agent_id.clone(),
+                agent_name: task.agent_name.clone(),
+                completed: 0,
+                failed: 0,
+                in_flight: 0,
+                orphaned: 0,
+                total_duration_ms: 0.0,
+                timed_tasks: 0,
+                status: latest.1.to_string(),
+                last_seen: latest.0,
+            });
+
+        match latest.1 {
+            "idle" => agent.completed += 1,
+            "failed" => agent.failed += 1,
+            "active" => agent.in_flight += 1,
+            _ => agent.orphaned += 1,
+        }
+        if task.finished_at.is_some() {
+            if let Some(duration_ms) = task.duration_ms {
+                agent.total_duration_ms += duration_ms;
+                agent.timed_tasks += 1;
+            }
+        }
+        // Ties go to the outcome, which closes whatever started alongside it
+        if latest.0 > agent.last_seen || (latest.0 == agent.last_seen && task.finished_at.is_some())
+        {
+            agent.last_seen = latest.0;
+            agent.status = latest.1.to_string();
+        }
+    }
+
+    let mut agents: Vec<AgentStats> = agents.into_values().collect();
+    agents.sort_by(|a, b| {
+        b.finished()
+            .cmp(&a.finished())
+            .then_with(|| a.agent_id.cmp(&b.agent_id))
+    });
+    Ok(agents)
+}
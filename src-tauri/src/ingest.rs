@@ -0,0 +1,190 @@
+// ===========================================================================
+// Event Ingestion
+// ===========================================================================
+//
+// `POST /api/events` for services running without DataForge. Takes one event
+// or an array of them in the `events` schema, validates the batch and writes
+// it to the local event store; a batch is written whole or not at all.
+// Select the store as the data source (`--source local`, or `auto` with no
+// DataForge database) to see the events on every dashboard and command.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::clock;
+use crate::error::{self, CommandResult, ForgeCommandError};
+use crate::event_store::{self, StoredEvent};
+use crate::period::SQL_DATETIME_FORMAT;
+use crate::schema;
+
+/// Largest batch accepted in one request.
+const MAX_BATCH_SIZE: usize = 1000;
+
+const SEVERITIES: [&str; 4] = ["debug", "info", "warning", "error"];
+
+/// Validation errors listed in the response before the rest are summarised.
+const MAX_REPORTED_ERRORS: usize = 10;
+
+/// One event as posted. Everything but `service` and `event_type` is optional.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IncomingEvent {
+    event_id: Option<String>,
+    /// RFC 3339, or `YYYY-MM-DD HH:MM:SS` in UTC. Defaults to now.
+    timestamp: Option<String>,
+    service: String,
+    event_type: String,
+    /// Defaults to `info`.
+    severity: Option<String>,
+    metrics: Option<Map<String, Value>>,
+    metadata: Option<Map<String, Value>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IngestResult {
+    pub received: usize,
+    pub inserted: u64,
+    /// Events whose `event_id` was already stored; they are left unchanged.
+    pub duplicates: u64,
+    /// Ids in request order, including the ones assigned here.
+    pub event_ids: Vec<String>,
+}
+
+pub(crate) fn parse_timestamp(raw: &str) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(raw)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(raw, SQL_DATETIME_FORMAT)
+                .or_else(|_| NaiveDateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M:%S"))
+                .map(|timestamp| timestamp.and_utc())
+        })
+        .map_err(|_| format!("timestamp {:?} is not RFC 3339 or YYYY-MM-DD HH:MM:SS", raw))
+}
+
+fn validate(raw: Value) -> Result<StoredEvent, Vec<String>> {
+    let event: IncomingEvent = serde_json::from_value(raw).map_err(|e| vec![e.to_string()])?;
+    let mut errors = Vec::new();
+
+    for (name, value) in [
+        ("service", &event.service),
+        ("event_type", &event.event_type),
+    ] {
+        if value.trim().is_empty() {
+            errors.push(format!("{} must not be empty", name));
+        }
+    }
+    if event
+        .event_id
+        .as_deref()
+        .is_some_and(|id| id.trim().is_empty())
+    {
+        errors.push("event_id must not be empty when given".to_string());
+    }
+
+    let severity = event.severity.unwrap_or_else(|| "info".to_string());
+    if !SEVERITIES.contains(&severity.as_str()) {
+        errors.push(format!(
+            "severity {:?} must be one of {}",
+            severity,
+            SEVERITIES.join(", ")
+        ));
+    }
+
+    let timestamp = match event.timestamp.as_deref().map(parse_timestamp) {
+        Some(Ok(timestamp)) => timestamp,
+        Some(Err(e)) => {
+            errors.push(e);
+            clock::now()
+        }
+        None => clock::now(),
+    };
+
+    let metrics = event.metrics.unwrap_or_default();
+    let metadata = event.metadata.unwrap_or_default();
+    errors.extend(schema::mistyped_fields(
+        &event.service,
+        &event.event_type,
+        &metrics,
+        &metadata,
+    ));
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut stored = StoredEvent::new(&event.service, &event.event_type, &severity, timestamp);
+    if let Some(event_id) = event.event_id {
+        stored.event_id = event_id;
+    }
+    stored.metrics = metrics;
+    stored.metadata = metadata;
+    Ok(stored)
+}
+
+/// Validates and stores a posted body: a single event object or an array.
+pub async fn ingest_events(body: Value) -> CommandResult<IngestResult> {
+    error::command("ingest_events", async move {
+        let batch = match body {
+            Value::Array(events) => events,
+            event @ Value::Object(_) => vec![event],
+            _ => {
+                return Err(ForgeCommandError::invalid_input(
+                    "Body must be an event object or an array of events",
+                ))
+            }
+        };
+        if batch.is_empty() {
+            return Err(ForgeCommandError::invalid_input("No events in request"));
+        }
+        if batch.len() > MAX_BATCH_SIZE {
+            return Err(ForgeCommandError::invalid_input(format!(
+                "Batch of {} events exceeds the limit of {}",
+                batch.len(),
+                MAX_BATCH_SIZE
+            )));
+        }
+
+        let received = batch.len();
+        let mut events = Vec::with_capacity(received);
+        let mut errors = Vec::new();
+        for (index, raw) in batch.into_iter().enumerate() {
+            match validate(raw) {
+                Ok(event) => events.push(event),
+                Err(messages) => errors.extend(
+                    messages
+                        .into_iter()
+                        .map(|message| format!("event {}: {}", index, message)),
+                ),
+            }
+        }
+
+        if !errors.is_empty() {
+            let mut message = errors
+                .iter()
+                .take(MAX_REPORTED_ERRORS)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join("; ");
+            if errors.len() > MAX_REPORTED_ERRORS {
+                message.push_str(&format!(
+                    "; and {} more",
+                    errors.len() - MAX_REPORTED_ERRORS
+                ));
+            }
+            return Err(ForgeCommandError::invalid_input(format!(
+                "Rejected batch, nothing stored: {}",
+                message
+            )));
+        }
+
+        let inserted = event_store::insert_events(&events).await?;
+        Ok(IngestResult {
+            received,
+            inserted,
+            duplicates: received as u64 - inserted,
+            event_ids: events.into_iter().map(|event| event.event_id).collect(),
+        })
+    })
+    .await
+}
@@ -0,0 +1,354 @@
+// ===========================================================================
+// Daily Insights
+// ===========================================================================
+//
+// Local replacement for the Analytics Agent: a morning summary of token burn,
+// projected spend, week-over-week change, cost drivers and efficiency trend.
+// Generated documents are kept in the Forge Command store so history survives
+// restarts.
+
+use chrono::{Duration, NaiveDateTime, NaiveTime, SecondsFormat};
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqlitePool, Row};
+
+use crate::advisor::TimedQuery;
+use crate::clock;
+use crate::error::{self, CommandResult, ForgeCommandError};
+use crate::period::{column_bound, ComparisonWindow, TimeWindow};
+use crate::store::get_store_pool;
+use crate::{get_db_pool, query_neuroforge_metrics, safe_ratio, NeuroForgeMetrics};
+
+/// Week-over-week change (percent) treated as flat/stable.
+const STABLE_BAND_PERCENT: f64 = 5.0;
+
+/// Whole days of spend cost projections are based on.
+const PROJECTION_DAYS: i64 = 14;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InsightValue {
+    pub value: f64,
+    pub unit: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direction: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InsightMetrics {
+    pub token_burn_rate: InsightValue,
+    pub cost_burn_rate: InsightValue,
+    pub cost_projection_30d: InsightValue,
+    pub cost_change_vs_last_week: InsightValue,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostDriver {
+    pub category: String,
+    pub name: String,
+    pub percentage: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Efficiency {
+    pub status: String,
+    pub change_vs_baseline: f64,
+    pub investigation_needed: bool,
+}
+
+/// Insights document, in the schema the Analytics Agent spec defines.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyInsights {
+    pub generated_at: String,
+    pub metrics: InsightMetrics,
+    pub cost_drivers: Vec<CostDriver>,
+    pub efficiency: Efficiency,
+    pub recommendations: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DailyInsightsResponse {
+    pub insights: DailyInsights,
+    pub generated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CostProjection {
+    pub days_ahead: i64,
+    pub projected_cost: f64,
+    pub confidence: f64,
+}
+
+fn direction(change_percent: f64) -> &'static str {
+    if change_percent > STABLE_BAND_PERCENT {
+        "up"
+    } else if change_percent < -STABLE_BAND_PERCENT {
+        "down"
+    } else {
+        "flat"
+    }
+}
+
+fn percent_change(current: f64, previous: f64) -> f64 {
+    safe_ratio((current - previous) * 100.0, previous)
+}
+
+fn cost_per_1k_tokens(metrics: &NeuroForgeMetrics) -> f64 {
+    safe_ratio(metrics.total_cost * 1000.0, metrics.total_tokens as f64)
+}
+
+async fn operation_drivers(
+    pool: &SqlitePool,
+    window: &TimeWindow,
+    total_requests: i64,
+) -> CommandResult<Vec<CostDriver>> {
+    let drivers = sqlx::query(
+        "SELECT
+            json_extract(metadata, '$.operation') as operation,
+            COUNT(*) as requests
+         FROM events
+         WHERE service = 'neuroforge'
+         AND event_type = 'model_request'
+         AND json_extract(metadata, '$.operation') IS NOT NULL
+         AND timestamp > ?
+         AND timestamp <= ?
+         GROUP BY operation
+         ORDER BY requests DESC
+         LIMIT 3",
+    )
+    .bind(window.start_bound())
+    .bind(window.end_bound())
+    .fetch_all_timed(pool)
+    .await?
+    .into_iter()
+    .map(|row| CostDriver {
+        category: "operation".to_string(),
+        name: row.get::<String, _>("operation"),
+        percentage: safe_ratio(row.get::<i64, _>("requests") as f64 * 100.0, total_requests as f64),
+    })
+    .collect();
+
+    Ok(drivers)
+}
+
+/// Builds a fresh insights document from the events table.
+pub async fn generate_insights() -> CommandResult<DailyInsights> {
+    let pool = get_db_pool().await?;
+
+    let last_day = query_neuroforge_metrics(&pool, &TimeWindow::last_hours(24), None).await?;
+    let this_week_window = TimeWindow::last_hours(24 * 7);
+    let last_week_window = this_week_window.comparison(&ComparisonWindow::PreviousPeriod)?;
+    let this_week = query_neuroforge_metrics(&pool, &this_week_window, None).await?;
+    let last_week = query_neuroforge_metrics(&pool, &last_week_window, None).await?;
+
+    let cost_burn_rate = last_day.total_cost / 24.0;
+    let cost_change = percent_change(this_week.total_cost, last_week.total_cost);
+
+    // Models by share of spend, then operations by share of request volume
+    let mut cost_drivers: Vec<CostDriver> = this_week
+        .top_models
+        .iter()
+        .take(3)
+        .filter(|model| model.cost > 0.0)
+        .map(|model| CostDriver {
+            category: "model".to_string(),
+            name: model.model.clone(),
+            percentage: safe_ratio(model.cost * 100.0, this_week.total_cost),
+        })
+        .collect();
+    cost_drivers.extend(operation_drivers(&pool, &this_week_window, this_week.total_requests).await?);
+
+    // Efficiency is cost per 1k tokens; cheaper than last week is improving
+    let efficiency_change = -percent_change(cost_per_1k_tokens(&this_week), cost_per_1k_tokens(&last_week));
+    let efficiency_status = match direction(efficiency_change) {
+        "up" => "improving",
+        "down" => "declining",
+        _ => "stable",
+    };
+
+    let mut recommendations = Vec::new();
+    if cost_change > STABLE_BAND_PERCENT * 2.0 {
+        recommendations.push(format!(
+            "Spend is up {:.1}% week over week; review rate limits for the heaviest callers",
+            cost_change
+        ));
+    }
+    if let Some(top) = cost_drivers.iter().find(|d| d.category == "model" && d.percentage > 50.0) {
+        recommendations.push(format!(
+            "{} accounts for {:.0}% of spend; switch low-priority tasks to a cheaper model",
+            top.name, top.percentage
+        ));
+    }
+    if efficiency_status == "declining" {
+        recommendations.push(format!(
+            "Cost per 1k tokens rose {:.1}% vs last week; investigate prompt sizes and model mix",
+            -efficiency_change
+        ));
+    }
+    if recommendations.is_empty() {
+        recommendations.push("Spend and efficiency are within normal range; no action needed".to_string());
+    }
+
+    Ok(DailyInsights {
+        generated_at: clock::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        metrics: InsightMetrics {
+            token_burn_rate: InsightValue {
+                value: last_day.total_tokens as f64 / 24.0,
+                unit: "tokens/hour".to_string(),
+                direction: None,
+            },
+            cost_burn_rate: InsightValue {
+                value: cost_burn_rate,
+                unit: "USD/hour".to_string(),
+                direction: None,
+            },
+            cost_projection_30d: InsightValue {
+                value: cost_burn_rate * 24.0 * 30.0,
+                unit: "USD".to_string(),
+                direction: None,
+            },
+            cost_change_vs_last_week: InsightValue {
+                value: cost_change,
+                unit: "percentage".to_string(),
+                direction: Some(direction(cost_change).to_string()),
+            },
+        },
+        cost_drivers,
+        efficiency: Efficiency {
+            status: efficiency_status.to_string(),
+            change_vs_baseline: efficiency_change,
+            investigation_needed: efficiency_status == "declining",
+        },
+        recommendations,
+    })
+}
+
+/// Generates insights and appends them to the history.
+pub async fn generate_and_store_insights() -> CommandResult<DailyInsights> {
+    let insights = generate_insights().await?;
+    let store = get_store_pool().await?;
+
+    let document = serde_json::to_string(&insights)?;
+    sqlx::query("INSERT INTO daily_insights (generated_at, insights) VALUES (?, ?)")
+        .bind(&insights.generated_at)
+        .bind(document)
+        .execute(&store)
+        .await?;
+
+    Ok(insights)
+}
+
+async fn load_insights(limit: i64) -> CommandResult<Vec<DailyInsights>> {
+    let store = get_store_pool().await?;
+
+    sqlx::query("SELECT insights FROM daily_insights ORDER BY generated_at DESC LIMIT ?")
+        .bind(limit)
+        .fetch_all(&store)
+        .await?
+        .into_iter()
+        .map(|row| {
+            serde_json::from_str(&row.get::<String, _>("insights"))
+                .map_err(|e| ForgeCommandError::Internal(format!("Corrupt insights document: {}", e)))
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub async fn get_daily_insights() -> CommandResult<DailyInsightsResponse> {
+    error::command("get_daily_insights", async move {
+        let insights = match load_insights(1).await?.pop() {
+            Some(insights) => insights,
+            // Nothing generated yet (first launch); build one now rather than show nothing
+            None => generate_and_store_insights().await?,
+        };
+
+        Ok(DailyInsightsResponse {
+            generated_at: insights.generated_at.clone(),
+            insights,
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_insights_history(limit: i64) -> CommandResult<Vec<DailyInsights>> {
+    error::command("get_insights_history", async move {
+        load_insights(limit).await
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn generate_insights_now() -> CommandResult<DailyInsightsResponse> {
+    error::command("generate_insights_now", async move {
+        let insights = generate_and_store_insights().await?;
+
+        Ok(DailyInsightsResponse {
+            generated_at: insights.generated_at.clone(),
+            insights,
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_cost_projection(days_ahead: i64) -> CommandResult<CostProjection> {
+    error::command("get_cost_projection", async move {
+        let pool = get_db_pool().await?;
+        query_cost_projection(&pool, clock::now_naive(), days_ahead).await
+    })
+    .await
+}
+
+/// Projects spend `days_ahead` from NeuroForge cost over the 14 whole UTC
+/// days before the one containing `at`. A partial day at either end would
+/// read as a low-spend day and drag the mean down.
+pub async fn query_cost_projection(
+    pool: &SqlitePool,
+    at: NaiveDateTime,
+    days_ahead: i64,
+) -> CommandResult<CostProjection> {
+    let today = at.date().and_time(NaiveTime::MIN);
+    let start = today - Duration::days(PROJECTION_DAYS);
+
+    let daily_costs: Vec<f64> = sqlx::query(
+        "SELECT
+            date(timestamp) as day,
+            SUM(CAST(json_extract(metrics, '$.cost_usd') AS FLOAT)) as cost
+         FROM events
+         WHERE service = 'neuroforge'
+         AND event_type = 'model_request'
+         AND timestamp >= ?
+         AND timestamp < ?
+         GROUP BY day",
+    )
+    .bind(column_bound(start))
+    .bind(column_bound(today))
+    .fetch_all_timed(pool)
+    .await?
+    .into_iter()
+    .map(|row| row.get::<Option<f64>, _>("cost").unwrap_or(0.0))
+    .collect();
+
+    if daily_costs.is_empty() {
+        return Ok(CostProjection {
+            days_ahead,
+            projected_cost: 0.0,
+            confidence: 0.0,
+        });
+    }
+
+    // Days without any requests count as zero spend
+    let days = PROJECTION_DAYS as f64;
+    let mean = daily_costs.iter().sum::<f64>() / days;
+    let variance = (daily_costs.iter().map(|c| (c - mean).powi(2)).sum::<f64>()
+        + (days - daily_costs.len() as f64) * mean.powi(2))
+        / days;
+
+    // Confidence falls as day-to-day spend gets noisier relative to its mean
+    let coefficient_of_variation = safe_ratio(variance.sqrt(), mean);
+
+    Ok(CostProjection {
+        days_ahead,
+        projected_cost: mean * days_ahead as f64,
+        confidence: (1.0 - coefficient_of_variation).clamp(0.0, 1.0),
+    })
+}
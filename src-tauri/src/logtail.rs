@@ -0,0 +1,581 @@
+// ===========================================================================
+// Log Tail Collector
+// ===========================================================================
+//
+// Follows JSON-lines log files and writes each line as an event to the local
+// event store, using per-file mapping rules from `log-sources.json` (in the
+// data directory, or `FORGE_COMMAND_LOG_SOURCES`). Without that file the
+// collector stays off.
+//
+// Files are identified by a fingerprint of their first line rather than their
+// path, which catches rotation (rename + new file) and truncation on every
+// platform; on Unix the inode also tells apart a new file that starts with
+// the same line. Offsets are saved in the store after each batch; event ids
+// are derived from fingerprint, inode and byte offset, so lines re-read after
+// a crash are skipped instead of counted twice. A line longer than
+// `MAX_READ_BYTES` is skipped.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::env;
+use std::fs::{File, Metadata};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::Row;
+
+use crate::clock;
+use crate::error::{self, CommandResult, ForgeCommandError};
+use crate::event_store::{self, StoredEvent};
+use crate::ingest::parse_timestamp;
+use crate::store::{data_dir, get_store_pool};
+
+/// Most bytes read from one file per poll; a backlog drains over several polls.
+const MAX_READ_BYTES: u64 = 1024 * 1024;
+
+/// Bytes of the first line used for the fingerprint.
+const FINGERPRINT_BYTES: usize = 1024;
+
+/// Latest status per configured file, for `get_log_tail_status`.
+static STATUS: Mutex<Vec<LogFileStatus>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LogTailConfig {
+    #[serde(default = "default_poll_interval")]
+    pub poll_interval_secs: u64,
+    pub files: Vec<FileRule>,
+}
+
+/// How one file's lines map onto events. Field names may be dotted paths
+/// into nested objects (`usage.total_tokens`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileRule {
+    pub path: PathBuf,
+    /// Service for every line; otherwise read from `service_field`.
+    pub service: Option<String>,
+    #[serde(default = "default_service_field")]
+    pub service_field: String,
+    #[serde(default = "default_event_type_field")]
+    pub event_type_field: String,
+    /// Event type for lines without `event_type_field`.
+    #[serde(default = "default_event_type")]
+    pub event_type: String,
+    #[serde(default = "default_severity_field")]
+    pub severity_field: String,
+    /// Level values to severities, checked before the built-in names.
+    #[serde(default)]
+    pub severity_map: BTreeMap<String, String>,
+    #[serde(default = "default_timestamp_field")]
+    pub timestamp_field: String,
+    /// Field holding a stable id; by default ids come from the file position.
+    pub event_id_field: Option<String>,
+    /// Metrics key → line field.
+    #[serde(default)]
+    pub metrics: BTreeMap<String, String>,
+    /// Metadata key → line field.
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+    /// Keep fields no rule mentions: numbers as metrics, the rest as metadata.
+    #[serde(default = "default_true")]
+    pub keep_unmapped: bool,
+    /// Skip the existing contents of a file seen for the first time.
+    #[serde(default)]
+    pub start_at_end: bool,
+}
+
+fn default_poll_interval() -> u64 {
+    2
+}
+
+fn default_service_field() -> String {
+    "service".to_string()
+}
+
+fn default_event_type_field() -> String {
+    "event_type".to_string()
+}
+
+fn default_event_type() -> String {
+    "log".to_string()
+}
+
+fn default_severity_field() -> String {
+    "level".to_string()
+}
+
+fn default_timestamp_field() -> String {
+    "timestamp".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LogFileStatus {
+    pub path: String,
+    pub offset: u64,
+    /// Lines read since startup, including skipped ones.
+    pub lines: u64,
+    pub events: u64,
+    /// Lines that weren't JSON objects or had no service.
+    pub skipped: u64,
+    pub rotations: u64,
+    pub last_read_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+pub fn config_path() -> PathBuf {
+    env::var("FORGE_COMMAND_LOG_SOURCES")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| data_dir().join("log-sources.json"))
+}
+
+fn load_config() -> Result<Option<LogTailConfig>, String> {
+    let path = config_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let raw = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let config: LogTailConfig =
+        serde_json::from_str(&raw).map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+
+    for rule in &config.files {
+        for (level, severity) in &rule.severity_map {
+            if event_store::normalize_severity(severity) != severity {
+                return Err(format!(
+                    "{}: severity_map maps {:?} to {:?}; use error, warning, info or debug",
+                    rule.path.display(),
+                    level,
+                    severity
+                ));
+            }
+        }
+    }
+
+    Ok(Some(config))
+}
+
+// ===========================================================================
+// Line Mapping
+// ===========================================================================
+
+fn lookup<'a>(line: &'a Map<String, Value>, path: &str) -> Option<&'a Value> {
+    let mut parts = path.split('.');
+    let mut value = line.get(parts.next()?)?;
+    for part in parts {
+        value = value.as_object()?.get(part)?;
+    }
+    Some(value).filter(|value| !value.is_null())
+}
+
+fn line_timestamp(value: &Value) -> Option<DateTime<Utc>> {
+    match value {
+        Value::String(raw) => parse_timestamp(raw).ok(),
+        // Epoch seconds, or milliseconds for values too large to be seconds
+        Value::Number(number) => {
+            let epoch = number.as_f64()?;
+            let millis = if epoch > 1e11 { epoch } else { epoch * 1000.0 };
+            DateTime::from_timestamp_millis(millis as i64)
+        }
+        _ => None,
+    }
+}
+
+fn map_line(rule: &FileRule, line: &str, default_id: String) -> Result<StoredEvent, String> {
+    let Value::Object(fields) = serde_json::from_str::<Value>(line).map_err(|e| e.to_string())?
+    else {
+        return Err("line is not a JSON object".to_string());
+    };
+    let text = |path: &str| lookup(&fields, path).and_then(Value::as_str);
+
+    let service = match &rule.service {
+        Some(service) => service.clone(),
+        None => text(&rule.service_field)
+            .ok_or_else(|| format!("no {:?} field", rule.service_field))?
+            .to_lowercase(),
+    };
+    let event_type = text(&rule.event_type_field).unwrap_or(&rule.event_type);
+    let severity = match text(&rule.severity_field) {
+        Some(level) => rule
+            .severity_map
+            .get(level)
+            .map(String::as_str)
+            .unwrap_or_else(|| event_store::normalize_severity(level)),
+        None => "info",
+    };
+    let timestamp = lookup(&fields, &rule.timestamp_field)
+        .and_then(line_timestamp)
+        .unwrap_or_else(clock::now);
+
+    let mut event = StoredEvent::new(&service, event_type, severity, timestamp);
+    event.event_id = rule
+        .event_id_field
+        .as_deref()
+        .and_then(|field| lookup(&fields, field))
+        .map(|id| id.as_str().map_or_else(|| id.to_string(), str::to_string))
+        .unwrap_or(default_id);
+
+    for (key, path) in &rule.metrics {
+        if let Some(value) = lookup(&fields, path) {
+            event.metrics.insert(key.clone(), value.clone());
+        }
+    }
+    for (key, path) in &rule.metadata {
+        if let Some(value) = lookup(&fields, path) {
+            event.metadata.insert(key.clone(), value.clone());
+        }
+    }
+
+    if rule.keep_unmapped {
+        let mut used: BTreeSet<&str> = [
+            &rule.service_field,
+            &rule.event_type_field,
+            &rule.severity_field,
+            &rule.timestamp_field,
+        ]
+        .into_iter()
+        .chain(&rule.event_id_field)
+        .chain(rule.metrics.values())
+        .chain(rule.metadata.values())
+        .filter_map(|path| path.split('.').next())
+        .collect();
+        used.insert("");
+
+        for (key, value) in &fields {
+            if used.contains(key.as_str()) || value.is_null() {
+                continue;
+            }
+            let column = if value.is_number() {
+                &mut event.metrics
+            } else {
+                &mut event.metadata
+            };
+            column.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
+
+    Ok(event)
+}
+
+// ===========================================================================
+// Tailing
+// ===========================================================================
+
+/// FNV-1a; stable across builds, unlike the std hasher.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// The inode on Unix; other platforms rely on the fingerprint and size alone.
+#[cfg(unix)]
+fn file_id(metadata: &Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.ino())
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &Metadata) -> Option<u64> {
+    None
+}
+
+/// Fingerprint of the file's first complete line; `None` until it has one.
+fn fingerprint(file: &mut File) -> std::io::Result<Option<String>> {
+    file.seek(SeekFrom::Start(0))?;
+    let mut first = Vec::new();
+    BufReader::new(file.take(FINGERPRINT_BYTES as u64)).read_until(b'\n', &mut first)?;
+    Ok((first.ends_with(b"\n") || first.len() == FINGERPRINT_BYTES)
+        .then(|| format!("{:016x}", fnv1a(&first))))
+}
+
+pub(crate) struct Tail {
+    rule: FileRule,
+    file: Option<File>,
+    fingerprint: Option<String>,
+    file_id: Option<u64>,
+    offset: u64,
+    /// Inside a line longer than `MAX_READ_BYTES`; skip to its end.
+    discarding: bool,
+    pub(crate) status: LogFileStatus,
+}
+
+impl Tail {
+    pub(crate) fn new(rule: FileRule) -> Self {
+        let status = LogFileStatus {
+            path: rule.path.display().to_string(),
+            ..LogFileStatus::default()
+        };
+        Tail {
+            rule,
+            file: None,
+            fingerprint: None,
+            file_id: None,
+            offset: 0,
+            discarding: false,
+            status,
+        }
+    }
+
+    fn key(&self) -> String {
+        self.rule.path.display().to_string()
+    }
+
+    /// Opens the file, resuming from the saved offset when it's still the
+    /// same file.
+    async fn open(&mut self) -> CommandResult<bool> {
+        let Ok(mut file) = File::open(&self.rule.path) else {
+            return Ok(false);
+        };
+        let fingerprint = fingerprint(&mut file)?;
+        let metadata = file.metadata()?;
+        let file_id = file_id(&metadata);
+
+        let store = get_store_pool().await?;
+        let saved =
+            sqlx::query("SELECT fingerprint, file_id, offset FROM log_offsets WHERE path = ?")
+                .bind(self.key())
+                .fetch_optional(&store)
+                .await?
+                .map(|row| {
+                    (
+                        row.get::<String, _>("fingerprint"),
+                        row.get::<Option<i64>, _>("file_id").map(|id| id as u64),
+                        row.get::<i64, _>("offset") as u64,
+                    )
+                });
+
+        self.offset = match saved {
+            Some((saved_fingerprint, saved_id, offset))
+                if Some(&saved_fingerprint) == fingerprint.as_ref()
+                    && (saved_id.is_none() || saved_id == file_id)
+                    && offset <= metadata.len() =>
+            {
+                offset
+            }
+            // Rotated while we weren't running: start the new file from the top
+            Some(_) => 0,
+            None if self.rule.start_at_end => metadata.len(),
+            None => 0,
+        };
+        self.fingerprint = fingerprint;
+        self.file_id = file_id;
+        self.discarding = false;
+        self.file = Some(file);
+        Ok(true)
+    }
+
+    async fn save_offset(&self) -> CommandResult<()> {
+        let Some(fingerprint) = &self.fingerprint else {
+            return Ok(());
+        };
+        let store = get_store_pool().await?;
+        sqlx::query(
+            "INSERT INTO log_offsets (path, fingerprint, file_id, offset, updated_at)
+             VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT(path) DO UPDATE SET
+                fingerprint = excluded.fingerprint,
+                file_id = excluded.file_id,
+                offset = excluded.offset,
+                updated_at = excluded.updated_at",
+        )
+        .bind(self.key())
+        .bind(fingerprint)
+        .bind(self.file_id.map(|id| id as i64))
+        .bind(self.offset as i64)
+        .bind(clock::now().to_rfc3339_opts(SecondsFormat::Secs, true))
+        .execute(&store)
+        .await?;
+        Ok(())
+    }
+
+    /// Reads complete lines past the offset, up to `MAX_READ_BYTES`. Returns
+    /// whether it reached the end of the file. A line that doesn't fit in
+    /// `MAX_READ_BYTES` is counted as skipped and passed over rather than
+    /// waited on forever.
+    async fn read_lines(&mut self) -> CommandResult<bool> {
+        let Some(file) = self.file.as_mut() else {
+            return Ok(true);
+        };
+
+        // Truncated in place (copytruncate): start over
+        if file.metadata()?.len() < self.offset {
+            self.offset = 0;
+            self.fingerprint = None;
+            self.discarding = false;
+            self.status.rotations += 1;
+        }
+        if self.fingerprint.is_none() {
+            self.fingerprint = fingerprint(file)?;
+        }
+        let Some(fingerprint) = self.fingerprint.clone() else {
+            return Ok(true);
+        };
+        let id_prefix = match self.file_id {
+            Some(id) => format!("{}-{:x}", fingerprint, id),
+            None => fingerprint,
+        };
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut buffer = Vec::new();
+        file.take(MAX_READ_BYTES).read_to_end(&mut buffer)?;
+        let at_end = (buffer.len() as u64) < MAX_READ_BYTES;
+
+        // The rest of an oversized line from the last read
+        let mut skip = 0;
+        if self.discarding {
+            skip = match buffer.iter().position(|byte| *byte == b'\n') {
+                Some(i) => {
+                    self.discarding = false;
+                    i + 1
+                }
+                None => buffer.len(),
+            };
+        }
+
+        // Partial last line: leave it for the next poll
+        let mut complete = buffer
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map_or(0, |i| i + 1)
+            .max(skip);
+        if complete == 0 && !at_end {
+            // A full buffer without a line end will never complete
+            complete = buffer.len();
+            skip = complete;
+            self.discarding = true;
+            self.status.lines += 1;
+            self.status.skipped += 1;
+        }
+
+        let mut events = Vec::new();
+        let mut position = self.offset + skip as u64;
+        for raw in buffer[skip..complete].split_inclusive(|byte| *byte == b'\n') {
+            let line_offset = position;
+            position += raw.len() as u64;
+
+            let line = String::from_utf8_lossy(raw);
+            if line.trim().is_empty() {
+                continue;
+            }
+            self.status.lines += 1;
+            match map_line(
+                &self.rule,
+                line.trim(),
+                format!("{}-{}", id_prefix, line_offset),
+            ) {
+                Ok(event) => events.push(event),
+                Err(_) => self.status.skipped += 1,
+            }
+        }
+
+        if !events.is_empty() {
+            event_store::insert_events(&events).await?;
+            self.status.events += events.len() as u64;
+        }
+        if complete > 0 {
+            self.offset = position;
+            self.save_offset().await?;
+        }
+        Ok(at_end)
+    }
+
+    /// After draining the open file, switches to a new file at the path if
+    /// the old one was rotated away.
+    fn check_rotation(&mut self) -> CommandResult<bool> {
+        let Ok(mut current) = File::open(&self.rule.path) else {
+            // Renamed and not recreated yet; keep the old handle
+            return Ok(false);
+        };
+        let metadata = current.metadata()?;
+        let current_id = file_id(&metadata);
+        let current_fingerprint = fingerprint(&mut current)?;
+        let replaced = match (self.file_id, current_id) {
+            // Same first line or not, another inode is another file
+            (Some(ours), Some(theirs)) => ours != theirs,
+            _ => match (&self.fingerprint, &current_fingerprint) {
+                (Some(ours), Some(theirs)) => ours != theirs || metadata.len() < self.offset,
+                // New file without a full line yet, smaller than where we are
+                (Some(_), None) => metadata.len() < self.offset,
+                (None, _) => false,
+            },
+        };
+
+        if replaced {
+            self.file = Some(current);
+            self.fingerprint = current_fingerprint;
+            self.file_id = current_id;
+            self.offset = 0;
+            self.discarding = false;
+            self.status.rotations += 1;
+        }
+        Ok(replaced)
+    }
+
+    pub(crate) async fn poll(&mut self) -> CommandResult<()> {
+        if self.file.is_none() && !self.open().await? {
+            return Err(ForgeCommandError::not_found(format!(
+                "{} does not exist yet",
+                self.rule.path.display()
+            )));
+        }
+
+        if self.read_lines().await? && self.check_rotation()? {
+            self.read_lines().await?;
+        }
+
+        self.status.offset = self.offset;
+        self.status.last_read_at = Some(clock::now().to_rfc3339_opts(SecondsFormat::Secs, true));
+        Ok(())
+    }
+}
+
+/// Follows the configured files until the process exits.
+pub async fn run_collector() {
+    let config = match load_config() {
+        Ok(Some(config)) => config,
+        Ok(None) => return,
+        Err(e) => {
+            eprintln!("Log tail collector disabled: {}", e);
+            return;
+        }
+    };
+    eprintln!(
+        "Log tail collector following {} file(s)",
+        config.files.len()
+    );
+
+    let mut tails: Vec<Tail> = config.files.into_iter().map(Tail::new).collect();
+    let interval = Duration::from_secs(config.poll_interval_secs.max(1));
+
+    loop {
+        for tail in &mut tails {
+            tail.status.last_error = tail.poll().await.err().map(|e| e.to_string());
+        }
+        if let Ok(mut status) = STATUS.lock() {
+            *status = tails.iter().map(|tail| tail.status.clone()).collect();
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+// ===========================================================================
+// IPC Commands
+// ===========================================================================
+
+#[tauri::command]
+pub async fn get_log_tail_status() -> CommandResult<Vec<LogFileStatus>> {
+    error::command("get_log_tail_status", async move {
+        Ok(STATUS.lock().map_err(ForgeCommandError::internal)?.clone())
+    })
+    .await
+}
@@ -1,10 +1,45 @@
 // Prevents additional console window on Windows in release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use chrono::{Duration, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 use sqlx::{sqlite::SqlitePool, Row};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::env;
 
+mod advisor;
+mod anomaly;
+mod breakdown;
+mod cli;
+mod clock;
+mod error;
+mod event_store;
+mod export;
+mod forgeagents;
+mod ingest;
+mod insights;
+mod logtail;
+mod otlp;
+mod period;
+mod prometheus;
+mod rake;
+mod report;
+mod rollup;
+mod scheduler;
+mod schema;
+mod scrape;
+mod server;
+mod store;
+#[cfg(test)]
+mod tests;
+
+use advisor::TimedQuery;
+use anomaly::{AnomalyAlert, AnomalyMarker, AnomalyMethod};
+use error::{CommandResult, ForgeCommandError, ResultExt};
+use event_store::EventSource;
+use period::{ComparisonWindow, PeriodComparison, TimeWindow};
+use rollup::{RollupAggregate, RollupSeries};
+
 // ===========================================================================
 // Data Models
 // ===========================================================================
@@ -36,15 +71,19 @@ struct DataForgeMetrics {
     avg_search_duration: f64,
     avg_similarity: f64,
     error_rate: f64,
+    comparison: Option<Box<PeriodComparison<DataForgeMetrics>>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct NeuroForgeMetrics {
     total_requests: i64,
     total_tokens: i64,
+    total_prompt_tokens: i64,
+    total_completion_tokens: i64,
     total_cost: f64,
     avg_evaluation_score: f64,
     top_models: Vec<ModelMetric>,
+    comparison: Option<Box<PeriodComparison<NeuroForgeMetrics>>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -52,8 +91,14 @@ struct ModelMetric {
     model: String,
     requests: i64,
     tokens: i64,
+    prompt_tokens: i64,
+    completion_tokens: i64,
     cost: f64,
     avg_latency: f64,
+    avg_evaluation_score: f64,
+    tokens_per_request: f64,
+    cost_per_1k_tokens: f64,
+    cost_per_score_point: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -65,25 +110,55 @@ struct TimeSeriesPoint {
 #[derive(Debug, Serialize, Deserialize)]
 struct CostOverTime {
     datapoints: Vec<TimeSeriesPoint>,
+    comparison_datapoints: Vec<TimeSeriesPoint>,
+    anomalies: Vec<AnomalyMarker>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct TokenUsageOverTime {
     datapoints: Vec<TimeSeriesPoint>,
+    prompt_datapoints: Vec<TimeSeriesPoint>,
+    completion_datapoints: Vec<TimeSeriesPoint>,
+    comparison_datapoints: Vec<TimeSeriesPoint>,
+    anomalies: Vec<AnomalyMarker>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ModelTokenSeries {
+    model: String,
+    datapoints: Vec<TimeSeriesPoint>,
+    comparison_datapoints: Vec<TimeSeriesPoint>,
+    anomalies: Vec<AnomalyMarker>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TokenUsageByModelOverTime {
+    hours: Vec<String>,
+    models: Vec<ModelTokenSeries>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct SearchPerformanceOverTime {
     datapoints: Vec<TimeSeriesPoint>,
+    comparison_datapoints: Vec<TimeSeriesPoint>,
+    anomalies: Vec<AnomalyMarker>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct ForgeAgentsMetrics {
     active_agents: i64,
+    /// Tasks finished in the window plus those started in it and unfinished.
     total_tasks: i64,
+    completed_tasks: i64,
+    failed_tasks: i64,
+    in_flight_tasks: i64,
+    /// Started in the window and without an outcome for over an hour.
+    orphaned_tasks: i64,
     avg_latency_ms: f64,
+    /// Completed over finished tasks.
     success_rate: f64,
     recent_agents: Vec<AgentInfo>,
+    comparison: Option<Box<PeriodComparison<ForgeAgentsMetrics>>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -92,27 +167,53 @@ struct AgentInfo {
     agent_name: String,
     status: String,
     tasks_completed: i64,
+    tasks_failed: i64,
+    tasks_in_flight: i64,
+    tasks_orphaned: i64,
+    success_rate: f64,
     avg_latency_ms: f64,
+    last_seen: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct AgentActivityOverTime {
     datapoints: Vec<TimeSeriesPoint>,
+    comparison_datapoints: Vec<TimeSeriesPoint>,
+    anomalies: Vec<AnomalyMarker>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct AgentLatencyOverTime {
     datapoints: Vec<TimeSeriesPoint>,
+    comparison_datapoints: Vec<TimeSeriesPoint>,
+    anomalies: Vec<AnomalyMarker>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct RakeMetrics {
     total_pipelines: i64,
     active_pipelines: i64,
+    total_runs: i64,
+    successful_runs: i64,
+    failed_runs: i64,
+    success_rate: f64,
     records_ingested: i64,
+    /// Records per hour over the window.
     ingestion_rate: f64,
+    avg_run_duration_ms: f64,
     error_rate: f64,
+    /// Jobs waiting and in flight at the end of the window.
+    queued_jobs: i64,
+    running_jobs: i64,
+    /// Queue waits of the jobs that started in the window.
+    avg_queue_wait_ms: f64,
+    queue_wait_p50_ms: f64,
+    queue_wait_p95_ms: f64,
+    queue_wait_p99_ms: f64,
+    /// Data lag per pipeline, stalest first.
+    freshness: Vec<rake::SourceFreshness>,
     recent_pipelines: Vec<PipelineInfo>,
+    comparison: Option<Box<PeriodComparison<RakeMetrics>>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -120,25 +221,67 @@ struct PipelineInfo {
     pipeline_id: String,
     pipeline_name: String,
     status: String,
+    runs: i64,
+    successful_runs: i64,
+    failed_runs: i64,
     records_processed: i64,
+    avg_run_duration_ms: f64,
     last_run: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct IngestionOverTime {
     datapoints: Vec<TimeSeriesPoint>,
+    comparison_datapoints: Vec<TimeSeriesPoint>,
+    anomalies: Vec<AnomalyMarker>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct ErrorRateOverTime {
     datapoints: Vec<TimeSeriesPoint>,
+    comparison_datapoints: Vec<TimeSeriesPoint>,
+    anomalies: Vec<AnomalyMarker>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ScrapedMetricOverTime {
+    service: String,
+    metric: String,
+    kind: scrape::SeriesKind,
+    datapoints: Vec<TimeSeriesPoint>,
+    comparison_datapoints: Vec<TimeSeriesPoint>,
+    anomalies: Vec<AnomalyMarker>,
 }
 
 // ===========================================================================
 // Database Connection
 // ===========================================================================
 
-async fn get_db_pool() -> Result<SqlitePool, String> {
+/// Opens the events database the dashboards read, detecting how it writes
+/// timestamps the first time each database is used.
+async fn get_db_pool() -> CommandResult<SqlitePool> {
+    Ok(get_db_source().await?.1)
+}
+
+/// Like `get_db_pool`, along with the key identifying the database.
+async fn get_db_source() -> CommandResult<(String, SqlitePool)> {
+    let (source, pool) = open_events_pool().await?;
+    advisor::ensure_timestamp_layout(&source, &pool).await;
+    Ok((source, pool))
+}
+
+/// The events database for the configured source, with a key identifying it.
+async fn open_events_pool() -> CommandResult<(String, SqlitePool)> {
+    let local = || async {
+        let pool = event_store::get_event_store_pool().await?;
+        Ok((event_store::events_db_path().display().to_string(), pool))
+    };
+
+    let source = event_store::event_source()?;
+    if source == EventSource::Local {
+        return local().await;
+    }
+
     // Use DataForge's database for telemetry
     let database_url = env::var("DATABASE_URL")
         .unwrap_or_else(|_| {
@@ -151,9 +294,27 @@ async fn get_db_pool() -> Result<SqlitePool, String> {
         format!("sqlite://{}", database_url)
     };
 
-    SqlitePool::connect(&db_url)
-        .await
-        .map_err(|e| format!("Failed to connect to database: {}", e))
+    // sqlx reports a missing file as a generic open failure, so check first
+    let path = db_url
+        .trim_start_matches("sqlite://")
+        .trim_start_matches("sqlite:")
+        .split('?')
+        .next()
+        .unwrap_or_default();
+    if !path.is_empty() && path != ":memory:" && !std::path::Path::new(path).exists() {
+        // Without DataForge, read events sent straight to Forge Command
+        if source == EventSource::Auto && event_store::events_db_path().exists() {
+            return local().await;
+        }
+        return Err(ForgeCommandError::DatabaseMissing {
+            path: path.to_string(),
+        });
+    }
+
+    let pool = SqlitePool::connect(&db_url).await.map_err(|e| {
+        ForgeCommandError::DatabaseUnavailable(format!("Failed to connect to database: {}", e))
+    })?;
+    Ok((db_url, pool))
 }
 
 // ===========================================================================
@@ -161,58 +322,84 @@ async fn get_db_pool() -> Result<SqlitePool, String> {
 // ===========================================================================
 
 #[tauri::command]
-async fn get_system_health() -> Result<SystemHealth, String> {
-    let pool = get_db_pool().await?;
+async fn get_system_health() -> CommandResult<SystemHealth> {
+    error::command("get_system_health", async move {
+        let pool = get_db_pool().await?;
+        query_system_health(&pool, clock::now_naive()).await
+    })
+    .await
+}
+
+/// Service status and 24h uptime as of `at`.
+async fn query_system_health(pool: &SqlitePool, at: NaiveDateTime) -> CommandResult<SystemHealth> {
+    let health_cutoff = period::column_bound(at - Duration::minutes(5));
+    let health_end = period::column_bound(at);
 
     // Query for DataForge health (events in last 5 minutes)
     let dataforge_recent = sqlx::query(
         "SELECT COUNT(*) as count FROM events
          WHERE service = 'dataforge'
-         AND datetime(timestamp) > datetime('now', '-5 minutes')"
+         AND timestamp > ?
+         AND timestamp <= ?"
     )
-    .fetch_one(&pool)
+    .bind(&health_cutoff)
+    .bind(&health_end)
+    .fetch_one_timed(pool)
     .await
-    .map_err(|e| e.to_string())?
+    .in_query("dataforge_recent")
+    .in_service("dataforge")?
     .get::<i64, _>("count");
 
     // Query for NeuroForge health
     let neuroforge_recent = sqlx::query(
         "SELECT COUNT(*) as count FROM events
          WHERE service = 'neuroforge'
-         AND datetime(timestamp) > datetime('now', '-5 minutes')"
+         AND timestamp > ?
+         AND timestamp <= ?"
     )
-    .fetch_one(&pool)
+    .bind(&health_cutoff)
+    .bind(&health_end)
+    .fetch_one_timed(pool)
     .await
-    .map_err(|e| e.to_string())?
+    .in_query("neuroforge_recent")
+    .in_service("neuroforge")?
     .get::<i64, _>("count");
 
     // Query for ForgeAgents health
     let forgeagents_recent = sqlx::query(
         "SELECT COUNT(*) as count FROM events
          WHERE service = 'forgeagents'
-         AND datetime(timestamp) > datetime('now', '-5 minutes')"
+         AND timestamp > ?
+         AND timestamp <= ?"
     )
-    .fetch_one(&pool)
+    .bind(&health_cutoff)
+    .bind(&health_end)
+    .fetch_one_timed(pool)
     .await
-    .map_err(|e| e.to_string())?
+    .in_query("forgeagents_recent")
+    .in_service("forgeagents")?
     .get::<i64, _>("count");
 
     // Query for Rake health
     let rake_recent = sqlx::query(
         "SELECT COUNT(*) as count FROM events
          WHERE service = 'rake'
-         AND datetime(timestamp) > datetime('now', '-5 minutes')"
+         AND timestamp > ?
+         AND timestamp <= ?"
     )
-    .fetch_one(&pool)
+    .bind(&health_cutoff)
+    .bind(&health_end)
+    .fetch_one_timed(pool)
     .await
-    .map_err(|e| e.to_string())?
+    .in_query("rake_recent")
+    .in_service("rake")?
     .get::<i64, _>("count");
 
     // Calculate uptime (% of successful vs total events in last 24h)
-    let dataforge_uptime = calculate_uptime(&pool, "dataforge").await?;
-    let neuroforge_uptime = calculate_uptime(&pool, "neuroforge").await?;
-    let forgeagents_uptime = calculate_uptime(&pool, "forgeagents").await?;
-    let rake_uptime = calculate_uptime(&pool, "rake").await?;
+    let dataforge_uptime = calculate_uptime(pool, at, "dataforge").await?;
+    let neuroforge_uptime = calculate_uptime(pool, at, "neuroforge").await?;
+    let forgeagents_uptime = calculate_uptime(pool, at, "forgeagents").await?;
+    let rake_uptime = calculate_uptime(pool, at, "rake").await?;
 
     Ok(SystemHealth {
         dataforge_status: if dataforge_recent > 0 { "UP".to_string() } else { "DOWN".to_string() },
@@ -226,19 +413,23 @@ async fn get_system_health() -> Result<SystemHealth, String> {
     })
 }
 
-async fn calculate_uptime(pool: &SqlitePool, service: &str) -> Result<f64, String> {
+async fn calculate_uptime(pool: &SqlitePool, at: NaiveDateTime, service: &str) -> CommandResult<f64> {
     let result = sqlx::query(
         "SELECT
             COUNT(*) FILTER (WHERE severity != 'error') as success,
             COUNT(*) as total
          FROM events
          WHERE service = ?
-         AND datetime(timestamp) > datetime('now', '-24 hours')"
+         AND timestamp > ?
+         AND timestamp <= ?"
     )
     .bind(service)
-    .fetch_one(pool)
+    .bind(period::column_bound(at - Duration::hours(24)))
+    .bind(period::column_bound(at))
+    .fetch_one_timed(pool)
     .await
-    .map_err(|e| e.to_string())?;
+    .in_query("uptime")
+    .in_service(service)?;
 
     let success: i64 = result.get("success");
     let total: i64 = result.get("total");
@@ -250,37 +441,287 @@ async fn calculate_uptime(pool: &SqlitePool, service: &str) -> Result<f64, Strin
     }
 }
 
-#[tauri::command]
-async fn get_recent_events(limit: i64) -> Result<Vec<RecentEvent>, String> {
-    let pool = get_db_pool().await?;
+/// Divides two values, returning 0.0 instead of NaN/inf when the denominator is empty.
+fn safe_ratio(numerator: f64, denominator: f64) -> f64 {
+    if denominator == 0.0 {
+        0.0
+    } else {
+        numerator / denominator
+    }
+}
 
-    let events = sqlx::query_as::<_, (String, String, String, String, String)>(
-        "SELECT event_id, timestamp, service, event_type, severity
+/// Runs an hourly series query selecting `hour` and `value`, bound to whole
+/// hours from the one the window starts in up to the window end.
+async fn fetch_hourly_series(
+    pool: &SqlitePool,
+    query: &str,
+    window: &TimeWindow,
+) -> CommandResult<Vec<TimeSeriesPoint>> {
+    let datapoints = sqlx::query(query)
+        .bind(window.hour_start_bound())
+        .bind(window.end_bound())
+        .fetch_all_timed(pool)
+        .await
+        .in_query("hourly_series")?
+        .into_iter()
+        .map(|row| TimeSeriesPoint {
+            timestamp: row.get::<String, _>("hour"),
+            value: row.get::<Option<f64>, _>("value").unwrap_or(0.0),
+        })
+        .collect();
+
+    Ok(datapoints)
+}
+
+/// NeuroForge spend per hour.
+const COST_SERIES: RollupSeries<'static> = RollupSeries {
+    service: "neuroforge",
+    event_type: Some("model_request"),
+    aggregate: RollupAggregate::Sum("cost_usd"),
+    label_format: "%Y-%m-%d %H:00",
+};
+
+const COST_QUERY: &str = "SELECT
+        strftime('%Y-%m-%d %H:00', timestamp) as hour,
+        SUM(CAST(json_extract(metrics, '$.cost_usd') AS FLOAT)) as value
+     FROM events
+     WHERE service = 'neuroforge'
+     AND event_type = 'model_request'
+     AND timestamp >= ?
+     AND timestamp <= ?
+     GROUP BY hour
+     ORDER BY hour ASC";
+
+/// Average DataForge search latency per hour.
+const SEARCH_LATENCY_SERIES: RollupSeries<'static> = RollupSeries {
+    service: "dataforge",
+    event_type: Some("query"),
+    aggregate: RollupAggregate::Avg("duration_ms"),
+    label_format: "%Y-%m-%d %H:00",
+};
+
+const SEARCH_LATENCY_QUERY: &str = "SELECT
+        strftime('%Y-%m-%d %H:00', timestamp) as hour,
+        AVG(CAST(json_extract(metrics, '$.duration_ms') AS FLOAT)) as value
+     FROM events
+     WHERE service = 'dataforge'
+     AND event_type = 'query'
+     AND timestamp >= ?
+     AND timestamp <= ?
+     GROUP BY hour
+     ORDER BY hour ASC";
+
+/// Average ForgeAgents task latency per hour.
+const AGENT_LATENCY_SERIES: RollupSeries<'static> = RollupSeries {
+    service: "forgeagents",
+    event_type: Some("agent_task_completed"),
+    aggregate: RollupAggregate::Avg("duration_ms"),
+    label_format: "%Y-%m-%d %H:00",
+};
+
+const AGENT_LATENCY_QUERY: &str = "SELECT
+        strftime('%Y-%m-%d %H:00', timestamp) as hour,
+        AVG(CAST(json_extract(metrics, '$.duration_ms') AS FLOAT)) as value
+     FROM events
+     WHERE service = 'forgeagents'
+     AND event_type = 'agent_task_completed'
+     AND timestamp >= ?
+     AND timestamp <= ?
+     GROUP BY hour
+     ORDER BY hour ASC";
+
+/// Share of Rake events with severity `error` per hour.
+const RAKE_ERROR_RATE_SERIES: RollupSeries<'static> = RollupSeries {
+    service: "rake",
+    event_type: None,
+    aggregate: RollupAggregate::ErrorRate,
+    label_format: "%Y-%m-%d %H:00:00",
+};
+
+const RAKE_ERROR_RATE_QUERY: &str = "SELECT
+        strftime('%Y-%m-%d %H:00:00', timestamp) as hour,
+        CAST(SUM(CASE WHEN severity = 'error' THEN 1 ELSE 0 END) AS FLOAT) /
+        NULLIF(COUNT(*), 0) * 100.0 as value
+     FROM events
+     WHERE service = 'rake'
+     AND timestamp >= ?
+     AND timestamp <= ?
+     GROUP BY hour
+     ORDER BY hour ASC";
+
+/// NeuroForge tokens per hour.
+const TOKENS_SERIES: RollupSeries<'static> = RollupSeries {
+    service: "neuroforge",
+    event_type: Some("model_request"),
+    aggregate: RollupAggregate::Sum("tokens_total"),
+    label_format: "%Y-%m-%d %H:00",
+};
+
+const TOKENS_QUERY: &str = "SELECT
+        strftime('%Y-%m-%d %H:00', timestamp) as hour,
+        CAST(SUM(CAST(json_extract(metrics, '$.tokens_total') AS INTEGER)) AS FLOAT) as value
+     FROM events
+     WHERE service = 'neuroforge'
+     AND event_type = 'model_request'
+     AND timestamp >= ?
+     AND timestamp <= ?
+     GROUP BY hour
+     ORDER BY hour ASC";
+
+/// Rake records ingested per hour, read from the configured records field.
+fn ingestion_series(records_field: &str) -> (RollupSeries<'_>, String) {
+    let series = RollupSeries {
+        service: "rake",
+        event_type: Some("ingestion_complete"),
+        aggregate: RollupAggregate::Sum(records_field),
+        label_format: "%Y-%m-%d %H:00:00",
+    };
+    let query = format!(
+        "SELECT
+            strftime('%Y-%m-%d %H:00:00', timestamp) as hour,
+            CAST(SUM(CAST(json_extract(metrics, '$.{}') AS INTEGER)) AS FLOAT) as value
          FROM events
-         ORDER BY timestamp DESC
-         LIMIT ?"
-    )
-    .bind(limit)
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| e.to_string())?
-    .into_iter()
-    .map(|(event_id, timestamp, service, event_type, severity)| RecentEvent {
-        event_id,
-        timestamp,
-        service,
-        event_type,
-        severity,
+         WHERE service = 'rake'
+         AND event_type = 'ingestion_complete'
+         AND timestamp >= ?
+         AND timestamp <= ?
+         GROUP BY hour
+         ORDER BY hour ASC",
+        records_field
+    );
+
+    (series, query)
+}
+
+/// Hourly rollup points for `series`, or `None` when the rollup store can't
+/// serve the window.
+async fn fetch_rollup_series(series: &RollupSeries<'_>, window: &TimeWindow) -> Option<Vec<TimeSeriesPoint>> {
+    match rollup::fetch_series(series, window).await {
+        Ok(points) => points,
+        Err(e) => {
+            eprintln!("Rollup read failed, falling back to raw events: {}", e);
+            None
+        }
+    }
+}
+
+/// Serves a series from the rollup store when it covers the window, otherwise
+/// runs the raw hourly query. Count and sum series get a zero for every hour
+/// without events.
+async fn fetch_series(
+    pool: &SqlitePool,
+    series: &RollupSeries<'_>,
+    query: &str,
+    window: &TimeWindow,
+) -> CommandResult<Vec<TimeSeriesPoint>> {
+    let points = match fetch_rollup_series(series, window).await {
+        Some(points) => points,
+        None => fetch_hourly_series(pool, query, window)
+            .await
+            .in_service(series.service)?,
+    };
+
+    Ok(if series.aggregate.is_additive() {
+        anomaly::fill_hourly_gaps(points, window, series.label_format)
+    } else {
+        points
     })
-    .collect();
+}
+
+/// Runs the same series query over the comparison window and shifts it onto the
+/// current window so the two overlay. Empty when no comparison is requested.
+async fn fetch_comparison_series(
+    pool: &SqlitePool,
+    series: &RollupSeries<'_>,
+    query: &str,
+    window: &TimeWindow,
+    comparison: Option<ComparisonWindow>,
+) -> CommandResult<Vec<TimeSeriesPoint>> {
+    let Some(comparison) = comparison else {
+        return Ok(Vec::new());
+    };
 
-    Ok(events)
+    let previous_window = window.comparison(&comparison)?;
+    let datapoints = fetch_series(pool, series, query, &previous_window).await?;
+
+    Ok(period::shift_series(datapoints, &previous_window, window))
+}
+
+/// Anomalies `method` finds in `datapoints`, the series over `window`, with
+/// the series fetched again over a longer window when the method needs it.
+async fn detect_series_anomalies(
+    pool: &SqlitePool,
+    series: &RollupSeries<'_>,
+    query: &str,
+    window: &TimeWindow,
+    datapoints: &[TimeSeriesPoint],
+    method: AnomalyMethod,
+) -> CommandResult<Vec<AnomalyMarker>> {
+    anomaly::detect_with_history(datapoints, window, method, |history| async move {
+        fetch_series(pool, series, query, &history).await
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_recent_events(limit: i64, service: Option<String>) -> CommandResult<Vec<RecentEvent>> {
+    error::command("get_recent_events", async move {
+        let pool = get_db_pool().await?;
+
+        let events = sqlx::query_as::<_, (String, String, String, String, String)>(
+            "SELECT event_id, timestamp, service, event_type, severity
+             FROM events
+             WHERE (? IS NULL OR service = ?)
+             ORDER BY timestamp DESC
+             LIMIT ?"
+        )
+        .bind(&service)
+        .bind(&service)
+        .bind(limit)
+        .fetch_all_timed(&pool)
+        .await
+        .in_query("recent_events")?
+        .into_iter()
+        .map(|(event_id, timestamp, service, event_type, severity)| RecentEvent {
+            event_id,
+            timestamp,
+            service,
+            event_type,
+            severity,
+        })
+        .collect();
+
+        Ok(events)
+    })
+    .await
 }
 
 #[tauri::command]
-async fn get_dataforge_metrics() -> Result<DataForgeMetrics, String> {
-    let pool = get_db_pool().await?;
+async fn get_dataforge_metrics(
+    hours: Option<i64>,
+    comparison: Option<ComparisonWindow>,
+) -> CommandResult<DataForgeMetrics> {
+    error::command("get_dataforge_metrics", async move {
+        let pool = get_db_pool().await?;
+        let window = TimeWindow::last_hours_or_all(hours);
+
+        let mut metrics = query_dataforge_metrics(&pool, &window).await.in_service("dataforge")?;
+
+        if let Some(comparison) = comparison {
+            let previous_window = window.comparison(&comparison)?;
+            let previous = query_dataforge_metrics(&pool, &previous_window).await.in_service("dataforge")?;
+            metrics.comparison = Some(Box::new(PeriodComparison::new(&metrics, previous, previous_window)));
+        }
+
+        Ok(metrics)
+    })
+    .await
+}
 
+async fn query_dataforge_metrics(
+    pool: &SqlitePool,
+    window: &TimeWindow,
+) -> CommandResult<DataForgeMetrics> {
     // Get search metrics
     let metrics = sqlx::query(
         "SELECT
@@ -288,11 +729,15 @@ async fn get_dataforge_metrics() -> Result<DataForgeMetrics, String> {
             AVG(CAST(json_extract(metrics, '$.duration_ms') AS FLOAT)) as avg_duration,
             AVG(CAST(json_extract(metrics, '$.avg_similarity') AS FLOAT)) as avg_similarity
          FROM events
-         WHERE service = 'dataforge' AND event_type = 'query'"
+         WHERE service = 'dataforge' AND event_type = 'query'
+         AND timestamp > ?
+         AND timestamp <= ?"
     )
-    .fetch_one(&pool)
+    .bind(window.start_bound())
+    .bind(window.end_bound())
+    .fetch_one_timed(pool)
     .await
-    .map_err(|e| e.to_string())?;
+    .in_query("dataforge_metrics")?;
 
     // Calculate error rate
     let error_rate_result = sqlx::query(
@@ -301,433 +746,824 @@ async fn get_dataforge_metrics() -> Result<DataForgeMetrics, String> {
             NULLIF(COUNT(*), 0) * 100.0 as error_rate
          FROM events
          WHERE service = 'dataforge'
-         AND event_type IN ('query', 'query_error')"
+         AND event_type IN ('query', 'query_error')
+         AND timestamp > ?
+         AND timestamp <= ?"
     )
-    .fetch_one(&pool)
+    .bind(window.start_bound())
+    .bind(window.end_bound())
+    .fetch_one_timed(pool)
     .await
-    .map_err(|e| e.to_string())?;
+    .in_query("dataforge_error_rate")?;
 
     Ok(DataForgeMetrics {
         total_searches: metrics.get::<Option<i64>, _>("total_searches").unwrap_or(0),
         avg_search_duration: metrics.get::<Option<f64>, _>("avg_duration").unwrap_or(0.0),
         avg_similarity: metrics.get::<Option<f64>, _>("avg_similarity").unwrap_or(0.0),
         error_rate: error_rate_result.get::<Option<f64>, _>("error_rate").unwrap_or(0.0),
+        comparison: None,
     })
 }
 
 #[tauri::command]
-async fn get_neuroforge_metrics() -> Result<NeuroForgeMetrics, String> {
-    let pool = get_db_pool().await?;
+async fn get_neuroforge_metrics(
+    limit: Option<i64>,
+    hours: Option<i64>,
+    comparison: Option<ComparisonWindow>,
+) -> CommandResult<NeuroForgeMetrics> {
+    error::command("get_neuroforge_metrics", async move {
+        let pool = get_db_pool().await?;
+        let window = TimeWindow::last_hours_or_all(hours);
+
+        let mut metrics = query_neuroforge_metrics(&pool, &window, limit).await.in_service("neuroforge")?;
+
+        if let Some(comparison) = comparison {
+            let previous_window = window.comparison(&comparison)?;
+            let previous = query_neuroforge_metrics(&pool, &previous_window, limit).await.in_service("neuroforge")?;
+            metrics.comparison = Some(Box::new(PeriodComparison::new(&metrics, previous, previous_window)));
+        }
+
+        Ok(metrics)
+    })
+    .await
+}
 
+async fn query_neuroforge_metrics(
+    pool: &SqlitePool,
+    window: &TimeWindow,
+    limit: Option<i64>,
+) -> CommandResult<NeuroForgeMetrics> {
     // Get overall metrics
     let overall = sqlx::query(
         "SELECT
             COUNT(*) as total_requests,
             SUM(CAST(json_extract(metrics, '$.tokens_total') AS INTEGER)) as total_tokens,
+            SUM(CAST(json_extract(metrics, '$.tokens_prompt') AS INTEGER)) as prompt_tokens,
+            SUM(CAST(json_extract(metrics, '$.tokens_completion') AS INTEGER)) as completion_tokens,
             SUM(CAST(json_extract(metrics, '$.cost_usd') AS FLOAT)) as total_cost,
             AVG(CAST(json_extract(metrics, '$.evaluation_score') AS FLOAT)) as avg_score
          FROM events
-         WHERE service = 'neuroforge' AND event_type = 'model_request'"
+         WHERE service = 'neuroforge' AND event_type = 'model_request'
+         AND timestamp > ?
+         AND timestamp <= ?"
     )
-    .fetch_one(&pool)
+    .bind(window.start_bound())
+    .bind(window.end_bound())
+    .fetch_one_timed(pool)
     .await
-    .map_err(|e| e.to_string())?;
+    .in_query("neuroforge_totals")?;
 
-    // Get per-model metrics
+    // Get per-model metrics (every model unless a limit is given)
     let models = sqlx::query(
         "SELECT
             json_extract(metadata, '$.model') as model,
             COUNT(*) as requests,
             SUM(CAST(json_extract(metrics, '$.tokens_total') AS INTEGER)) as tokens,
+            SUM(CAST(json_extract(metrics, '$.tokens_prompt') AS INTEGER)) as prompt_tokens,
+            SUM(CAST(json_extract(metrics, '$.tokens_completion') AS INTEGER)) as completion_tokens,
             SUM(CAST(json_extract(metrics, '$.cost_usd') AS FLOAT)) as cost,
-            AVG(CAST(json_extract(metrics, '$.duration_ms') AS FLOAT)) as avg_latency
+            AVG(CAST(json_extract(metrics, '$.duration_ms') AS FLOAT)) as avg_latency,
+            AVG(CAST(json_extract(metrics, '$.evaluation_score') AS FLOAT)) as avg_score
          FROM events
          WHERE service = 'neuroforge' AND event_type = 'model_request'
+         AND timestamp > ?
+         AND timestamp <= ?
          GROUP BY json_extract(metadata, '$.model')
          ORDER BY cost DESC
-         LIMIT 5"
+         LIMIT ?"
     )
-    .fetch_all(&pool)
+    .bind(window.start_bound())
+    .bind(window.end_bound())
+    .bind(limit.unwrap_or(-1))
+    .fetch_all_timed(pool)
     .await
-    .map_err(|e| e.to_string())?
+    .in_query("neuroforge_top_models")?
     .into_iter()
-    .map(|row| ModelMetric {
-        model: row.get::<Option<String>, _>("model").unwrap_or_else(|| "unknown".to_string()),
-        requests: row.get::<Option<i64>, _>("requests").unwrap_or(0),
-        tokens: row.get::<Option<i64>, _>("tokens").unwrap_or(0),
-        cost: row.get::<Option<f64>, _>("cost").unwrap_or(0.0),
-        avg_latency: row.get::<Option<f64>, _>("avg_latency").unwrap_or(0.0),
+    .map(|row| {
+        let requests = row.get::<Option<i64>, _>("requests").unwrap_or(0);
+        let tokens = row.get::<Option<i64>, _>("tokens").unwrap_or(0);
+        let cost = row.get::<Option<f64>, _>("cost").unwrap_or(0.0);
+        let avg_evaluation_score = row.get::<Option<f64>, _>("avg_score").unwrap_or(0.0);
+
+        ModelMetric {
+            model: row.get::<Option<String>, _>("model").unwrap_or_else(|| "unknown".to_string()),
+            requests,
+            tokens,
+            prompt_tokens: row.get::<Option<i64>, _>("prompt_tokens").unwrap_or(0),
+            completion_tokens: row.get::<Option<i64>, _>("completion_tokens").unwrap_or(0),
+            cost,
+            avg_latency: row.get::<Option<f64>, _>("avg_latency").unwrap_or(0.0),
+            avg_evaluation_score,
+            tokens_per_request: safe_ratio(tokens as f64, requests as f64),
+            cost_per_1k_tokens: safe_ratio(cost * 1000.0, tokens as f64),
+            // Average spend per request divided by the average quality it bought
+            cost_per_score_point: safe_ratio(
+                safe_ratio(cost, requests as f64),
+                avg_evaluation_score,
+            ),
+        }
     })
     .collect();
 
     Ok(NeuroForgeMetrics {
         total_requests: overall.get::<Option<i64>, _>("total_requests").unwrap_or(0),
         total_tokens: overall.get::<Option<i64>, _>("total_tokens").unwrap_or(0),
+        total_prompt_tokens: overall.get::<Option<i64>, _>("prompt_tokens").unwrap_or(0),
+        total_completion_tokens: overall.get::<Option<i64>, _>("completion_tokens").unwrap_or(0),
         total_cost: overall.get::<Option<f64>, _>("total_cost").unwrap_or(0.0),
         avg_evaluation_score: overall.get::<Option<f64>, _>("avg_score").unwrap_or(0.0),
         top_models: models,
+        comparison: None,
     })
 }
 
 #[tauri::command]
-async fn get_cost_over_time(hours: i64) -> Result<CostOverTime, String> {
-    let pool = get_db_pool().await?;
-
-    let datapoints = sqlx::query(
-        "SELECT
-            strftime('%Y-%m-%d %H:00', timestamp) as hour,
-            SUM(CAST(json_extract(metrics, '$.cost_usd') AS FLOAT)) as total_cost
-         FROM events
-         WHERE service = 'neuroforge'
-         AND event_type = 'model_request'
-         AND datetime(timestamp) > datetime('now', ? || ' hours')
-         GROUP BY hour
-         ORDER BY hour ASC"
-    )
-    .bind(format!("-{}", hours))
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| e.to_string())?
-    .into_iter()
-    .map(|row| TimeSeriesPoint {
-        timestamp: row.get::<String, _>("hour"),
-        value: row.get::<Option<f64>, _>("total_cost").unwrap_or(0.0),
+async fn get_cost_over_time(
+    hours: i64,
+    anomaly: Option<AnomalyMethod>,
+    comparison: Option<ComparisonWindow>,
+) -> CommandResult<CostOverTime> {
+    error::command("get_cost_over_time", async move {
+        let pool = get_db_pool().await?;
+        let window = TimeWindow::last_hours(hours);
+
+        let datapoints = fetch_series(&pool, &COST_SERIES, COST_QUERY, &window).await?;
+        let comparison_datapoints = fetch_comparison_series(&pool, &COST_SERIES, COST_QUERY, &window, comparison).await?;
+        let anomalies =
+            detect_series_anomalies(&pool, &COST_SERIES, COST_QUERY, &window, &datapoints, anomaly.unwrap_or_default()).await?;
+
+        Ok(CostOverTime {
+            datapoints,
+            comparison_datapoints,
+            anomalies,
+        })
     })
-    .collect();
-
-    Ok(CostOverTime { datapoints })
+    .await
 }
 
 #[tauri::command]
-async fn get_token_usage_over_time(hours: i64) -> Result<TokenUsageOverTime, String> {
-    let pool = get_db_pool().await?;
-
-    let datapoints = sqlx::query(
-        "SELECT
-            strftime('%Y-%m-%d %H:00', timestamp) as hour,
-            SUM(CAST(json_extract(metrics, '$.tokens_total') AS INTEGER)) as total_tokens
-         FROM events
-         WHERE service = 'neuroforge'
-         AND event_type = 'model_request'
-         AND datetime(timestamp) > datetime('now', ? || ' hours')
-         GROUP BY hour
-         ORDER BY hour ASC"
-    )
-    .bind(format!("-{}", hours))
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| e.to_string())?
-    .into_iter()
-    .map(|row| TimeSeriesPoint {
-        timestamp: row.get::<String, _>("hour"),
-        value: row.get::<Option<i64>, _>("total_tokens").unwrap_or(0) as f64,
+async fn get_token_usage_over_time(
+    hours: i64,
+    anomaly: Option<AnomalyMethod>,
+    comparison: Option<ComparisonWindow>,
+) -> CommandResult<TokenUsageOverTime> {
+    error::command("get_token_usage_over_time", async move {
+        let pool = get_db_pool().await?;
+        let window = TimeWindow::last_hours(hours);
+
+        let token_series = |metric| RollupSeries {
+            aggregate: RollupAggregate::Sum(metric),
+            ..TOKENS_SERIES
+        };
+
+        let rolled_up = match fetch_rollup_series(&TOKENS_SERIES, &window).await {
+            Some(total) => match (
+                fetch_rollup_series(&token_series("tokens_prompt"), &window).await,
+                fetch_rollup_series(&token_series("tokens_completion"), &window).await,
+            ) {
+                (Some(prompt), Some(completion)) => Some((total, prompt, completion)),
+                _ => None,
+            },
+            None => None,
+        };
+
+        let (datapoints, prompt_datapoints, completion_datapoints) = match rolled_up {
+            Some(series) => series,
+            None => {
+                let rows = sqlx::query(
+                    "SELECT
+                        strftime('%Y-%m-%d %H:00', timestamp) as hour,
+                        SUM(CAST(json_extract(metrics, '$.tokens_total') AS INTEGER)) as total_tokens,
+                        SUM(CAST(json_extract(metrics, '$.tokens_prompt') AS INTEGER)) as prompt_tokens,
+                        SUM(CAST(json_extract(metrics, '$.tokens_completion') AS INTEGER)) as completion_tokens
+                     FROM events
+                     WHERE service = 'neuroforge'
+                     AND event_type = 'model_request'
+                     AND timestamp >= ?
+                     AND timestamp <= ?
+                     GROUP BY hour
+                     ORDER BY hour ASC"
+                )
+                .bind(window.hour_start_bound())
+                .bind(window.end_bound())
+                .fetch_all_timed(&pool)
+                .await
+                .in_query("token_usage_hourly")
+                .in_service("neuroforge")?;
+
+                let mut datapoints = Vec::with_capacity(rows.len());
+                let mut prompt_datapoints = Vec::with_capacity(rows.len());
+                let mut completion_datapoints = Vec::with_capacity(rows.len());
+
+                for row in rows {
+                    let hour = row.get::<String, _>("hour");
+                    datapoints.push(TimeSeriesPoint {
+                        timestamp: hour.clone(),
+                        value: row.get::<Option<i64>, _>("total_tokens").unwrap_or(0) as f64,
+                    });
+                    prompt_datapoints.push(TimeSeriesPoint {
+                        timestamp: hour.clone(),
+                        value: row.get::<Option<i64>, _>("prompt_tokens").unwrap_or(0) as f64,
+                    });
+                    completion_datapoints.push(TimeSeriesPoint {
+                        timestamp: hour,
+                        value: row.get::<Option<i64>, _>("completion_tokens").unwrap_or(0) as f64,
+                    });
+                }
+
+                (datapoints, prompt_datapoints, completion_datapoints)
+            }
+        };
+        let fill = |points| anomaly::fill_hourly_gaps(points, &window, TOKENS_SERIES.label_format);
+        let (datapoints, prompt_datapoints, completion_datapoints) =
+            (fill(datapoints), fill(prompt_datapoints), fill(completion_datapoints));
+
+        let comparison_datapoints =
+            fetch_comparison_series(&pool, &TOKENS_SERIES, TOKENS_QUERY, &window, comparison).await?;
+        let anomalies =
+            detect_series_anomalies(&pool, &TOKENS_SERIES, TOKENS_QUERY, &window, &datapoints, anomaly.unwrap_or_default()).await?;
+
+        Ok(TokenUsageOverTime {
+            datapoints,
+            prompt_datapoints,
+            completion_datapoints,
+            comparison_datapoints,
+            anomalies,
+        })
     })
-    .collect();
-
-    Ok(TokenUsageOverTime { datapoints })
+    .await
 }
 
-#[tauri::command]
-async fn get_search_performance_over_time(hours: i64) -> Result<SearchPerformanceOverTime, String> {
-    let pool = get_db_pool().await?;
+/// `(hour, model, tokens)` rows over `window`, from the rollup store when it
+/// covers the window.
+async fn fetch_tokens_by_model(pool: &SqlitePool, window: &TimeWindow) -> CommandResult<Vec<(String, String, f64)>> {
+    let rolled_up = match rollup::fetch_series_by_model(&TOKENS_SERIES, window).await {
+        Ok(rows) => rows,
+        Err(e) => {
+            eprintln!("Rollup read failed, falling back to raw events: {}", e);
+            None
+        }
+    };
 
-    let datapoints = sqlx::query(
-        "SELECT
-            strftime('%Y-%m-%d %H:00', timestamp) as hour,
-            AVG(CAST(json_extract(metrics, '$.duration_ms') AS FLOAT)) as avg_duration
-         FROM events
-         WHERE service = 'dataforge'
-         AND event_type = 'query'
-         AND datetime(timestamp) > datetime('now', ? || ' hours')
-         GROUP BY hour
-         ORDER BY hour ASC"
-    )
-    .bind(format!("-{}", hours))
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| e.to_string())?
-    .into_iter()
-    .map(|row| TimeSeriesPoint {
-        timestamp: row.get::<String, _>("hour"),
-        value: row.get::<Option<f64>, _>("avg_duration").unwrap_or(0.0),
+    Ok(match rolled_up {
+        Some(rows) => rows,
+        None => sqlx::query(
+                "SELECT
+                    strftime('%Y-%m-%d %H:00', timestamp) as hour,
+                    COALESCE(json_extract(metadata, '$.model'), 'unknown') as model,
+                    SUM(CAST(json_extract(metrics, '$.tokens_total') AS INTEGER)) as total_tokens
+                 FROM events
+                 WHERE service = 'neuroforge'
+                 AND event_type = 'model_request'
+                 AND timestamp >= ?
+                 AND timestamp <= ?
+                 GROUP BY hour, model
+                 ORDER BY hour ASC"
+            )
+            .bind(window.hour_start_bound())
+            .bind(window.end_bound())
+            .fetch_all_timed(pool)
+            .await
+            .in_query("token_usage_by_model")
+            .in_service("neuroforge")?
+            .into_iter()
+            .map(|row| {
+                (
+                    row.get::<String, _>("hour"),
+                    row.get::<String, _>("model"),
+                    row.get::<Option<i64>, _>("total_tokens").unwrap_or(0) as f64,
+                )
+            })
+            .collect(),
     })
-    .collect();
+}
 
-    Ok(SearchPerformanceOverTime { datapoints })
+/// Splits `rows` into one series per model over `window`. Every model series
+/// gets a point for every hour so the chart can stack them.
+fn token_series_by_model(
+    rows: Vec<(String, String, f64)>,
+    window: &TimeWindow,
+) -> (Vec<String>, BTreeMap<String, Vec<TimeSeriesPoint>>) {
+    let mut hours_seen: BTreeSet<String> = BTreeSet::new();
+    let mut by_model: BTreeMap<String, HashMap<String, f64>> = BTreeMap::new();
+
+    for (hour, model, tokens) in rows {
+        hours_seen.insert(hour.clone());
+        by_model.entry(model).or_default().insert(hour, tokens);
+    }
+    if !hours_seen.is_empty() {
+        hours_seen.extend(anomaly::window_hours(window, TOKENS_SERIES.label_format));
+    }
+
+    let hours: Vec<String> = hours_seen.into_iter().collect();
+    let series = by_model
+        .into_iter()
+        .map(|(model, values)| {
+            let datapoints = hours
+                .iter()
+                .map(|hour| TimeSeriesPoint {
+                    timestamp: hour.clone(),
+                    value: values.get(hour).copied().unwrap_or(0.0),
+                })
+                .collect();
+            (model, datapoints)
+        })
+        .collect();
+
+    (hours, series)
 }
 
 #[tauri::command]
-async fn get_forgeagents_metrics() -> Result<ForgeAgentsMetrics, String> {
-    let pool = get_db_pool().await?;
-
-    // Get overall agent metrics
-    let overall = sqlx::query(
-        "SELECT
-            COUNT(DISTINCT json_extract(metadata, '$.agent_id')) as active_agents,
-            COUNT(*) as total_tasks,
-            AVG(CAST(json_extract(metrics, '$.duration_ms') AS FLOAT)) as avg_latency,
-            CAST(COUNT(*) FILTER (WHERE event_type = 'agent_task_completed') AS FLOAT) /
-            NULLIF(COUNT(*), 0) * 100.0 as success_rate
-         FROM events
-         WHERE service = 'forgeagents'
-         AND event_type IN ('agent_task_started', 'agent_task_completed', 'agent_task_failed')"
-    )
-    .fetch_one(&pool)
+async fn get_token_usage_by_model_over_time(
+    hours: i64,
+    anomaly: Option<AnomalyMethod>,
+    comparison: Option<ComparisonWindow>,
+) -> CommandResult<TokenUsageByModelOverTime> {
+    error::command("get_token_usage_by_model_over_time", async move {
+        let pool = get_db_pool().await?;
+        let window = TimeWindow::last_hours(hours);
+        let method = anomaly.unwrap_or_default();
+
+        let (hours, series) = token_series_by_model(fetch_tokens_by_model(&pool, &window).await?, &window);
+        let history = match method.history_window(&window) {
+            Some(history_window) => Some(
+                token_series_by_model(fetch_tokens_by_model(&pool, &history_window).await?, &history_window).1,
+            ),
+            None => None,
+        };
+        // Shifted onto the current window; empty for a model the comparison window didn't see
+        let mut previous = match comparison {
+            Some(comparison) => {
+                let previous_window = window.comparison(&comparison)?;
+                token_series_by_model(fetch_tokens_by_model(&pool, &previous_window).await?, &previous_window)
+                    .1
+                    .into_iter()
+                    .map(|(model, points)| (model, period::shift_series(points, &previous_window, &window)))
+                    .collect()
+            }
+            None => BTreeMap::new(),
+        };
+
+        let models = series
+            .into_iter()
+            .map(|(model, datapoints)| {
+                let anomalies = match history.as_ref().and_then(|history| history.get(&model)) {
+                    Some(history) => anomaly::detect_within(&datapoints, history, method),
+                    None => anomaly::detect(&datapoints, method),
+                };
+
+                ModelTokenSeries {
+                    comparison_datapoints: previous.remove(&model).unwrap_or_default(),
+                    model,
+                    datapoints,
+                    anomalies,
+                }
+            })
+            .collect();
+
+        Ok(TokenUsageByModelOverTime { hours, models })
+    })
     .await
-    .map_err(|e| e.to_string())?;
+}
 
-    // Get recent agent activity (top 5 agents)
-    let agents = sqlx::query(
-        "SELECT
-            json_extract(metadata, '$.agent_id') as agent_id,
-            json_extract(metadata, '$.agent_name') as agent_name,
-            json_extract(metadata, '$.status') as status,
-            COUNT(*) as tasks_completed,
-            AVG(CAST(json_extract(metrics, '$.duration_ms') AS FLOAT)) as avg_latency
-         FROM events
-         WHERE service = 'forgeagents'
-         AND event_type = 'agent_task_completed'
-         GROUP BY agent_id
-         ORDER BY tasks_completed DESC
-         LIMIT 5"
-    )
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| e.to_string())?
-    .into_iter()
-    .map(|row| AgentInfo {
-        agent_id: row.get::<Option<String>, _>("agent_id").unwrap_or_else(|| "unknown".to_string()),
-        agent_name: row.get::<Option<String>, _>("agent_name").unwrap_or_else(|| "Unknown Agent".to_string()),
-        status: row.get::<Option<String>, _>("status").unwrap_or_else(|| "active".to_string()),
-        tasks_completed: row.get::<Option<i64>, _>("tasks_completed").unwrap_or(0),
-        avg_latency_ms: row.get::<Option<f64>, _>("avg_latency").unwrap_or(0.0),
+#[tauri::command]
+async fn get_search_performance_over_time(
+    hours: i64,
+    anomaly: Option<AnomalyMethod>,
+    comparison: Option<ComparisonWindow>,
+) -> CommandResult<SearchPerformanceOverTime> {
+    error::command("get_search_performance_over_time", async move {
+        let pool = get_db_pool().await?;
+        let window = TimeWindow::last_hours(hours);
+
+        let datapoints = fetch_series(&pool, &SEARCH_LATENCY_SERIES, SEARCH_LATENCY_QUERY, &window).await?;
+        let comparison_datapoints = fetch_comparison_series(&pool, &SEARCH_LATENCY_SERIES, SEARCH_LATENCY_QUERY, &window, comparison).await?;
+        let anomalies =
+            detect_series_anomalies(&pool, &SEARCH_LATENCY_SERIES, SEARCH_LATENCY_QUERY, &window, &datapoints, anomaly.unwrap_or_default()).await?;
+
+        Ok(SearchPerformanceOverTime {
+            datapoints,
+            comparison_datapoints,
+            anomalies,
+        })
     })
-    .collect();
+    .await
+}
 
-    Ok(ForgeAgentsMetrics {
-        active_agents: overall.get::<Option<i64>, _>("active_agents").unwrap_or(0),
-        total_tasks: overall.get::<Option<i64>, _>("total_tasks").unwrap_or(0),
-        avg_latency_ms: overall.get::<Option<f64>, _>("avg_latency").unwrap_or(0.0),
-        success_rate: overall.get::<Option<f64>, _>("success_rate").unwrap_or(0.0),
-        recent_agents: agents,
+#[tauri::command]
+async fn get_forgeagents_metrics(
+    limit: Option<i64>,
+    hours: Option<i64>,
+    comparison: Option<ComparisonWindow>,
+) -> CommandResult<ForgeAgentsMetrics> {
+    error::command("get_forgeagents_metrics", async move {
+        let pool = get_db_pool().await?;
+        let window = TimeWindow::last_hours_or_all(hours);
+
+        let mut metrics = query_forgeagents_metrics(&pool, &window, limit).await.in_service("forgeagents")?;
+
+        if let Some(comparison) = comparison {
+            let previous_window = window.comparison(&comparison)?;
+            let previous = query_forgeagents_metrics(&pool, &previous_window, limit).await.in_service("forgeagents")?;
+            metrics.comparison = Some(Box::new(PeriodComparison::new(&metrics, previous, previous_window)));
+        }
+
+        Ok(metrics)
     })
+    .await
 }
 
-#[tauri::command]
-async fn get_agent_activity_over_time(hours: i64) -> Result<AgentActivityOverTime, String> {
-    let pool = get_db_pool().await?;
+async fn query_forgeagents_metrics(
+    pool: &SqlitePool,
+    window: &TimeWindow,
+    limit: Option<i64>,
+) -> CommandResult<ForgeAgentsMetrics> {
+    // Task totals per agent; the overall totals add these up
+    let agents = forgeagents::fetch_agent_stats(pool, window).await?;
+
+    let completed_tasks: i64 = agents.iter().map(|a| a.completed).sum();
+    let failed_tasks: i64 = agents.iter().map(|a| a.failed).sum();
+    let in_flight_tasks: i64 = agents.iter().map(|a| a.in_flight).sum();
+    let orphaned_tasks: i64 = agents.iter().map(|a| a.orphaned).sum();
+    let total_duration_ms: f64 = agents.iter().map(|a| a.total_duration_ms).sum();
+    let timed_tasks: i64 = agents.iter().map(|a| a.timed_tasks).sum();
+    let finished_tasks = completed_tasks + failed_tasks;
+
+    let recent_agents = agents
+        .iter()
+        .take(limit.filter(|limit| *limit >= 0).map_or(usize::MAX, |limit| limit as usize))
+        .map(|a| AgentInfo {
+            agent_id: a.agent_id.clone(),
+            agent_name: a.agent_name.clone(),
+            status: a.status.clone(),
+            tasks_completed: a.completed,
+            tasks_failed: a.failed,
+            tasks_in_flight: a.in_flight,
+            tasks_orphaned: a.orphaned,
+            success_rate: a.success_rate(),
+            avg_latency_ms: a.avg_duration_ms(),
+            last_seen: a.last_seen.and_utc().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        })
+        .collect();
 
-    let datapoints = sqlx::query(
-        "SELECT
-            strftime('%Y-%m-%d %H:00', timestamp) as hour,
-            COUNT(*) as task_count
-         FROM events
-         WHERE service = 'forgeagents'
-         AND event_type = 'agent_task_completed'
-         AND datetime(timestamp) > datetime('now', ? || ' hours')
-         GROUP BY hour
-         ORDER BY hour ASC"
-    )
-    .bind(format!("-{}", hours))
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| e.to_string())?
-    .into_iter()
-    .map(|row| TimeSeriesPoint {
-        timestamp: row.get::<String, _>("hour"),
-        value: row.get::<Option<i64>, _>("task_count").unwrap_or(0) as f64,
+    Ok(ForgeAgentsMetrics {
+        active_agents: agents.len() as i64,
+        total_tasks: finished_tasks + in_flight_tasks + orphaned_tasks,
+        completed_tasks,
+        failed_tasks,
+        in_flight_tasks,
+        orphaned_tasks,
+        avg_latency_ms: safe_ratio(total_duration_ms, timed_tasks as f64),
+        success_rate: safe_ratio(completed_tasks as f64 * 100.0, finished_tasks as f64),
+        recent_agents,
+        comparison: None,
     })
-    .collect();
-
-    Ok(AgentActivityOverTime { datapoints })
 }
 
 #[tauri::command]
-async fn get_agent_latency_over_time(hours: i64) -> Result<AgentLatencyOverTime, String> {
-    let pool = get_db_pool().await?;
-
-    let datapoints = sqlx::query(
-        "SELECT
-            strftime('%Y-%m-%d %H:00', timestamp) as hour,
-            AVG(CAST(json_extract(metrics, '$.duration_ms') AS FLOAT)) as avg_latency
-         FROM events
-         WHERE service = 'forgeagents'
-         AND event_type = 'agent_task_completed'
-         AND datetime(timestamp) > datetime('now', ? || ' hours')
-         GROUP BY hour
-         ORDER BY hour ASC"
-    )
-    .bind(format!("-{}", hours))
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| e.to_string())?
-    .into_iter()
-    .map(|row| TimeSeriesPoint {
-        timestamp: row.get::<String, _>("hour"),
-        value: row.get::<Option<f64>, _>("avg_latency").unwrap_or(0.0),
+async fn get_agent_activity_over_time(
+    hours: i64,
+    anomaly: Option<AnomalyMethod>,
+    comparison: Option<ComparisonWindow>,
+) -> CommandResult<AgentActivityOverTime> {
+    error::command("get_agent_activity_over_time", async move {
+        let pool = get_db_pool().await?;
+        let window = TimeWindow::last_hours(hours);
+
+        let query = "SELECT
+                strftime('%Y-%m-%d %H:00', timestamp) as hour,
+                CAST(COUNT(*) AS FLOAT) as value
+             FROM events
+             WHERE service = 'forgeagents'
+             AND event_type = 'agent_task_completed'
+             AND timestamp >= ?
+             AND timestamp <= ?
+             GROUP BY hour
+             ORDER BY hour ASC";
+
+        let series = RollupSeries {
+            service: "forgeagents",
+            event_type: Some("agent_task_completed"),
+            aggregate: RollupAggregate::Count,
+            label_format: "%Y-%m-%d %H:00",
+        };
+
+        let datapoints = fetch_series(&pool, &series, query, &window).await?;
+        let comparison_datapoints = fetch_comparison_series(&pool, &series, query, &window, comparison).await?;
+        let anomalies =
+            detect_series_anomalies(&pool, &series, query, &window, &datapoints, anomaly.unwrap_or_default()).await?;
+
+        Ok(AgentActivityOverTime {
+            datapoints,
+            comparison_datapoints,
+            anomalies,
+        })
     })
-    .collect();
-
-    Ok(AgentLatencyOverTime { datapoints })
+    .await
 }
 
 #[tauri::command]
-async fn get_rake_metrics() -> Result<RakeMetrics, String> {
-    let pool = get_db_pool().await?;
-
-    // Get overall pipeline metrics
-    let metrics = sqlx::query(
-        "SELECT
-            COUNT(DISTINCT json_extract(metrics, '$.pipeline_id')) as total_pipelines,
-            COUNT(*) as records_ingested
-         FROM events
-         WHERE service = 'rake'
-         AND event_type = 'ingestion_complete'"
-    )
-    .fetch_one(&pool)
+async fn get_agent_latency_over_time(
+    hours: i64,
+    anomaly: Option<AnomalyMethod>,
+    comparison: Option<ComparisonWindow>,
+) -> CommandResult<AgentLatencyOverTime> {
+    error::command("get_agent_latency_over_time", async move {
+        let pool = get_db_pool().await?;
+        let window = TimeWindow::last_hours(hours);
+
+        let datapoints = fetch_series(&pool, &AGENT_LATENCY_SERIES, AGENT_LATENCY_QUERY, &window).await?;
+        let comparison_datapoints = fetch_comparison_series(&pool, &AGENT_LATENCY_SERIES, AGENT_LATENCY_QUERY, &window, comparison).await?;
+        let anomalies =
+            detect_series_anomalies(&pool, &AGENT_LATENCY_SERIES, AGENT_LATENCY_QUERY, &window, &datapoints, anomaly.unwrap_or_default()).await?;
+
+        Ok(AgentLatencyOverTime {
+            datapoints,
+            comparison_datapoints,
+            anomalies,
+        })
+    })
     .await
-    .map_err(|e| e.to_string())?;
-
-    let total_pipelines: i64 = metrics.get("total_pipelines");
-    let records_ingested: i64 = metrics.get("records_ingested");
+}
 
-    // Calculate active pipelines (active in last hour)
-    let active_pipelines = sqlx::query(
-        "SELECT COUNT(DISTINCT json_extract(metrics, '$.pipeline_id')) as count
-         FROM events
-         WHERE service = 'rake'
-         AND datetime(timestamp) > datetime('now', '-1 hour')"
-    )
-    .fetch_one(&pool)
+#[tauri::command]
+async fn get_rake_metrics(
+    limit: Option<i64>,
+    hours: Option<i64>,
+    comparison: Option<ComparisonWindow>,
+) -> CommandResult<RakeMetrics> {
+    error::command("get_rake_metrics", async move {
+        let pool = get_db_pool().await?;
+        let window = TimeWindow::last_hours_or_all(hours);
+
+        let mut metrics = query_rake_metrics(&pool, &window, limit).await.in_service("rake")?;
+
+        if let Some(comparison) = comparison {
+            let previous_window = window.comparison(&comparison)?;
+            let previous = query_rake_metrics(&pool, &previous_window, limit).await.in_service("rake")?;
+            metrics.comparison = Some(Box::new(PeriodComparison::new(&metrics, previous, previous_window)));
+        }
+
+        Ok(metrics)
+    })
     .await
-    .map_err(|e| e.to_string())?
-    .get::<i64, _>("count");
+}
 
-    // Calculate ingestion rate (records per hour)
-    let ingestion_rate = sqlx::query(
-        "SELECT COUNT(*) / 24.0 as rate
+async fn query_rake_metrics(
+    pool: &SqlitePool,
+    window: &TimeWindow,
+    limit: Option<i64>,
+) -> CommandResult<RakeMetrics> {
+    // Run totals per pipeline; the overall totals add these up
+    let pipelines = rake::fetch_pipeline_stats(pool, window, None).await?;
+    let queue = rake::fetch_queue_stats(pool, window).await?;
+    let freshness = rake::fetch_freshness(pool, window).await?;
+
+    // Pipelines with any event in the last hour of the window
+    let active: BTreeSet<String> = sqlx::query_as::<_, (String,)>(
+        "SELECT DISTINCT json_extract(metrics, '$.pipeline_id') as pipeline_id
          FROM events
          WHERE service = 'rake'
-         AND event_type = 'ingestion_complete'
-         AND datetime(timestamp) > datetime('now', '-24 hours')"
+         AND timestamp > ?
+         AND timestamp <= ?
+         AND json_extract(metrics, '$.pipeline_id') IS NOT NULL"
     )
-    .fetch_one(&pool)
+    .bind(period::column_bound(window.end - Duration::hours(1)))
+    .bind(window.end_bound())
+    .fetch_all_timed(pool)
     .await
-    .map_err(|e| e.to_string())?
-    .get::<Option<f64>, _>("rate")
-    .unwrap_or(0.0);
+    .in_query("rake_active_pipelines")?
+    .into_iter()
+    .map(|(pipeline_id,)| pipeline_id)
+    .collect();
 
-    // Calculate error rate
+    // Calculate error rate over the 24 hours ending the window
     let error_rate = sqlx::query(
         "SELECT
             CAST(SUM(CASE WHEN severity = 'error' THEN 1 ELSE 0 END) AS FLOAT) /
             NULLIF(COUNT(*), 0) * 100.0 as error_rate
          FROM events
          WHERE service = 'rake'
-         AND datetime(timestamp) > datetime('now', '-24 hours')"
+         AND timestamp > ?
+         AND timestamp <= ?"
     )
-    .fetch_one(&pool)
+    .bind(period::column_bound(window.end - Duration::hours(24)))
+    .bind(window.end_bound())
+    .fetch_one_timed(pool)
     .await
-    .map_err(|e| e.to_string())?
+    .in_query("rake_error_rate")?
     .get::<Option<f64>, _>("error_rate")
     .unwrap_or(0.0);
 
-    // Get recent pipelines (top 5 by activity)
-    let recent_pipelines = sqlx::query_as::<_, (String, String, String, i64, String)>(
-        "SELECT
-            COALESCE(json_extract(metrics, '$.pipeline_id'), 'unknown') as pipeline_id,
-            COALESCE(json_extract(metrics, '$.pipeline_name'), 'Unknown Pipeline') as pipeline_name,
-            CASE
-                WHEN MAX(datetime(timestamp)) > datetime('now', '-1 hour') THEN 'active'
-                ELSE 'idle'
-            END as status,
-            COUNT(*) as records_processed,
-            MAX(timestamp) as last_run
-         FROM events
-         WHERE service = 'rake'
-         GROUP BY pipeline_id
-         ORDER BY records_processed DESC
-         LIMIT 5"
-    )
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| e.to_string())?
-    .into_iter()
-    .map(|(pipeline_id, pipeline_name, status, records_processed, last_run)| PipelineInfo {
-        pipeline_id,
-        pipeline_name,
-        status,
-        records_processed,
-        last_run,
-    })
-    .collect();
+    let total_runs: i64 = pipelines.iter().map(|p| p.runs).sum();
+    let successful_runs: i64 = pipelines.iter().map(|p| p.successful_runs).sum();
+    let records_ingested: i64 = pipelines.iter().map(|p| p.records).sum();
+    let total_duration_ms: f64 = pipelines.iter().map(|p| p.total_duration_ms).sum();
+    let timed_runs: i64 = pipelines.iter().map(|p| p.timed_runs).sum();
+
+    // An all-time window is measured from the first run instead
+    let span = if window.is_bounded() {
+        window.duration()
+    } else {
+        pipelines
+            .iter()
+            .map(|p| p.first_run.as_str())
+            .min()
+            .and_then(|first| ingest::parse_timestamp(first).ok())
+            .map_or(Duration::zero(), |first| window.end - first.naive_utc())
+    };
+    let span_hours = (span.num_seconds() as f64 / 3600.0).max(1.0);
+
+    let recent_pipelines = pipelines
+        .iter()
+        .take(limit.filter(|limit| *limit >= 0).map_or(usize::MAX, |limit| limit as usize))
+        .map(|p| PipelineInfo {
+            pipeline_id: p.pipeline_id.clone(),
+            pipeline_name: p.pipeline_name.clone(),
+            status: if active.contains(&p.pipeline_id) { "active" } else { "idle" }.to_string(),
+            runs: p.runs,
+            successful_runs: p.successful_runs,
+            failed_runs: p.failed_runs,
+            records_processed: p.records,
+            avg_run_duration_ms: p.avg_duration_ms(),
+            last_run: p.last_run.clone(),
+        })
+        .collect();
 
     Ok(RakeMetrics {
-        total_pipelines,
-        active_pipelines,
+        total_pipelines: pipelines.len() as i64,
+        active_pipelines: active.len() as i64,
+        total_runs,
+        successful_runs,
+        failed_runs: total_runs - successful_runs,
+        success_rate: safe_ratio(successful_runs as f64 * 100.0, total_runs as f64),
         records_ingested,
-        ingestion_rate,
+        ingestion_rate: records_ingested as f64 / span_hours,
+        avg_run_duration_ms: safe_ratio(total_duration_ms, timed_runs as f64),
         error_rate,
+        queued_jobs: queue.queued_jobs,
+        running_jobs: queue.running_jobs,
+        avg_queue_wait_ms: queue.avg_wait_ms,
+        queue_wait_p50_ms: queue.wait_p50_ms,
+        queue_wait_p95_ms: queue.wait_p95_ms,
+        queue_wait_p99_ms: queue.wait_p99_ms,
+        freshness,
         recent_pipelines,
+        comparison: None,
     })
 }
 
 #[tauri::command]
-async fn get_ingestion_over_time(hours: i64) -> Result<IngestionOverTime, String> {
-    let pool = get_db_pool().await?;
-
-    let datapoints: Vec<TimeSeriesPoint> = sqlx::query(
-        "SELECT
-            strftime('%Y-%m-%d %H:00:00', timestamp) as hour,
-            COUNT(*) as count
-         FROM events
-         WHERE service = 'rake'
-         AND event_type = 'ingestion_complete'
-         AND datetime(timestamp) > datetime('now', ? || ' hours')
-         GROUP BY hour
-         ORDER BY hour ASC"
-    )
-    .bind(format!("-{}", hours))
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| e.to_string())?
-    .into_iter()
-    .map(|row| TimeSeriesPoint {
-        timestamp: row.get::<String, _>("hour"),
-        value: row.get::<i64, _>("count") as f64,
+async fn get_ingestion_over_time(
+    hours: i64,
+    anomaly: Option<AnomalyMethod>,
+    comparison: Option<ComparisonWindow>,
+) -> CommandResult<IngestionOverTime> {
+    error::command("get_ingestion_over_time", async move {
+        let pool = get_db_pool().await?;
+        let window = TimeWindow::last_hours(hours);
+        let records_field = rake::records_field()?;
+        let (series, query) = ingestion_series(&records_field);
+
+        let datapoints = fetch_series(&pool, &series, &query, &window).await?;
+        let comparison_datapoints = fetch_comparison_series(&pool, &series, &query, &window, comparison).await?;
+        let anomalies =
+            detect_series_anomalies(&pool, &series, &query, &window, &datapoints, anomaly.unwrap_or_default()).await?;
+
+        Ok(IngestionOverTime {
+            datapoints,
+            comparison_datapoints,
+            anomalies,
+        })
     })
-    .collect();
-
-    Ok(IngestionOverTime { datapoints })
+    .await
 }
 
 #[tauri::command]
-async fn get_error_rate_over_time(hours: i64) -> Result<ErrorRateOverTime, String> {
-    let pool = get_db_pool().await?;
+async fn get_error_rate_over_time(
+    hours: i64,
+    anomaly: Option<AnomalyMethod>,
+    comparison: Option<ComparisonWindow>,
+) -> CommandResult<ErrorRateOverTime> {
+    error::command("get_error_rate_over_time", async move {
+        let pool = get_db_pool().await?;
+        let window = TimeWindow::last_hours(hours);
+
+        let datapoints = fetch_series(&pool, &RAKE_ERROR_RATE_SERIES, RAKE_ERROR_RATE_QUERY, &window).await?;
+        let comparison_datapoints = fetch_comparison_series(&pool, &RAKE_ERROR_RATE_SERIES, RAKE_ERROR_RATE_QUERY, &window, comparison).await?;
+        let anomalies =
+            detect_series_anomalies(&pool, &RAKE_ERROR_RATE_SERIES, RAKE_ERROR_RATE_QUERY, &window, &datapoints, anomaly.unwrap_or_default()).await?;
+
+        Ok(ErrorRateOverTime {
+            datapoints,
+            comparison_datapoints,
+            anomalies,
+        })
+    })
+    .await
+}
 
-    let datapoints: Vec<TimeSeriesPoint> = sqlx::query(
-        "SELECT
-            strftime('%Y-%m-%d %H:00:00', timestamp) as hour,
-            CAST(SUM(CASE WHEN severity = 'error' THEN 1 ELSE 0 END) AS FLOAT) /
-            NULLIF(COUNT(*), 0) * 100.0 as error_rate
-         FROM events
-         WHERE service = 'rake'
-         AND datetime(timestamp) > datetime('now', ? || ' hours')
-         GROUP BY hour
-         ORDER BY hour ASC"
-    )
-    .bind(format!("-{}", hours))
-    .fetch_all(&pool)
+/// Hourly series of a metric scraped from a service's Prometheus endpoint:
+/// increases per hour for counters, hourly means for gauges.
+#[tauri::command]
+async fn get_scraped_metric_over_time(
+    service: String,
+    metric: String,
+    hours: i64,
+    anomaly: Option<AnomalyMethod>,
+    comparison: Option<ComparisonWindow>,
+) -> CommandResult<ScrapedMetricOverTime> {
+    error::command("get_scraped_metric_over_time", async move {
+        let window = TimeWindow::last_hours(hours);
+        let kind = scrape::series_kind(&service, &metric).await?;
+
+        let aggregate = kind.aggregate(&metric);
+        // Counter increases are zero in an hour without scrapes
+        let fill = |points, window: &TimeWindow| {
+            if aggregate.is_additive() {
+                anomaly::fill_hourly_gaps(points, window, "%Y-%m-%d %H:00:00")
+            } else {
+                points
+            }
+        };
+
+        let datapoints = fill(rollup::fetch_scraped_series(&service, aggregate, &window).await?, &window);
+        let comparison_datapoints = match comparison {
+            Some(comparison) => {
+                let previous_window = window.comparison(&comparison)?;
+                let points = rollup::fetch_scraped_series(&service, aggregate, &previous_window).await?;
+                period::shift_series(fill(points, &previous_window), &previous_window, &window)
+            }
+            None => Vec::new(),
+        };
+        let scraped = service.as_str();
+        let anomalies = anomaly::detect_with_history(&datapoints, &window, anomaly.unwrap_or_default(), |history| async move {
+            Ok(fill(rollup::fetch_scraped_series(scraped, aggregate, &history).await?, &history))
+        })
+        .await?;
+
+        Ok(ScrapedMetricOverTime {
+            service,
+            metric,
+            kind,
+            datapoints,
+            comparison_datapoints,
+            anomalies,
+        })
+    })
     .await
-    .map_err(|e| e.to_string())?
-    .into_iter()
-    .map(|row| TimeSeriesPoint {
-        timestamp: row.get::<String, _>("hour"),
-        value: row.get::<Option<f64>, _>("error_rate").unwrap_or(0.0),
+}
+
+#[tauri::command]
+async fn get_anomaly_alerts(
+    hours: i64,
+    anomaly: Option<AnomalyMethod>,
+) -> CommandResult<Vec<AnomalyAlert>> {
+    error::command("get_anomaly_alerts", async move {
+        let pool = get_db_pool().await?;
+        let window = TimeWindow::last_hours(hours);
+
+        query_anomaly_alerts(&pool, &window, anomaly.unwrap_or_default()).await
     })
-    .collect();
+    .await
+}
+
+/// Alerts for every anomaly `method` finds in the alerting series over
+/// `window`, newest first.
+async fn query_anomaly_alerts(
+    pool: &SqlitePool,
+    window: &TimeWindow,
+    method: AnomalyMethod,
+) -> CommandResult<Vec<AnomalyAlert>> {
+    let records_field = rake::records_field()?;
+    let (ingestion, ingestion_query) = ingestion_series(&records_field);
+
+    let series: [(&str, &str, &RollupSeries, &str); 6] = [
+        ("Token cost", "USD", &COST_SERIES, COST_QUERY),
+        ("Token usage", "tokens", &TOKENS_SERIES, TOKENS_QUERY),
+        ("Search latency", "ms", &SEARCH_LATENCY_SERIES, SEARCH_LATENCY_QUERY),
+        ("Agent latency", "ms", &AGENT_LATENCY_SERIES, AGENT_LATENCY_QUERY),
+        ("Ingestion volume", "records", &ingestion, &ingestion_query),
+        ("Error rate", "percentage", &RAKE_ERROR_RATE_SERIES, RAKE_ERROR_RATE_QUERY),
+    ];
+
+    let mut alerts = Vec::new();
+    for (name, unit, series, query) in series {
+        let datapoints = fetch_series(pool, series, query, window).await?;
+        let markers = detect_series_anomalies(pool, series, query, window, &datapoints, method).await?;
+        let series_alerts = anomaly::to_alerts(series.service, name, unit, method, &markers);
+
+        // The feed is a record, not a dependency of the alerts themselves
+        if let Err(e) = anomaly::record_alerts(name, &series_alerts).await {
+            eprintln!("Alert feed write failed: {}", e);
+        }
+        alerts.extend(series_alerts);
+    }
 
-    Ok(ErrorRateOverTime { datapoints })
+    // Newest first, matching the recent events feed
+    alerts.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+
+    Ok(alerts)
 }
 
 // ===========================================================================
@@ -735,7 +1571,31 @@ async fn get_error_rate_over_time(hours: i64) -> Result<ErrorRateOverTime, Strin
 // ===========================================================================
 
 fn main() {
+    match cli::launch(env::args_os()) {
+        cli::Launch::Window => {}
+        cli::Launch::Headless(cli) => std::process::exit(cli::run(*cli)),
+        cli::Launch::Usage(error) => error.exit(),
+    }
+
     tauri::Builder::default()
+        .setup(|_app| {
+            tauri::async_runtime::spawn(scheduler::run_scheduler());
+            tauri::async_runtime::spawn(logtail::run_collector());
+            tauri::async_runtime::spawn(scrape::run_collector());
+            let otlp_config = otlp::OtlpConfig::from_env().unwrap_or_else(|e| {
+                eprintln!("OTLP receiver disabled: {}", e);
+                otlp::OtlpConfig::default()
+            });
+            // The receivers need the local event store before they write to it
+            tauri::async_runtime::spawn(async move {
+                otlp::prepare_event_store(&otlp_config).await;
+                tauri::async_runtime::spawn(otlp::run_receivers(otlp_config));
+                advisor::run_startup_check().await;
+                schema::run_startup_check().await;
+                rollup::run_rollup_refresh().await;
+            });
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             get_system_health,
             get_recent_events,
@@ -743,6 +1603,7 @@ fn main() {
             get_neuroforge_metrics,
             get_cost_over_time,
             get_token_usage_over_time,
+            get_token_usage_by_model_over_time,
             get_search_performance_over_time,
             get_forgeagents_metrics,
             get_agent_activity_over_time,
@@ -750,6 +1611,37 @@ fn main() {
             get_rake_metrics,
             get_ingestion_over_time,
             get_error_rate_over_time,
+            rake::get_queue_depth_over_time,
+            rake::get_queue_wait_over_time,
+            get_scraped_metric_over_time,
+            get_anomaly_alerts,
+            anomaly::get_alert_feed,
+            advisor::get_index_report,
+            advisor::apply_index_suggestions,
+            advisor::get_slow_queries,
+            breakdown::get_breakdown,
+            export::export_events,
+            export::export_series,
+            export::cancel_export,
+            insights::get_daily_insights,
+            insights::get_insights_history,
+            insights::generate_insights_now,
+            insights::get_cost_projection,
+            rake::get_pipeline_runs,
+            rake::get_pipeline_detail,
+            report::generate_report_now,
+            report::get_latest_reports,
+            rollup::get_rollup_status,
+            rollup::set_rollup_retention,
+            scheduler::list_jobs,
+            scheduler::run_job_now,
+            scheduler::pause_job,
+            scheduler::resume_job,
+            scheduler::get_job_runs,
+            schema::get_schema_report,
+            logtail::get_log_tail_status,
+            scrape::get_scrape_status,
+            scrape::get_scraped_metrics,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
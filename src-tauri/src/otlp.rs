@@ -0,0 +1,713 @@
+// ===========================================================================
+// OTLP Receiver
+// ===========================================================================
+//
+// Accepts OpenTelemetry logs, metrics and traces over OTLP/HTTP (protobuf or
+// JSON, optionally gzipped) and OTLP/gRPC, maps them onto the events model
+// and writes them to the local event store.
+//
+// Mapping:
+// - service: the resource's `service.name`, lowercased; records without one
+//   are rejected
+// - event_type: an `event_type` attribute if present, else the log's
+//   `event.name` attribute, the span name or the metric name
+// - attributes: `metrics.*` and `metadata.*` keys go to that column with the
+//   prefix stripped; other numeric attributes go to metrics, the rest to
+//   metadata
+// - severity: `error`, `warning`, `info` or `debug`, from the log severity or
+//   the span status; metric points are `info`
+
+use std::env;
+use std::io::Read;
+use std::net::SocketAddr;
+
+use axum::body::Bytes;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use chrono::{DateTime, Utc};
+use opentelemetry_proto::tonic::collector::logs::v1::{
+    logs_service_server::{LogsService, LogsServiceServer},
+    ExportLogsPartialSuccess, ExportLogsServiceRequest, ExportLogsServiceResponse,
+};
+use opentelemetry_proto::tonic::collector::metrics::v1::{
+    metrics_service_server::{MetricsService, MetricsServiceServer},
+    ExportMetricsPartialSuccess, ExportMetricsServiceRequest, ExportMetricsServiceResponse,
+};
+use opentelemetry_proto::tonic::collector::trace::v1::{
+    trace_service_server::{TraceService, TraceServiceServer},
+    ExportTracePartialSuccess, ExportTraceServiceRequest, ExportTraceServiceResponse,
+};
+use opentelemetry_proto::tonic::common::v1::{any_value, AnyValue, KeyValue};
+use opentelemetry_proto::tonic::metrics::v1::{metric, number_data_point};
+use opentelemetry_proto::tonic::resource::v1::Resource;
+use opentelemetry_proto::tonic::trace::v1::status::StatusCode as SpanStatusCode;
+use prost::Message;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Number, Value};
+
+use crate::clock;
+use crate::error::{CommandResult, ForgeCommandError};
+use crate::event_store::{self, StoredEvent};
+
+const DEFAULT_HTTP_BIND: &str = "127.0.0.1:4318";
+const DEFAULT_GRPC_BIND: &str = "127.0.0.1:4317";
+
+/// Which receivers to run. Either may be off.
+#[derive(Debug, Clone, Default)]
+pub struct OtlpConfig {
+    pub http: Option<SocketAddr>,
+    pub grpc: Option<SocketAddr>,
+}
+
+impl OtlpConfig {
+    /// Reads `FORGE_COMMAND_OTLP_HTTP`/`FORGE_COMMAND_OTLP_GRPC`; a receiver
+    /// runs only if its address is set.
+    pub fn from_env() -> Result<Self, String> {
+        Ok(OtlpConfig {
+            http: env_bind("FORGE_COMMAND_OTLP_HTTP")?,
+            grpc: env_bind("FORGE_COMMAND_OTLP_GRPC")?,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.http.is_some() || self.grpc.is_some()
+    }
+
+    /// Fills unset receivers with the standard OTLP ports on localhost.
+    pub fn with_defaults(self) -> Self {
+        OtlpConfig {
+            http: self.http.or_else(|| DEFAULT_HTTP_BIND.parse().ok()),
+            grpc: self.grpc.or_else(|| DEFAULT_GRPC_BIND.parse().ok()),
+        }
+    }
+}
+
+fn env_bind(name: &str) -> Result<Option<SocketAddr>, String> {
+    match env::var(name) {
+        Ok(bind) if !bind.is_empty() => bind
+            .parse()
+            .map(Some)
+            .map_err(|e| format!("Invalid {} {:?}: {}", name, bind, e)),
+        _ => Ok(None),
+    }
+}
+
+// ===========================================================================
+// Mapping
+// ===========================================================================
+
+/// Events mapped from one export request, plus what had to be dropped.
+#[derive(Default)]
+struct Converted {
+    events: Vec<StoredEvent>,
+    rejected: i64,
+}
+
+impl Converted {
+    fn reject_message(&self) -> String {
+        if self.rejected == 0 {
+            String::new()
+        } else {
+            format!(
+                "{} record(s) rejected: resource has no service.name attribute",
+                self.rejected
+            )
+        }
+    }
+}
+
+fn service_name(resource: &Option<Resource>) -> Option<String> {
+    resource
+        .as_ref()?
+        .attributes
+        .iter()
+        .find(|attribute| attribute.key == "service.name")
+        .and_then(|attribute| match any_to_json(attribute.value.as_ref()) {
+            Value::String(name) if !name.is_empty() => Some(name.to_lowercase()),
+            _ => None,
+        })
+}
+
+fn any_to_json(value: Option<&AnyValue>) -> Value {
+    match value.and_then(|value| value.value.as_ref()) {
+        Some(any_value::Value::StringValue(value)) => Value::String(value.clone()),
+        Some(any_value::Value::BoolValue(value)) => Value::Bool(*value),
+        Some(any_value::Value::IntValue(value)) => Value::from(*value),
+        Some(any_value::Value::DoubleValue(value)) => number(*value),
+        Some(any_value::Value::ArrayValue(array)) => Value::Array(
+            array
+                .values
+                .iter()
+                .map(|value| any_to_json(Some(value)))
+                .collect(),
+        ),
+        Some(any_value::Value::KvlistValue(list)) => Value::Object(
+            list.values
+                .iter()
+                .map(|kv| (kv.key.clone(), any_to_json(kv.value.as_ref())))
+                .collect(),
+        ),
+        Some(any_value::Value::BytesValue(bytes)) => Value::String(hex(bytes)),
+        None => Value::Null,
+    }
+}
+
+/// NaN and infinities have no JSON form and are stored as null.
+fn number(value: f64) -> Value {
+    Number::from_f64(value).map_or(Value::Null, Value::Number)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn timestamp(nanos: u64, fallback: u64) -> DateTime<Utc> {
+    let nanos = if nanos == 0 { fallback } else { nanos };
+    if nanos == 0 {
+        return clock::now();
+    }
+    DateTime::from_timestamp(
+        (nanos / 1_000_000_000) as i64,
+        (nanos % 1_000_000_000) as u32,
+    )
+    .unwrap_or_else(clock::now)
+}
+
+/// Sorts attributes into metrics and metadata; returns the `event_type`
+/// attribute if one was given.
+fn apply_attributes(event: &mut StoredEvent, attributes: &[KeyValue]) -> Option<String> {
+    let mut event_type = None;
+
+    for attribute in attributes {
+        let value = any_to_json(attribute.value.as_ref());
+        let key = attribute.key.as_str();
+
+        if key == "event_type" {
+            event_type = value.as_str().map(str::to_string);
+        } else if let Some(key) = key.strip_prefix("metrics.") {
+            event.metrics.insert(key.to_string(), value);
+        } else if let Some(key) = key.strip_prefix("metadata.") {
+            event.metadata.insert(key.to_string(), value);
+        } else if value.is_number() {
+            event.metrics.insert(key.to_string(), value);
+        } else {
+            event.metadata.insert(key.to_string(), value);
+        }
+    }
+
+    event_type
+}
+
+/// OTLP severity numbers: 1-8 trace/debug, 9-12 info, 13-16 warn, 17+ error/fatal.
+fn log_severity(number: i32, text: &str) -> &'static str {
+    match number {
+        17.. => "error",
+        13..=16 => "warning",
+        9..=12 => "info",
+        1..=8 => "debug",
+        _ => event_store::normalize_severity(text),
+    }
+}
+
+fn convert_logs(request: ExportLogsServiceRequest) -> Converted {
+    let mut converted = Converted::default();
+
+    for resource_logs in request.resource_logs {
+        let records = resource_logs
+            .scope_logs
+            .into_iter()
+            .flat_map(|scope| scope.log_records);
+        let Some(service) = service_name(&resource_logs.resource) else {
+            converted.rejected += records.count() as i64;
+            continue;
+        };
+
+        for record in records {
+            let mut event = StoredEvent::new(
+                &service,
+                "log",
+                log_severity(record.severity_number, &record.severity_text),
+                timestamp(record.time_unix_nano, record.observed_time_unix_nano),
+            );
+            let event_type = apply_attributes(&mut event, &record.attributes);
+            event.event_type = event_type
+                .or_else(|| {
+                    event
+                        .metadata
+                        .remove("event.name")?
+                        .as_str()
+                        .map(str::to_string)
+                })
+                .unwrap_or(event.event_type);
+
+            if record.body.is_some() {
+                event
+                    .metadata
+                    .insert("message".to_string(), any_to_json(record.body.as_ref()));
+            }
+            if !record.trace_id.is_empty() {
+                event
+                    .metadata
+                    .insert("trace_id".to_string(), Value::String(hex(&record.trace_id)));
+                event
+                    .metadata
+                    .insert("span_id".to_string(), Value::String(hex(&record.span_id)));
+            }
+            converted.events.push(event);
+        }
+    }
+
+    converted
+}
+
+fn convert_traces(request: ExportTraceServiceRequest) -> Converted {
+    let mut converted = Converted::default();
+
+    for resource_spans in request.resource_spans {
+        let spans = resource_spans
+            .scope_spans
+            .into_iter()
+            .flat_map(|scope| scope.spans);
+        let Some(service) = service_name(&resource_spans.resource) else {
+            converted.rejected += spans.count() as i64;
+            continue;
+        };
+
+        for span in spans {
+            let failed = span
+                .status
+                .as_ref()
+                .is_some_and(|status| status.code == SpanStatusCode::Error as i32);
+            let mut event = StoredEvent::new(
+                &service,
+                &span.name,
+                if failed { "error" } else { "info" },
+                timestamp(span.end_time_unix_nano, span.start_time_unix_nano),
+            );
+            // Span ids are unique per trace, so a re-sent span maps to the same event
+            event.event_id = format!("{}{}", hex(&span.trace_id), hex(&span.span_id));
+            if let Some(event_type) = apply_attributes(&mut event, &span.attributes) {
+                event.event_type = event_type;
+            }
+
+            if span.end_time_unix_nano >= span.start_time_unix_nano && span.start_time_unix_nano > 0
+            {
+                let duration_ms =
+                    (span.end_time_unix_nano - span.start_time_unix_nano) as f64 / 1_000_000.0;
+                event
+                    .metrics
+                    .entry("duration_ms")
+                    .or_insert_with(|| number(duration_ms));
+            }
+            event
+                .metadata
+                .insert("trace_id".to_string(), Value::String(hex(&span.trace_id)));
+            event
+                .metadata
+                .insert("span_id".to_string(), Value::String(hex(&span.span_id)));
+            if !span.parent_span_id.is_empty() {
+                event.metadata.insert(
+                    "parent_span_id".to_string(),
+                    Value::String(hex(&span.parent_span_id)),
+                );
+            }
+            if let Some(status) = span.status.filter(|status| !status.message.is_empty()) {
+                event
+                    .metadata
+                    .insert("status_message".to_string(), Value::String(status.message));
+            }
+            converted.events.push(event);
+        }
+    }
+
+    converted
+}
+
+/// One event per data point: the value (or histogram/summary aggregates)
+/// under metrics, the metric's name and unit under metadata.
+fn convert_metrics(request: ExportMetricsServiceRequest) -> Converted {
+    let mut converted = Converted::default();
+
+    for resource_metrics in request.resource_metrics {
+        let metrics: Vec<_> = resource_metrics
+            .scope_metrics
+            .into_iter()
+            .flat_map(|scope| scope.metrics)
+            .collect();
+        let Some(service) = service_name(&resource_metrics.resource) else {
+            converted.rejected += metrics.iter().map(data_point_count).sum::<i64>();
+            continue;
+        };
+
+        for metric in metrics {
+            let points: Vec<(u64, &[KeyValue], Map<String, Value>)> = match &metric.data {
+                Some(metric::Data::Gauge(gauge)) => gauge
+                    .data_points
+                    .iter()
+                    .map(|point| {
+                        (
+                            point.time_unix_nano,
+                            &point.attributes[..],
+                            number_value(&point.value),
+                        )
+                    })
+                    .collect(),
+                Some(metric::Data::Sum(sum)) => sum
+                    .data_points
+                    .iter()
+                    .map(|point| {
+                        (
+                            point.time_unix_nano,
+                            &point.attributes[..],
+                            number_value(&point.value),
+                        )
+                    })
+                    .collect(),
+                Some(metric::Data::Histogram(histogram)) => histogram
+                    .data_points
+                    .iter()
+                    .map(|point| {
+                        let values = aggregates(point.count, point.sum, point.min, point.max);
+                        (point.time_unix_nano, &point.attributes[..], values)
+                    })
+                    .collect(),
+                Some(metric::Data::ExponentialHistogram(histogram)) => histogram
+                    .data_points
+                    .iter()
+                    .map(|point| {
+                        let values = aggregates(point.count, point.sum, point.min, point.max);
+                        (point.time_unix_nano, &point.attributes[..], values)
+                    })
+                    .collect(),
+                Some(metric::Data::Summary(summary)) => summary
+                    .data_points
+                    .iter()
+                    .map(|point| {
+                        let mut values = aggregates(point.count, Some(point.sum), None, None);
+                        for quantile in &point.quantile_values {
+                            values.insert(
+                                format!("p{}", quantile.quantile * 100.0),
+                                number(quantile.value),
+                            );
+                        }
+                        (point.time_unix_nano, &point.attributes[..], values)
+                    })
+                    .collect(),
+                None => Vec::new(),
+            };
+
+            for (time, attributes, values) in points {
+                let mut event =
+                    StoredEvent::new(&service, &metric.name, "info", timestamp(time, 0));
+                if let Some(event_type) = apply_attributes(&mut event, attributes) {
+                    event.event_type = event_type;
+                }
+                event.metrics.extend(values);
+                event
+                    .metadata
+                    .insert("metric".to_string(), Value::String(metric.name.clone()));
+                if !metric.unit.is_empty() {
+                    event
+                        .metadata
+                        .insert("unit".to_string(), Value::String(metric.unit.clone()));
+                }
+                converted.events.push(event);
+            }
+        }
+    }
+
+    converted
+}
+
+fn data_point_count(metric: &opentelemetry_proto::tonic::metrics::v1::Metric) -> i64 {
+    (match &metric.data {
+        Some(metric::Data::Gauge(gauge)) => gauge.data_points.len(),
+        Some(metric::Data::Sum(sum)) => sum.data_points.len(),
+        Some(metric::Data::Histogram(histogram)) => histogram.data_points.len(),
+        Some(metric::Data::ExponentialHistogram(histogram)) => histogram.data_points.len(),
+        Some(metric::Data::Summary(summary)) => summary.data_points.len(),
+        None => 0,
+    }) as i64
+}
+
+fn number_value(value: &Option<number_data_point::Value>) -> Map<String, Value> {
+    let value = match value {
+        Some(number_data_point::Value::AsDouble(value)) => number(*value),
+        Some(number_data_point::Value::AsInt(value)) => Value::from(*value),
+        None => Value::Null,
+    };
+    Map::from_iter([("value".to_string(), value)])
+}
+
+fn aggregates(
+    count: u64,
+    sum: Option<f64>,
+    min: Option<f64>,
+    max: Option<f64>,
+) -> Map<String, Value> {
+    let mut values = Map::from_iter([("count".to_string(), Value::from(count))]);
+    for (name, value) in [("sum", sum), ("min", min), ("max", max)] {
+        if let Some(value) = value {
+            values.insert(name.to_string(), number(value));
+        }
+    }
+    values
+}
+
+// ===========================================================================
+// Export Handling
+// ===========================================================================
+
+/// One OTLP export request type, shared by the HTTP and gRPC receivers.
+trait Signal: Message + Default + DeserializeOwned + Send + 'static {
+    type Response: Message + Serialize;
+
+    fn convert(self) -> Converted;
+    fn response(rejected: i64, error_message: String) -> Self::Response;
+}
+
+impl Signal for ExportLogsServiceRequest {
+    type Response = ExportLogsServiceResponse;
+
+    fn convert(self) -> Converted {
+        convert_logs(self)
+    }
+
+    fn response(rejected: i64, error_message: String) -> Self::Response {
+        ExportLogsServiceResponse {
+            partial_success: (rejected > 0).then_some(ExportLogsPartialSuccess {
+                rejected_log_records: rejected,
+                error_message,
+            }),
+        }
+    }
+}
+
+impl Signal for ExportTraceServiceRequest {
+    type Response = ExportTraceServiceResponse;
+
+    fn convert(self) -> Converted {
+        convert_traces(self)
+    }
+
+    fn response(rejected: i64, error_message: String) -> Self::Response {
+        ExportTraceServiceResponse {
+            partial_success: (rejected > 0).then_some(ExportTracePartialSuccess {
+                rejected_spans: rejected,
+                error_message,
+            }),
+        }
+    }
+}
+
+impl Signal for ExportMetricsServiceRequest {
+    type Response = ExportMetricsServiceResponse;
+
+    fn convert(self) -> Converted {
+        convert_metrics(self)
+    }
+
+    fn response(rejected: i64, error_message: String) -> Self::Response {
+        ExportMetricsServiceResponse {
+            partial_success: (rejected > 0).then_some(ExportMetricsPartialSuccess {
+                rejected_data_points: rejected,
+                error_message,
+            }),
+        }
+    }
+}
+
+async fn ingest<S: Signal>(request: S) -> CommandResult<S::Response> {
+    let converted = request.convert();
+    event_store::insert_events(&converted.events).await?;
+    Ok(S::response(converted.rejected, converted.reject_message()))
+}
+
+// ===========================================================================
+// OTLP/HTTP
+// ===========================================================================
+
+async fn export_http<S: Signal>(headers: HeaderMap, body: Bytes) -> Response {
+    let header = |name: header::HeaderName| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let json = header(header::CONTENT_TYPE).starts_with("application/json");
+
+    let body = match header(header::CONTENT_ENCODING).as_str() {
+        "" | "identity" => body.to_vec(),
+        "gzip" => {
+            let mut decoded = Vec::new();
+            if let Err(e) = flate2::read::GzDecoder::new(&body[..]).read_to_end(&mut decoded) {
+                return (StatusCode::BAD_REQUEST, format!("Invalid gzip body: {}", e))
+                    .into_response();
+            }
+            decoded
+        }
+        other => {
+            return (
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                format!("Unsupported Content-Encoding {:?}", other),
+            )
+                .into_response()
+        }
+    };
+
+    let request = if json {
+        serde_json::from_slice::<S>(&body).map_err(|e| e.to_string())
+    } else {
+        S::decode(&body[..]).map_err(|e| e.to_string())
+    };
+    let request = match request {
+        Ok(request) => request,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                format!("Invalid OTLP payload: {}", e),
+            )
+                .into_response()
+        }
+    };
+
+    match ingest(request).await {
+        Ok(response) if json => (
+            [(header::CONTENT_TYPE, "application/json")],
+            serde_json::to_string(&response).unwrap_or_default(),
+        )
+            .into_response(),
+        Ok(response) => (
+            [(header::CONTENT_TYPE, "application/x-protobuf")],
+            response.encode_to_vec(),
+        )
+            .into_response(),
+        Err(e) => {
+            // 503 tells exporters to retry with backoff; anything else is dropped
+            let status = if e.retryable() {
+                StatusCode::SERVICE_UNAVAILABLE
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            (status, e.to_string()).into_response()
+        }
+    }
+}
+
+pub(crate) fn http_router() -> Router {
+    Router::new()
+        .route("/v1/logs", post(export_http::<ExportLogsServiceRequest>))
+        .route("/v1/traces", post(export_http::<ExportTraceServiceRequest>))
+        .route(
+            "/v1/metrics",
+            post(export_http::<ExportMetricsServiceRequest>),
+        )
+}
+
+async fn serve_http(bind: SocketAddr) -> Result<(), String> {
+    let listener = tokio::net::TcpListener::bind(bind)
+        .await
+        .map_err(|e| format!("Failed to bind {}: {}", bind, e))?;
+    eprintln!("OTLP/HTTP receiver listening on http://{}", bind);
+    axum::serve(listener, http_router())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ===========================================================================
+// OTLP/gRPC
+// ===========================================================================
+
+pub(crate) struct GrpcReceiver;
+
+fn grpc_status(error: ForgeCommandError) -> tonic::Status {
+    if error.retryable() {
+        tonic::Status::unavailable(error.to_string())
+    } else {
+        tonic::Status::internal(error.to_string())
+    }
+}
+
+async fn export_grpc<S: Signal>(
+    request: tonic::Request<S>,
+) -> Result<tonic::Response<S::Response>, tonic::Status> {
+    ingest(request.into_inner())
+        .await
+        .map(tonic::Response::new)
+        .map_err(grpc_status)
+}
+
+#[tonic::async_trait]
+impl LogsService for GrpcReceiver {
+    async fn export(
+        &self,
+        request: tonic::Request<ExportLogsServiceRequest>,
+    ) -> Result<tonic::Response<ExportLogsServiceResponse>, tonic::Status> {
+        export_grpc(request).await
+    }
+}
+
+#[tonic::async_trait]
+impl TraceService for GrpcReceiver {
+    async fn export(
+        &self,
+        request: tonic::Request<ExportTraceServiceRequest>,
+    ) -> Result<tonic::Response<ExportTraceServiceResponse>, tonic::Status> {
+        export_grpc(request).await
+    }
+}
+
+#[tonic::async_trait]
+impl MetricsService for GrpcReceiver {
+    async fn export(
+        &self,
+        request: tonic::Request<ExportMetricsServiceRequest>,
+    ) -> Result<tonic::Response<ExportMetricsServiceResponse>, tonic::Status> {
+        export_grpc(request).await
+    }
+}
+
+async fn serve_grpc(bind: SocketAddr) -> Result<(), String> {
+    eprintln!("OTLP/gRPC receiver listening on {}", bind);
+    tonic::transport::Server::builder()
+        .add_service(LogsServiceServer::new(GrpcReceiver))
+        .add_service(TraceServiceServer::new(GrpcReceiver))
+        .add_service(MetricsServiceServer::new(GrpcReceiver))
+        .serve(bind)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Creates the local event store up front, so `auto` source selection picks
+/// it up before the first event arrives.
+pub async fn prepare_event_store(config: &OtlpConfig) {
+    if config.is_enabled() {
+        if let Err(e) = event_store::get_event_store_pool().await {
+            eprintln!("OTLP receiver: {}", e);
+        }
+    }
+}
+
+/// Runs the configured receivers until the process exits. Failures are
+/// logged rather than fatal so the dashboards keep working without them.
+pub async fn run_receivers(config: OtlpConfig) {
+    let http = async {
+        if let Some(bind) = config.http {
+            if let Err(e) = serve_http(bind).await {
+                eprintln!("OTLP/HTTP receiver stopped: {}", e);
+            }
+        }
+    };
+    let grpc = async {
+        if let Some(bind) = config.grpc {
+            if let Err(e) = serve_grpc(bind).await {
+                eprintln!("OTLP/gRPC receiver stopped: {}", e);
+            }
+        }
+    };
+    tokio::join!(http, grpc);
+}
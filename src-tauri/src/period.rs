@@ -0,0 +1,297 @@
+// ===========================================================================
+// Time Windows & Period Comparison
+// ===========================================================================
+//
+// Queries take explicit [start, end] bounds instead of SQLite's 'now' so the
+// same query can run over a comparison window ("is this worse than last week?").
+
+use std::collections::BTreeMap;
+use std::sync::{PoisonError, RwLock};
+
+use chrono::{Duration, NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+
+use crate::anomaly::{parse_bucket_format, parse_bucket_timestamp};
+use crate::clock;
+use crate::error::{CommandResult, ForgeCommandError};
+use crate::TimeSeriesPoint;
+
+/// Format matching SQLite's `datetime()` output, so bounds compare as strings.
+pub const SQL_DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// How the events table writes `timestamp`. Time filters compare the raw
+/// column against bounds rendered in the same layout, which keeps them
+/// sargable (an index on `timestamp` can serve the range) where wrapping the
+/// column in `datetime()` would force a full scan.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampLayout {
+    /// `2025-01-10T14:30:00Z`, as DataForge writes it. Bounds carry the `Z`
+    /// too, so an event stamped exactly on a bound compares equal to it.
+    IsoT,
+    /// `2025-01-10 14:30:00`, SQLite's own `datetime()` format.
+    Space,
+}
+
+impl TimestampLayout {
+    fn format(self) -> &'static str {
+        match self {
+            TimestampLayout::IsoT => "%Y-%m-%dT%H:%M:%SZ",
+            TimestampLayout::Space => SQL_DATETIME_FORMAT,
+        }
+    }
+}
+
+/// The layout detected in the events table, with the source it was sampled from.
+static TIMESTAMP_LAYOUT: RwLock<Option<(String, TimestampLayout)>> = RwLock::new(None);
+
+/// Records the layout detected in the events table behind `source`.
+pub fn set_timestamp_layout(source: &str, layout: TimestampLayout) {
+    *TIMESTAMP_LAYOUT.write().unwrap_or_else(PoisonError::into_inner) = Some((source.to_string(), layout));
+}
+
+/// The events source the current layout was detected from, if any.
+pub fn timestamp_layout_source() -> Option<String> {
+    TIMESTAMP_LAYOUT
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_ref()
+        .map(|(source, _)| source.clone())
+}
+
+pub fn timestamp_layout() -> TimestampLayout {
+    TIMESTAMP_LAYOUT
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_ref()
+        .map_or(TimestampLayout::IsoT, |(_, layout)| *layout)
+}
+
+/// Renders `at` for comparison against the raw events `timestamp` column.
+pub fn column_bound(at: NaiveDateTime) -> String {
+    at.format(timestamp_layout().format()).to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeWindow {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+impl TimeWindow {
+    /// The `hours` leading up to now.
+    pub fn last_hours(hours: i64) -> Self {
+        let end = clock::now_naive();
+        TimeWindow {
+            start: end - Duration::hours(hours),
+            end,
+        }
+    }
+
+    /// Either the last `hours`, or everything up to now when no window is given.
+    pub fn last_hours_or_all(hours: Option<i64>) -> Self {
+        match hours {
+            Some(hours) => TimeWindow::last_hours(hours),
+            None => TimeWindow {
+                start: NaiveDateTime::MIN,
+                end: clock::now_naive(),
+            },
+        }
+    }
+
+    pub fn parse(range: &TimeRange) -> CommandResult<Self> {
+        let parse = |value: &str| {
+            parse_bucket_timestamp(value)
+                .or_else(|| {
+                    chrono::DateTime::parse_from_rfc3339(value)
+                        .ok()
+                        .map(|dt| dt.naive_utc())
+                })
+                .ok_or_else(|| {
+                    ForgeCommandError::invalid_input(format!("Invalid timestamp in range: {}", value))
+                })
+        };
+
+        let window = TimeWindow {
+            start: parse(&range.start)?,
+            end: parse(&range.end)?,
+        };
+
+        if window.start >= window.end {
+            return Err(ForgeCommandError::invalid_input(format!(
+                "Range start {} is not before end {}",
+                range.start, range.end
+            )));
+        }
+
+        Ok(window)
+    }
+
+    pub fn is_bounded(&self) -> bool {
+        self.start != NaiveDateTime::MIN
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+
+    pub fn start_sql(&self) -> String {
+        if self.is_bounded() {
+            self.start.format(SQL_DATETIME_FORMAT).to_string()
+        } else {
+            // NaiveDateTime::MIN formats with a negative year SQLite can't compare
+            "0000-01-01 00:00:00".to_string()
+        }
+    }
+
+    pub fn end_sql(&self) -> String {
+        self.end.format(SQL_DATETIME_FORMAT).to_string()
+    }
+
+    /// Lower bound for filtering the events `timestamp` column directly.
+    pub fn start_bound(&self) -> String {
+        if self.is_bounded() {
+            column_bound(self.start)
+        } else {
+            "0000-01-01 00:00:00".to_string()
+        }
+    }
+
+    /// Upper bound for filtering the events `timestamp` column directly.
+    pub fn end_bound(&self) -> String {
+        column_bound(self.end)
+    }
+
+    /// Inclusive lower bound for hourly series: the start of the hour the
+    /// window starts in, so the first bucket always covers its whole hour,
+    /// as it does when served from rollups.
+    pub fn hour_start_bound(&self) -> String {
+        if self.is_bounded() {
+            column_bound(self.start.date().and_hms_opt(self.start.hour(), 0, 0).unwrap_or(self.start))
+        } else {
+            self.start_bound()
+        }
+    }
+
+    pub fn to_range(self) -> TimeRange {
+        TimeRange {
+            start: self.start_sql(),
+            end: self.end_sql(),
+        }
+    }
+
+    /// Resolves the window to compare this one against.
+    pub fn comparison(&self, comparison: &ComparisonWindow) -> CommandResult<TimeWindow> {
+        match comparison {
+            ComparisonWindow::Custom { range } => TimeWindow::parse(range),
+            _ if !self.is_bounded() => {
+                Err(ForgeCommandError::invalid_input(
+                    "Period comparison requires a bounded time window (pass hours)",
+                ))
+            }
+            ComparisonWindow::PreviousPeriod => Ok(TimeWindow {
+                start: self.start - self.duration(),
+                end: self.start,
+            }),
+            ComparisonWindow::SamePeriodLastWeek => Ok(TimeWindow {
+                start: self.start - Duration::weeks(1),
+                end: self.end - Duration::weeks(1),
+            }),
+        }
+    }
+}
+
+/// A time range as exchanged with the frontend (`YYYY-MM-DD HH:MM:SS` or RFC 3339).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TimeRange {
+    pub start: String,
+    pub end: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ComparisonWindow {
+    PreviousPeriod,
+    SamePeriodLastWeek,
+    Custom { range: TimeRange },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MetricDelta {
+    pub current: f64,
+    pub previous: f64,
+    pub absolute: f64,
+    /// `None` when the previous value was zero.
+    pub percent: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PeriodComparison<T> {
+    pub range: TimeRange,
+    pub metrics: T,
+    pub deltas: BTreeMap<String, MetricDelta>,
+}
+
+impl<T: Serialize> PeriodComparison<T> {
+    /// Builds deltas for every top-level numeric field shared by both snapshots.
+    pub fn new<C: Serialize>(current: &C, previous: T, window: TimeWindow) -> Self {
+        let current_json = serde_json::to_value(current).unwrap_or_default();
+        let previous_json = serde_json::to_value(&previous).unwrap_or_default();
+
+        let deltas = match (current_json.as_object(), previous_json.as_object()) {
+            (Some(current_fields), Some(previous_fields)) => current_fields
+                .iter()
+                .filter_map(|(name, value)| {
+                    let current = value.as_f64()?;
+                    let previous = previous_fields.get(name)?.as_f64()?;
+                    Some((name.clone(), MetricDelta::new(current, previous)))
+                })
+                .collect(),
+            _ => BTreeMap::new(),
+        };
+
+        PeriodComparison {
+            range: window.to_range(),
+            metrics: previous,
+            deltas,
+        }
+    }
+}
+
+impl MetricDelta {
+    pub fn new(current: f64, previous: f64) -> Self {
+        MetricDelta {
+            current,
+            previous,
+            absolute: current - previous,
+            percent: if previous == 0.0 {
+                None
+            } else {
+                Some((current - previous) / previous.abs() * 100.0)
+            },
+        }
+    }
+}
+
+/// Moves comparison points onto the current window so both series overlay on one
+/// axis. The offset is rounded to whole hours to keep hourly buckets aligned.
+pub fn shift_series(
+    points: Vec<TimeSeriesPoint>,
+    from: &TimeWindow,
+    to: &TimeWindow,
+) -> Vec<TimeSeriesPoint> {
+    let offset = Duration::hours((to.start - from.start).num_hours());
+
+    points
+        .into_iter()
+        .filter_map(|point| {
+            // Keep whichever bucket format the query produced
+            let (parsed, format) = parse_bucket_format(&point.timestamp)?;
+
+            Some(TimeSeriesPoint {
+                timestamp: (parsed + offset).format(format).to_string(),
+                value: point.value,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,537 @@
+// ===========================================================================
+// Prometheus Exposition
+// ===========================================================================
+//
+// Renders Forge telemetry from the events table in the Prometheus text format
+// for `/metrics`. Counters are accumulated incrementally: the first scrape
+// totals the whole table, later scrapes only add events newer than the last
+// one. Gauges and latency quantiles are computed over recent windows.
+//
+// Label cardinality is opt-in per dimension (model, agent, pipeline) and
+// capped: the first `max_label_values` values seen keep their own label, the
+// rest are folded into "other" so counters stay monotonic.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqlitePool, Row};
+use tokio::sync::Mutex;
+
+use crate::advisor::TimedQuery;
+use crate::clock;
+use crate::error::{CommandResult, ResultExt};
+use crate::get_db_pool;
+use crate::period::column_bound;
+use crate::rake;
+
+/// Events newer than this are left for the next scrape, so late writes
+/// within the lag still land in the counters.
+const COUNTER_LAG_SECONDS: i64 = 30;
+
+/// Services always reported by `forge_service_up`, even with no events.
+const SERVICES: [&str; 4] = ["dataforge", "neuroforge", "forgeagents", "rake"];
+
+const QUANTILES: [f64; 3] = [0.5, 0.9, 0.99];
+
+const OTHER: &str = "other";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelDimension {
+    Model,
+    Agent,
+    Pipeline,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExporterConfig {
+    pub labels: BTreeSet<LabelDimension>,
+    pub max_label_values: usize,
+}
+
+impl ExporterConfig {
+    /// `labels` is a comma-separated list such as `model,pipeline`; empty or
+    /// `none` keeps every series per service only.
+    pub fn new(labels: &str, max_label_values: usize) -> Result<Self, String> {
+        if max_label_values == 0 {
+            return Err("--metrics-max-label-values must be at least 1".to_string());
+        }
+        let labels = labels
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty() && *name != "none")
+            .map(|name| {
+                serde_json::from_value(serde_json::Value::String(name.to_string())).map_err(|_| {
+                    format!(
+                        "Unknown metrics label {:?} (use model, agent or pipeline)",
+                        name
+                    )
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(ExporterConfig {
+            labels,
+            max_label_values,
+        })
+    }
+}
+
+/// Accumulated counters, keyed by raw dimension values; an empty string means
+/// the dimension is disabled or absent.
+#[derive(Default)]
+struct Counters {
+    watermark: Option<String>,
+    /// (service, event_type, severity)
+    events: BTreeMap<(String, String, String), f64>,
+    /// (service, model or agent) → (count, sum of duration_ms)
+    latency: BTreeMap<(String, String), (f64, f64)>,
+    /// (model, prompt|completion)
+    tokens: BTreeMap<(String, &'static str), f64>,
+    cost: BTreeMap<String, f64>,
+    /// pipeline → (runs, records)
+    pipelines: BTreeMap<String, (f64, f64)>,
+    /// Values that kept their own label, per dimension.
+    kept: BTreeMap<LabelDimension, BTreeSet<String>>,
+}
+
+pub struct Exporter {
+    config: ExporterConfig,
+    counters: Mutex<Counters>,
+}
+
+impl Counters {
+    /// Label value for a dimension: empty when the dimension is disabled,
+    /// "other" once the cap is reached for unseen values.
+    fn label(
+        &mut self,
+        config: &ExporterConfig,
+        dimension: LabelDimension,
+        value: Option<String>,
+    ) -> String {
+        if !config.labels.contains(&dimension) {
+            return String::new();
+        }
+
+        let value = value.unwrap_or_else(|| "unknown".to_string());
+        let kept = self.kept.entry(dimension).or_default();
+        if kept.contains(&value) || kept.len() < config.max_label_values {
+            kept.insert(value.clone());
+            value
+        } else {
+            OTHER.to_string()
+        }
+    }
+}
+
+/// Sorts rows so the busiest label values claim the capped slots first.
+fn by_count_desc(mut rows: Vec<sqlx::sqlite::SqliteRow>) -> Vec<sqlx::sqlite::SqliteRow> {
+    rows.sort_by_key(|row| std::cmp::Reverse(row.get::<i64, _>("count")));
+    rows
+}
+
+impl Exporter {
+    pub fn new(config: ExporterConfig) -> Self {
+        Exporter {
+            config,
+            counters: Mutex::new(Counters::default()),
+        }
+    }
+
+    /// Adds events in (watermark, now - lag] to the counters.
+    async fn advance(&self, pool: &SqlitePool, counters: &mut Counters) -> CommandResult<()> {
+        let from = counters
+            .watermark
+            .clone()
+            .unwrap_or_else(|| "0000-01-01 00:00:00".to_string());
+        let to = column_bound(clock::now_naive() - Duration::seconds(COUNTER_LAG_SECONDS));
+        if to <= from {
+            return Ok(());
+        }
+
+        let rows = sqlx::query(
+            "SELECT service, event_type, severity, COUNT(*) AS count
+             FROM events
+             WHERE timestamp > ? AND timestamp <= ?
+             GROUP BY service, event_type, severity",
+        )
+        .bind(&from)
+        .bind(&to)
+        .fetch_all_timed(pool)
+        .await
+        .in_query("exporter_events")?;
+        for row in rows {
+            let key = (
+                row.get("service"),
+                row.get("event_type"),
+                row.get("severity"),
+            );
+            *counters.events.entry(key).or_default() += row.get::<i64, _>("count") as f64;
+        }
+
+        let rows = sqlx::query(
+            "SELECT
+                service,
+                CASE service
+                    WHEN 'neuroforge' THEN json_extract(metadata, '$.model')
+                    WHEN 'forgeagents' THEN json_extract(metadata, '$.agent_id')
+                END AS dimension,
+                COUNT(*) AS count,
+                CAST(SUM(json_extract(metrics, '$.duration_ms')) AS FLOAT) AS total
+             FROM events
+             WHERE ((service = 'dataforge' AND event_type = 'query')
+                 OR (service = 'neuroforge' AND event_type = 'model_request')
+                 OR (service = 'forgeagents' AND event_type = 'agent_task_completed'))
+             AND json_extract(metrics, '$.duration_ms') IS NOT NULL
+             AND timestamp > ? AND timestamp <= ?
+             GROUP BY service, dimension",
+        )
+        .bind(&from)
+        .bind(&to)
+        .fetch_all_timed(pool)
+        .await
+        .in_query("exporter_latency")?;
+        for row in by_count_desc(rows) {
+            let service: String = row.get("service");
+            let dimension = match service.as_str() {
+                "neuroforge" => {
+                    counters.label(&self.config, LabelDimension::Model, row.get("dimension"))
+                }
+                "forgeagents" => {
+                    counters.label(&self.config, LabelDimension::Agent, row.get("dimension"))
+                }
+                _ => String::new(),
+            };
+            let entry = counters.latency.entry((service, dimension)).or_default();
+            entry.0 += row.get::<i64, _>("count") as f64;
+            entry.1 += row.get::<Option<f64>, _>("total").unwrap_or(0.0);
+        }
+
+        let rows = sqlx::query(
+            "SELECT
+                json_extract(metadata, '$.model') AS model,
+                COUNT(*) AS count,
+                CAST(SUM(json_extract(metrics, '$.tokens_prompt')) AS FLOAT) AS prompt,
+                CAST(SUM(json_extract(metrics, '$.tokens_completion')) AS FLOAT) AS completion,
+                CAST(SUM(json_extract(metrics, '$.cost_usd')) AS FLOAT) AS cost
+             FROM events
+             WHERE service = 'neuroforge' AND event_type = 'model_request'
+             AND timestamp > ? AND timestamp <= ?
+             GROUP BY model",
+        )
+        .bind(&from)
+        .bind(&to)
+        .fetch_all_timed(pool)
+        .await
+        .in_query("exporter_tokens")?;
+        for row in by_count_desc(rows) {
+            let model = counters.label(&self.config, LabelDimension::Model, row.get("model"));
+            for kind in ["prompt", "completion"] {
+                *counters.tokens.entry((model.clone(), kind)).or_default() +=
+                    row.get::<Option<f64>, _>(kind).unwrap_or(0.0);
+            }
+            *counters.cost.entry(model).or_default() +=
+                row.get::<Option<f64>, _>("cost").unwrap_or(0.0);
+        }
+
+        let rows = sqlx::query(&format!(
+            "SELECT
+                json_extract(metrics, '$.pipeline_id') AS pipeline,
+                COUNT(*) AS count,
+                CAST(SUM(json_extract(metrics, '$.{}')) AS FLOAT) AS records
+             FROM events
+             WHERE service = 'rake' AND event_type = 'ingestion_complete'
+             AND timestamp > ? AND timestamp <= ?
+             GROUP BY pipeline",
+            rake::records_field()?
+        ))
+        .bind(&from)
+        .bind(&to)
+        .fetch_all_timed(pool)
+        .await
+        .in_query("exporter_pipelines")?;
+        for row in by_count_desc(rows) {
+            let pipeline =
+                counters.label(&self.config, LabelDimension::Pipeline, row.get("pipeline"));
+            let entry = counters.pipelines.entry(pipeline).or_default();
+            entry.0 += row.get::<i64, _>("count") as f64;
+            entry.1 += row.get::<Option<f64>, _>("records").unwrap_or(0.0);
+        }
+
+        counters.watermark = Some(to);
+        Ok(())
+    }
+
+    /// Renders the full exposition for one scrape.
+    pub async fn render(&self) -> CommandResult<String> {
+        let pool = get_db_pool().await?;
+        let mut counters = self.counters.lock().await;
+        self.advance(&pool, &mut counters).await?;
+
+        let now = clock::now_naive();
+        let mut out = String::new();
+
+        // Service status: any event in the last 5 minutes, error share over the last hour
+        let rows = sqlx::query(
+            "SELECT
+                service,
+                COUNT(*) FILTER (WHERE timestamp > ?) AS recent,
+                COUNT(*) AS total,
+                COUNT(*) FILTER (WHERE severity = 'error') AS errors
+             FROM events
+             WHERE timestamp > ?
+             GROUP BY service",
+        )
+        .bind(column_bound(now - Duration::minutes(5)))
+        .bind(column_bound(now - Duration::hours(1)))
+        .fetch_all_timed(&pool)
+        .await
+        .in_query("exporter_status")?;
+
+        let mut status: BTreeMap<String, (i64, i64, i64)> = SERVICES
+            .iter()
+            .map(|service| (service.to_string(), (0, 0, 0)))
+            .collect();
+        for row in rows {
+            status.insert(
+                row.get("service"),
+                (row.get("recent"), row.get("total"), row.get("errors")),
+            );
+        }
+
+        header(
+            &mut out,
+            "forge_service_up",
+            "gauge",
+            "1 when the service wrote an event in the last 5 minutes.",
+        );
+        for (service, (recent, _, _)) in &status {
+            sample(
+                &mut out,
+                "forge_service_up",
+                &[("service", service)],
+                if *recent > 0 { 1.0 } else { 0.0 },
+            );
+        }
+
+        header(
+            &mut out,
+            "forge_service_error_ratio",
+            "gauge",
+            "Share of the service's events in the last hour with severity error.",
+        );
+        for (service, (_, total, errors)) in &status {
+            let ratio = if *total == 0 {
+                0.0
+            } else {
+                *errors as f64 / *total as f64
+            };
+            sample(
+                &mut out,
+                "forge_service_error_ratio",
+                &[("service", service)],
+                ratio,
+            );
+        }
+
+        header(
+            &mut out,
+            "forge_events_total",
+            "counter",
+            "Events written, by service, event type and severity.",
+        );
+        for ((service, event_type, severity), count) in &counters.events {
+            sample(
+                &mut out,
+                "forge_events_total",
+                &[
+                    ("service", service),
+                    ("event_type", event_type),
+                    ("severity", severity),
+                ],
+                *count,
+            );
+        }
+
+        self.render_latency(&pool, &mut counters, &mut out).await?;
+
+        header(
+            &mut out,
+            "forge_tokens_total",
+            "counter",
+            "NeuroForge tokens, by kind.",
+        );
+        for ((model, kind), tokens) in &counters.tokens {
+            sample(
+                &mut out,
+                "forge_tokens_total",
+                &with_dimension(&[("kind", kind)], "model", model),
+                *tokens,
+            );
+        }
+
+        header(
+            &mut out,
+            "forge_cost_usd_total",
+            "counter",
+            "NeuroForge spend in USD.",
+        );
+        for (model, cost) in &counters.cost {
+            sample(
+                &mut out,
+                "forge_cost_usd_total",
+                &with_dimension(&[], "model", model),
+                *cost,
+            );
+        }
+
+        header(
+            &mut out,
+            "forge_pipeline_runs_total",
+            "counter",
+            "Completed Rake ingestion runs.",
+        );
+        for (pipeline, (runs, _)) in &counters.pipelines {
+            sample(
+                &mut out,
+                "forge_pipeline_runs_total",
+                &with_dimension(&[], "pipeline", pipeline),
+                *runs,
+            );
+        }
+
+        header(
+            &mut out,
+            "forge_pipeline_records_total",
+            "counter",
+            "Records ingested by Rake.",
+        );
+        for (pipeline, (_, records)) in &counters.pipelines {
+            sample(
+                &mut out,
+                "forge_pipeline_records_total",
+                &with_dimension(&[], "pipeline", pipeline),
+                *records,
+            );
+        }
+
+        Ok(out)
+    }
+
+    /// Latency summary: quantiles over the last hour, cumulative sum and count.
+    async fn render_latency(
+        &self,
+        pool: &SqlitePool,
+        counters: &mut Counters,
+        out: &mut String,
+    ) -> CommandResult<()> {
+        let rows = sqlx::query(
+            "SELECT
+                service,
+                CASE service
+                    WHEN 'neuroforge' THEN json_extract(metadata, '$.model')
+                    WHEN 'forgeagents' THEN json_extract(metadata, '$.agent_id')
+                END AS dimension,
+                CAST(json_extract(metrics, '$.duration_ms') AS FLOAT) AS duration
+             FROM events
+             WHERE ((service = 'dataforge' AND event_type = 'query')
+                 OR (service = 'neuroforge' AND event_type = 'model_request')
+                 OR (service = 'forgeagents' AND event_type = 'agent_task_completed'))
+             AND json_extract(metrics, '$.duration_ms') IS NOT NULL
+             AND timestamp > ?",
+        )
+        .bind(column_bound(clock::now_naive() - Duration::hours(1)))
+        .fetch_all_timed(pool)
+        .await
+        .in_query("exporter_latency_window")?;
+
+        let mut durations: BTreeMap<(String, String), Vec<f64>> = BTreeMap::new();
+        for row in rows {
+            let service: String = row.get("service");
+            let dimension = match service.as_str() {
+                "neuroforge" => {
+                    counters.label(&self.config, LabelDimension::Model, row.get("dimension"))
+                }
+                "forgeagents" => {
+                    counters.label(&self.config, LabelDimension::Agent, row.get("dimension"))
+                }
+                _ => String::new(),
+            };
+            if let Some(duration) = row.get::<Option<f64>, _>("duration") {
+                durations
+                    .entry((service, dimension))
+                    .or_default()
+                    .push(duration);
+            }
+        }
+
+        header(
+            out,
+            "forge_latency_ms",
+            "summary",
+            "Request latency; quantiles cover the last hour.",
+        );
+        for ((service, dimension), (count, sum)) in &counters.latency {
+            let dimension_name = if service == "forgeagents" {
+                "agent"
+            } else {
+                "model"
+            };
+            let labels = with_dimension(&[("service", service)], dimension_name, dimension);
+
+            if let Some(values) = durations.get_mut(&(service.clone(), dimension.clone())) {
+                values.sort_by(|a, b| a.total_cmp(b));
+                for quantile in QUANTILES {
+                    let rank =
+                        ((quantile * values.len() as f64).ceil() as usize).clamp(1, values.len());
+                    let quantile_label = quantile.to_string();
+                    let mut quantile_labels = labels.clone();
+                    quantile_labels.push(("quantile", &quantile_label));
+                    sample(out, "forge_latency_ms", &quantile_labels, values[rank - 1]);
+                }
+            }
+            sample(out, "forge_latency_ms_sum", &labels, *sum);
+            sample(out, "forge_latency_ms_count", &labels, *count);
+        }
+
+        Ok(())
+    }
+}
+
+/// Appends the dimension label unless it is disabled (empty).
+fn with_dimension<'a>(
+    labels: &[(&'a str, &'a str)],
+    name: &'a str,
+    value: &'a str,
+) -> Vec<(&'a str, &'a str)> {
+    let mut labels = labels.to_vec();
+    if !value.is_empty() {
+        labels.push((name, value));
+    }
+    labels
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: f64) {
+    let labels: Vec<String> = labels
+        .iter()
+        .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+        .collect();
+    if labels.is_empty() {
+        let _ = writeln!(out, "{} {}", name, value);
+    } else {
+        let _ = writeln!(out, "{}{{{}}} {}", name, labels.join(","), value);
+    }
+}
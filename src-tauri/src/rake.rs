@@ -0,0 +1,907 @@
+// ===========================================================================
+// Rake Pipeline Runs
+// ===========================================================================
+//
+// Rake reports a run as an `ingestion_started` event followed by an
+// `ingestion_complete` or `ingestion_failed` event with the same
+// `metadata.run_id`. A run belongs to the window its outcome lands in. Its
+// duration is the gap between the paired events, falling back to the
+// reported `metrics.duration_ms` when the start can't be found. Records
+// ingested come from `metrics.records`, or the metrics key named by
+// `FORGE_COMMAND_RAKE_RECORDS_FIELD`.
+
+use std::collections::BTreeMap;
+use std::env;
+
+use chrono::{Duration, NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    query::Query,
+    sqlite::{Sqlite, SqliteArguments, SqlitePool, SqliteRow},
+    Row,
+};
+
+use crate::advisor::TimedQuery;
+use crate::anomaly::{self, AnomalyMarker, AnomalyMethod};
+use crate::error::{self, CommandResult, ForgeCommandError, ResultExt};
+use crate::ingest;
+use crate::period::{self, ComparisonWindow, TimeRange, TimeWindow};
+use crate::{get_db_pool, safe_ratio, TimeSeriesPoint};
+
+pub const DEFAULT_RECORDS_FIELD: &str = "records";
+
+/// Starts are looked up this far before the window, so runs that began
+/// before it still pair with their outcome.
+const RUN_LOOKBACK_HOURS: i64 = 24;
+
+/// Window of `get_pipeline_runs` when no range is given.
+const DEFAULT_RUNS_HOURS: i64 = 24;
+
+/// Distinct failure reasons listed in a pipeline's detail.
+const FAILURE_REASON_LIMIT: i64 = 10;
+
+/// The metrics key holding a run's record count. Restricted to identifier
+/// characters since it is spliced into `json_extract` paths, and to a
+/// top-level key since that is what the rollup store keeps.
+pub fn records_field() -> CommandResult<String> {
+    let field = match env::var("FORGE_COMMAND_RAKE_RECORDS_FIELD") {
+        Ok(field) if !field.is_empty() => field,
+        _ => return Ok(DEFAULT_RECORDS_FIELD.to_string()),
+    };
+
+    if field.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        Ok(field)
+    } else {
+        Err(ForgeCommandError::invalid_input(format!(
+            "FORGE_COMMAND_RAKE_RECORDS_FIELD {:?} must be a metrics key of letters, digits and underscores",
+            field
+        )))
+    }
+}
+
+/// `runs(pipeline_id, pipeline_name, run_id, status, started_at, finished_at,
+/// records, records_in, records_rejected, duration_ms, failure_reason)`, one
+/// row per run finishing in the window, optionally for a single pipeline.
+/// `records` is the configured records field, i.e. records written; rejected
+/// records are reported directly or inferred from `records_in`. Binds: the
+/// start lookup bounds, the pipeline (twice), then the window bounds.
+fn runs_cte(records_field: &str) -> String {
+    format!(
+        "WITH starts AS (
+            SELECT json_extract(metadata, '$.run_id') AS run_id, MIN(timestamp) AS started_at
+            FROM events
+            WHERE service = 'rake' AND event_type = 'ingestion_started'
+            AND timestamp > ? AND timestamp <= ?
+            GROUP BY run_id
+        ),
+        runs AS (
+            SELECT
+                COALESCE(json_extract(o.metrics, '$.pipeline_id'), 'unknown') AS pipeline_id,
+                COALESCE(json_extract(o.metrics, '$.pipeline_name'), 'Unknown Pipeline') AS pipeline_name,
+                json_extract(o.metadata, '$.run_id') AS run_id,
+                CASE WHEN o.event_type = 'ingestion_complete' THEN 'success' ELSE 'failed' END AS status,
+                s.started_at,
+                o.timestamp AS finished_at,
+                CAST(json_extract(o.metrics, '$.{records_field}') AS INTEGER) AS records,
+                CAST(json_extract(o.metrics, '$.records_in') AS INTEGER) AS records_in,
+                COALESCE(
+                    CAST(json_extract(o.metrics, '$.records_rejected') AS INTEGER),
+                    CAST(json_extract(o.metrics, '$.records_in') AS INTEGER)
+                        - CAST(json_extract(o.metrics, '$.{records_field}') AS INTEGER)
+                ) AS records_rejected,
+                COALESCE(
+                    ROUND((julianday(o.timestamp) - julianday(s.started_at)) * 86400000.0),
+                    CAST(json_extract(o.metrics, '$.duration_ms') AS FLOAT)
+                ) AS duration_ms,
+                CASE WHEN o.event_type = 'ingestion_failed' THEN
+                    COALESCE(json_extract(o.metadata, '$.error'), json_extract(o.metadata, '$.reason'))
+                END AS failure_reason
+            FROM events o
+            LEFT JOIN starts s ON s.run_id = json_extract(o.metadata, '$.run_id')
+            WHERE o.service = 'rake'
+            AND o.event_type IN ('ingestion_complete', 'ingestion_failed')
+            AND (? IS NULL OR json_extract(o.metrics, '$.pipeline_id') = ?)
+            AND o.timestamp > ?
+            AND o.timestamp <= ?
+        )"
+    )
+}
+
+/// Lower bound for the start lookup of runs finishing in `window`.
+fn lookback_bound(window: &TimeWindow) -> String {
+    if window.is_bounded() {
+        period::column_bound(window.start - Duration::hours(RUN_LOOKBACK_HOURS))
+    } else {
+        window.start_bound()
+    }
+}
+
+/// Binds the [`runs_cte`] parameters onto `query`.
+fn bind_runs<'q>(
+    query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    window: &TimeWindow,
+    pipeline_id: Option<&'q str>,
+) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    query
+        .bind(lookback_bound(window))
+        .bind(window.end_bound())
+        .bind(pipeline_id)
+        .bind(pipeline_id)
+        .bind(window.start_bound())
+        .bind(window.end_bound())
+}
+
+/// Run totals for one pipeline over a window.
+#[derive(Debug, Clone)]
+pub struct PipelineStats {
+    pub pipeline_id: String,
+    pub pipeline_name: String,
+    pub runs: i64,
+    pub successful_runs: i64,
+    pub failed_runs: i64,
+    pub records: i64,
+    /// Sum and count of the runs with a known duration, so averages can be
+    /// combined across pipelines.
+    pub total_duration_ms: f64,
+    pub timed_runs: i64,
+    pub first_run: String,
+    pub last_run: String,
+}
+
+impl PipelineStats {
+    pub fn avg_duration_ms(&self) -> f64 {
+        safe_ratio(self.total_duration_ms, self.timed_runs as f64)
+    }
+}
+
+/// Per-pipeline run totals for runs finishing in `window`, most records
+/// first; every pipeline unless one is given.
+pub async fn fetch_pipeline_stats(
+    pool: &SqlitePool,
+    window: &TimeWindow,
+    pipeline_id: Option<&str>,
+) -> CommandResult<Vec<PipelineStats>> {
+    let query = format!(
+        "{}
+        SELECT
+            pipeline_id,
+            MAX(pipeline_name) AS pipeline_name,
+            COUNT(*) AS runs,
+            SUM(status = 'success') AS successful_runs,
+            SUM(status = 'failed') AS failed_runs,
+            COALESCE(SUM(records), 0) AS records,
+            COALESCE(SUM(duration_ms), 0.0) AS total_duration_ms,
+            COUNT(duration_ms) AS timed_runs,
+            MIN(finished_at) AS first_run,
+            MAX(finished_at) AS last_run
+         FROM runs
+         GROUP BY pipeline_id
+         ORDER BY records DESC, runs DESC, pipeline_id ASC",
+        runs_cte(&records_field()?)
+    );
+
+    let stats = bind_runs(sqlx::query(&query), window, pipeline_id)
+        .fetch_all_timed(pool)
+        .await
+        .in_query("rake_pipeline_stats")?
+        .into_iter()
+        .map(|row| PipelineStats {
+            pipeline_id: row.get("pipeline_id"),
+            pipeline_name: row.get("pipeline_name"),
+            runs: row.get("runs"),
+            successful_runs: row.get("successful_runs"),
+            failed_runs: row.get("failed_runs"),
+            records: row.get("records"),
+            total_duration_ms: row.get("total_duration_ms"),
+            timed_runs: row.get("timed_runs"),
+            first_run: row.get("first_run"),
+            last_run: row.get("last_run"),
+        })
+        .collect();
+
+    Ok(stats)
+}
+
+// ===========================================================================
+// Run History
+// ===========================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineRun {
+    /// `None` for producers that don't report run ids; such runs can't be
+    /// paired with their start.
+    pub run_id: Option<String>,
+    pub pipeline_id: String,
+    pub pipeline_name: String,
+    /// `success`, `failed`, or `running` when no outcome has been reported.
+    pub status: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+    pub duration_ms: Option<f64>,
+    pub records_in: Option<i64>,
+    pub records_out: Option<i64>,
+    pub records_rejected: Option<i64>,
+    pub failure_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailureReason {
+    pub reason: String,
+    pub count: i64,
+    pub last_seen: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PipelineDetail {
+    pub pipeline_id: String,
+    pub pipeline_name: String,
+    pub runs: i64,
+    pub successful_runs: i64,
+    pub failed_runs: i64,
+    pub running_runs: i64,
+    pub success_rate: f64,
+    pub records_ingested: i64,
+    pub avg_run_duration_ms: f64,
+    pub last_run: Option<String>,
+    pub last_failure: Option<PipelineRun>,
+    /// Hourly trends, bucketed by when runs finished.
+    pub runs_over_time: Vec<TimeSeriesPoint>,
+    pub success_rate_over_time: Vec<TimeSeriesPoint>,
+    pub records_over_time: Vec<TimeSeriesPoint>,
+    pub duration_over_time: Vec<TimeSeriesPoint>,
+    /// Most frequent failure reasons first.
+    pub failure_reasons: Vec<FailureReason>,
+}
+
+/// Runs of `pipeline_id` in `window`, newest first: every run finishing in
+/// it, plus runs started in it that have no outcome yet.
+async fn fetch_runs(
+    pool: &SqlitePool,
+    window: &TimeWindow,
+    pipeline_id: &str,
+    limit: Option<i64>,
+) -> CommandResult<Vec<PipelineRun>> {
+    let query = format!(
+        "{}
+        SELECT * FROM (
+            SELECT run_id, pipeline_id, pipeline_name, status, started_at, finished_at,
+                   duration_ms, records_in, records AS records_out, records_rejected, failure_reason
+            FROM runs
+            UNION ALL
+            SELECT
+                json_extract(metadata, '$.run_id'),
+                COALESCE(json_extract(metrics, '$.pipeline_id'), 'unknown'),
+                COALESCE(json_extract(metrics, '$.pipeline_name'), 'Unknown Pipeline'),
+                'running', timestamp, NULL, NULL, NULL, NULL, NULL, NULL
+            FROM events
+            WHERE service = 'rake' AND event_type = 'ingestion_started'
+            AND json_extract(metrics, '$.pipeline_id') = ?
+            AND timestamp > ?
+            AND timestamp <= ?
+            AND json_extract(metadata, '$.run_id') NOT IN (
+                SELECT json_extract(metadata, '$.run_id')
+                FROM events
+                WHERE service = 'rake'
+                AND event_type IN ('ingestion_complete', 'ingestion_failed')
+                AND timestamp > ?
+                AND timestamp <= ?
+                AND json_extract(metadata, '$.run_id') IS NOT NULL
+            )
+        )
+        ORDER BY COALESCE(started_at, finished_at) DESC
+        LIMIT ?",
+        runs_cte(&records_field()?)
+    );
+
+    let runs = bind_runs(sqlx::query(&query), window, Some(pipeline_id))
+        .bind(pipeline_id)
+        .bind(window.start_bound())
+        .bind(window.end_bound())
+        .bind(window.start_bound())
+        .bind(window.end_bound())
+        .bind(limit.unwrap_or(-1))
+        .fetch_all_timed(pool)
+        .await
+        .in_query("rake_pipeline_runs")?
+        .into_iter()
+        .map(|row| PipelineRun {
+            run_id: row.get("run_id"),
+            pipeline_id: row.get("pipeline_id"),
+            pipeline_name: row.get("pipeline_name"),
+            status: row.get("status"),
+            started_at: row.get("started_at"),
+            finished_at: row.get("finished_at"),
+            duration_ms: row.get("duration_ms"),
+            records_in: row.get("records_in"),
+            records_out: row.get("records_out"),
+            records_rejected: row.get("records_rejected"),
+            failure_reason: row.get("failure_reason"),
+        })
+        .collect();
+
+    Ok(runs)
+}
+
+/// Fails with `NotFound` when Rake has never reported `pipeline_id`, so a
+/// typo isn't mistaken for a quiet pipeline.
+async fn require_pipeline(pool: &SqlitePool, pipeline_id: &str) -> CommandResult<()> {
+    let known = !sqlx::query(
+        "SELECT 1 FROM events
+         WHERE service = 'rake' AND json_extract(metrics, '$.pipeline_id') = ?
+         LIMIT 1",
+    )
+    .bind(pipeline_id)
+    .fetch_all_timed(pool)
+    .await
+    .in_query("rake_pipeline_exists")?
+    .is_empty();
+
+    if known {
+        Ok(())
+    } else {
+        Err(ForgeCommandError::not_found(format!(
+            "Rake has no events for pipeline {:?}",
+            pipeline_id
+        )))
+    }
+}
+
+// ===========================================================================
+// Job Queue & Freshness
+// ===========================================================================
+//
+// Rake can announce a job with `ingestion_queued` before it starts. A job is
+// queued until it starts (or finishes, when the start is missing) and in
+// flight from its start until its outcome. Jobs stuck in either stage for
+// longer than the run lookback count as abandoned, so a lost outcome doesn't
+// hold the depth up forever.
+
+const QUEUED_EVENT: &str = "ingestion_queued";
+
+/// Labels of the hourly queue series, matching the other Rake series.
+const HOUR_LABEL_FORMAT: &str = "%Y-%m-%d %H:00:00";
+
+/// One job's lifecycle, from the first event of each kind with its run id.
+#[derive(Debug, Clone, Copy)]
+struct Job {
+    queued_at: Option<NaiveDateTime>,
+    started_at: Option<NaiveDateTime>,
+    finished_at: Option<NaiveDateTime>,
+}
+
+impl Job {
+    fn first_seen(&self) -> Option<NaiveDateTime> {
+        [self.queued_at, self.started_at, self.finished_at]
+            .into_iter()
+            .flatten()
+            .min()
+    }
+
+    /// Time from queued to started, for jobs seen in both stages.
+    fn wait_ms(&self) -> Option<f64> {
+        let wait = self.started_at? - self.queued_at?;
+        Some(wait.num_milliseconds().max(0) as f64)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Stage {
+    Queued = 0,
+    Running = 1,
+}
+
+/// Jobs with a lifecycle event in `(from, to]`.
+async fn fetch_jobs(pool: &SqlitePool, from: String, to: String) -> CommandResult<Vec<Job>> {
+    let rows = sqlx::query(
+        "SELECT
+            json_extract(metadata, '$.run_id') AS run_id,
+            MIN(CASE WHEN event_type = ? THEN timestamp END) AS queued_at,
+            MIN(CASE WHEN event_type = 'ingestion_started' THEN timestamp END) AS started_at,
+            MIN(CASE WHEN event_type IN ('ingestion_complete', 'ingestion_failed') THEN timestamp END) AS finished_at
+         FROM events
+         WHERE service = 'rake'
+         AND event_type IN (?, 'ingestion_started', 'ingestion_complete', 'ingestion_failed')
+         AND timestamp > ?
+         AND timestamp <= ?
+         AND json_extract(metadata, '$.run_id') IS NOT NULL
+         GROUP BY run_id",
+    )
+    .bind(QUEUED_EVENT)
+    .bind(QUEUED_EVENT)
+    .bind(from)
+    .bind(to)
+    .fetch_all_timed(pool)
+    .await
+    .in_query("rake_jobs")?;
+
+    let parse = |row: &SqliteRow, column: &str| {
+        row.get::<Option<String>, _>(column)
+            .and_then(|raw| ingest::parse_timestamp(&raw).ok())
+            .map(|at| at.naive_utc())
+    };
+
+    Ok(rows
+        .iter()
+        .map(|row| Job {
+            queued_at: parse(row, "queued_at"),
+            started_at: parse(row, "started_at"),
+            finished_at: parse(row, "finished_at"),
+        })
+        .collect())
+}
+
+/// Jobs that could be queued or in flight at some point of `window`.
+async fn fetch_window_jobs(pool: &SqlitePool, window: &TimeWindow) -> CommandResult<Vec<Job>> {
+    fetch_jobs(pool, lookback_bound(window), window.end_bound()).await
+}
+
+/// Every change to the queue depth and in-flight count as `(at, stage,
+/// delta)`, in time order with departures before arrivals at the same instant.
+fn timeline(jobs: &[Job]) -> Vec<(NaiveDateTime, Stage, i64)> {
+    let mut changes = Vec::new();
+
+    for job in jobs {
+        let Some(first_seen) = job.first_seen() else {
+            continue;
+        };
+        let abandoned = first_seen + Duration::hours(RUN_LOOKBACK_HOURS);
+
+        if let Some(queued_at) = job.queued_at {
+            let left = [job.started_at, job.finished_at, Some(abandoned)]
+                .into_iter()
+                .flatten()
+                .min()
+                .unwrap_or(abandoned);
+            if left > queued_at {
+                changes.push((queued_at, Stage::Queued, 1));
+                changes.push((left, Stage::Queued, -1));
+            }
+        }
+
+        if let Some(started_at) = job.started_at {
+            let left = job.finished_at.unwrap_or(abandoned).min(abandoned);
+            if left > started_at {
+                changes.push((started_at, Stage::Running, 1));
+                changes.push((left, Stage::Running, -1));
+            }
+        }
+    }
+
+    changes.sort_by_key(|&(at, stage, delta)| (at, delta, stage));
+    changes
+}
+
+/// Queued and in-flight jobs at `at`.
+fn depth_at(changes: &[(NaiveDateTime, Stage, i64)], at: NaiveDateTime) -> [i64; 2] {
+    let mut depth = [0; 2];
+    for (_, stage, delta) in changes.iter().take_while(|(time, ..)| *time <= at) {
+        depth[*stage as usize] += delta;
+    }
+    depth
+}
+
+fn floor_hour(at: NaiveDateTime) -> NaiveDateTime {
+    at.date().and_hms_opt(at.hour(), 0, 0).unwrap_or(at)
+}
+
+/// Peak queue depth and peak in-flight count per hour of `window`. Every hour
+/// gets a point, since an empty queue is a value too; without any jobs the
+/// series are empty.
+fn hourly_peaks(
+    changes: &[(NaiveDateTime, Stage, i64)],
+    window: &TimeWindow,
+) -> (Vec<TimeSeriesPoint>, Vec<TimeSeriesPoint>) {
+    let mut queued = Vec::new();
+    let mut running = Vec::new();
+    if changes.is_empty() {
+        return (queued, running);
+    }
+
+    let mut changes = changes
+        .iter()
+        .filter(|(at, ..)| *at <= window.end)
+        .peekable();
+    let mut depth = [0i64; 2];
+    let mut hour = floor_hour(window.start);
+
+    while hour < window.end {
+        let next = hour + Duration::hours(1);
+        while let Some((_, stage, delta)) = changes.next_if(|(at, ..)| *at < hour) {
+            depth[*stage as usize] += delta;
+        }
+
+        let mut peak = depth;
+        while let Some((_, stage, delta)) = changes.next_if(|(at, ..)| *at < next) {
+            let stage = *stage as usize;
+            depth[stage] += delta;
+            peak[stage] = peak[stage].max(depth[stage]);
+        }
+
+        let timestamp = hour.format(HOUR_LABEL_FORMAT).to_string();
+        queued.push(TimeSeriesPoint {
+            timestamp: timestamp.clone(),
+            value: peak[Stage::Queued as usize] as f64,
+        });
+        running.push(TimeSeriesPoint {
+            timestamp,
+            value: peak[Stage::Running as usize] as f64,
+        });
+        hour = next;
+    }
+
+    (queued, running)
+}
+
+/// Nearest-rank percentile of ascending `sorted` values; 0.0 when empty.
+fn percentile(sorted: &[f64], percent: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (percent / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Queue waits of the jobs starting in `window`, ascending, with start times.
+fn waits_in(jobs: &[Job], window: &TimeWindow) -> Vec<(NaiveDateTime, f64)> {
+    let mut waits: Vec<(NaiveDateTime, f64)> = jobs
+        .iter()
+        .filter_map(|job| Some((job.started_at?, job.wait_ms()?)))
+        .filter(|(started_at, _)| *started_at > window.start && *started_at <= window.end)
+        .collect();
+    waits.sort_by(|a, b| a.1.total_cmp(&b.1));
+    waits
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct QueueStats {
+    /// Jobs queued or in flight at the end of the window.
+    pub queued_jobs: i64,
+    pub running_jobs: i64,
+    /// Waits of the jobs that started in the window.
+    pub avg_wait_ms: f64,
+    pub wait_p50_ms: f64,
+    pub wait_p95_ms: f64,
+    pub wait_p99_ms: f64,
+}
+
+pub async fn fetch_queue_stats(
+    pool: &SqlitePool,
+    window: &TimeWindow,
+) -> CommandResult<QueueStats> {
+    let jobs = fetch_window_jobs(pool, window).await?;
+    let [queued_jobs, running_jobs] = depth_at(&timeline(&jobs), window.end);
+    let waits: Vec<f64> = waits_in(&jobs, window)
+        .into_iter()
+        .map(|(_, wait)| wait)
+        .collect();
+
+    Ok(QueueStats {
+        queued_jobs,
+        running_jobs,
+        avg_wait_ms: safe_ratio(waits.iter().sum(), waits.len() as f64),
+        wait_p50_ms: percentile(&waits, 50.0),
+        wait_p95_ms: percentile(&waits, 95.0),
+        wait_p99_ms: percentile(&waits, 99.0),
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceFreshness {
+    pub pipeline_id: String,
+    pub pipeline_name: String,
+    /// Newest record ingested so far, or the last successful run when the
+    /// pipeline doesn't report `metadata.newest_record_at`.
+    pub newest_record_at: String,
+    /// Seconds from `newest_record_at` to the end of the window.
+    pub lag_seconds: f64,
+}
+
+/// How far behind each pipeline's data is at the end of `window`, stalest
+/// first. Looks at every successful run up to then, so a pipeline that has
+/// stopped ingesting stays listed with a growing lag.
+pub async fn fetch_freshness(
+    pool: &SqlitePool,
+    window: &TimeWindow,
+) -> CommandResult<Vec<SourceFreshness>> {
+    let rows = sqlx::query(
+        "SELECT
+            COALESCE(json_extract(metrics, '$.pipeline_id'), 'unknown') AS pipeline_id,
+            MAX(COALESCE(json_extract(metrics, '$.pipeline_name'), 'Unknown Pipeline')) AS pipeline_name,
+            MAX(json_extract(metadata, '$.newest_record_at')) AS newest_reported,
+            MAX(timestamp) AS last_success
+         FROM events
+         WHERE service = 'rake'
+         AND event_type = 'ingestion_complete'
+         AND timestamp <= ?
+         GROUP BY pipeline_id",
+    )
+    .bind(window.end_bound())
+    .fetch_all_timed(pool)
+    .await
+    .in_query("rake_freshness")?;
+
+    let mut freshness: Vec<SourceFreshness> = rows
+        .iter()
+        .filter_map(|row| {
+            let newest_record_at: String = row
+                .get::<Option<String>, _>("newest_reported")
+                .unwrap_or_else(|| row.get("last_success"));
+            let newest = ingest::parse_timestamp(&newest_record_at).ok()?.naive_utc();
+
+            Some(SourceFreshness {
+                pipeline_id: row.get("pipeline_id"),
+                pipeline_name: row.get("pipeline_name"),
+                newest_record_at,
+                lag_seconds: ((window.end - newest).num_milliseconds().max(0) as f64) / 1000.0,
+            })
+        })
+        .collect();
+
+    freshness.sort_by(|a, b| {
+        b.lag_seconds
+            .total_cmp(&a.lag_seconds)
+            .then_with(|| a.pipeline_id.cmp(&b.pipeline_id))
+    });
+    Ok(freshness)
+}
+
+// ===========================================================================
+// IPC Commands
+// ===========================================================================
+
+/// Run history of one pipeline over `range` (the last 24 hours by default).
+#[tauri::command]
+pub async fn get_pipeline_runs(
+    pipeline_id: String,
+    range: Option<TimeRange>,
+    limit: Option<i64>,
+) -> CommandResult<Vec<PipelineRun>> {
+    error::command("get_pipeline_runs", async move {
+        let pool = get_db_pool().await?;
+        let window = match &range {
+            Some(range) => TimeWindow::parse(range)?,
+            None => TimeWindow::last_hours(DEFAULT_RUNS_HOURS),
+        };
+
+        require_pipeline(&pool, &pipeline_id).await?;
+        fetch_runs(&pool, &window, &pipeline_id, limit).await
+    })
+    .await
+}
+
+/// Totals, hourly trends and failure reasons for one pipeline.
+#[tauri::command]
+pub async fn get_pipeline_detail(
+    pipeline_id: String,
+    hours: Option<i64>,
+) -> CommandResult<PipelineDetail> {
+    error::command("get_pipeline_detail", async move {
+        let pool = get_db_pool().await?;
+        let window = TimeWindow::last_hours_or_all(hours);
+        let cte = runs_cte(&records_field()?);
+
+        require_pipeline(&pool, &pipeline_id).await?;
+
+        let stats = fetch_pipeline_stats(&pool, &window, Some(&pipeline_id))
+            .await?
+            .into_iter()
+            .next();
+        let runs = fetch_runs(&pool, &window, &pipeline_id, None).await?;
+
+        let hourly_query = format!(
+            "{}
+            SELECT
+                strftime('%Y-%m-%d %H:00:00', finished_at) AS hour,
+                CAST(COUNT(*) AS FLOAT) AS runs,
+                CAST(SUM(status = 'success') AS FLOAT) * 100.0 / COUNT(*) AS success_rate,
+                CAST(COALESCE(SUM(records), 0) AS FLOAT) AS records,
+                AVG(duration_ms) AS avg_duration
+             FROM runs
+             GROUP BY hour
+             ORDER BY hour ASC",
+            cte
+        );
+        let hourly = bind_runs(sqlx::query(&hourly_query), &window, Some(&pipeline_id))
+            .fetch_all_timed(&pool)
+            .await
+            .in_query("rake_pipeline_hourly")?;
+
+        let series = |column: &str| -> Vec<TimeSeriesPoint> {
+            hourly
+                .iter()
+                .map(|row| TimeSeriesPoint {
+                    timestamp: row.get("hour"),
+                    value: row.get::<Option<f64>, _>(column).unwrap_or(0.0),
+                })
+                .collect()
+        };
+
+        let reasons_query = format!(
+            "{}
+            SELECT
+                COALESCE(failure_reason, 'unknown') AS reason,
+                COUNT(*) AS count,
+                MAX(finished_at) AS last_seen
+             FROM runs
+             WHERE status = 'failed'
+             GROUP BY reason
+             ORDER BY count DESC, last_seen DESC
+             LIMIT ?",
+            cte
+        );
+        let failure_reasons = bind_runs(sqlx::query(&reasons_query), &window, Some(&pipeline_id))
+            .bind(FAILURE_REASON_LIMIT)
+            .fetch_all_timed(&pool)
+            .await
+            .in_query("rake_pipeline_failures")?
+            .into_iter()
+            .map(|row| FailureReason {
+                reason: row.get("reason"),
+                count: row.get("count"),
+                last_seen: row.get("last_seen"),
+            })
+            .collect();
+
+        let pipeline_name = stats
+            .as_ref()
+            .map(|stats| stats.pipeline_name.clone())
+            .or_else(|| runs.first().map(|run| run.pipeline_name.clone()))
+            .unwrap_or_else(|| "Unknown Pipeline".to_string());
+        let (runs_total, successful_runs, failed_runs) = stats
+            .as_ref()
+            .map_or((0, 0, 0), |s| (s.runs, s.successful_runs, s.failed_runs));
+
+        Ok(PipelineDetail {
+            pipeline_id,
+            pipeline_name,
+            runs: runs_total,
+            successful_runs,
+            failed_runs,
+            running_runs: runs.iter().filter(|run| run.status == "running").count() as i64,
+            success_rate: safe_ratio(successful_runs as f64 * 100.0, runs_total as f64),
+            records_ingested: stats.as_ref().map_or(0, |s| s.records),
+            avg_run_duration_ms: stats.as_ref().map_or(0.0, PipelineStats::avg_duration_ms),
+            last_run: stats.as_ref().map(|s| s.last_run.clone()),
+            last_failure: runs.iter().find(|run| run.status == "failed").cloned(),
+            runs_over_time: series("runs"),
+            success_rate_over_time: series("success_rate"),
+            records_over_time: series("records"),
+            duration_over_time: series("avg_duration"),
+            failure_reasons,
+        })
+    })
+    .await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QueueDepthOverTime {
+    /// Peak queue depth per hour.
+    pub datapoints: Vec<TimeSeriesPoint>,
+    /// Peak jobs in flight per hour.
+    pub running_datapoints: Vec<TimeSeriesPoint>,
+    pub comparison_datapoints: Vec<TimeSeriesPoint>,
+    pub anomalies: Vec<AnomalyMarker>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QueueWaitOverTime {
+    /// p95 queue wait (ms) of the jobs starting in each hour.
+    pub datapoints: Vec<TimeSeriesPoint>,
+    pub median_datapoints: Vec<TimeSeriesPoint>,
+    pub comparison_datapoints: Vec<TimeSeriesPoint>,
+    pub anomalies: Vec<AnomalyMarker>,
+}
+
+async fn queue_depth_series(
+    pool: &SqlitePool,
+    window: &TimeWindow,
+) -> CommandResult<(Vec<TimeSeriesPoint>, Vec<TimeSeriesPoint>)> {
+    let jobs = fetch_window_jobs(pool, window).await?;
+    Ok(hourly_peaks(&timeline(&jobs), window))
+}
+
+/// Hourly (p95, p50) queue waits, bucketed by start time.
+async fn queue_wait_series(
+    pool: &SqlitePool,
+    window: &TimeWindow,
+) -> CommandResult<(Vec<TimeSeriesPoint>, Vec<TimeSeriesPoint>)> {
+    let jobs = fetch_window_jobs(pool, window).await?;
+
+    let mut hours: BTreeMap<NaiveDateTime, Vec<f64>> = BTreeMap::new();
+    for (started_at, wait) in waits_in(&jobs, window) {
+        hours.entry(floor_hour(started_at)).or_default().push(wait);
+    }
+
+    Ok(hours
+        .into_iter()
+        .map(|(hour, waits)| {
+            let timestamp = hour.format(HOUR_LABEL_FORMAT).to_string();
+            (
+                TimeSeriesPoint {
+                    timestamp: timestamp.clone(),
+                    value: percentile(&waits, 95.0),
+                },
+                TimeSeriesPoint {
+                    timestamp,
+                    value: percentile(&waits, 50.0),
+                },
+            )
+        })
+        .unzip())
+}
+
+/// Hourly peak of queued and in-flight Rake jobs.
+#[tauri::command]
+pub async fn get_queue_depth_over_time(
+    hours: i64,
+    anomaly: Option<AnomalyMethod>,
+    comparison: Option<ComparisonWindow>,
+) -> CommandResult<QueueDepthOverTime> {
+    error::command("get_queue_depth_over_time", async move {
+        let pool = get_db_pool().await?;
+        let window = TimeWindow::last_hours(hours);
+
+        let (datapoints, running_datapoints) = queue_depth_series(&pool, &window).await?;
+        let comparison_datapoints = match comparison {
+            Some(comparison) => {
+                let previous_window = window.comparison(&comparison)?;
+                let (points, _) = queue_depth_series(&pool, &previous_window).await?;
+                period::shift_series(points, &previous_window, &window)
+            }
+            None => Vec::new(),
+        };
+        let anomalies = anomaly::detect_with_history(
+            &datapoints,
+            &window,
+            anomaly.unwrap_or_default(),
+            |history| async move { Ok(queue_depth_series(&pool, &history).await?.0) },
+        )
+        .await?;
+
+        Ok(QueueDepthOverTime {
+            datapoints,
+            running_datapoints,
+            comparison_datapoints,
+            anomalies,
+        })
+    })
+    .await
+}
+
+/// Hourly p95 and median queue wait of Rake jobs.
+#[tauri::command]
+pub async fn get_queue_wait_over_time(
+    hours: i64,
+    anomaly: Option<AnomalyMethod>,
+    comparison: Option<ComparisonWindow>,
+) -> CommandResult<QueueWaitOverTime> {
+    error::command("get_queue_wait_over_time", async move {
+        let pool = get_db_pool().await?;
+        let window = TimeWindow::last_hours(hours);
+
+        let (datapoints, median_datapoints) = queue_wait_series(&pool, &window).await?;
+        let comparison_datapoints = match comparison {
+            Some(comparison) => {
+                let previous_window = window.comparison(&comparison)?;
+                let (points, _) = queue_wait_series(&pool, &previous_window).await?;
+                period::shift_series(points, &previous_window, &window)
+            }
+            None => Vec::new(),
+        };
+        let anomalies = anomaly::detect_with_history(
+            &datapoints,
+            &window,
+            anomaly.unwrap_or_default(),
+            |history| async move { Ok(queue_wait_series(&pool, &history).await?.0) },
+        )
+        .await?;
+
+        Ok(QueueWaitOverTime {
+            datapoints,
+            median_datapoints,
+            comparison_datapoints,
+            anomalies,
+        })
+    })
+    .await
+}
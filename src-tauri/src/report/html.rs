@@ -0,0 +1,134 @@
+// Standalone HTML renderer: inline CSS and inline SVG charts, no external assets.
+
+use super::{Block, ChartSeries, ReportDocument};
+
+const CHART_WIDTH: f64 = 720.0;
+const CHART_HEIGHT: f64 = 200.0;
+const CHART_PADDING: f64 = 36.0;
+
+const STYLE: &str = "
+body { font-family: -apple-system, 'Segoe UI', Helvetica, Arial, sans-serif; color: #1f2937; max-width: 800px; margin: 40px auto; padding: 0 16px; }
+h1 { color: #111827; margin-bottom: 4px; }
+h2 { color: #ea580c; border-bottom: 1px solid #e5e7eb; padding-bottom: 4px; margin-top: 32px; }
+.meta { color: #6b7280; font-size: 14px; }
+table { border-collapse: collapse; width: 100%; margin: 12px 0; font-size: 14px; }
+th, td { text-align: left; padding: 6px 8px; border-bottom: 1px solid #e5e7eb; }
+th { background: #f9fafb; }
+dl { display: grid; grid-template-columns: max-content auto; gap: 4px 16px; font-size: 14px; }
+dt { font-weight: 600; }
+dd { margin: 0; }
+figure { margin: 16px 0; }
+figcaption { font-weight: 600; font-size: 14px; margin-bottom: 4px; }
+";
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn svg_chart(chart: &ChartSeries) -> String {
+    if chart.points.is_empty() {
+        return "<p class=\"meta\">No data in this period.</p>".to_string();
+    }
+
+    let values: Vec<f64> = chart.points.iter().map(|p| p.value).collect();
+    let min = values.iter().cloned().fold(f64::INFINITY, f64::min).min(0.0);
+    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let span = if max > min { max - min } else { 1.0 };
+
+    let plot_width = CHART_WIDTH - CHART_PADDING * 2.0;
+    let plot_height = CHART_HEIGHT - CHART_PADDING * 2.0;
+    let step = if values.len() > 1 { plot_width / (values.len() - 1) as f64 } else { 0.0 };
+
+    let path: Vec<String> = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let x = CHART_PADDING + step * i as f64;
+            let y = CHART_PADDING + plot_height - (value - min) / span * plot_height;
+            format!("{:.1},{:.1}", x, y)
+        })
+        .collect();
+
+    let bottom = CHART_HEIGHT - CHART_PADDING;
+    let right = CHART_WIDTH - CHART_PADDING;
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {w} {h}\" width=\"100%\" role=\"img\">\
+<line x1=\"{p}\" y1=\"{p}\" x2=\"{p}\" y2=\"{b}\" stroke=\"#9ca3af\"/>\
+<line x1=\"{p}\" y1=\"{b}\" x2=\"{r}\" y2=\"{b}\" stroke=\"#9ca3af\"/>\
+<polyline fill=\"none\" stroke=\"#ea580c\" stroke-width=\"2\" points=\"{points}\"/>\
+<text x=\"{p}\" y=\"{label_top}\" font-size=\"11\" fill=\"#6b7280\">{max:.2} {unit}</text>\
+<text x=\"{p}\" y=\"{label_bottom}\" font-size=\"11\" fill=\"#6b7280\">{first}</text>\
+<text x=\"{r}\" y=\"{label_bottom}\" font-size=\"11\" fill=\"#6b7280\" text-anchor=\"end\">{last}</text>\
+</svg>",
+        w = CHART_WIDTH,
+        h = CHART_HEIGHT,
+        p = CHART_PADDING,
+        b = bottom,
+        r = right,
+        points = path.join(" "),
+        label_top = CHART_PADDING - 8.0,
+        label_bottom = bottom + 16.0,
+        max = max,
+        unit = escape(&chart.unit),
+        first = escape(&chart.points[0].timestamp),
+        last = escape(&chart.points[chart.points.len() - 1].timestamp),
+    )
+}
+
+pub fn render(document: &ReportDocument) -> String {
+    let mut body = format!(
+        "<h1>{}</h1>\n<p class=\"meta\">Period: {}<br>Generated: {}</p>\n",
+        escape(&document.title),
+        escape(&document.period),
+        escape(&document.generated_at)
+    );
+
+    for section in &document.sections {
+        body.push_str(&format!("<h2>{}</h2>\n", escape(&section.heading)));
+
+        for block in &section.blocks {
+            match block {
+                Block::Paragraph(text) => body.push_str(&format!("<p>{}</p>\n", escape(text))),
+                Block::KeyValues(pairs) => {
+                    body.push_str("<dl>\n");
+                    for (key, value) in pairs {
+                        body.push_str(&format!("<dt>{}</dt><dd>{}</dd>\n", escape(key), escape(value)));
+                    }
+                    body.push_str("</dl>\n");
+                }
+                Block::Table { headers, rows } => {
+                    body.push_str("<table>\n<tr>");
+                    for header in headers {
+                        body.push_str(&format!("<th>{}</th>", escape(header)));
+                    }
+                    body.push_str("</tr>\n");
+                    for row in rows {
+                        body.push_str("<tr>");
+                        for cell in row {
+                            body.push_str(&format!("<td>{}</td>", escape(cell)));
+                        }
+                        body.push_str("</tr>\n");
+                    }
+                    body.push_str("</table>\n");
+                }
+                Block::Chart(chart) => body.push_str(&format!(
+                    "<figure><figcaption>{}</figcaption>{}</figure>\n",
+                    escape(&chart.title),
+                    svg_chart(chart)
+                )),
+            }
+        }
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape(&document.title),
+        STYLE,
+        body
+    )
+}
@@ -0,0 +1,89 @@
+// Markdown renderer. Charts become unicode sparklines with a min/max/last
+// summary so they still read in a terminal or a plain-text email.
+
+use super::{Block, ChartSeries, ReportDocument};
+
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+fn escape_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
+}
+
+fn table(out: &mut String, headers: &[String], rows: &[Vec<String>]) {
+    out.push_str(&format!("| {} |\n", headers.iter().map(|h| escape_cell(h)).collect::<Vec<_>>().join(" | ")));
+    out.push_str(&format!("|{}\n", "---|".repeat(headers.len())));
+    for row in rows {
+        out.push_str(&format!("| {} |\n", row.iter().map(|c| escape_cell(c)).collect::<Vec<_>>().join(" | ")));
+    }
+    out.push('\n');
+}
+
+fn sparkline(chart: &ChartSeries) -> String {
+    let values: Vec<f64> = chart.points.iter().map(|p| p.value).collect();
+    let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let span = max - min;
+
+    values
+        .iter()
+        .map(|value| {
+            let level = if span > 0.0 {
+                (((value - min) / span) * (SPARK_LEVELS.len() - 1) as f64).round() as usize
+            } else {
+                0
+            };
+            SPARK_LEVELS[level.min(SPARK_LEVELS.len() - 1)]
+        })
+        .collect()
+}
+
+fn chart(out: &mut String, chart: &ChartSeries) {
+    out.push_str(&format!("**{}** ({})\n\n", chart.title, chart.unit));
+
+    if chart.points.is_empty() {
+        out.push_str("_No data in this period._\n\n");
+        return;
+    }
+
+    let values: Vec<f64> = chart.points.iter().map(|p| p.value).collect();
+    let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let last = values.last().copied().unwrap_or_default();
+
+    out.push_str(&format!("```\n{}\n```\n\n", sparkline(chart)));
+    out.push_str(&format!(
+        "{} to {} · min {:.2} · max {:.2} · last {:.2}\n\n",
+        chart.points[0].timestamp,
+        chart.points[chart.points.len() - 1].timestamp,
+        min,
+        max,
+        last
+    ));
+}
+
+pub fn render(document: &ReportDocument) -> String {
+    let mut out = format!(
+        "# {}\n\n_Period: {}_  \n_Generated: {}_\n\n",
+        document.title, document.period, document.generated_at
+    );
+
+    for section in &document.sections {
+        out.push_str(&format!("## {}\n\n", section.heading));
+
+        for block in &section.blocks {
+            match block {
+                Block::Paragraph(text) => out.push_str(&format!("{}\n\n", text)),
+                Block::KeyValues(pairs) => {
+                    for (key, value) in pairs {
+                        out.push_str(&format!("- **{}:** {}\n", key, value));
+                    }
+                    out.push('\n');
+                }
+                Block::Table { headers, rows } => table(&mut out, headers, rows),
+                Block::Chart(series) => chart(&mut out, series),
+            }
+        }
+    }
+
+    out
+}
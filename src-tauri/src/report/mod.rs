@@ -0,0 +1,640 @@
+// ===========================================================================
+// Reports
+// ===========================================================================
+//
+// Ops, Cost and Health reports built straight from the events table. A report
+// is assembled once as a format-neutral document and then rendered to PDF,
+// HTML and Markdown, so every format carries the same numbers.
+
+mod html;
+mod markdown;
+mod pdf;
+
+use std::path::PathBuf;
+
+use chrono::SecondsFormat;
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqlitePool, Row};
+
+use crate::advisor::TimedQuery;
+use crate::anomaly::AnomalyMethod;
+use crate::clock;
+use crate::error::{self, CommandResult, ForgeCommandError};
+use crate::period::TimeWindow;
+use crate::store::{data_dir, get_store_pool};
+use crate::{
+    fetch_series, get_db_pool, insights, query_anomaly_alerts, query_dataforge_metrics,
+    query_forgeagents_metrics, query_neuroforge_metrics, query_rake_metrics, query_system_health,
+    safe_ratio, TimeSeriesPoint, COST_QUERY, COST_SERIES, RAKE_ERROR_RATE_QUERY,
+    RAKE_ERROR_RATE_SERIES, SEARCH_LATENCY_QUERY, SEARCH_LATENCY_SERIES,
+};
+
+/// Reports cover the week leading up to generation.
+const REPORT_WINDOW_HOURS: i64 = 24 * 7;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportType {
+    Ops,
+    Cost,
+    Health,
+}
+
+impl ReportType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReportType::Ops => "ops",
+            ReportType::Cost => "cost",
+            ReportType::Health => "health",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            ReportType::Ops => "Ops Report",
+            ReportType::Cost => "Cost Report",
+            ReportType::Health => "Health Report",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    Pdf,
+    Html,
+    Markdown,
+}
+
+impl ReportFormat {
+    pub const ALL: [ReportFormat; 3] = [ReportFormat::Pdf, ReportFormat::Html, ReportFormat::Markdown];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReportFormat::Pdf => "pdf",
+            ReportFormat::Html => "html",
+            ReportFormat::Markdown => "markdown",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            ReportFormat::Pdf => "pdf",
+            ReportFormat::Html => "html",
+            ReportFormat::Markdown => "md",
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Document model
+// ---------------------------------------------------------------------------
+
+#[derive(Debug)]
+pub struct ChartSeries {
+    pub title: String,
+    pub unit: String,
+    pub points: Vec<TimeSeriesPoint>,
+}
+
+#[derive(Debug)]
+pub enum Block {
+    Paragraph(String),
+    KeyValues(Vec<(String, String)>),
+    Table {
+        headers: Vec<String>,
+        rows: Vec<Vec<String>>,
+    },
+    Chart(ChartSeries),
+}
+
+#[derive(Debug)]
+pub struct Section {
+    pub heading: String,
+    pub blocks: Vec<Block>,
+}
+
+#[derive(Debug)]
+pub struct ReportDocument {
+    pub title: String,
+    pub generated_at: String,
+    pub period: String,
+    pub sections: Vec<Section>,
+}
+
+impl Section {
+    fn new(heading: &str) -> Self {
+        Section {
+            heading: heading.to_string(),
+            blocks: Vec::new(),
+        }
+    }
+
+    fn paragraph(mut self, text: impl Into<String>) -> Self {
+        self.blocks.push(Block::Paragraph(text.into()));
+        self
+    }
+
+    fn key_values(mut self, pairs: Vec<(&str, String)>) -> Self {
+        self.blocks.push(Block::KeyValues(
+            pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect(),
+        ));
+        self
+    }
+
+    fn table(mut self, headers: &[&str], rows: Vec<Vec<String>>) -> Self {
+        self.blocks.push(Block::Table {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows,
+        });
+        self
+    }
+
+    fn chart(mut self, title: &str, unit: &str, points: Vec<TimeSeriesPoint>) -> Self {
+        self.blocks.push(Block::Chart(ChartSeries {
+            title: title.to_string(),
+            unit: unit.to_string(),
+            points,
+        }));
+        self
+    }
+}
+
+fn usd(value: f64) -> String {
+    format!("${:.2}", value)
+}
+
+fn percent(value: f64) -> String {
+    format!("{:.1}%", value)
+}
+
+fn millis(value: f64) -> String {
+    format!("{:.0} ms", value)
+}
+
+// ---------------------------------------------------------------------------
+// Report builders
+// ---------------------------------------------------------------------------
+
+const SERVICES: [&str; 4] = ["dataforge", "neuroforge", "forgeagents", "rake"];
+
+async fn uptime_by_service(pool: &SqlitePool, window: &TimeWindow) -> CommandResult<Vec<(String, f64, i64)>> {
+    let mut rows = Vec::new();
+
+    for service in SERVICES {
+        let row = sqlx::query(
+            "SELECT
+                COUNT(*) FILTER (WHERE severity != 'error') as success,
+                COUNT(*) as total
+             FROM events
+             WHERE service = ?
+             AND timestamp > ?
+             AND timestamp <= ?",
+        )
+        .bind(service)
+        .bind(window.start_bound())
+        .bind(window.end_bound())
+        .fetch_one_timed(pool)
+        .await?;
+
+        let success: i64 = row.get("success");
+        let total: i64 = row.get("total");
+        let uptime = if total == 0 { 100.0 } else { safe_ratio(success as f64 * 100.0, total as f64) };
+
+        rows.push((service.to_string(), uptime, total - success));
+    }
+
+    Ok(rows)
+}
+
+async fn error_summary(pool: &SqlitePool, window: &TimeWindow) -> CommandResult<Vec<Vec<String>>> {
+    let rows = sqlx::query(
+        "SELECT service, event_type, COUNT(*) as errors, MAX(timestamp) as last_seen
+         FROM events
+         WHERE severity = 'error'
+         AND timestamp > ?
+         AND timestamp <= ?
+         GROUP BY service, event_type
+         ORDER BY errors DESC
+         LIMIT 15",
+    )
+    .bind(window.start_bound())
+    .bind(window.end_bound())
+    .fetch_all_timed(pool)
+    .await?
+    .into_iter()
+    .map(|row| {
+        vec![
+            row.get::<String, _>("service"),
+            row.get::<String, _>("event_type"),
+            row.get::<i64, _>("errors").to_string(),
+            row.get::<String, _>("last_seen"),
+        ]
+    })
+    .collect();
+
+    Ok(rows)
+}
+
+async fn cost_by_service(pool: &SqlitePool, window: &TimeWindow) -> CommandResult<Vec<(String, f64)>> {
+    let rows = sqlx::query(
+        "SELECT service, SUM(CAST(json_extract(metrics, '$.cost_usd') AS FLOAT)) as cost
+         FROM events
+         WHERE json_extract(metrics, '$.cost_usd') IS NOT NULL
+         AND timestamp > ?
+         AND timestamp <= ?
+         GROUP BY service
+         ORDER BY cost DESC",
+    )
+    .bind(window.start_bound())
+    .bind(window.end_bound())
+    .fetch_all_timed(pool)
+    .await?
+    .into_iter()
+    .map(|row| (row.get::<String, _>("service"), row.get::<Option<f64>, _>("cost").unwrap_or(0.0)))
+    .collect();
+
+    Ok(rows)
+}
+
+async fn count_events(pool: &SqlitePool, window: &TimeWindow, filter: &str) -> CommandResult<i64> {
+    let query = format!(
+        "SELECT COUNT(*) as count
+         FROM events
+         WHERE {}
+         AND timestamp > ?
+         AND timestamp <= ?",
+        filter
+    );
+
+    sqlx::query(&query)
+        .bind(window.start_bound())
+        .bind(window.end_bound())
+        .fetch_one_timed(pool)
+        .await
+        .map(|row| row.get::<i64, _>("count"))
+        .map_err(ForgeCommandError::from)
+}
+
+fn window_hours(window: &TimeWindow) -> f64 {
+    (window.duration().num_seconds() as f64 / 3600.0).max(1.0)
+}
+
+fn period_label(window: &TimeWindow) -> String {
+    format!("{} to {} UTC", window.start_sql(), window.end_sql())
+}
+
+async fn build_ops_report(pool: &SqlitePool, window: &TimeWindow) -> CommandResult<Vec<Section>> {
+    let uptime = uptime_by_service(pool, window).await?;
+    let errors = error_summary(pool, window).await?;
+    let neuroforge = query_neuroforge_metrics(pool, window, Some(10)).await?;
+    let dataforge = query_dataforge_metrics(pool, window).await?;
+    let agents = query_forgeagents_metrics(pool, window, None).await?;
+    let cost = fetch_series(pool, &COST_SERIES, COST_QUERY, window).await?;
+    let search = fetch_series(pool, &SEARCH_LATENCY_SERIES, SEARCH_LATENCY_QUERY, window).await?;
+    let incidents = query_anomaly_alerts(pool, window, AnomalyMethod::default()).await?;
+
+    Ok(vec![
+        Section::new("System Uptime").table(
+            &["Service", "Uptime", "Error events"],
+            uptime
+                .into_iter()
+                .map(|(service, uptime, failures)| vec![service, percent(uptime), failures.to_string()])
+                .collect(),
+        ),
+        if errors.is_empty() {
+            Section::new("Error Summary").paragraph("No error events in this period.")
+        } else {
+            Section::new("Error Summary").table(&["Service", "Event type", "Errors", "Last seen"], errors)
+        },
+        Section::new("Cost Breakdown")
+            .key_values(vec![
+                ("Total spend", usd(neuroforge.total_cost)),
+                ("Requests", neuroforge.total_requests.to_string()),
+                ("Tokens", neuroforge.total_tokens.to_string()),
+            ])
+            .table(
+                &["Model", "Requests", "Cost", "Cost / 1k tokens"],
+                neuroforge
+                    .top_models
+                    .iter()
+                    .map(|m| vec![m.model.clone(), m.requests.to_string(), usd(m.cost), usd(m.cost_per_1k_tokens)])
+                    .collect(),
+            )
+            .chart("Hourly cost", "USD", cost),
+        Section::new("Performance Trends")
+            .key_values(vec![
+                ("Avg search latency", millis(dataforge.avg_search_duration)),
+                ("Search error rate", percent(dataforge.error_rate)),
+                ("Avg agent latency", millis(agents.avg_latency_ms)),
+                ("Agent success rate", percent(agents.success_rate)),
+            ])
+            .chart("Search latency", "ms", search),
+        if incidents.is_empty() {
+            Section::new("Incidents").paragraph("No anomalies detected in this period.")
+        } else {
+            Section::new("Incidents").table(
+                &["Time", "Service", "Severity", "Detail"],
+                incidents
+                    .into_iter()
+                    .take(20)
+                    .map(|alert| vec![alert.timestamp, alert.service, alert.severity, alert.message])
+                    .collect(),
+            )
+        },
+    ])
+}
+
+async fn build_cost_report(pool: &SqlitePool, window: &TimeWindow) -> CommandResult<Vec<Section>> {
+    let neuroforge = query_neuroforge_metrics(pool, window, None).await?;
+    let by_service = cost_by_service(pool, window).await?;
+    let cost = fetch_series(pool, &COST_SERIES, COST_QUERY, window).await?;
+    let month = insights::query_cost_projection(pool, window.end, 30).await?;
+    let year = insights::query_cost_projection(pool, window.end, 365).await?;
+
+    let users = sqlx::query(
+        "SELECT COUNT(DISTINCT json_extract(metadata, '$.user_id')) as users
+         FROM events
+         WHERE service = 'neuroforge'
+         AND event_type = 'model_request'
+         AND timestamp > ?
+         AND timestamp <= ?",
+    )
+    .bind(window.start_bound())
+    .bind(window.end_bound())
+    .fetch_one_timed(pool)
+    .await?
+    .get::<i64, _>("users");
+
+    let total_spend: f64 = by_service.iter().map(|(_, cost)| cost).sum();
+
+    Ok(vec![
+        Section::new("Total Spend")
+            .key_values(vec![
+                ("Total spend", usd(total_spend)),
+                ("LLM spend", usd(neuroforge.total_cost)),
+                (
+                    "Cost per user (estimated)",
+                    if users > 0 {
+                        format!("{} across {} users", usd(total_spend / users as f64), users)
+                    } else {
+                        "n/a (no user_id in metadata)".to_string()
+                    },
+                ),
+            ])
+            .chart("Hourly cost", "USD", cost),
+        Section::new("By Service").table(
+            &["Service", "Cost", "Share"],
+            by_service
+                .iter()
+                .map(|(service, cost)| {
+                    vec![service.clone(), usd(*cost), percent(safe_ratio(cost * 100.0, total_spend))]
+                })
+                .collect(),
+        ),
+        Section::new("By Model").table(
+            &["Model", "Requests", "Prompt tokens", "Completion tokens", "Cost", "Cost / score point"],
+            neuroforge
+                .top_models
+                .iter()
+                .map(|m| {
+                    vec![
+                        m.model.clone(),
+                        m.requests.to_string(),
+                        m.prompt_tokens.to_string(),
+                        m.completion_tokens.to_string(),
+                        usd(m.cost),
+                        format!("{:.4}", m.cost_per_score_point),
+                    ]
+                })
+                .collect(),
+        ),
+        Section::new("Projections")
+            .key_values(vec![
+                ("Next 30 days", usd(month.projected_cost)),
+                ("Next 12 months", usd(year.projected_cost)),
+                ("Confidence", percent(month.confidence * 100.0)),
+            ])
+            .paragraph("Projections extrapolate the average daily spend of the last 14 days."),
+    ])
+}
+
+async fn build_health_report(pool: &SqlitePool, window: &TimeWindow) -> CommandResult<Vec<Section>> {
+    let health = query_system_health(pool, window.end).await?;
+    let dataforge = query_dataforge_metrics(pool, window).await?;
+    let neuroforge = query_neuroforge_metrics(pool, window, None).await?;
+    let agents = query_forgeagents_metrics(pool, window, None).await?;
+    let rake = query_rake_metrics(pool, window, None).await?;
+    let rake_errors = fetch_series(pool, &RAKE_ERROR_RATE_SERIES, RAKE_ERROR_RATE_QUERY, window).await?;
+
+    let neuroforge_errors = count_events(pool, window, "service = 'neuroforge' AND severity = 'error'").await?;
+    let neuroforge_error_rate = safe_ratio(
+        neuroforge_errors as f64 * 100.0,
+        (neuroforge.total_requests + neuroforge_errors) as f64,
+    );
+    let policy_violations =
+        count_events(pool, window, "service = 'forgeagents' AND event_type LIKE '%policy_violation%'").await?;
+
+    // Thresholds from the monitoring agent spec
+    let mut recommendations = Vec::new();
+    if dataforge.avg_search_duration > 1000.0 {
+        recommendations.push("DataForge search latency is above 1000 ms; scale DataForge or review indexes.");
+    } else if dataforge.avg_search_duration > 500.0 {
+        recommendations.push("DataForge search latency is above 500 ms; watch for further regression.");
+    }
+    if neuroforge_error_rate > 5.0 {
+        recommendations.push("NeuroForge error rate is above 5%; check provider rate limits and fallbacks.");
+    }
+    if rake.error_rate > 5.0 {
+        recommendations.push("Rake failure rate is above 5%; add workers or investigate failing sources.");
+    }
+    if policy_violations as f64 / window_hours(window) > 10.0 {
+        recommendations.push("ForgeAgents policy violations exceed 10/hour; review agent policies.");
+    }
+    if recommendations.is_empty() {
+        recommendations.push("All services are within thresholds; no scaling changes needed.");
+    }
+
+    Ok(vec![
+        Section::new("Service Status").table(
+            &["Service", "Status", "Uptime (24h)"],
+            vec![
+                vec!["dataforge".into(), health.dataforge_status, percent(health.dataforge_uptime)],
+                vec!["neuroforge".into(), health.neuroforge_status, percent(health.neuroforge_uptime)],
+                vec!["forgeagents".into(), health.forgeagents_status, percent(health.forgeagents_uptime)],
+                vec!["rake".into(), health.rake_status, percent(health.rake_uptime)],
+            ],
+        ),
+        Section::new("Performance Metrics")
+            .key_values(vec![
+                ("DataForge searches", dataforge.total_searches.to_string()),
+                ("DataForge avg latency", millis(dataforge.avg_search_duration)),
+                ("NeuroForge requests", neuroforge.total_requests.to_string()),
+                ("NeuroForge error rate", percent(neuroforge_error_rate)),
+                ("ForgeAgents avg latency", millis(agents.avg_latency_ms)),
+                ("Rake error rate (24h)", percent(rake.error_rate)),
+            ])
+            .chart("Rake error rate", "%", rake_errors),
+        Section::new("Scaling Recommendations")
+            .table(&["Recommendation"], recommendations.into_iter().map(|r| vec![r.to_string()]).collect()),
+        Section::new("Security & Policy")
+            .key_values(vec![("Policy violations", policy_violations.to_string())])
+            .paragraph("API keys are never rotated automatically; rotations are surfaced as alerts only."),
+    ])
+}
+
+pub async fn build_report(report_type: ReportType) -> CommandResult<ReportDocument> {
+    let pool = get_db_pool().await?;
+    let window = TimeWindow::last_hours(REPORT_WINDOW_HOURS);
+
+    let sections = match report_type {
+        ReportType::Ops => build_ops_report(&pool, &window).await?,
+        ReportType::Cost => build_cost_report(&pool, &window).await?,
+        ReportType::Health => build_health_report(&pool, &window).await?,
+    };
+
+    Ok(ReportDocument {
+        title: format!("Forge {}", report_type.title()),
+        generated_at: clock::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        period: period_label(&window),
+        sections,
+    })
+}
+
+pub fn render(document: &ReportDocument, format: ReportFormat) -> Vec<u8> {
+    match format {
+        ReportFormat::Pdf => pdf::render(document),
+        ReportFormat::Html => html::render(document).into_bytes(),
+        ReportFormat::Markdown => markdown::render(document).into_bytes(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Storage & commands
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReportInfo {
+    pub name: String,
+    pub report_type: String,
+    pub format: String,
+    pub date: String,
+    pub path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GeneratedReport {
+    /// Path of the PDF when one was produced, otherwise the first format written.
+    pub report_path: String,
+    pub files: Vec<ReportInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LatestReports {
+    pub reports: Vec<ReportInfo>,
+}
+
+fn reports_dir() -> PathBuf {
+    std::env::var("FORGE_COMMAND_REPORTS_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| data_dir().join("reports"))
+}
+
+/// Builds a report, writes it in each requested format and records the files.
+pub async fn generate_report(
+    report_type: ReportType,
+    formats: &[ReportFormat],
+) -> CommandResult<GeneratedReport> {
+    let document = build_report(report_type).await?;
+    let store = get_store_pool().await?;
+
+    let dir = reports_dir();
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| ForgeCommandError::Io(format!("Failed to create reports directory: {}", e)))?;
+
+    let stamp = clock::now().format("%Y%m%d_%H%M%S");
+    let mut files = Vec::new();
+
+    for &format in formats {
+        let name = format!("{}_report_{}.{}", report_type.as_str(), stamp, format.extension());
+        let path = dir.join(&name);
+
+        tokio::fs::write(&path, render(&document, format))
+            .await
+            .map_err(|e| ForgeCommandError::Io(format!("Failed to write {}: {}", path.display(), e)))?;
+
+        let path = path.display().to_string();
+        sqlx::query("INSERT INTO reports (report_type, format, generated_at, path) VALUES (?, ?, ?, ?)")
+            .bind(report_type.as_str())
+            .bind(format.as_str())
+            .bind(&document.generated_at)
+            .bind(&path)
+            .execute(&store)
+            .await?;
+
+        files.push(ReportInfo {
+            name,
+            report_type: report_type.as_str().to_string(),
+            format: format.as_str().to_string(),
+            date: document.generated_at.clone(),
+            path,
+        });
+    }
+
+    let report_path = files
+        .iter()
+        .find(|file| file.format == ReportFormat::Pdf.as_str())
+        .or_else(|| files.first())
+        .map(|file| file.path.clone())
+        .ok_or_else(|| ForgeCommandError::invalid_input("No report formats requested"))?;
+
+    Ok(GeneratedReport { report_path, files })
+}
+
+#[tauri::command]
+pub async fn generate_report_now(
+    report_type: ReportType,
+    formats: Option<Vec<ReportFormat>>,
+) -> CommandResult<GeneratedReport> {
+    error::command("generate_report_now", async move {
+        let formats = formats.unwrap_or_else(|| ReportFormat::ALL.to_vec());
+        generate_report(report_type, &formats).await
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_latest_reports(limit: Option<i64>) -> CommandResult<LatestReports> {
+    error::command("get_latest_reports", async move {
+        let store = get_store_pool().await?;
+
+        let reports = sqlx::query(
+            "SELECT report_type, format, generated_at, path
+             FROM reports
+             ORDER BY generated_at DESC, report_id DESC
+             LIMIT ?",
+        )
+        .bind(limit.unwrap_or(30))
+        .fetch_all(&store)
+        .await?
+        .into_iter()
+        .map(|row| {
+            let path = row.get::<String, _>("path");
+            ReportInfo {
+                name: PathBuf::from(&path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                report_type: row.get("report_type"),
+                format: row.get("format"),
+                date: row.get("generated_at"),
+                path,
+            }
+        })
+        .collect();
+
+        Ok(LatestReports { reports })
+    })
+    .await
+}
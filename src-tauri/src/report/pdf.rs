@@ -0,0 +1,306 @@
+// Vector PDF renderer. Text uses the built-in Helvetica faces (no font
+// embedding) and charts are drawn as paths, so output stays sharp at any zoom
+// and needs no webview.
+
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str};
+
+use super::{Block, ChartSeries, ReportDocument};
+
+// A4 in points
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 50.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - MARGIN * 2.0;
+
+const FONT_REGULAR: Name = Name(b"F1");
+const FONT_BOLD: Name = Name(b"F2");
+
+const BODY_SIZE: f32 = 10.0;
+const LINE_HEIGHT: f32 = 14.0;
+const CHART_HEIGHT: f32 = 140.0;
+
+/// Helvetica averages roughly half an em per character; good enough for wrapping.
+const AVG_CHAR_WIDTH: f32 = 0.5;
+
+const ACCENT: (f32, f32, f32) = (0.92, 0.35, 0.05);
+const MUTED: (f32, f32, f32) = (0.45, 0.45, 0.5);
+
+/// Maps text onto WinAnsi bytes for the standard fonts; anything outside
+/// Latin-1 becomes '?'.
+fn encode(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| if (c as u32) < 256 { c as u8 } else { b'?' })
+        .collect()
+}
+
+fn max_chars(width: f32, size: f32) -> usize {
+    (width / (size * AVG_CHAR_WIDTH)).floor().max(1.0) as usize
+}
+
+fn truncate(text: &str, limit: usize) -> String {
+    if text.chars().count() <= limit {
+        text.to_string()
+    } else {
+        let mut truncated: String = text.chars().take(limit.saturating_sub(3)).collect();
+        truncated.push_str("...");
+        truncated
+    }
+}
+
+fn wrap(text: &str, limit: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > limit {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+struct Layout {
+    pages: Vec<Content>,
+    current: Content,
+    y: f32,
+}
+
+impl Layout {
+    fn new() -> Self {
+        Layout {
+            pages: Vec::new(),
+            current: Content::new(),
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    fn new_page(&mut self) {
+        let finished = std::mem::replace(&mut self.current, Content::new());
+        self.pages.push(finished);
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn text_at(&mut self, x: f32, y: f32, font: Name, size: f32, color: (f32, f32, f32), text: &str) {
+        self.current
+            .set_fill_rgb(color.0, color.1, color.2)
+            .begin_text()
+            .set_font(font, size)
+            .next_line(x, y)
+            .show(Str(&encode(text)))
+            .end_text();
+    }
+
+    fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, width: f32, color: (f32, f32, f32)) {
+        self.current
+            .set_stroke_rgb(color.0, color.1, color.2)
+            .set_line_width(width)
+            .move_to(x1, y1)
+            .line_to(x2, y2)
+            .stroke();
+    }
+
+    fn heading(&mut self, text: &str, size: f32) {
+        self.ensure_space(size + LINE_HEIGHT * 2.0);
+        self.y -= size;
+        self.text_at(MARGIN, self.y, FONT_BOLD, size, (0.1, 0.1, 0.12), text);
+        self.y -= 6.0;
+        self.line(MARGIN, self.y, PAGE_WIDTH - MARGIN, self.y, 0.5, ACCENT);
+        self.y -= LINE_HEIGHT;
+    }
+
+    fn paragraph(&mut self, text: &str, color: (f32, f32, f32)) {
+        for line in wrap(text, max_chars(CONTENT_WIDTH, BODY_SIZE)) {
+            self.ensure_space(LINE_HEIGHT);
+            self.text_at(MARGIN, self.y, FONT_REGULAR, BODY_SIZE, color, &line);
+            self.y -= LINE_HEIGHT;
+        }
+        self.y -= 4.0;
+    }
+
+    fn key_values(&mut self, pairs: &[(String, String)]) {
+        let key_width = CONTENT_WIDTH * 0.4;
+        for (key, value) in pairs {
+            self.ensure_space(LINE_HEIGHT);
+            self.text_at(MARGIN, self.y, FONT_BOLD, BODY_SIZE, (0.1, 0.1, 0.12), &truncate(key, max_chars(key_width, BODY_SIZE)));
+            self.text_at(
+                MARGIN + key_width,
+                self.y,
+                FONT_REGULAR,
+                BODY_SIZE,
+                (0.1, 0.1, 0.12),
+                &truncate(value, max_chars(CONTENT_WIDTH - key_width, BODY_SIZE)),
+            );
+            self.y -= LINE_HEIGHT;
+        }
+        self.y -= 4.0;
+    }
+
+    fn table_row(&mut self, cells: &[String], font: Name) {
+        let column_width = CONTENT_WIDTH / cells.len().max(1) as f32;
+        let limit = max_chars(column_width - 4.0, BODY_SIZE);
+
+        self.ensure_space(LINE_HEIGHT);
+        for (i, cell) in cells.iter().enumerate() {
+            let x = MARGIN + column_width * i as f32;
+            self.text_at(x, self.y, font, BODY_SIZE, (0.1, 0.1, 0.12), &truncate(cell, limit));
+        }
+        self.y -= 4.0;
+        self.line(MARGIN, self.y, PAGE_WIDTH - MARGIN, self.y, 0.25, (0.85, 0.85, 0.88));
+        self.y -= LINE_HEIGHT - 4.0;
+    }
+
+    fn table(&mut self, headers: &[String], rows: &[Vec<String>]) {
+        self.table_row(headers, FONT_BOLD);
+        for row in rows {
+            // Repeat the header when a table runs onto a new page
+            if self.y - LINE_HEIGHT < MARGIN {
+                self.new_page();
+                self.table_row(headers, FONT_BOLD);
+            }
+            self.table_row(row, FONT_REGULAR);
+        }
+        self.y -= 6.0;
+    }
+
+    fn chart(&mut self, chart: &ChartSeries) {
+        self.ensure_space(CHART_HEIGHT + LINE_HEIGHT * 3.0);
+        self.text_at(MARGIN, self.y, FONT_BOLD, BODY_SIZE, (0.1, 0.1, 0.12), &format!("{} ({})", chart.title, chart.unit));
+        self.y -= LINE_HEIGHT;
+
+        if chart.points.is_empty() {
+            self.paragraph("No data in this period.", MUTED);
+            return;
+        }
+
+        let top = self.y;
+        let bottom = top - CHART_HEIGHT;
+        let left = MARGIN + 40.0;
+        let right = PAGE_WIDTH - MARGIN;
+
+        self.line(left, bottom, right, bottom, 0.5, MUTED);
+        self.line(left, bottom, left, top, 0.5, MUTED);
+
+        let values: Vec<f64> = chart.points.iter().map(|p| p.value).collect();
+        let min = values.iter().cloned().fold(f64::INFINITY, f64::min).min(0.0);
+        let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let span = if max > min { max - min } else { 1.0 };
+        let step = if values.len() > 1 {
+            (right - left) / (values.len() - 1) as f32
+        } else {
+            0.0
+        };
+
+        self.current
+            .set_stroke_rgb(ACCENT.0, ACCENT.1, ACCENT.2)
+            .set_line_width(1.2);
+        for (i, value) in values.iter().enumerate() {
+            let x = left + step * i as f32;
+            let y = bottom + ((value - min) / span) as f32 * CHART_HEIGHT;
+            if i == 0 {
+                self.current.move_to(x, y);
+            } else {
+                self.current.line_to(x, y);
+            }
+        }
+        self.current.stroke();
+
+        self.text_at(MARGIN, top - 8.0, FONT_REGULAR, 7.0, MUTED, &format!("{:.2}", max));
+        self.text_at(MARGIN, bottom, FONT_REGULAR, 7.0, MUTED, &format!("{:.2}", min));
+        self.text_at(left, bottom - 10.0, FONT_REGULAR, 7.0, MUTED, &chart.points[0].timestamp);
+        let last = &chart.points[chart.points.len() - 1].timestamp;
+        let last_width = last.chars().count() as f32 * 7.0 * AVG_CHAR_WIDTH;
+        self.text_at(right - last_width, bottom - 10.0, FONT_REGULAR, 7.0, MUTED, last);
+
+        self.y = bottom - LINE_HEIGHT * 2.0;
+    }
+
+    fn finish(mut self) -> Vec<Content> {
+        self.pages.push(self.current);
+        self.pages
+    }
+}
+
+pub fn render(document: &ReportDocument) -> Vec<u8> {
+    let mut layout = Layout::new();
+
+    layout.heading(&document.title, 20.0);
+    layout.paragraph(&format!("Period: {}", document.period), MUTED);
+    layout.paragraph(&format!("Generated: {}", document.generated_at), MUTED);
+
+    for section in &document.sections {
+        layout.y -= 6.0;
+        layout.heading(&section.heading, 13.0);
+
+        for block in &section.blocks {
+            match block {
+                Block::Paragraph(text) => layout.paragraph(text, (0.1, 0.1, 0.12)),
+                Block::KeyValues(pairs) => layout.key_values(pairs),
+                Block::Table { headers, rows } => layout.table(headers, rows),
+                Block::Chart(chart) => layout.chart(chart),
+            }
+        }
+    }
+
+    let pages = layout.finish();
+    let page_count = pages.len();
+
+    let catalog_id = Ref::new(1);
+    let page_tree_id = Ref::new(2);
+    let regular_id = Ref::new(3);
+    let bold_id = Ref::new(4);
+    let page_ids: Vec<Ref> = (0..page_count).map(|i| Ref::new(5 + i as i32 * 2)).collect();
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.pages(page_tree_id)
+        .kids(page_ids.iter().copied())
+        .count(page_count as i32);
+
+    pdf.type1_font(regular_id)
+        .base_font(Name(b"Helvetica"))
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+    pdf.type1_font(bold_id)
+        .base_font(Name(b"Helvetica-Bold"))
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+
+    for (i, mut content) in pages.into_iter().enumerate() {
+        let page_id = page_ids[i];
+        let content_id = Ref::new(page_id.get() + 1);
+
+        let footer = format!("{} - page {} of {}", document.title, i + 1, page_count);
+        content
+            .set_fill_rgb(MUTED.0, MUTED.1, MUTED.2)
+            .begin_text()
+            .set_font(FONT_REGULAR, 8.0)
+            .next_line(MARGIN, MARGIN / 2.0)
+            .show(Str(&encode(&footer)))
+            .end_text();
+
+        let mut page = pdf.page(page_id);
+        page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT));
+        page.parent(page_tree_id);
+        page.contents(content_id);
+        let mut resources = page.resources();
+        resources.fonts().pair(FONT_REGULAR, regular_id).pair(FONT_BOLD, bold_id);
+        resources.finish();
+        page.finish();
+
+        pdf.stream(content_id, &content.finish());
+    }
+
+    pdf.finish()
+}
@@ -0,0 +1,755 @@
+// ===========================================================================
+// Rollup Store
+// ===========================================================================
+//
+// Pre-aggregated per-minute/hour/day buckets kept in the Forge Command store,
+// so charts stop re-running json_extract over every raw event on each refresh.
+// Progress is tracked by the events table's rowid rather than by time: each
+// refresh rebuilds the buckets from the earliest event inserted since the
+// last one, so backfilled and late-arriving events land in their buckets
+// whatever their timestamps. Switching to another events database starts
+// over from scratch, since its rowids say nothing about what was rolled up.
+//
+// Hour buckets are downsampled from minutes and day buckets from hours, and
+// each resolution has its own retention. Every numeric key in an event's
+// metrics is rolled up, along with event and error counts. Samples scraped
+// from Prometheus endpoints are written straight into every resolution under
+// their own event type, since there are no raw events to rebuild them from.
+
+use std::time::Duration;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqlitePool, Row};
+
+use crate::clock;
+use crate::error::{self, CommandResult, ForgeCommandError};
+use crate::period::{column_bound, TimeWindow, SQL_DATETIME_FORMAT};
+use crate::store::get_store_pool;
+use crate::{get_db_source, TimeSeriesPoint};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Rollups older than this (relative to a window's end) are too stale to serve.
+const MAX_STALENESS_MINUTES: i64 = 3;
+
+/// Event type of scraped Prometheus samples. Rebuilds from raw events leave
+/// these rows alone, and series over every event type of a service skip them.
+pub const SCRAPE_EVENT_TYPE: &str = "prometheus_scrape";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Resolution {
+    Minute,
+    Hour,
+    Day,
+}
+
+impl Resolution {
+    pub const ALL: [Resolution; 3] = [Resolution::Minute, Resolution::Hour, Resolution::Day];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Resolution::Minute => "minute",
+            Resolution::Hour => "hour",
+            Resolution::Day => "day",
+        }
+    }
+
+    /// Bucket start format, valid for both chrono and SQLite's strftime.
+    fn bucket_format(self) -> &'static str {
+        match self {
+            Resolution::Minute => "%Y-%m-%d %H:%M:00",
+            Resolution::Hour => "%Y-%m-%d %H:00:00",
+            Resolution::Day => "%Y-%m-%d 00:00:00",
+        }
+    }
+
+    fn default_retention_hours(self) -> i64 {
+        match self {
+            Resolution::Minute => 48,
+            Resolution::Hour => 90 * 24,
+            Resolution::Day => 730 * 24,
+        }
+    }
+
+    /// Shortest retention that still lets the next resolution be downsampled
+    /// from this one.
+    fn min_retention_hours(self) -> i64 {
+        match self {
+            Resolution::Minute => 2,
+            Resolution::Hour => 48,
+            Resolution::Day => 24,
+        }
+    }
+
+    fn floor(self, at: NaiveDateTime) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&at.format(self.bucket_format()).to_string(), SQL_DATETIME_FORMAT)
+            .unwrap_or(at)
+    }
+
+    fn floor_sql(self, at: NaiveDateTime) -> String {
+        at.format(self.bucket_format()).to_string()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollupStatus {
+    pub resolution: Resolution,
+    pub retention_hours: i64,
+    /// Earliest bucket the rollups fully cover.
+    pub covered_from: Option<String>,
+    /// Time of the last refresh; buckets up to here are current.
+    pub watermark: Option<String>,
+    pub buckets: i64,
+}
+
+struct RollupState {
+    retention_hours: i64,
+    covered_from: Option<NaiveDateTime>,
+    watermark: Option<NaiveDateTime>,
+    /// Highest events rowid rolled up by the last refresh.
+    last_rowid: Option<i64>,
+    /// Events database the rollups were built from.
+    source: Option<String>,
+}
+
+fn parse_sql_datetime(value: Option<String>) -> Option<NaiveDateTime> {
+    value.and_then(|value| NaiveDateTime::parse_from_str(&value, SQL_DATETIME_FORMAT).ok())
+}
+
+async fn load_state(store: &SqlitePool, resolution: Resolution) -> CommandResult<RollupState> {
+    let row = sqlx::query(
+        "SELECT retention_hours, covered_from, watermark, last_rowid, source FROM rollup_state WHERE resolution = ?",
+    )
+        .bind(resolution.as_str())
+        .fetch_optional(store)
+        .await?;
+
+    Ok(match row {
+        Some(row) => RollupState {
+            retention_hours: row.get("retention_hours"),
+            covered_from: parse_sql_datetime(row.get("covered_from")),
+            watermark: parse_sql_datetime(row.get("watermark")),
+            last_rowid: row.get("last_rowid"),
+            source: row.get("source"),
+        },
+        None => RollupState {
+            retention_hours: resolution.default_retention_hours(),
+            covered_from: None,
+            watermark: None,
+            last_rowid: None,
+            source: None,
+        },
+    })
+}
+
+async fn save_state(store: &SqlitePool, resolution: Resolution, state: &RollupState) -> CommandResult<()> {
+    sqlx::query(
+        "INSERT INTO rollup_state (resolution, retention_hours, covered_from, watermark, last_rowid, source)
+         VALUES (?, ?, ?, ?, ?, ?)
+         ON CONFLICT(resolution) DO UPDATE SET
+            retention_hours = excluded.retention_hours,
+            covered_from = excluded.covered_from,
+            watermark = excluded.watermark,
+            last_rowid = excluded.last_rowid,
+            source = excluded.source",
+    )
+    .bind(resolution.as_str())
+    .bind(state.retention_hours)
+    .bind(state.covered_from.map(|at| at.format(SQL_DATETIME_FORMAT).to_string()))
+    .bind(state.watermark.map(|at| at.format(SQL_DATETIME_FORMAT).to_string()))
+    .bind(state.last_rowid)
+    .bind(&state.source)
+    .execute(store)
+    .await?;
+
+    Ok(())
+}
+
+// ===========================================================================
+// Building
+// ===========================================================================
+
+/// Rebuilds `resolution` buckets from the one containing `from` onwards,
+/// straight from raw events.
+async fn aggregate_events(
+    events: &SqlitePool,
+    store: &SqlitePool,
+    resolution: Resolution,
+    from: NaiveDateTime,
+) -> CommandResult<()> {
+    let format = resolution.bucket_format();
+    let from_bucket = resolution.floor_sql(from);
+    let from_bound = column_bound(resolution.floor(from));
+
+    let counts = sqlx::query(&format!(
+        "SELECT
+            strftime('{format}', timestamp) AS bucket,
+            service,
+            event_type,
+            COALESCE(json_extract(metadata, '$.model'), '') AS dimension,
+            COUNT(*) AS events,
+            SUM(CASE WHEN severity = 'error' THEN 1 ELSE 0 END) AS errors
+         FROM events
+         WHERE timestamp >= ?
+         GROUP BY bucket, service, event_type, dimension
+         HAVING bucket IS NOT NULL"
+    ))
+    .bind(&from_bound)
+    .fetch_all(events)
+    .await?;
+
+    // Malformed metrics JSON would abort json_each for the whole query
+    let values = sqlx::query(&format!(
+        "SELECT
+            strftime('{format}', e.timestamp) AS bucket,
+            e.service,
+            e.event_type,
+            COALESCE(json_extract(e.metadata, '$.model'), '') AS dimension,
+            m.key AS metric,
+            COUNT(*) AS samples,
+            CAST(SUM(m.value) AS FLOAT) AS total,
+            CAST(MIN(m.value) AS FLOAT) AS minimum,
+            CAST(MAX(m.value) AS FLOAT) AS maximum
+         FROM events e, json_each(CASE WHEN json_valid(e.metrics) THEN e.metrics ELSE '{{}}' END) m
+         WHERE m.type IN ('integer', 'real')
+         AND e.timestamp >= ?
+         GROUP BY bucket, e.service, e.event_type, dimension, metric
+         HAVING bucket IS NOT NULL"
+    ))
+    .bind(&from_bound)
+    .fetch_all(events)
+    .await?;
+
+    let mut tx = store.begin().await?;
+
+    for table in ["rollup_counts", "rollup_values"] {
+        sqlx::query(&format!(
+            "DELETE FROM {} WHERE resolution = ? AND bucket >= ? AND event_type != ?",
+            table
+        ))
+        .bind(resolution.as_str())
+        .bind(&from_bucket)
+        .bind(SCRAPE_EVENT_TYPE)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for row in counts {
+        sqlx::query(
+            "INSERT INTO rollup_counts (resolution, bucket, service, event_type, dimension, events, errors)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(resolution.as_str())
+        .bind(row.get::<Option<String>, _>("bucket"))
+        .bind(row.get::<String, _>("service"))
+        .bind(row.get::<String, _>("event_type"))
+        .bind(row.get::<String, _>("dimension"))
+        .bind(row.get::<i64, _>("events"))
+        .bind(row.get::<Option<i64>, _>("errors").unwrap_or(0))
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for row in values {
+        sqlx::query(
+            "INSERT INTO rollup_values
+                (resolution, bucket, service, event_type, dimension, metric, samples, total, minimum, maximum)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(resolution.as_str())
+        .bind(row.get::<Option<String>, _>("bucket"))
+        .bind(row.get::<String, _>("service"))
+        .bind(row.get::<String, _>("event_type"))
+        .bind(row.get::<String, _>("dimension"))
+        .bind(row.get::<String, _>("metric"))
+        .bind(row.get::<i64, _>("samples"))
+        .bind(row.get::<Option<f64>, _>("total").unwrap_or(0.0))
+        .bind(row.get::<Option<f64>, _>("minimum"))
+        .bind(row.get::<Option<f64>, _>("maximum"))
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    Ok(tx.commit().await?)
+}
+
+/// Rebuilds `to` buckets from `from` onwards out of the finer `source` buckets.
+async fn downsample(store: &SqlitePool, source: Resolution, to: Resolution, from: &str) -> CommandResult<()> {
+    let format = to.bucket_format();
+    let mut tx = store.begin().await?;
+
+    for table in ["rollup_counts", "rollup_values"] {
+        sqlx::query(&format!("DELETE FROM {} WHERE resolution = ? AND bucket >= ?", table))
+            .bind(to.as_str())
+            .bind(from)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    sqlx::query(&format!(
+        "INSERT INTO rollup_counts (resolution, bucket, service, event_type, dimension, events, errors)
+         SELECT ?, strftime('{format}', bucket) AS target, service, event_type, dimension, SUM(events), SUM(errors)
+         FROM rollup_counts
+         WHERE resolution = ? AND bucket >= ?
+         GROUP BY target, service, event_type, dimension"
+    ))
+    .bind(to.as_str())
+    .bind(source.as_str())
+    .bind(from)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(&format!(
+        "INSERT INTO rollup_values
+            (resolution, bucket, service, event_type, dimension, metric, samples, total, minimum, maximum)
+         SELECT ?, strftime('{format}', bucket) AS target, service, event_type, dimension, metric,
+                SUM(samples), SUM(total), MIN(minimum), MAX(maximum)
+         FROM rollup_values
+         WHERE resolution = ? AND bucket >= ?
+         GROUP BY target, service, event_type, dimension, metric"
+    ))
+    .bind(to.as_str())
+    .bind(source.as_str())
+    .bind(from)
+    .execute(&mut *tx)
+    .await?;
+
+    Ok(tx.commit().await?)
+}
+
+/// Drops buckets that fall outside the resolution's retention.
+async fn prune(store: &SqlitePool, resolution: Resolution, cutoff: &str) -> CommandResult<()> {
+    for table in ["rollup_counts", "rollup_values"] {
+        sqlx::query(&format!("DELETE FROM {} WHERE resolution = ? AND bucket < ?", table))
+            .bind(resolution.as_str())
+            .bind(cutoff)
+            .execute(store)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Highest rowid in the events table. Rows get increasing rowids as they
+/// are inserted, so anything above the last refresh's value is new.
+async fn max_event_rowid(events: &SqlitePool) -> CommandResult<i64> {
+    Ok(sqlx::query("SELECT COALESCE(MAX(rowid), 0) AS last_rowid FROM events")
+        .fetch_one(events)
+        .await?
+        .get("last_rowid"))
+}
+
+/// Earliest timestamp among the events inserted after `after` up to `until`.
+async fn earliest_inserted(events: &SqlitePool, after: i64, until: i64) -> CommandResult<Option<NaiveDateTime>> {
+    let earliest = sqlx::query(
+        "SELECT strftime('%Y-%m-%d %H:%M:%S', MIN(timestamp)) AS earliest
+         FROM events
+         WHERE rowid > ? AND rowid <= ?",
+    )
+    .bind(after)
+    .bind(until)
+    .fetch_one(events)
+    .await?
+    .get("earliest");
+
+    Ok(parse_sql_datetime(earliest))
+}
+
+/// Brings every resolution up to date. The first refresh backfills each
+/// resolution from raw events over its full retention; later refreshes
+/// rebuild every bucket from the earliest newly inserted event onwards. A
+/// resolution is downsampled from the finer one where that still covers the
+/// rebuilt range, and aggregated from raw events where it has been pruned.
+/// Rollups built from another events database are replaced entirely.
+pub async fn refresh_rollups() -> CommandResult<()> {
+    let (source, events) = get_db_source().await?;
+    let store = get_store_pool().await?;
+    let now = clock::now_naive();
+    let last_rowid = max_event_rowid(&events).await?;
+
+    let minute_state = load_state(&store, Resolution::Minute).await?;
+
+    match (minute_state.watermark, minute_state.last_rowid) {
+        // Rowids only go backwards when the events table was replaced
+        (Some(_), Some(previous))
+            if previous <= last_rowid && minute_state.source.as_deref() == Some(source.as_str()) =>
+        {
+            let rebuild_from = earliest_inserted(&events, previous, last_rowid).await?;
+            let mut finer: Option<(Resolution, Option<NaiveDateTime>)> = None;
+
+            for resolution in Resolution::ALL {
+                let state = load_state(&store, resolution).await?;
+
+                if let Some(rebuild_from) = rebuild_from {
+                    // Buckets before a resolution's own coverage are never served
+                    let from = resolution.floor(state.covered_from.map_or(rebuild_from, |covered| rebuild_from.max(covered)));
+                    match finer {
+                        Some((source, Some(source_covered))) if source_covered <= from => {
+                            downsample(&store, source, resolution, &resolution.floor_sql(from)).await?
+                        }
+                        _ => aggregate_events(&events, &store, resolution, from).await?,
+                    }
+                }
+
+                let cutoff = resolution.floor(now - chrono::Duration::hours(state.retention_hours));
+                prune(&store, resolution, &cutoff.format(SQL_DATETIME_FORMAT).to_string()).await?;
+
+                let covered_from = state.covered_from.map(|covered| covered.max(cutoff));
+                finer = Some((resolution, covered_from));
+
+                save_state(
+                    &store,
+                    resolution,
+                    &RollupState {
+                        covered_from,
+                        watermark: Some(now),
+                        last_rowid: Some(last_rowid),
+                        source: Some(source.clone()),
+                        ..state
+                    },
+                )
+                .await?;
+            }
+        }
+        _ => {
+            for resolution in Resolution::ALL {
+                let state = load_state(&store, resolution).await?;
+                let from = resolution.floor(now - chrono::Duration::hours(state.retention_hours));
+                aggregate_events(&events, &store, resolution, from).await?;
+                // Drops whatever an earlier source left before the rebuilt range
+                prune(&store, resolution, &from.format(SQL_DATETIME_FORMAT).to_string()).await?;
+
+                save_state(
+                    &store,
+                    resolution,
+                    &RollupState {
+                        covered_from: Some(from),
+                        watermark: Some(now),
+                        last_rowid: Some(last_rowid),
+                        source: Some(source.clone()),
+                        ..state
+                    },
+                )
+                .await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Background loop keeping the rollups current.
+pub async fn run_rollup_refresh() {
+    loop {
+        if let Err(e) = refresh_rollups().await {
+            eprintln!("Rollup refresh failed: {}", e);
+        }
+        tokio::time::sleep(REFRESH_INTERVAL).await;
+    }
+}
+
+// ===========================================================================
+// Scraped Samples
+// ===========================================================================
+
+/// One scraped value: a metric of `service`, with its labels rendered into
+/// the dimension.
+#[derive(Debug, Clone)]
+pub struct ScrapedSample {
+    pub dimension: String,
+    pub metric: String,
+    pub value: f64,
+}
+
+/// Adds one scrape of `service` to the buckets containing `at` in every
+/// resolution. Each label set counts one event per scrape.
+pub async fn record_scrape(service: &str, samples: &[ScrapedSample], at: NaiveDateTime) -> CommandResult<()> {
+    let store = get_store_pool().await?;
+    let mut tx = store.begin().await?;
+    let mut dimensions: Vec<&str> = samples.iter().map(|sample| sample.dimension.as_str()).collect();
+    dimensions.sort_unstable();
+    dimensions.dedup();
+
+    for resolution in Resolution::ALL {
+        let bucket = resolution.floor_sql(at);
+
+        for dimension in &dimensions {
+            sqlx::query(
+                "INSERT INTO rollup_counts (resolution, bucket, service, event_type, dimension, events, errors)
+                 VALUES (?, ?, ?, ?, ?, 1, 0)
+                 ON CONFLICT(resolution, bucket, service, event_type, dimension)
+                 DO UPDATE SET events = events + 1",
+            )
+            .bind(resolution.as_str())
+            .bind(&bucket)
+            .bind(service)
+            .bind(SCRAPE_EVENT_TYPE)
+            .bind(dimension)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for sample in samples {
+            sqlx::query(
+                "INSERT INTO rollup_values
+                    (resolution, bucket, service, event_type, dimension, metric, samples, total, minimum, maximum)
+                 VALUES (?, ?, ?, ?, ?, ?, 1, ?, ?, ?)
+                 ON CONFLICT(resolution, bucket, service, event_type, dimension, metric) DO UPDATE SET
+                    samples = samples + 1,
+                    total = total + excluded.total,
+                    minimum = MIN(minimum, excluded.minimum),
+                    maximum = MAX(maximum, excluded.maximum)",
+            )
+            .bind(resolution.as_str())
+            .bind(&bucket)
+            .bind(service)
+            .bind(SCRAPE_EVENT_TYPE)
+            .bind(&sample.dimension)
+            .bind(&sample.metric)
+            .bind(sample.value)
+            .bind(sample.value)
+            .bind(sample.value)
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
+    Ok(tx.commit().await?)
+}
+
+// ===========================================================================
+// Reading
+// ===========================================================================
+
+#[derive(Debug, Clone, Copy)]
+pub enum RollupAggregate<'a> {
+    /// Number of events per bucket.
+    Count,
+    /// Sum of a metrics key.
+    Sum(&'a str),
+    /// Mean of a metrics key over the events that report it.
+    Avg(&'a str),
+    /// Percentage of events with severity `error`.
+    ErrorRate,
+}
+
+impl RollupAggregate<'_> {
+    /// Counts and sums are zero in an hour without events; means and rates
+    /// have no value there.
+    pub fn is_additive(&self) -> bool {
+        matches!(self, RollupAggregate::Count | RollupAggregate::Sum(_))
+    }
+}
+
+/// A chart series that can be served from hourly rollups.
+#[derive(Debug, Clone, Copy)]
+pub struct RollupSeries<'a> {
+    pub service: &'a str,
+    /// `None` covers every event type of the service except scraped samples.
+    pub event_type: Option<&'a str>,
+    pub aggregate: RollupAggregate<'a>,
+    /// strftime format of the returned timestamps, matching the raw query.
+    pub label_format: &'static str,
+}
+
+/// Whether hourly rollups fully cover `window`. The last bucket holds its
+/// whole hour, so windows ending before the last refresh are left to raw
+/// events rather than counting what came after their end. Rollups built from
+/// another events database never cover anything.
+async fn covers(store: &SqlitePool, window: &TimeWindow) -> CommandResult<bool> {
+    let state = load_state(store, Resolution::Hour).await?;
+    let (source, _) = get_db_source().await?;
+
+    Ok(match (state.covered_from, state.watermark) {
+        (Some(covered_from), Some(watermark)) => {
+            window.is_bounded()
+                && state.source.as_deref() == Some(source.as_str())
+                && covered_from <= Resolution::Hour.floor(window.start)
+                && watermark >= window.end - chrono::Duration::minutes(MAX_STALENESS_MINUTES)
+                && watermark <= window.end
+        }
+        _ => false,
+    })
+}
+
+/// Bucket rows for a series, optionally split by dimension (model).
+async fn query_series(
+    store: &SqlitePool,
+    series: &RollupSeries<'_>,
+    window: &TimeWindow,
+    by_dimension: bool,
+) -> CommandResult<Vec<(String, String, f64)>> {
+    let metric = match series.aggregate {
+        RollupAggregate::Sum(metric) | RollupAggregate::Avg(metric) => metric,
+        RollupAggregate::Count | RollupAggregate::ErrorRate => "",
+    };
+    let dimension = if by_dimension { "c.dimension" } else { "''" };
+
+    let rows = sqlx::query(&format!(
+        "SELECT
+            strftime('{}', c.bucket) AS hour,
+            {dimension} AS dimension,
+            SUM(c.events) AS events,
+            SUM(c.errors) AS errors,
+            SUM(v.samples) AS samples,
+            CAST(SUM(v.total) AS FLOAT) AS total
+         FROM rollup_counts c
+         LEFT JOIN rollup_values v
+            ON v.resolution = c.resolution AND v.bucket = c.bucket AND v.service = c.service
+            AND v.event_type = c.event_type AND v.dimension = c.dimension AND v.metric = ?
+         WHERE c.resolution = 'hour'
+         AND c.service = ?
+         AND (c.event_type = ? OR (? IS NULL AND c.event_type != ?))
+         AND c.bucket >= ?
+         AND c.bucket <= ?
+         GROUP BY hour, {dimension}
+         ORDER BY hour ASC",
+        series.label_format
+    ))
+    .bind(metric)
+    .bind(series.service)
+    .bind(series.event_type)
+    .bind(series.event_type)
+    .bind(SCRAPE_EVENT_TYPE)
+    .bind(Resolution::Hour.floor_sql(window.start))
+    .bind(window.end_sql())
+    .fetch_all(store)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let events = row.get::<i64, _>("events") as f64;
+            let total = row.get::<Option<f64>, _>("total").unwrap_or(0.0);
+            let samples = row.get::<Option<i64>, _>("samples").unwrap_or(0) as f64;
+
+            let value = match series.aggregate {
+                RollupAggregate::Count => events,
+                RollupAggregate::Sum(_) => total,
+                RollupAggregate::Avg(_) => crate::safe_ratio(total, samples),
+                RollupAggregate::ErrorRate => crate::safe_ratio(row.get::<i64, _>("errors") as f64, events) * 100.0,
+            };
+
+            (row.get("hour"), row.get("dimension"), value)
+        })
+        .collect())
+}
+
+/// Hourly points for `series`, or `None` when the rollups don't cover the
+/// window and the caller should query raw events. The first bucket covers
+/// its whole hour even when the window starts part-way through it.
+pub async fn fetch_series(series: &RollupSeries<'_>, window: &TimeWindow) -> CommandResult<Option<Vec<TimeSeriesPoint>>> {
+    let store = get_store_pool().await?;
+    if !covers(&store, window).await? {
+        return Ok(None);
+    }
+
+    let points = query_series(&store, series, window, false)
+        .await?
+        .into_iter()
+        .map(|(timestamp, _, value)| TimeSeriesPoint { timestamp, value })
+        .collect();
+
+    Ok(Some(points))
+}
+
+/// Hourly points for a scraped metric of `service`, summed (counters) or
+/// averaged (gauges) across label sets. Scraped samples only exist as
+/// rollups, so there is no coverage check or raw fallback.
+pub async fn fetch_scraped_series(
+    service: &str,
+    aggregate: RollupAggregate<'_>,
+    window: &TimeWindow,
+) -> CommandResult<Vec<TimeSeriesPoint>> {
+    let store = get_store_pool().await?;
+    let series = RollupSeries {
+        service,
+        event_type: Some(SCRAPE_EVENT_TYPE),
+        aggregate,
+        label_format: "%Y-%m-%d %H:00:00",
+    };
+
+    Ok(query_series(&store, &series, window, false)
+        .await?
+        .into_iter()
+        .map(|(timestamp, _, value)| TimeSeriesPoint { timestamp, value })
+        .collect())
+}
+
+/// Like [`fetch_series`], split by model as `(hour, model, value)`. Events
+/// without a model are reported as `unknown`.
+pub async fn fetch_series_by_model(
+    series: &RollupSeries<'_>,
+    window: &TimeWindow,
+) -> CommandResult<Option<Vec<(String, String, f64)>>> {
+    let store = get_store_pool().await?;
+    if !covers(&store, window).await? {
+        return Ok(None);
+    }
+
+    let rows = query_series(&store, series, window, true)
+        .await?
+        .into_iter()
+        .map(|(hour, model, value)| {
+            let model = if model.is_empty() { "unknown".to_string() } else { model };
+            (hour, model, value)
+        })
+        .collect();
+
+    Ok(Some(rows))
+}
+
+// ===========================================================================
+// IPC Commands
+// ===========================================================================
+
+#[tauri::command]
+pub async fn get_rollup_status() -> CommandResult<Vec<RollupStatus>> {
+    error::command("get_rollup_status", async move {
+        let store = get_store_pool().await?;
+        let mut statuses = Vec::new();
+
+        for resolution in Resolution::ALL {
+            let state = load_state(&store, resolution).await?;
+            let buckets: i64 = sqlx::query("SELECT COUNT(DISTINCT bucket) AS buckets FROM rollup_counts WHERE resolution = ?")
+                .bind(resolution.as_str())
+                .fetch_one(&store)
+                .await?
+                .get("buckets");
+
+            statuses.push(RollupStatus {
+                resolution,
+                retention_hours: state.retention_hours,
+                covered_from: state.covered_from.map(|at| at.format(SQL_DATETIME_FORMAT).to_string()),
+                watermark: state.watermark.map(|at| at.format(SQL_DATETIME_FORMAT).to_string()),
+                buckets,
+            });
+        }
+
+        Ok(statuses)
+    })
+    .await
+}
+
+/// Changes how long a resolution is kept. Shorter retention is applied on the
+/// next refresh; longer retention only accumulates from now on.
+#[tauri::command]
+pub async fn set_rollup_retention(resolution: Resolution, retention_hours: i64) -> CommandResult<Vec<RollupStatus>> {
+    error::command("set_rollup_retention", async move {
+        if retention_hours < resolution.min_retention_hours() {
+            return Err(ForgeCommandError::invalid_input(format!(
+                "{} rollups need at least {} hours of retention",
+                resolution.as_str(),
+                resolution.min_retention_hours()
+            )));
+        }
+
+        let store = get_store_pool().await?;
+        let state = load_state(&store, resolution).await?;
+        save_state(&store, resolution, &RollupState { retention_hours, ..state }).await?;
+
+        get_rollup_status().await
+    })
+    .await
+}
@@ -0,0 +1,436 @@
+// ===========================================================================
+// Scheduled Jobs
+// ===========================================================================
+//
+// Cron-driven jobs (daily insights, weekly reports, periodic diagnostics).
+// Definitions and last-run state live in the Forge Command store so schedules
+// survive restarts. The scheduler polls rather than sleeping until the next
+// run, so a job whose time passed while the laptop was asleep (or the app was
+// closed) is caught up once on the next tick.
+
+use std::collections::BTreeSet;
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use cron::Schedule;
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqlitePool, Row};
+use tokio::sync::Mutex;
+
+use crate::anomaly::{self, AnomalyMethod};
+use crate::clock;
+use crate::error::{self, CommandResult, ForgeCommandError};
+use crate::period::TimeWindow;
+use crate::report::{self, ReportFormat, ReportType};
+use crate::store::get_store_pool;
+use crate::{get_db_pool, get_system_health, insights, query_anomaly_alerts};
+
+const TICK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Diagnostics score the last hour against this much history, since the
+/// detectors need several hourly points before they trust a baseline.
+const DIAGNOSTICS_BASELINE_HOURS: i64 = 24;
+
+/// Run history older than this is pruned after each run.
+const JOB_RUN_RETENTION_DAYS: i64 = 30;
+
+/// Jobs currently executing, so a manual run can't overlap a scheduled one.
+static RUNNING_JOBS: Mutex<BTreeSet<String>> = Mutex::const_new(BTreeSet::new());
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JobAction {
+    DailyInsights,
+    Report { report_type: ReportType },
+    WeeklyReports,
+    Diagnostics,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RunTrigger {
+    Schedule,
+    CatchUp,
+    Manual,
+}
+
+impl RunTrigger {
+    fn as_str(self) -> &'static str {
+        match self {
+            RunTrigger::Schedule => "schedule",
+            RunTrigger::CatchUp => "catch_up",
+            RunTrigger::Manual => "manual",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobInfo {
+    pub job_id: String,
+    pub name: String,
+    pub action: JobAction,
+    pub schedule: String,
+    pub paused: bool,
+    pub last_run_at: Option<String>,
+    pub last_status: Option<String>,
+    pub next_run_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JobRun {
+    pub run_id: i64,
+    pub job_id: String,
+    pub trigger: String,
+    pub started_at: String,
+    pub finished_at: String,
+    pub status: String,
+    pub output: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Jobs seeded on first start. Schedules use local time.
+const DEFAULT_JOBS: [(&str, &str, JobAction, &str); 3] = [
+    ("daily_insights", "Daily insights", JobAction::DailyInsights, "0 8 * * *"),
+    ("weekly_reports", "Weekly reports", JobAction::WeeklyReports, "0 18 * * Sun"),
+    ("diagnostics", "Periodic diagnostics", JobAction::Diagnostics, "*/15 * * * *"),
+];
+
+fn now_rfc3339() -> String {
+    clock::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Parses a cron expression. Standard five-field expressions are accepted and
+/// treated as firing at second zero.
+pub fn parse_schedule(expression: &str) -> CommandResult<Schedule> {
+    let normalized = if expression.split_whitespace().count() == 5 {
+        format!("0 {}", expression)
+    } else {
+        expression.to_string()
+    };
+
+    Schedule::from_str(&normalized).map_err(|e| {
+        ForgeCommandError::invalid_input(format!("Invalid cron expression {:?}: {}", expression, e))
+    })
+}
+
+fn next_run(schedule: &Schedule, after: DateTime<Utc>) -> Option<String> {
+    schedule
+        .after(&after.with_timezone(&Local))
+        .next()
+        .map(|next| next.with_timezone(&Utc).to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+fn row_to_job(row: &sqlx::sqlite::SqliteRow) -> CommandResult<JobInfo> {
+    Ok(JobInfo {
+        job_id: row.get("job_id"),
+        name: row.get("name"),
+        action: serde_json::from_str(&row.get::<String, _>("action"))
+            .map_err(|e| ForgeCommandError::Internal(format!("Corrupt job action: {}", e)))?,
+        schedule: row.get("schedule"),
+        paused: row.get::<i64, _>("paused") != 0,
+        last_run_at: row.get("last_run_at"),
+        last_status: row.get("last_status"),
+        next_run_at: row.get("next_run_at"),
+    })
+}
+
+async fn seed_default_jobs(store: &SqlitePool) -> CommandResult<()> {
+    for (job_id, name, action, expression) in DEFAULT_JOBS {
+        let schedule = parse_schedule(expression)?;
+        let action = serde_json::to_string(&action)?;
+
+        sqlx::query(
+            "INSERT OR IGNORE INTO jobs (job_id, name, action, schedule, paused, next_run_at)
+             VALUES (?, ?, ?, ?, 0, ?)",
+        )
+        .bind(job_id)
+        .bind(name)
+        .bind(action)
+        .bind(expression)
+        .bind(next_run(&schedule, clock::now()))
+        .execute(store)
+        .await?;
+    }
+
+    Ok(())
+}
+
+async fn load_jobs(store: &SqlitePool) -> CommandResult<Vec<JobInfo>> {
+    sqlx::query("SELECT * FROM jobs ORDER BY job_id")
+        .fetch_all(store)
+        .await?
+        .iter()
+        .map(row_to_job)
+        .collect()
+}
+
+async fn load_job(store: &SqlitePool, job_id: &str) -> CommandResult<JobInfo> {
+    let row = sqlx::query("SELECT * FROM jobs WHERE job_id = ?")
+        .bind(job_id)
+        .fetch_optional(store)
+        .await?
+        .ok_or_else(|| ForgeCommandError::not_found(format!("Unknown job: {}", job_id)))?;
+
+    row_to_job(&row)
+}
+
+pub(crate) async fn execute_action(action: JobAction) -> CommandResult<String> {
+    match action {
+        JobAction::DailyInsights => {
+            let insights = insights::generate_and_store_insights().await?;
+            Ok(insights.recommendations.join("\n"))
+        }
+        JobAction::Report { report_type } => {
+            let generated = report::generate_report(report_type, &ReportFormat::ALL).await?;
+            Ok(generated.report_path)
+        }
+        JobAction::WeeklyReports => {
+            let mut paths = Vec::new();
+            for report_type in [ReportType::Ops, ReportType::Cost, ReportType::Health] {
+                paths.push(report::generate_report(report_type, &ReportFormat::ALL).await?.report_path);
+            }
+            Ok(paths.join("\n"))
+        }
+        JobAction::Diagnostics => {
+            let health = get_system_health().await?;
+            let pool = get_db_pool().await?;
+            let window = TimeWindow::last_hours(DIAGNOSTICS_BASELINE_HOURS);
+
+            // Alerts land on hour buckets; keep those overlapping the last hour
+            let cutoff = window.end - chrono::Duration::hours(2);
+            let alerts: Vec<_> = query_anomaly_alerts(&pool, &window, AnomalyMethod::default())
+                .await?
+                .into_iter()
+                .filter(|alert| {
+                    anomaly::parse_bucket_timestamp(&alert.timestamp)
+                        .is_some_and(|hour| hour > cutoff)
+                })
+                .collect();
+            let critical = alerts.iter().filter(|alert| alert.severity == "critical").count();
+
+            serde_json::to_string(&serde_json::json!({
+                "health": health,
+                "alerts_last_hour": alerts.len(),
+                "critical_alerts": critical,
+            }))
+            .map_err(ForgeCommandError::from)
+        }
+    }
+}
+
+/// Runs a job, recording the run and advancing its schedule.
+async fn run_job(store: &SqlitePool, job: &JobInfo, trigger: RunTrigger) -> CommandResult<JobRun> {
+    if !RUNNING_JOBS.lock().await.insert(job.job_id.clone()) {
+        return Err(ForgeCommandError::invalid_input(format!(
+            "Job {} is already running",
+            job.job_id
+        )));
+    }
+
+    let started_at = now_rfc3339();
+    let result = execute_action(job.action).await;
+    let finished_at = now_rfc3339();
+
+    RUNNING_JOBS.lock().await.remove(&job.job_id);
+
+    let (status, output, error) = match result {
+        Ok(output) => ("success", Some(output), None),
+        Err(error) => ("failed", None, Some(error.to_string())),
+    };
+
+    let run_id = sqlx::query(
+        "INSERT INTO job_runs (job_id, trigger, started_at, finished_at, status, output, error)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&job.job_id)
+    .bind(trigger.as_str())
+    .bind(&started_at)
+    .bind(&finished_at)
+    .bind(status)
+    .bind(&output)
+    .bind(&error)
+    .execute(store)
+    .await?
+    .last_insert_rowid();
+
+    let retention_cutoff = clock::now() - chrono::Duration::days(JOB_RUN_RETENTION_DAYS);
+    sqlx::query("DELETE FROM job_runs WHERE started_at < ?")
+        .bind(retention_cutoff.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .execute(store)
+        .await?;
+
+    // Next run is computed from now, so any backlog of missed runs collapses into this one
+    let schedule = parse_schedule(&job.schedule)?;
+    sqlx::query("UPDATE jobs SET last_run_at = ?, last_status = ?, next_run_at = ? WHERE job_id = ?")
+        .bind(&started_at)
+        .bind(status)
+        .bind(next_run(&schedule, clock::now()))
+        .bind(&job.job_id)
+        .execute(store)
+        .await?;
+
+    Ok(JobRun {
+        run_id,
+        job_id: job.job_id.clone(),
+        trigger: trigger.as_str().to_string(),
+        started_at,
+        finished_at,
+        status: status.to_string(),
+        output,
+        error,
+    })
+}
+
+/// Runs every job that is due. A job more than one tick overdue was missed
+/// (sleep, app closed) and is recorded as a catch-up run.
+pub(crate) async fn run_due_jobs(store: &SqlitePool) -> CommandResult<()> {
+    let now = clock::now();
+
+    for job in load_jobs(store).await? {
+        if job.paused {
+            continue;
+        }
+
+        let Some(next_run_at) = job
+            .next_run_at
+            .as_deref()
+            .and_then(|next| DateTime::parse_from_rfc3339(next).ok())
+        else {
+            continue;
+        };
+
+        if next_run_at > now {
+            continue;
+        }
+
+        let overdue = (now - next_run_at.with_timezone(&Utc)).to_std().unwrap_or_default();
+        let trigger = if overdue > TICK_INTERVAL * 2 {
+            RunTrigger::CatchUp
+        } else {
+            RunTrigger::Schedule
+        };
+
+        if let Err(e) = run_job(store, &job, trigger).await {
+            eprintln!("Scheduled job {} failed to run: {}", job.job_id, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Background loop driving all scheduled jobs.
+pub async fn run_scheduler() {
+    let store = match get_store_pool().await {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("Scheduler disabled: {}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = seed_default_jobs(&store).await {
+        eprintln!("Failed to seed scheduled jobs: {}", e);
+    }
+
+    loop {
+        if let Err(e) = run_due_jobs(&store).await {
+            eprintln!("Scheduler tick failed: {}", e);
+        }
+        tokio::time::sleep(TICK_INTERVAL).await;
+    }
+}
+
+// ===========================================================================
+// IPC Commands
+// ===========================================================================
+
+#[tauri::command]
+pub async fn list_jobs() -> CommandResult<Vec<JobInfo>> {
+    error::command("list_jobs", async move {
+        let store = get_store_pool().await?;
+        // The scheduler may not be running (headless or CLI use)
+        seed_default_jobs(&store).await?;
+        load_jobs(&store).await
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn run_job_now(job_id: String) -> CommandResult<JobRun> {
+    error::command("run_job_now", async move {
+        let store = get_store_pool().await?;
+        let job = load_job(&store, &job_id).await?;
+        run_job(&store, &job, RunTrigger::Manual).await
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn pause_job(job_id: String) -> CommandResult<JobInfo> {
+    error::command("pause_job", async move {
+        let store = get_store_pool().await?;
+        load_job(&store, &job_id).await?;
+
+        sqlx::query("UPDATE jobs SET paused = 1 WHERE job_id = ?")
+            .bind(&job_id)
+            .execute(&store)
+            .await?;
+
+        load_job(&store, &job_id).await
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn resume_job(job_id: String) -> CommandResult<JobInfo> {
+    error::command("resume_job", async move {
+        let store = get_store_pool().await?;
+        let job = load_job(&store, &job_id).await?;
+        let schedule = parse_schedule(&job.schedule)?;
+
+        // Resume from now; runs skipped while paused are not caught up
+        sqlx::query("UPDATE jobs SET paused = 0, next_run_at = ? WHERE job_id = ?")
+            .bind(next_run(&schedule, clock::now()))
+            .bind(&job_id)
+            .execute(&store)
+            .await?;
+
+        load_job(&store, &job_id).await
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_job_runs(job_id: Option<String>, limit: Option<i64>) -> CommandResult<Vec<JobRun>> {
+    error::command("get_job_runs", async move {
+        let store = get_store_pool().await?;
+
+        let runs = sqlx::query(
+            "SELECT * FROM job_runs
+             WHERE (? IS NULL OR job_id = ?)
+             ORDER BY started_at DESC, run_id DESC
+             LIMIT ?",
+        )
+        .bind(&job_id)
+        .bind(&job_id)
+        .bind(limit.unwrap_or(50))
+        .fetch_all(&store)
+        .await?
+        .into_iter()
+        .map(|row| JobRun {
+            run_id: row.get("run_id"),
+            job_id: row.get("job_id"),
+            trigger: row.get("trigger"),
+            started_at: row.get("started_at"),
+            finished_at: row.get("finished_at"),
+            status: row.get("status"),
+            output: row.get("output"),
+            error: row.get("error"),
+        })
+        .collect();
+
+        Ok(runs)
+    })
+    .await
+}
@@ -0,0 +1,561 @@
+// ===========================================================================
+// Events Schema Validation
+// ===========================================================================
+//
+// Declares what Forge Command expects from the events table and from the
+// `metrics`/`metadata` JSON of each service's event types, then checks a
+// sample of recent events against it. A renamed column or a producer that
+// stops writing `metrics.duration_ms` shows up here instead of as an opaque
+// sqlx error or a chart that quietly drops to zero.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Mutex;
+
+use chrono::SecondsFormat;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::{sqlite::SqlitePool, Row};
+
+use crate::advisor::{TimedQuery, EXPECTED_COLUMNS};
+use crate::breakdown::JsonColumn::{self, Metadata, Metrics};
+use crate::clock;
+use crate::error::{self, CommandResult, ForgeCommandError};
+use crate::get_db_pool;
+
+use self::ValueType::{Integer, Number};
+
+/// Recent events sampled per event type.
+const SAMPLE_SIZE: i64 = 200;
+
+/// Below this share of sampled events, an optional field is reported as sparse.
+const SPARSE_COVERAGE: f64 = 0.5;
+
+/// Report from the startup check, served until a fresh one is requested.
+static LAST_REPORT: Mutex<Option<SchemaReport>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueType {
+    String,
+    Integer,
+    /// Integer or real.
+    Number,
+}
+
+impl ValueType {
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            ValueType::String => value.is_string(),
+            ValueType::Integer => value.is_i64() || value.is_u64(),
+            ValueType::Number => value.is_number(),
+        }
+    }
+}
+
+fn value_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "real",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+struct FieldSpec {
+    column: JsonColumn,
+    key: &'static str,
+    value_type: ValueType,
+    /// Required fields are reported whenever missing; optional ones only
+    /// when they become sparse.
+    required: bool,
+}
+
+const fn field(
+    column: JsonColumn,
+    key: &'static str,
+    value_type: ValueType,
+    required: bool,
+) -> FieldSpec {
+    FieldSpec {
+        column,
+        key,
+        value_type,
+        required,
+    }
+}
+
+struct EventSpec {
+    service: &'static str,
+    event_type: &'static str,
+    /// Failures and optional lifecycle stages can go unreported for long
+    /// stretches, so finding none isn't reported as a silent producer.
+    occasional: bool,
+    fields: &'static [FieldSpec],
+}
+
+/// Fields each dashboard query reads, per service and event type.
+const EXPECTED_EVENTS: &[EventSpec] = &[
+    EventSpec {
+        service: "dataforge",
+        event_type: "query",
+        occasional: false,
+        fields: &[
+            field(Metrics, "duration_ms", Number, true),
+            field(Metrics, "avg_similarity", Number, false),
+        ],
+    },
+    EventSpec {
+        service: "neuroforge",
+        event_type: "model_request",
+        occasional: false,
+        fields: &[
+            field(Metrics, "duration_ms", Number, true),
+            field(Metrics, "tokens_prompt", Integer, true),
+            field(Metrics, "tokens_completion", Integer, true),
+            field(Metrics, "tokens_total", Integer, true),
+            field(Metrics, "cost_usd", Number, true),
+            field(Metrics, "evaluation_score", Number, false),
+            field(Metadata, "model", ValueType::String, true),
+            field(Metadata, "operation", ValueType::String, false),
+            field(Metadata, "user_id", ValueType::String, false),
+        ],
+    },
+    EventSpec {
+        service: "forgeagents",
+        event_type: "agent_task_completed",
+        occasional: false,
+        fields: &[
+            field(Metrics, "duration_ms", Number, true),
+            field(Metadata, "agent_id", ValueType::String, true),
+            field(Metadata, "agent_name", ValueType::String, false),
+            field(Metadata, "task_id", ValueType::String, false),
+            field(Metadata, "status", ValueType::String, false),
+        ],
+    },
+    EventSpec {
+        service: "forgeagents",
+        event_type: "agent_task_failed",
+        occasional: true,
+        fields: &[
+            field(Metrics, "duration_ms", Number, false),
+            field(Metadata, "agent_id", ValueType::String, true),
+            field(Metadata, "agent_name", ValueType::String, false),
+            field(Metadata, "task_id", ValueType::String, false),
+        ],
+    },
+    EventSpec {
+        service: "forgeagents",
+        event_type: "agent_task_started",
+        occasional: false,
+        fields: &[
+            field(Metadata, "agent_id", ValueType::String, true),
+            field(Metadata, "task_id", ValueType::String, false),
+        ],
+    },
+    EventSpec {
+        service: "rake",
+        event_type: "ingestion_queued",
+        occasional: true,
+        fields: &[
+            field(Metrics, "pipeline_id", ValueType::String, true),
+            field(Metadata, "run_id", ValueType::String, true),
+        ],
+    },
+    EventSpec {
+        service: "rake",
+        event_type: "ingestion_started",
+        occasional: true,
+        fields: &[
+            field(Metrics, "pipeline_id", ValueType::String, true),
+            field(Metrics, "pipeline_name", ValueType::String, false),
+            field(Metadata, "run_id", ValueType::String, true),
+        ],
+    },
+    // `records` is the default records field; see `rake::records_field`
+    EventSpec {
+        service: "rake",
+        event_type: "ingestion_complete",
+        occasional: false,
+        fields: &[
+            field(Metrics, "pipeline_id", ValueType::String, true),
+            field(Metrics, "pipeline_name", ValueType::String, false),
+            field(Metrics, "records", Integer, true),
+            field(Metrics, "duration_ms", Number, false),
+            field(Metadata, "run_id", ValueType::String, false),
+        ],
+    },
+    EventSpec {
+        service: "rake",
+        event_type: "ingestion_failed",
+        occasional: true,
+        fields: &[
+            field(Metrics, "pipeline_id", ValueType::String, true),
+            field(Metrics, "pipeline_name", ValueType::String, false),
+            field(Metadata, "run_id", ValueType::String, false),
+        ],
+    },
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FieldIssueKind {
+    /// A required field is absent from some or all sampled events.
+    Missing,
+    /// The field is absent but an undeclared key of the right type appeared
+    /// alongside, most likely its new name.
+    PossiblyRenamed { candidate: String },
+    /// Values are present but of the wrong JSON type.
+    Mistyped {
+        expected: ValueType,
+        found: String,
+        example: String,
+    },
+    /// An optional field is present in too few events to be trusted.
+    Sparse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldIssue {
+    /// `metrics.duration_ms` style path.
+    pub field: String,
+    #[serde(flatten)]
+    pub issue: FieldIssueKind,
+    /// Share of sampled events affected.
+    pub affected_ratio: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventTypeReport {
+    pub service: String,
+    pub event_type: String,
+    pub sampled: i64,
+    pub issues: Vec<FieldIssue>,
+    /// Keys seen in the sample that the declared schema doesn't mention.
+    pub undeclared_fields: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnReport {
+    pub missing: Vec<String>,
+    pub unexpected: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaReport {
+    pub checked_at: String,
+    pub columns: ColumnReport,
+    pub event_types: Vec<EventTypeReport>,
+    /// Declared event types with no events at all, e.g. a producer that stopped.
+    pub silent_event_types: Vec<String>,
+    pub issue_count: usize,
+}
+
+/// Declared fields of a single event that hold the wrong type, as
+/// `metrics.key: expected number, found string`. Undeclared event types and
+/// missing fields pass.
+pub(crate) fn mistyped_fields(
+    service: &str,
+    event_type: &str,
+    metrics: &Map<String, Value>,
+    metadata: &Map<String, Value>,
+) -> Vec<String> {
+    let Some(spec) = EXPECTED_EVENTS
+        .iter()
+        .find(|spec| spec.service == service && spec.event_type == event_type)
+    else {
+        return Vec::new();
+    };
+
+    spec.fields
+        .iter()
+        .filter_map(|spec_field| {
+            let document = if spec_field.column == Metrics {
+                metrics
+            } else {
+                metadata
+            };
+            let value = document
+                .get(spec_field.key)
+                .filter(|value| !value.is_null())?;
+            (!spec_field.value_type.matches(value)).then(|| {
+                format!(
+                    "{}.{}: expected {}, found {}",
+                    spec_field.column.as_sql(),
+                    spec_field.key,
+                    serde_json::to_value(spec_field.value_type)
+                        .ok()
+                        .and_then(|name| name.as_str().map(str::to_string))
+                        .unwrap_or_default(),
+                    value_type_name(value)
+                )
+            })
+        })
+        .collect()
+}
+
+/// Loose match between a missing key and an undeclared one: shared
+/// underscore-separated words, or one name containing the other.
+fn looks_like_rename(expected: &str, candidate: &str) -> bool {
+    let words =
+        |name: &str| -> BTreeSet<String> { name.split('_').map(str::to_lowercase).collect() };
+    candidate.contains(expected)
+        || expected.contains(candidate)
+        || !words(expected).is_disjoint(&words(candidate))
+}
+
+fn check_event_type(spec: &EventSpec, samples: &[(Value, Value)]) -> EventTypeReport {
+    let sampled = samples.len();
+    let declared: BTreeSet<(JsonColumn, &str)> =
+        spec.fields.iter().map(|f| (f.column, f.key)).collect();
+
+    // Undeclared top-level keys, with the type of their first non-null value
+    let mut undeclared: BTreeMap<String, (JsonColumn, &Value)> = BTreeMap::new();
+    for (metrics, metadata) in samples {
+        for (column, document) in [(Metrics, metrics), (Metadata, metadata)] {
+            if let Value::Object(map) = document {
+                for (key, value) in map {
+                    if !declared.contains(&(column, key.as_str())) && !value.is_null() {
+                        undeclared
+                            .entry(format!("{}.{}", column.as_sql(), key))
+                            .or_insert((column, value));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut issues = Vec::new();
+    for spec_field in spec.fields {
+        let path = format!("{}.{}", spec_field.column.as_sql(), spec_field.key);
+        let mut missing = 0usize;
+        let mut mistyped: Option<&Value> = None;
+        let mut mistyped_count = 0usize;
+
+        for (metrics, metadata) in samples {
+            let document = if spec_field.column == Metrics {
+                metrics
+            } else {
+                metadata
+            };
+            match document.get(spec_field.key) {
+                None | Some(Value::Null) => missing += 1,
+                Some(value) if !spec_field.value_type.matches(value) => {
+                    mistyped_count += 1;
+                    mistyped.get_or_insert(value);
+                }
+                Some(_) => {}
+            }
+        }
+
+        let ratio = |count: usize| {
+            if sampled == 0 {
+                0.0
+            } else {
+                count as f64 / sampled as f64
+            }
+        };
+
+        if let Some(example) = mistyped {
+            issues.push(FieldIssue {
+                field: path.clone(),
+                issue: FieldIssueKind::Mistyped {
+                    expected: spec_field.value_type,
+                    found: value_type_name(example).to_string(),
+                    example: example.to_string(),
+                },
+                affected_ratio: ratio(mistyped_count),
+            });
+        }
+
+        let flag_missing = if spec_field.required {
+            missing > 0
+        } else {
+            sampled > 0 && 1.0 - ratio(missing) < SPARSE_COVERAGE
+        };
+        if !flag_missing {
+            continue;
+        }
+
+        let candidate = undeclared.iter().find(|(name, (column, value))| {
+            *column == spec_field.column
+                && spec_field.value_type.matches(value)
+                && looks_like_rename(
+                    spec_field.key,
+                    name.split_once('.').map(|(_, key)| key).unwrap_or(name),
+                )
+        });
+
+        let issue = match candidate {
+            Some((name, _)) if missing == sampled => FieldIssueKind::PossiblyRenamed {
+                candidate: name.clone(),
+            },
+            _ if spec_field.required => FieldIssueKind::Missing,
+            _ => FieldIssueKind::Sparse,
+        };
+
+        issues.push(FieldIssue {
+            field: path,
+            issue,
+            affected_ratio: ratio(missing),
+        });
+    }
+
+    EventTypeReport {
+        service: spec.service.to_string(),
+        event_type: spec.event_type.to_string(),
+        sampled: sampled as i64,
+        issues,
+        undeclared_fields: undeclared.into_keys().collect(),
+    }
+}
+
+fn parse_document(raw: Option<String>) -> Value {
+    raw.and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or(Value::Null)
+}
+
+pub async fn build_schema_report(pool: &SqlitePool) -> CommandResult<SchemaReport> {
+    let columns: Vec<String> = sqlx::query("SELECT name FROM pragma_table_info('events')")
+        .fetch_all_timed(pool)
+        .await?
+        .iter()
+        .map(|row| row.get::<String, _>("name"))
+        .collect();
+
+    let column_report = ColumnReport {
+        missing: EXPECTED_COLUMNS
+            .iter()
+            .filter(|expected| !columns.iter().any(|column| column == *expected))
+            .map(|column| column.to_string())
+            .collect(),
+        unexpected: columns
+            .iter()
+            .filter(|column| !EXPECTED_COLUMNS.contains(&column.as_str()))
+            .cloned()
+            .collect(),
+    };
+
+    let mut event_types = Vec::new();
+    let mut silent_event_types = Vec::new();
+
+    // Without the core columns the per-event checks can't run at all
+    if column_report.missing.is_empty() {
+        for spec in EXPECTED_EVENTS {
+            let samples: Vec<(Value, Value)> = sqlx::query(
+                "SELECT metrics, metadata
+                 FROM events
+                 WHERE service = ? AND event_type = ?
+                 ORDER BY timestamp DESC
+                 LIMIT ?",
+            )
+            .bind(spec.service)
+            .bind(spec.event_type)
+            .bind(SAMPLE_SIZE)
+            .fetch_all_timed(pool)
+            .await?
+            .into_iter()
+            .map(|row| {
+                (
+                    parse_document(row.get::<Option<String>, _>("metrics")),
+                    parse_document(row.get::<Option<String>, _>("metadata")),
+                )
+            })
+            .collect();
+
+            if samples.is_empty() {
+                if !spec.occasional {
+                    silent_event_types.push(format!("{}/{}", spec.service, spec.event_type));
+                }
+                continue;
+            }
+
+            event_types.push(check_event_type(spec, &samples));
+        }
+    }
+
+    let issue_count = column_report.missing.len()
+        + silent_event_types.len()
+        + event_types
+            .iter()
+            .map(|report| report.issues.len())
+            .sum::<usize>();
+
+    Ok(SchemaReport {
+        checked_at: clock::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        columns: column_report,
+        event_types,
+        silent_event_types,
+        issue_count,
+    })
+}
+
+/// Validates the schema once at startup and logs anything that will break
+/// or skew the dashboards.
+pub async fn run_startup_check() {
+    let report = match get_db_pool().await {
+        Ok(pool) => build_schema_report(&pool).await,
+        Err(e) => Err(e),
+    };
+
+    let report = match report {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Schema validation skipped: {}", e);
+            return;
+        }
+    };
+
+    for column in &report.columns.missing {
+        eprintln!("Schema: events table has no {} column", column);
+    }
+    for event_type in &report.silent_event_types {
+        eprintln!("Schema: no {} events found", event_type);
+    }
+    for event_type in &report.event_types {
+        for issue in &event_type.issues {
+            eprintln!(
+                "Schema: {}/{} {} {:?} ({:.0}% of sampled events)",
+                event_type.service,
+                event_type.event_type,
+                issue.field,
+                issue.issue,
+                issue.affected_ratio * 100.0
+            );
+        }
+    }
+
+    if let Ok(mut last) = LAST_REPORT.lock() {
+        *last = Some(report);
+    }
+}
+
+// ===========================================================================
+// IPC Commands
+// ===========================================================================
+
+/// Returns the schema report. The startup report is reused unless `refresh`
+/// is set or none exists yet.
+#[tauri::command]
+pub async fn get_schema_report(refresh: Option<bool>) -> CommandResult<SchemaReport> {
+    error::command("get_schema_report", async move {
+        if !refresh.unwrap_or(false) {
+            if let Some(report) = LAST_REPORT
+                .lock()
+                .map_err(ForgeCommandError::internal)?
+                .clone()
+            {
+                return Ok(report);
+            }
+        }
+
+        let pool = get_db_pool().await?;
+        let report = build_schema_report(&pool).await?;
+
+        *LAST_REPORT.lock().map_err(ForgeCommandError::internal)? = Some(report.clone());
+        Ok(report)
+    })
+    .await
+}
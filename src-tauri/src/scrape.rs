@@ -0,0 +1,787 @@
+// ===========================================================================
+// Prometheus Scrape Collector
+// ===========================================================================
+//
+// Pulls the `/metrics` endpoints listed in `scrape-targets.json` (in the data
+// directory, or `FORGE_COMMAND_SCRAPE_TARGETS`) on an interval, parses the
+// text exposition format and adds the samples to the rollup store. Without
+// that file the collector stays off.
+//
+// Gauges are stored as scraped. Counters are stored as the increase since the
+// previous scrape, so summing buckets gives the increase per hour; the first
+// scrape of a series only sets the baseline. Histograms become `_count` and
+// `_sum` increases plus `:p50`, `:p90` and `:p99` gauges estimated from the
+// bucket increases, and summary quantiles become `:p<quantile>` gauges.
+
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::SecondsFormat;
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+
+use crate::clock;
+use crate::error::{self, CommandResult, ForgeCommandError};
+use crate::rollup::{self, RollupAggregate, ScrapedSample};
+use crate::store::{data_dir, get_store_pool};
+
+/// Histogram quantiles estimated on every scrape.
+const HISTOGRAM_QUANTILES: [f64; 3] = [0.5, 0.9, 0.99];
+
+/// Latest status per configured target, for `get_scrape_status`.
+static STATUS: Mutex<Vec<ScrapeTargetStatus>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScrapeConfig {
+    #[serde(default = "default_interval")]
+    pub interval_secs: u64,
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64,
+    pub targets: Vec<ScrapeTarget>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScrapeTarget {
+    /// Service the samples are stored under, e.g. `neuroforge`.
+    pub service: String,
+    pub url: String,
+    pub bearer_token: Option<String>,
+    /// Metric family name prefixes to keep; everything when empty.
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Most stored series (metric and label set) per scrape; the rest are
+    /// dropped and counted in the status.
+    #[serde(default = "default_max_series")]
+    pub max_series: usize,
+}
+
+fn default_interval() -> u64 {
+    15
+}
+
+fn default_timeout() -> u64 {
+    5
+}
+
+fn default_max_series() -> usize {
+    2000
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScrapeTargetStatus {
+    pub service: String,
+    pub url: String,
+    pub up: bool,
+    pub last_scrape_at: Option<String>,
+    pub duration_ms: Option<u64>,
+    /// Series stored by the last scrape.
+    pub series: usize,
+    pub dropped_series: usize,
+    pub last_error: Option<String>,
+}
+
+pub fn config_path() -> PathBuf {
+    env::var("FORGE_COMMAND_SCRAPE_TARGETS")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| data_dir().join("scrape-targets.json"))
+}
+
+fn load_config() -> Result<Option<ScrapeConfig>, String> {
+    let path = config_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let raw = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let config: ScrapeConfig =
+        serde_json::from_str(&raw).map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+
+    for target in &config.targets {
+        if target.service.trim().is_empty() {
+            return Err(format!("{}: service must not be empty", target.url));
+        }
+        if !target.url.starts_with("http://") && !target.url.starts_with("https://") {
+            return Err(format!("{}: url must be http:// or https://", target.url));
+        }
+    }
+
+    Ok(Some(config))
+}
+
+// ===========================================================================
+// Exposition Format
+// ===========================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    Counter,
+    Gauge,
+    Histogram,
+    Summary,
+    Untyped,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub name: String,
+    pub labels: Vec<(String, String)>,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct MetricFamily {
+    pub name: String,
+    pub kind: MetricKind,
+    pub help: Option<String>,
+    pub samples: Vec<Sample>,
+}
+
+impl MetricKind {
+    fn parse(raw: &str) -> MetricKind {
+        match raw {
+            "counter" => MetricKind::Counter,
+            "gauge" => MetricKind::Gauge,
+            "histogram" => MetricKind::Histogram,
+            "summary" => MetricKind::Summary,
+            _ => MetricKind::Untyped,
+        }
+    }
+
+    /// Sample name suffixes that belong to a family of this kind.
+    fn suffixes(self) -> &'static [&'static str] {
+        match self {
+            MetricKind::Histogram => &["_bucket", "_sum", "_count", "_created"],
+            MetricKind::Summary => &["_sum", "_count", "_created"],
+            MetricKind::Counter => &["_total", "_created"],
+            MetricKind::Gauge | MetricKind::Untyped => &[],
+        }
+    }
+}
+
+fn is_name_char(c: char, first: bool) -> bool {
+    c.is_ascii_alphabetic() || c == '_' || c == ':' || (!first && c.is_ascii_digit())
+}
+
+fn unescape(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Parses `name{label="value",...} value [timestamp]`. Timestamps are
+/// ignored; samples are stored at scrape time.
+fn parse_sample(line: &str) -> Result<Sample, String> {
+    let name_end = line
+        .char_indices()
+        .find(|(i, c)| !is_name_char(*c, *i == 0))
+        .map_or(line.len(), |(i, _)| i);
+    if name_end == 0 {
+        return Err("missing metric name".to_string());
+    }
+    let name = line[..name_end].to_string();
+    let mut rest = &line[name_end..];
+
+    let mut labels = Vec::new();
+    if let Some(body) = rest.strip_prefix('{') {
+        rest = body;
+        loop {
+            rest = rest.trim_start();
+            if let Some(after) = rest.strip_prefix('}') {
+                rest = after;
+                break;
+            }
+
+            let label_end = rest
+                .char_indices()
+                .find(|(i, c)| !is_name_char(*c, *i == 0) || *c == ':')
+                .map_or(rest.len(), |(i, _)| i);
+            if label_end == 0 {
+                return Err("invalid label name".to_string());
+            }
+            let label = rest[..label_end].to_string();
+            rest = rest[label_end..]
+                .trim_start()
+                .strip_prefix('=')
+                .and_then(|after| after.trim_start().strip_prefix('"'))
+                .ok_or_else(|| format!("label {} has no quoted value", label))?;
+
+            let mut escaped = false;
+            let value_end = rest
+                .char_indices()
+                .find(|(_, c)| {
+                    let end = *c == '"' && !escaped;
+                    escaped = *c == '\\' && !escaped;
+                    end
+                })
+                .map(|(i, _)| i)
+                .ok_or_else(|| format!("label {} value is not terminated", label))?;
+            labels.push((label, unescape(&rest[..value_end])));
+
+            rest = rest[value_end + 1..].trim_start();
+            rest = rest.strip_prefix(',').unwrap_or(rest);
+        }
+    }
+
+    let value = rest
+        .split_whitespace()
+        .next()
+        .ok_or_else(|| "missing value".to_string())?;
+    let value = value
+        .parse::<f64>()
+        .map_err(|_| format!("invalid value {:?}", value))?;
+
+    labels.sort();
+    Ok(Sample {
+        name,
+        labels,
+        value,
+    })
+}
+
+/// Parses a text exposition (format 0.0.4) into families, in the order they
+/// first appear. Samples without a `# TYPE` form untyped families.
+pub fn parse_exposition(text: &str) -> Result<Vec<MetricFamily>, String> {
+    let mut families: Vec<MetricFamily> = Vec::new();
+    let mut by_name: HashMap<String, usize> = HashMap::new();
+
+    let mut family_index = |families: &mut Vec<MetricFamily>, name: &str, kind: MetricKind| {
+        *by_name.entry(name.to_string()).or_insert_with(|| {
+            families.push(MetricFamily {
+                name: name.to_string(),
+                kind,
+                help: None,
+                samples: Vec::new(),
+            });
+            families.len() - 1
+        })
+    };
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(comment) = line.strip_prefix('#') {
+            let mut parts = comment.trim_start().splitn(3, char::is_whitespace);
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some("TYPE"), Some(name), kind) => {
+                    let kind = MetricKind::parse(kind.unwrap_or("").trim());
+                    let index = family_index(&mut families, name, kind);
+                    families[index].kind = kind;
+                }
+                (Some("HELP"), Some(name), help) => {
+                    let index = family_index(&mut families, name, MetricKind::Untyped);
+                    families[index].help = help.map(|help| unescape(help.trim()));
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        let sample = parse_sample(line).map_err(|e| format!("line {}: {}", number + 1, e))?;
+
+        // Exact family first, then a typed family the suffix belongs to
+        let owner = families
+            .iter()
+            .position(|family| family.name == sample.name)
+            .or_else(|| {
+                families.iter().position(|family| {
+                    family.kind.suffixes().iter().any(|suffix| {
+                        sample.name.strip_suffix(suffix) == Some(family.name.as_str())
+                    })
+                })
+            });
+        let index = match owner {
+            Some(index) => index,
+            None => family_index(&mut families, &sample.name, MetricKind::Untyped),
+        };
+        families[index].samples.push(sample);
+    }
+
+    Ok(families)
+}
+
+// ===========================================================================
+// Conversion
+// ===========================================================================
+
+/// How a stored metric aggregates into hourly points.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SeriesKind {
+    /// Increases between scrapes; hourly points are sums.
+    Counter,
+    /// Scraped values; hourly points are means.
+    Gauge,
+}
+
+impl SeriesKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            SeriesKind::Counter => "counter",
+            SeriesKind::Gauge => "gauge",
+        }
+    }
+
+    fn parse(raw: &str) -> SeriesKind {
+        match raw {
+            "counter" => SeriesKind::Counter,
+            _ => SeriesKind::Gauge,
+        }
+    }
+
+    pub fn aggregate(self, metric: &str) -> RollupAggregate<'_> {
+        match self {
+            SeriesKind::Counter => RollupAggregate::Sum(metric),
+            SeriesKind::Gauge => RollupAggregate::Avg(metric),
+        }
+    }
+}
+
+/// Labels as `name="value",...`, the dimension stored in the rollups.
+fn render_labels<'a>(labels: impl Iterator<Item = &'a (String, String)>) -> String {
+    labels
+        .map(|(name, value)| format!("{}={:?}", name, value))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn without_label(sample: &Sample, label: &str) -> String {
+    render_labels(sample.labels.iter().filter(|(name, _)| name != label))
+}
+
+fn label_value<'a>(sample: &'a Sample, label: &str) -> Option<&'a str> {
+    sample
+        .labels
+        .iter()
+        .find(|(name, _)| name == label)
+        .map(|(_, value)| value.as_str())
+}
+
+/// `:p50` for 0.5, `:p99.9` for 0.999.
+fn quantile_suffix(quantile: f64) -> String {
+    let percent = (quantile * 1000.0).round() / 10.0;
+    format!(":p{}", percent)
+}
+
+/// Estimates a quantile from cumulative `(upper bound, count)` buckets sorted
+/// by bound, interpolating linearly inside the bucket, like PromQL's
+/// `histogram_quantile`.
+pub fn histogram_quantile(quantile: f64, buckets: &[(f64, f64)]) -> Option<f64> {
+    let total = buckets.last()?.1;
+    if total <= 0.0 {
+        return None;
+    }
+
+    let rank = quantile * total;
+    let index = buckets.iter().position(|(_, count)| *count >= rank)?;
+    let (upper, count) = buckets[index];
+    let (lower, below) = match index {
+        0 => (0.0_f64.min(upper), 0.0),
+        _ => buckets[index - 1],
+    };
+
+    if upper.is_infinite() {
+        // Above the highest finite bound: report that bound
+        return (index > 0).then_some(lower);
+    }
+    if count == below {
+        return Some(upper);
+    }
+    Some(lower + (upper - lower) * (rank - below) / (count - below))
+}
+
+/// Turns one scrape into stored samples, tracking counter values between
+/// scrapes.
+pub(crate) struct Converter<'a> {
+    previous: &'a HashMap<String, f64>,
+    /// Cumulative values seen in this scrape, the next one's `previous`.
+    pub(crate) current: HashMap<String, f64>,
+    pub(crate) samples: Vec<ScrapedSample>,
+    /// Metric → kind and help, for the catalog.
+    pub(crate) metrics: BTreeMap<String, (SeriesKind, Option<String>)>,
+}
+
+impl<'a> Converter<'a> {
+    pub(crate) fn new(previous: &'a HashMap<String, f64>) -> Self {
+        Converter {
+            previous,
+            current: HashMap::new(),
+            samples: Vec::new(),
+            metrics: BTreeMap::new(),
+        }
+    }
+
+    fn push(
+        &mut self,
+        metric: String,
+        dimension: String,
+        value: f64,
+        kind: SeriesKind,
+        help: &Option<String>,
+    ) {
+        if !value.is_finite() {
+            return;
+        }
+        self.metrics
+            .entry(metric.clone())
+            .or_insert_with(|| (kind, help.clone()));
+        self.samples.push(ScrapedSample {
+            dimension,
+            metric,
+            value,
+        });
+    }
+
+    /// Increase of a cumulative value since the last scrape; a drop means
+    /// the process restarted and counted up from zero.
+    fn increase(&mut self, key: String, value: f64) -> Option<f64> {
+        self.current.insert(key.clone(), value);
+        let previous = *self.previous.get(&key)?;
+        Some(if value >= previous {
+            value - previous
+        } else {
+            value
+        })
+    }
+
+    fn counter(&mut self, metric: String, dimension: String, value: f64, help: &Option<String>) {
+        if let Some(increase) = self.increase(format!("{}{{{}}}", metric, dimension), value) {
+            self.push(metric, dimension, increase, SeriesKind::Counter, help);
+        }
+    }
+
+    pub(crate) fn family(&mut self, family: &MetricFamily) {
+        let help = &family.help;
+        // Label set → (bound, bucket increase)
+        let mut histograms: BTreeMap<String, Vec<(f64, f64)>> = BTreeMap::new();
+
+        for sample in &family.samples {
+            if sample.name.ends_with("_created") && sample.name != family.name {
+                continue;
+            }
+
+            match family.kind {
+                MetricKind::Gauge | MetricKind::Untyped => {
+                    let dimension = render_labels(sample.labels.iter());
+                    self.push(
+                        sample.name.clone(),
+                        dimension,
+                        sample.value,
+                        SeriesKind::Gauge,
+                        help,
+                    );
+                }
+                MetricKind::Counter => {
+                    let dimension = render_labels(sample.labels.iter());
+                    self.counter(sample.name.clone(), dimension, sample.value, help);
+                }
+                MetricKind::Summary => match label_value(sample, "quantile") {
+                    Some(quantile) if sample.name == family.name => {
+                        if let Ok(quantile) = quantile.parse::<f64>() {
+                            let metric = format!("{}{}", family.name, quantile_suffix(quantile));
+                            let dimension = without_label(sample, "quantile");
+                            self.push(metric, dimension, sample.value, SeriesKind::Gauge, help);
+                        }
+                    }
+                    _ => {
+                        let dimension = render_labels(sample.labels.iter());
+                        self.counter(sample.name.clone(), dimension, sample.value, help);
+                    }
+                },
+                MetricKind::Histogram => {
+                    if sample.name.ends_with("_bucket") {
+                        let Some(bound) =
+                            label_value(sample, "le").and_then(|le| le.parse::<f64>().ok())
+                        else {
+                            continue;
+                        };
+                        let dimension = without_label(sample, "le");
+                        let key = format!("{}{{{}}}{}", sample.name, dimension, bound);
+                        if let Some(increase) = self.increase(key, sample.value) {
+                            histograms
+                                .entry(dimension)
+                                .or_default()
+                                .push((bound, increase));
+                        }
+                    } else {
+                        let dimension = render_labels(sample.labels.iter());
+                        self.counter(sample.name.clone(), dimension, sample.value, help);
+                    }
+                }
+            }
+        }
+
+        for (dimension, mut buckets) in histograms {
+            buckets.sort_by(|a, b| a.0.total_cmp(&b.0));
+            for quantile in HISTOGRAM_QUANTILES {
+                if let Some(value) = histogram_quantile(quantile, &buckets) {
+                    let metric = format!("{}{}", family.name, quantile_suffix(quantile));
+                    self.push(metric, dimension.clone(), value, SeriesKind::Gauge, help);
+                }
+            }
+        }
+    }
+}
+
+// ===========================================================================
+// Collector
+// ===========================================================================
+
+pub(crate) struct Scraper {
+    target: ScrapeTarget,
+    /// Cumulative values from the last scrape, by series.
+    previous: HashMap<String, f64>,
+    pub(crate) status: ScrapeTargetStatus,
+}
+
+impl Scraper {
+    pub(crate) fn new(target: ScrapeTarget) -> Self {
+        let status = ScrapeTargetStatus {
+            service: target.service.clone(),
+            url: target.url.clone(),
+            ..Default::default()
+        };
+        Scraper {
+            target,
+            previous: HashMap::new(),
+            status,
+        }
+    }
+
+    async fn fetch(&self, client: &reqwest::Client) -> CommandResult<String> {
+        let mut request = client
+            .get(&self.target.url)
+            .header(reqwest::header::ACCEPT, "text/plain;version=0.0.4");
+        if let Some(token) = &self.target.bearer_token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send().await.map_err(|e| {
+            ForgeCommandError::Io(format!("Failed to scrape {}: {}", self.target.url, e))
+        })?;
+        if !response.status().is_success() {
+            return Err(ForgeCommandError::Io(format!(
+                "{} answered {}",
+                self.target.url,
+                response.status()
+            )));
+        }
+
+        response.text().await.map_err(|e| {
+            ForgeCommandError::Io(format!("Failed to read {}: {}", self.target.url, e))
+        })
+    }
+
+    pub(crate) async fn scrape(&mut self, client: &reqwest::Client) -> CommandResult<()> {
+        let started = Instant::now();
+        let body = self.fetch(client).await?;
+        let families = parse_exposition(&body)
+            .map_err(|e| ForgeCommandError::invalid_input(format!("{}: {}", self.target.url, e)))?;
+
+        let mut converter = Converter::new(&self.previous);
+        for family in &families {
+            let included = self.target.include.is_empty()
+                || self
+                    .target
+                    .include
+                    .iter()
+                    .any(|prefix| family.name.starts_with(prefix.as_str()));
+            if included {
+                converter.family(family);
+            }
+        }
+
+        let Converter {
+            current,
+            mut samples,
+            metrics,
+            ..
+        } = converter;
+        let dropped = samples.len().saturating_sub(self.target.max_series);
+        samples.truncate(self.target.max_series);
+
+        let now = clock::now();
+        if !samples.is_empty() {
+            rollup::record_scrape(&self.target.service, &samples, now.naive_utc()).await?;
+        }
+        save_catalog(
+            &self.target.service,
+            &metrics,
+            now.to_rfc3339_opts(SecondsFormat::Secs, true),
+        )
+        .await?;
+        self.previous = current;
+
+        self.status.up = true;
+        self.status.series = samples.len();
+        self.status.dropped_series = dropped;
+        self.status.duration_ms = Some(started.elapsed().as_millis() as u64);
+        self.status.last_scrape_at = Some(now.to_rfc3339_opts(SecondsFormat::Secs, true));
+        Ok(())
+    }
+}
+
+async fn save_catalog(
+    service: &str,
+    metrics: &BTreeMap<String, (SeriesKind, Option<String>)>,
+    seen_at: String,
+) -> CommandResult<()> {
+    let store = get_store_pool().await?;
+    let mut tx = store.begin().await?;
+
+    for (metric, (kind, help)) in metrics {
+        sqlx::query(
+            "INSERT INTO scrape_metrics (service, metric, kind, help, last_seen)
+             VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT(service, metric) DO UPDATE SET
+                kind = excluded.kind,
+                help = excluded.help,
+                last_seen = excluded.last_seen",
+        )
+        .bind(service)
+        .bind(metric)
+        .bind(kind.as_str())
+        .bind(help)
+        .bind(&seen_at)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    Ok(tx.commit().await?)
+}
+
+/// Scrapes the configured targets until the process exits.
+pub async fn run_collector() {
+    let config = match load_config() {
+        Ok(Some(config)) => config,
+        Ok(None) => return,
+        Err(e) => {
+            eprintln!("Scrape collector disabled: {}", e);
+            return;
+        }
+    };
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("Scrape collector disabled: {}", e);
+            return;
+        }
+    };
+    eprintln!(
+        "Scrape collector pulling {} target(s)",
+        config.targets.len()
+    );
+
+    let mut scrapers: Vec<Scraper> = config.targets.into_iter().map(Scraper::new).collect();
+    let interval = Duration::from_secs(config.interval_secs.max(1));
+
+    loop {
+        for scraper in &mut scrapers {
+            if let Err(e) = scraper.scrape(&client).await {
+                scraper.status.up = false;
+                scraper.status.last_error = Some(e.to_string());
+            } else {
+                scraper.status.last_error = None;
+            }
+        }
+        if let Ok(mut status) = STATUS.lock() {
+            *status = scrapers
+                .iter()
+                .map(|scraper| scraper.status.clone())
+                .collect();
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// How `metric` of `service` was stored, for picking its aggregate.
+pub async fn series_kind(service: &str, metric: &str) -> CommandResult<SeriesKind> {
+    let store = get_store_pool().await?;
+    let kind: Option<String> =
+        sqlx::query("SELECT kind FROM scrape_metrics WHERE service = ? AND metric = ?")
+            .bind(service)
+            .bind(metric)
+            .fetch_optional(&store)
+            .await?
+            .map(|row| row.get("kind"));
+
+    match kind.as_deref() {
+        Some(kind) => Ok(SeriesKind::parse(kind)),
+        None => Err(ForgeCommandError::not_found(format!(
+            "No scraped metric {:?} for {}",
+            metric, service
+        ))),
+    }
+}
+
+// ===========================================================================
+// IPC Commands
+// ===========================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScrapedMetric {
+    pub service: String,
+    pub metric: String,
+    pub kind: SeriesKind,
+    pub help: Option<String>,
+    pub last_seen: String,
+}
+
+#[tauri::command]
+pub async fn get_scrape_status() -> CommandResult<Vec<ScrapeTargetStatus>> {
+    error::command("get_scrape_status", async move {
+        Ok(STATUS.lock().map_err(ForgeCommandError::internal)?.clone())
+    })
+    .await
+}
+
+/// Metrics scraped so far, optionally for one service.
+#[tauri::command]
+pub async fn get_scraped_metrics(service: Option<String>) -> CommandResult<Vec<ScrapedMetric>> {
+    error::command("get_scraped_metrics", async move {
+        let store = get_store_pool().await?;
+        let rows = sqlx::query(
+            "SELECT service, metric, kind, help, last_seen FROM scrape_metrics
+             WHERE ? IS NULL OR service = ?
+             ORDER BY service, metric",
+        )
+        .bind(&service)
+        .bind(&service)
+        .fetch_all(&store)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ScrapedMetric {
+                service: row.get("service"),
+                metric: row.get("metric"),
+                kind: SeriesKind::parse(row.get("kind")),
+                help: row.get("help"),
+                last_seen: row.get("last_seen"),
+            })
+            .collect())
+    })
+    .await
+}
@@ -0,0 +1,588 @@
+// ===========================================================================
+// Headless API Server
+// ===========================================================================
+//
+// `forge-command --serve` skips the window and serves the dashboard commands
+// as a local REST/JSON API, so scripts, Grafana's JSON datasource and CI
+// smoke tests read the same numbers the UI shows. Every route except the
+// OpenAPI description requires `Authorization: Bearer <token>`.
+
+use std::env;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::anomaly::AnomalyMethod;
+use crate::error::{CommandResult, ForgeCommandError};
+use crate::otlp::{self, OtlpConfig};
+use crate::period::{ComparisonWindow, TimeRange};
+use crate::prometheus::{Exporter, ExporterConfig};
+use crate::{advisor, ingest, logtail, rake, rollup, schema, scrape};
+
+const DEFAULT_BIND: &str = "127.0.0.1:8787";
+
+/// Series served under `/api/series/{name}`.
+const SERIES: [&str; 11] = [
+    "cost",
+    "token_usage",
+    "token_usage_by_model",
+    "search_performance",
+    "agent_activity",
+    "agent_latency",
+    "ingestion",
+    "error_rate",
+    "queue_depth",
+    "queue_wait",
+    "scraped",
+];
+
+pub struct ServeConfig {
+    pub bind: SocketAddr,
+    pub token: String,
+    /// Set to expose `/metrics` for Prometheus.
+    pub metrics: Option<ExporterConfig>,
+    pub otlp: OtlpConfig,
+}
+
+impl ServeConfig {
+    /// Falls back to `FORGE_COMMAND_API_BIND`/`FORGE_COMMAND_API_TOKEN`.
+    /// Without a token a random one is generated and printed.
+    pub fn new(
+        bind: Option<String>,
+        token: Option<String>,
+        metrics: Option<ExporterConfig>,
+        otlp: OtlpConfig,
+    ) -> Result<Self, String> {
+        let bind = bind
+            .or_else(|| env::var("FORGE_COMMAND_API_BIND").ok())
+            .unwrap_or_else(|| DEFAULT_BIND.to_string());
+        let bind = bind
+            .parse()
+            .map_err(|e| format!("Invalid bind address {:?}: {}", bind, e))?;
+
+        let token = match token.or_else(|| env::var("FORGE_COMMAND_API_TOKEN").ok()) {
+            Some(token) if !token.is_empty() => token,
+            _ => {
+                let token: String = rand::thread_rng()
+                    .sample_iter(&Alphanumeric)
+                    .take(32)
+                    .map(char::from)
+                    .collect();
+                eprintln!(
+                    "No API token configured; generated one for this session: {}",
+                    token
+                );
+                token
+            }
+        };
+
+        Ok(ServeConfig {
+            bind,
+            token,
+            metrics,
+            otlp,
+        })
+    }
+}
+
+// ===========================================================================
+// Query Parameters
+// ===========================================================================
+
+/// Query parameters shared by the routes. `comparison` and `anomaly` take the
+/// same `kind`/`method` names as the IPC arguments, with their fields flattened.
+#[derive(Debug, Default, Deserialize)]
+struct ApiQuery {
+    service: Option<String>,
+    /// Scraped metric name, for the `scraped` series.
+    metric: Option<String>,
+    hours: Option<i64>,
+    limit: Option<i64>,
+    comparison: Option<String>,
+    comparison_start: Option<String>,
+    comparison_end: Option<String>,
+    anomaly: Option<String>,
+    threshold: Option<f64>,
+    window: Option<usize>,
+    alpha: Option<f64>,
+    min_samples: Option<usize>,
+}
+
+impl ApiQuery {
+    fn series_hours(&self) -> i64 {
+        self.hours.unwrap_or(24)
+    }
+
+    fn comparison(&self) -> CommandResult<Option<ComparisonWindow>> {
+        let Some(kind) = self.comparison.as_deref() else {
+            return Ok(None);
+        };
+
+        let window = match kind {
+            "previous_period" => ComparisonWindow::PreviousPeriod,
+            "same_period_last_week" => ComparisonWindow::SamePeriodLastWeek,
+            "custom" => match (&self.comparison_start, &self.comparison_end) {
+                (Some(start), Some(end)) => ComparisonWindow::Custom {
+                    range: TimeRange {
+                        start: start.clone(),
+                        end: end.clone(),
+                    },
+                },
+                _ => {
+                    return Err(ForgeCommandError::invalid_input(
+                        "A custom comparison needs comparison_start and comparison_end",
+                    ))
+                }
+            },
+            other => {
+                return Err(ForgeCommandError::invalid_input(format!(
+                    "Unknown comparison {:?}",
+                    other
+                )))
+            }
+        };
+
+        Ok(Some(window))
+    }
+
+    fn anomaly(&self) -> CommandResult<Option<AnomalyMethod>> {
+        let Some(method) = self.anomaly.as_deref() else {
+            return Ok(None);
+        };
+
+        let threshold = self.threshold.unwrap_or(3.0);
+        let method = match method {
+            "rolling_z_score" => AnomalyMethod::RollingZScore {
+                window: self.window.unwrap_or(24),
+                threshold,
+            },
+            "ewma" => AnomalyMethod::Ewma {
+                alpha: self.alpha.unwrap_or(0.3),
+                threshold,
+            },
+            "seasonal_hour_of_week" => AnomalyMethod::SeasonalHourOfWeek {
+                threshold,
+                min_samples: self.min_samples.unwrap_or(3),
+            },
+            other => {
+                return Err(ForgeCommandError::invalid_input(format!(
+                    "Unknown anomaly method {:?}",
+                    other
+                )))
+            }
+        };
+
+        Ok(Some(method))
+    }
+}
+
+// ===========================================================================
+// Responses
+// ===========================================================================
+
+struct ApiError(ForgeCommandError);
+
+impl From<ForgeCommandError> for ApiError {
+    fn from(error: ForgeCommandError) -> Self {
+        ApiError(error)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match self.0.kind() {
+            ForgeCommandError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+            ForgeCommandError::NotFound(_) => StatusCode::NOT_FOUND,
+            ForgeCommandError::DatabaseMissing { .. }
+            | ForgeCommandError::DatabaseUnavailable(_)
+            | ForgeCommandError::DatabaseBusy(_)
+            | ForgeCommandError::QueryTimeout(_) => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        (status, Json(&self.0)).into_response()
+    }
+}
+
+type ApiResult = Result<Json<Value>, ApiError>;
+
+fn respond<T: serde::Serialize>(value: T) -> ApiResult {
+    Ok(Json(
+        serde_json::to_value(value).map_err(ForgeCommandError::from)?,
+    ))
+}
+
+// ===========================================================================
+// Handlers
+// ===========================================================================
+
+async fn health() -> ApiResult {
+    respond(crate::get_system_health().await?)
+}
+
+async fn events(Query(query): Query<ApiQuery>) -> ApiResult {
+    respond(crate::get_recent_events(query.limit.unwrap_or(50), query.service).await?)
+}
+
+async fn ingest_events(Json(body): Json<Value>) -> ApiResult {
+    respond(ingest::ingest_events(body).await?)
+}
+
+async fn service_metrics(Path(service): Path<String>, Query(query): Query<ApiQuery>) -> ApiResult {
+    let comparison = query.comparison()?;
+
+    match service.as_str() {
+        "dataforge" => respond(crate::get_dataforge_metrics(query.hours, comparison).await?),
+        "neuroforge" => {
+            respond(crate::get_neuroforge_metrics(query.limit, query.hours, comparison).await?)
+        }
+        "forgeagents" => {
+            respond(crate::get_forgeagents_metrics(query.limit, query.hours, comparison).await?)
+        }
+        "rake" => respond(crate::get_rake_metrics(query.limit, query.hours, comparison).await?),
+        other => Err(ForgeCommandError::not_found(format!("Unknown service {:?}", other)).into()),
+    }
+}
+
+async fn series(Path(name): Path<String>, Query(query): Query<ApiQuery>) -> ApiResult {
+    let hours = query.series_hours();
+    let anomaly = query.anomaly()?;
+    let comparison = query.comparison()?;
+
+    match name.as_str() {
+        "cost" => respond(crate::get_cost_over_time(hours, anomaly, comparison).await?),
+        "token_usage" => {
+            respond(crate::get_token_usage_over_time(hours, anomaly, comparison).await?)
+        }
+        "token_usage_by_model" => {
+            respond(crate::get_token_usage_by_model_over_time(hours, anomaly, comparison).await?)
+        }
+        "search_performance" => {
+            respond(crate::get_search_performance_over_time(hours, anomaly, comparison).await?)
+        }
+        "agent_activity" => {
+            respond(crate::get_agent_activity_over_time(hours, anomaly, comparison).await?)
+        }
+        "agent_latency" => {
+            respond(crate::get_agent_latency_over_time(hours, anomaly, comparison).await?)
+        }
+        "ingestion" => respond(crate::get_ingestion_over_time(hours, anomaly, comparison).await?),
+        "error_rate" => respond(crate::get_error_rate_over_time(hours, anomaly, comparison).await?),
+        "queue_depth" => {
+            respond(rake::get_queue_depth_over_time(hours, anomaly, comparison).await?)
+        }
+        "queue_wait" => respond(rake::get_queue_wait_over_time(hours, anomaly, comparison).await?),
+        "scraped" => match (query.service, query.metric) {
+            (Some(service), Some(metric)) => respond(
+                crate::get_scraped_metric_over_time(service, metric, hours, anomaly, comparison)
+                    .await?,
+            ),
+            _ => Err(ForgeCommandError::invalid_input(
+                "The scraped series needs service and metric",
+            )
+            .into()),
+        },
+        other => Err(ForgeCommandError::not_found(format!("Unknown series {:?}", other)).into()),
+    }
+}
+
+async fn anomalies(Query(query): Query<ApiQuery>) -> ApiResult {
+    respond(crate::get_anomaly_alerts(query.series_hours(), query.anomaly()?).await?)
+}
+
+async fn schema_report() -> ApiResult {
+    respond(schema::get_schema_report(None).await?)
+}
+
+async fn metrics(exporter: Arc<Exporter>) -> Result<Response, ApiError> {
+    let body = exporter.render().await?;
+    Ok((
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        body,
+    )
+        .into_response())
+}
+
+async fn openapi() -> Json<Value> {
+    Json(openapi_document())
+}
+
+/// Rejects requests without the configured bearer token.
+async fn require_token(State(token): State<String>, request: Request, next: Next) -> Response {
+    let presented = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match presented {
+        Some(presented) if constant_time_eq(presented.as_bytes(), token.as_bytes()) => {
+            next.run(request).await
+        }
+        _ => (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            Json(json!({
+                "code": "unauthorized",
+                "message": "Missing or invalid bearer token",
+                "retryable": false,
+            })),
+        )
+            .into_response(),
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+pub fn router(token: String, exporter: Option<Arc<Exporter>>) -> Router {
+    let mut api = Router::new()
+        .route("/api/health", get(health))
+        .route("/api/events", get(events).post(ingest_events))
+        .route("/api/metrics/:service", get(service_metrics))
+        .route("/api/series/:name", get(series))
+        .route("/api/anomalies", get(anomalies))
+        .route("/api/schema", get(schema_report));
+    if let Some(exporter) = exporter {
+        api = api.route("/metrics", get(move || metrics(exporter.clone())));
+    }
+    let api = api.route_layer(middleware::from_fn_with_state(token, require_token));
+
+    Router::new()
+        .route("/api/openapi.json", get(openapi))
+        .merge(api)
+}
+
+// ===========================================================================
+// OpenAPI
+// ===========================================================================
+
+fn openapi_document() -> Value {
+    let param = |name: &str, schema: Value, description: &str| json!({ "name": name, "in": "query", "required": false, "schema": schema, "description": description });
+    let hours = param(
+        "hours",
+        json!({ "type": "integer" }),
+        "Window length in hours",
+    );
+    let limit = param(
+        "limit",
+        json!({ "type": "integer" }),
+        "Maximum rows in lists",
+    );
+    let comparison = [
+        param(
+            "comparison",
+            json!({ "type": "string", "enum": ["previous_period", "same_period_last_week", "custom"] }),
+            "Period to compare against",
+        ),
+        param(
+            "comparison_start",
+            json!({ "type": "string" }),
+            "Start of a custom comparison",
+        ),
+        param(
+            "comparison_end",
+            json!({ "type": "string" }),
+            "End of a custom comparison",
+        ),
+    ];
+    let anomaly = [
+        param(
+            "anomaly",
+            json!({ "type": "string", "enum": ["rolling_z_score", "ewma", "seasonal_hour_of_week"] }),
+            "Anomaly detection method",
+        ),
+        param(
+            "threshold",
+            json!({ "type": "number" }),
+            "Anomaly score threshold",
+        ),
+        param(
+            "window",
+            json!({ "type": "integer" }),
+            "Rolling z-score window",
+        ),
+        param(
+            "alpha",
+            json!({ "type": "number" }),
+            "EWMA smoothing factor",
+        ),
+        param(
+            "min_samples",
+            json!({ "type": "integer" }),
+            "Seasonal baseline minimum samples",
+        ),
+    ];
+
+    let responses = json!({
+        "200": { "description": "Same payload as the matching IPC command",
+                 "content": { "application/json": { "schema": { "type": "object" } } } },
+        "400": { "$ref": "#/components/responses/Error" },
+        "401": { "$ref": "#/components/responses/Error" },
+        "404": { "$ref": "#/components/responses/Error" },
+        "503": { "$ref": "#/components/responses/Error" },
+    });
+    let get_operation = |summary: &str, parameters: Vec<Value>| json!({ "summary": summary, "parameters": parameters, "responses": responses });
+    let operation = |summary: &str, parameters: Vec<Value>| json!({ "get": get_operation(summary, parameters) });
+    let path_param = |name: &str, values: &[&str]| json!({ "name": name, "in": "path", "required": true, "schema": { "type": "string", "enum": values } });
+
+    let mut metrics_params = vec![
+        path_param(
+            "service",
+            &["dataforge", "neuroforge", "forgeagents", "rake"],
+        ),
+        hours.clone(),
+        limit.clone(),
+    ];
+    metrics_params.extend(comparison.iter().cloned());
+    let mut series_params = vec![
+        path_param("name", &SERIES),
+        hours.clone(),
+        param(
+            "service",
+            json!({ "type": "string" }),
+            "Scraped service (series `scraped`)",
+        ),
+        param(
+            "metric",
+            json!({ "type": "string" }),
+            "Scraped metric name (series `scraped`)",
+        ),
+    ];
+    series_params.extend(anomaly.iter().cloned());
+    series_params.extend(comparison.iter().cloned());
+    let mut anomaly_params = vec![hours];
+    anomaly_params.extend(anomaly.iter().cloned());
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Forge Command API",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Access to the Forge Command dashboard data, plus event ingestion for services without DataForge.",
+        },
+        "security": [{ "bearer": [] }],
+        "paths": {
+            "/api/health": operation("Service status and 24h uptime", vec![]),
+            "/api/events": {
+                "get": get_operation(
+                    "Most recent events",
+                    vec![limit, param("service", json!({ "type": "string" }), "Only events from this service")],
+                ),
+                "post": {
+                    "summary": "Store one event or a batch in the local event store",
+                    "requestBody": {
+                        "required": true,
+                        "content": { "application/json": { "schema": { "oneOf": [
+                            { "$ref": "#/components/schemas/Event" },
+                            { "type": "array", "items": { "$ref": "#/components/schemas/Event" }, "maxItems": 1000 },
+                        ] } } },
+                    },
+                    "responses": responses,
+                },
+            },
+            "/api/metrics/{service}": operation("Summary metrics for one service", metrics_params),
+            "/api/series/{name}": operation("Hourly time series", series_params),
+            "/api/anomalies": operation("Anomaly alerts across series", anomaly_params),
+            "/api/schema": operation("Events schema validation report", vec![]),
+        },
+        "components": {
+            "securitySchemes": { "bearer": { "type": "http", "scheme": "bearer" } },
+            "responses": {
+                "Error": {
+                    "description": "A ForgeCommandError",
+                    "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Error" } } },
+                },
+            },
+            "schemas": {
+                "Event": {
+                    "type": "object",
+                    "required": ["service", "event_type"],
+                    "additionalProperties": false,
+                    "properties": {
+                        "event_id": { "type": "string", "description": "Assigned when missing" },
+                        "timestamp": { "type": "string", "description": "RFC 3339; defaults to now" },
+                        "service": { "type": "string" },
+                        "event_type": { "type": "string" },
+                        "severity": { "type": "string", "enum": ["debug", "info", "warning", "error"], "default": "info" },
+                        "metrics": { "type": "object" },
+                        "metadata": { "type": "object" },
+                    },
+                },
+                "Error": {
+                    "type": "object",
+                    "required": ["code", "message", "retryable"],
+                    "properties": {
+                        "code": { "type": "string" },
+                        "message": { "type": "string" },
+                        "retryable": { "type": "boolean" },
+                        "context": {
+                            "type": "object",
+                            "properties": {
+                                "command": { "type": "string", "nullable": true },
+                                "service": { "type": "string", "nullable": true },
+                                "query": { "type": "string", "nullable": true },
+                            },
+                        },
+                    },
+                },
+            },
+        },
+    })
+}
+
+// ===========================================================================
+// Entry Point
+// ===========================================================================
+
+/// Runs the API until Ctrl-C. The scheduler is left to the desktop app so
+/// jobs don't run twice when both are open.
+pub fn run(config: ServeConfig) -> Result<(), String> {
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+
+    runtime.block_on(async move {
+        // Create the local event store first so `auto` source selection sees it
+        otlp::prepare_event_store(&config.otlp).await;
+        tokio::spawn(async {
+            advisor::run_startup_check().await;
+            schema::run_startup_check().await;
+            rollup::run_rollup_refresh().await;
+        });
+        tokio::spawn(otlp::run_receivers(config.otlp));
+        tokio::spawn(logtail::run_collector());
+        tokio::spawn(scrape::run_collector());
+
+        let listener = tokio::net::TcpListener::bind(config.bind)
+            .await
+            .map_err(|e| format!("Failed to bind {}: {}", config.bind, e))?;
+        eprintln!("Forge Command API listening on http://{}", config.bind);
+
+        if config.metrics.is_some() {
+            eprintln!("Prometheus metrics at http://{}/metrics", config.bind);
+        }
+
+        let exporter = config
+            .metrics
+            .map(|metrics| Arc::new(Exporter::new(metrics)));
+        axum::serve(listener, router(config.token, exporter))
+            .with_graceful_shutdown(async {
+                let _ = tokio::signal::ctrl_c().await;
+            })
+            .await
+            .map_err(|e| e.to_string())
+    })
+}
@@ -0,0 +1,167 @@
+// ===========================================================================
+// Forge Command Store
+// ===========================================================================
+//
+// Forge Command's own SQLite database. DataForge's events table stays
+// read-only; anything Forge Command generates itself lives here.
+
+use std::env;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+use tokio::sync::OnceCell;
+
+use crate::error::{CommandResult, ForgeCommandError};
+
+static STORE_POOL: OnceCell<SqlitePool> = OnceCell::const_new();
+
+const SCHEMA: &[&str] = &[
+    "CREATE TABLE IF NOT EXISTS daily_insights (
+        insight_id INTEGER PRIMARY KEY AUTOINCREMENT,
+        generated_at TEXT NOT NULL,
+        insights TEXT NOT NULL
+    )",
+    "CREATE TABLE IF NOT EXISTS reports (
+        report_id INTEGER PRIMARY KEY AUTOINCREMENT,
+        report_type TEXT NOT NULL,
+        format TEXT NOT NULL,
+        generated_at TEXT NOT NULL,
+        path TEXT NOT NULL
+    )",
+    "CREATE TABLE IF NOT EXISTS jobs (
+        job_id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        action TEXT NOT NULL,
+        schedule TEXT NOT NULL,
+        paused INTEGER NOT NULL DEFAULT 0,
+        last_run_at TEXT,
+        last_status TEXT,
+        next_run_at TEXT
+    )",
+    "CREATE TABLE IF NOT EXISTS job_runs (
+        run_id INTEGER PRIMARY KEY AUTOINCREMENT,
+        job_id TEXT NOT NULL,
+        trigger TEXT NOT NULL,
+        started_at TEXT NOT NULL,
+        finished_at TEXT NOT NULL,
+        status TEXT NOT NULL,
+        output TEXT,
+        error TEXT
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_job_runs_job ON job_runs (job_id, started_at)",
+    "CREATE TABLE IF NOT EXISTS rollup_counts (
+        resolution TEXT NOT NULL,
+        bucket TEXT NOT NULL,
+        service TEXT NOT NULL,
+        event_type TEXT NOT NULL,
+        dimension TEXT NOT NULL,
+        events INTEGER NOT NULL,
+        errors INTEGER NOT NULL,
+        PRIMARY KEY (resolution, bucket, service, event_type, dimension)
+    )",
+    "CREATE TABLE IF NOT EXISTS rollup_values (
+        resolution TEXT NOT NULL,
+        bucket TEXT NOT NULL,
+        service TEXT NOT NULL,
+        event_type TEXT NOT NULL,
+        dimension TEXT NOT NULL,
+        metric TEXT NOT NULL,
+        samples INTEGER NOT NULL,
+        total REAL NOT NULL,
+        minimum REAL,
+        maximum REAL,
+        PRIMARY KEY (resolution, bucket, service, event_type, dimension, metric)
+    )",
+    "CREATE TABLE IF NOT EXISTS rollup_state (
+        resolution TEXT PRIMARY KEY,
+        retention_hours INTEGER NOT NULL,
+        covered_from TEXT,
+        watermark TEXT,
+        last_rowid INTEGER,
+        source TEXT
+    )",
+    "CREATE TABLE IF NOT EXISTS anomaly_alerts (
+        alert_id INTEGER PRIMARY KEY AUTOINCREMENT,
+        service TEXT NOT NULL,
+        series TEXT NOT NULL,
+        timestamp TEXT NOT NULL,
+        severity TEXT NOT NULL,
+        detected_at TEXT NOT NULL,
+        alert TEXT NOT NULL,
+        UNIQUE (service, series, timestamp)
+    )",
+    "CREATE TABLE IF NOT EXISTS log_offsets (
+        path TEXT PRIMARY KEY,
+        fingerprint TEXT NOT NULL,
+        file_id INTEGER,
+        offset INTEGER NOT NULL,
+        updated_at TEXT NOT NULL
+    )",
+    "CREATE TABLE IF NOT EXISTS scrape_metrics (
+        service TEXT NOT NULL,
+        metric TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        help TEXT,
+        last_seen TEXT NOT NULL,
+        PRIMARY KEY (service, metric)
+    )",
+];
+
+/// Directory for files Forge Command writes (store database, reports).
+pub fn data_dir() -> PathBuf {
+    env::var("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|_| env::var("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .unwrap_or_else(|_| env::temp_dir())
+        .join("forge-command")
+}
+
+async fn open_store() -> CommandResult<SqlitePool> {
+    let store_url = match env::var("FORGE_COMMAND_DB") {
+        Ok(path) if path.starts_with("sqlite:") => path,
+        Ok(path) => format!("sqlite://{}", path),
+        Err(_) => {
+            let path = data_dir().join("forge-command.db");
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| {
+                        ForgeCommandError::Io(format!("Failed to create store directory: {}", e))
+                    })?;
+            }
+            format!("sqlite://{}", path.display())
+        }
+    };
+
+    let options = SqliteConnectOptions::from_str(&store_url)
+        .map_err(|e| ForgeCommandError::invalid_input(format!("Invalid store path: {}", e)))?
+        .create_if_missing(true);
+
+    let pool = SqlitePool::connect_with(options)
+        .await
+        .map_err(|e| {
+            ForgeCommandError::DatabaseUnavailable(format!(
+                "Failed to open Forge Command store: {}",
+                e
+            ))
+        })?;
+
+    for statement in SCHEMA {
+        sqlx::query(statement)
+            .execute(&pool)
+            .await
+            .map_err(|e| {
+                ForgeCommandError::QueryFailed(format!(
+                    "Failed to initialise Forge Command store: {}",
+                    e
+                ))
+            })?;
+    }
+
+    Ok(pool)
+}
+
+/// Returns the shared store pool, creating the database and schema on first use.
+pub async fn get_store_pool() -> CommandResult<SqlitePool> {
+    STORE_POOL.get_or_try_init(open_store).await.cloned()
+}
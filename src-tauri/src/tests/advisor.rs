@@ -0,0 +1,120 @@
+use std::path::PathBuf;
+
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+
+use super::harness::run;
+use super::ECOSYSTEM;
+use crate::error::CommandResult;
+use crate::period::{self, TimeWindow, TimestampLayout};
+use crate::{event_store, get_dataforge_metrics, get_db_pool};
+
+/// Creates an events database holding one event per timestamp, and points
+/// the commands at it.
+async fn use_events_db(name: &str, timestamps: &[&str]) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("forge-command-{}-{}.db", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let options = SqliteConnectOptions::new()
+        .filename(&path)
+        .create_if_missing(true);
+    let pool = SqlitePool::connect_with(options)
+        .await
+        .expect("database should open");
+    sqlx::query(event_store::SCHEMA)
+        .execute(&pool)
+        .await
+        .expect("schema should apply");
+    for (i, timestamp) in timestamps.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO events (event_id, timestamp, service, event_type, severity)
+             VALUES (?, ?, 'dataforge', 'query', 'info')",
+        )
+        .bind(format!("e{}", i))
+        .bind(timestamp)
+        .execute(&pool)
+        .await
+        .expect("event should insert");
+    }
+    pool.close().await;
+
+    std::env::set_var("DATABASE_URL", &path);
+    path
+}
+
+async fn layout_after_connect() -> CommandResult<(TimestampLayout, String)> {
+    get_db_pool().await?;
+    Ok((
+        period::timestamp_layout(),
+        TimeWindow::last_hours(1).start_bound(),
+    ))
+}
+
+#[test]
+fn timestamp_layout_follows_the_events_database() {
+    let layouts = run(ECOSYSTEM, || async {
+        let fixture = layout_after_connect().await?;
+
+        use_events_db("space-layout", &["2025-06-02 11:50:00"]).await;
+        let space = layout_after_connect().await?;
+
+        // An empty table keeps the last layout until it has rows to sample
+        let path = use_events_db("empty-layout", &[]).await;
+        let empty = layout_after_connect().await?;
+        let source = period::timestamp_layout_source();
+
+        let pool = SqlitePool::connect_with(SqliteConnectOptions::new().filename(&path)).await?;
+        sqlx::query(
+            "INSERT INTO events (event_id, timestamp, service, event_type, severity)
+             VALUES ('late', '2025-06-02T11:50:00Z', 'dataforge', 'query', 'info')",
+        )
+        .execute(&pool)
+        .await?;
+        pool.close().await;
+        let filled = layout_after_connect().await?;
+
+        Ok::<_, crate::error::ForgeCommandError>((fixture, space, empty, source, path, filled))
+    })
+    .expect("layouts");
+    let (fixture, space, empty, source, empty_path, filled) = layouts;
+
+    assert_eq!(
+        fixture,
+        (TimestampLayout::IsoT, "2025-06-02T11:00:00Z".to_string())
+    );
+    assert_eq!(
+        space,
+        (TimestampLayout::Space, "2025-06-02 11:00:00".to_string())
+    );
+    assert_eq!(empty.0, TimestampLayout::Space);
+    assert!(!source
+        .expect("source")
+        .contains(&empty_path.display().to_string()));
+    assert_eq!(
+        filled,
+        (TimestampLayout::IsoT, "2025-06-02T11:00:00Z".to_string())
+    );
+}
+
+#[test]
+fn window_bounds_match_events_stamped_exactly_on_them() {
+    let cases = [
+        ("iso-start", "2025-06-02T11:00:00Z"),
+        ("iso-end", "2025-06-02T12:00:00Z"),
+        ("space-start", "2025-06-02 11:00:00"),
+        ("space-end", "2025-06-02 12:00:00"),
+    ];
+
+    let searches = run(ECOSYSTEM, || async {
+        let mut searches = Vec::new();
+        for (name, timestamp) in cases {
+            use_events_db(name, &[timestamp]).await;
+            searches.push(get_dataforge_metrics(Some(1), None).await?.total_searches);
+        }
+        Ok::<_, crate::error::ForgeCommandError>(searches)
+    })
+    .expect("metrics");
+
+    // The window is (11:00, 12:00] in either layout: an event at its end
+    // counts, one at its start belongs to the hour before
+    assert_eq!(searches, [0, 1, 0, 1]);
+}
@@ -0,0 +1,271 @@
+use chrono::NaiveDateTime;
+
+use super::harness::{assert_close, assert_filled_series, run};
+use super::{SEASONAL_SPIKE, TRAFFIC_GAP};
+use crate::anomaly::{self, get_alert_feed, AnomalyDirection, AnomalyMethod};
+use crate::clock;
+use crate::error::ForgeCommandError;
+use crate::period::TimeWindow;
+use crate::store::get_store_pool;
+use crate::TimeSeriesPoint;
+use crate::{get_anomaly_alerts, get_ingestion_over_time, get_search_performance_over_time};
+
+const ROLLING: AnomalyMethod = AnomalyMethod::RollingZScore {
+    window: 24,
+    threshold: 3.0,
+};
+
+const EWMA: AnomalyMethod = AnomalyMethod::Ewma {
+    alpha: 0.5,
+    threshold: 3.0,
+};
+
+const SEASONAL: AnomalyMethod = AnomalyMethod::SeasonalHourOfWeek {
+    threshold: 3.0,
+    min_samples: 3,
+};
+
+fn at(timestamp: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S").expect("timestamp")
+}
+
+/// Consecutive hourly points from 2025-06-02 00:00.
+fn hourly(values: &[f64]) -> Vec<TimeSeriesPoint> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(hour, value)| TimeSeriesPoint {
+            timestamp: format!("2025-06-02 {:02}:00:00", hour),
+            value: *value,
+        })
+        .collect()
+}
+
+fn point(timestamp: &str, value: f64) -> TimeSeriesPoint {
+    TimeSeriesPoint {
+        timestamp: timestamp.to_string(),
+        value,
+    }
+}
+
+#[test]
+fn rolling_z_score_flags_a_spike() {
+    let markers = anomaly::detect(
+        &hourly(&[10.0, 12.0, 10.0, 12.0, 10.0, 12.0, 40.0]),
+        ROLLING,
+    );
+
+    assert_eq!(markers.len(), 1);
+    let marker = &markers[0];
+    assert_eq!(marker.timestamp, "2025-06-02 06:00:00");
+    assert_eq!(marker.direction, AnomalyDirection::Spike);
+    assert_close(marker.expected, 11.0);
+    assert_close(marker.score, 29.0);
+    assert_close(marker.lower_bound, 8.0);
+    assert_close(marker.upper_bound, 14.0);
+}
+
+#[test]
+fn rolling_z_score_flags_a_drop_to_zero() {
+    let markers = anomaly::detect(&hourly(&[10.0, 12.0, 10.0, 12.0, 0.0]), ROLLING);
+
+    assert_eq!(markers.len(), 1);
+    assert_eq!(markers[0].timestamp, "2025-06-02 04:00:00");
+    assert_eq!(markers[0].direction, AnomalyDirection::Drop);
+    assert_close(markers[0].score, -11.0);
+}
+
+#[test]
+fn rolling_z_score_needs_three_points_of_history() {
+    assert!(anomaly::detect(&hourly(&[10.0, 12.0, 40.0]), ROLLING).is_empty());
+
+    let markers = anomaly::detect(&hourly(&[10.0, 12.0, 11.0, 40.0]), ROLLING);
+    assert_eq!(markers.len(), 1);
+    assert_eq!(markers[0].timestamp, "2025-06-02 03:00:00");
+}
+
+#[test]
+fn rolling_z_score_only_looks_back_one_window() {
+    let method = AnomalyMethod::RollingZScore {
+        window: 3,
+        threshold: 3.0,
+    };
+    // The early 1000s have left the window by the last point
+    let values = [1000.0, 0.0, 1000.0, 10.0, 12.0, 10.0, 40.0];
+
+    let markers = anomaly::detect(&hourly(&values), method);
+    assert_eq!(markers.len(), 1);
+    assert_eq!(markers[0].timestamp, "2025-06-02 06:00:00");
+    assert_eq!(markers[0].direction, AnomalyDirection::Spike);
+}
+
+#[test]
+fn flat_baseline_is_never_scored() {
+    assert!(anomaly::detect(&hourly(&[10.0, 10.0, 10.0, 10.0, 50.0]), ROLLING).is_empty());
+}
+
+#[test]
+fn ewma_flags_a_spike_against_the_smoothed_mean() {
+    let markers = anomaly::detect(&hourly(&[10.0, 12.0, 10.0, 12.0, 10.0, 40.0]), EWMA);
+
+    assert_eq!(markers.len(), 1);
+    let marker = &markers[0];
+    assert_eq!(marker.timestamp, "2025-06-02 05:00:00");
+    assert_eq!(marker.direction, AnomalyDirection::Spike);
+    assert_close(marker.expected, 10.625);
+}
+
+#[test]
+fn ewma_flags_a_drop() {
+    let markers = anomaly::detect(&hourly(&[10.0, 12.0, 10.0, 12.0, 10.0, 0.0]), EWMA);
+
+    assert_eq!(markers.len(), 1);
+    assert_eq!(markers[0].direction, AnomalyDirection::Drop);
+}
+
+#[test]
+fn ewma_skips_the_warm_up_points() {
+    assert!(anomaly::detect(&hourly(&[10.0, 1000.0, 10.0]), EWMA).is_empty());
+}
+
+#[test]
+fn seasonal_baseline_compares_the_same_hour_of_week() {
+    let method = AnomalyMethod::SeasonalHourOfWeek {
+        threshold: 3.0,
+        min_samples: 3,
+    };
+    let points = [
+        point("2025-06-02 09:00:00", 10.0),
+        // A different hour of the week, with no peers to compare against
+        point("2025-06-02 10:00:00", 500.0),
+        point("2025-06-09 09:00:00", 12.0),
+        point("2025-06-16 09:00:00", 11.0),
+        point("2025-06-23 09:00:00", 50.0),
+    ];
+
+    let markers = anomaly::detect(&points, method);
+    assert_eq!(markers.len(), 1);
+    assert_eq!(markers[0].timestamp, "2025-06-23 09:00:00");
+    assert_eq!(markers[0].direction, AnomalyDirection::Spike);
+    assert_close(markers[0].expected, 11.0);
+}
+
+#[test]
+fn seasonal_baseline_needs_enough_samples() {
+    let method = AnomalyMethod::SeasonalHourOfWeek {
+        threshold: 3.0,
+        min_samples: 4,
+    };
+    let points = [
+        point("2025-06-02 09:00:00", 10.0),
+        point("2025-06-09 09:00:00", 12.0),
+        point("2025-06-16 09:00:00", 11.0),
+        point("2025-06-23 09:00:00", 50.0),
+    ];
+
+    assert!(anomaly::detect(&points, method).is_empty());
+}
+
+#[test]
+fn gaps_are_zero_filled_across_the_window() {
+    let window = TimeWindow {
+        start: at("2025-06-02 00:30:00"),
+        end: at("2025-06-02 04:00:00"),
+    };
+    let filled = anomaly::fill_hourly_gaps(
+        vec![point("2025-06-02 01:00", 5.0)],
+        &window,
+        "%Y-%m-%d %H:00",
+    );
+
+    let timestamps: Vec<&str> = filled.iter().map(|p| p.timestamp.as_str()).collect();
+    // The partial first hour counts; the instant at the end doesn't
+    assert_eq!(
+        timestamps,
+        [
+            "2025-06-02 00:00",
+            "2025-06-02 01:00",
+            "2025-06-02 02:00",
+            "2025-06-02 03:00"
+        ]
+    );
+    let values: Vec<f64> = filled.iter().map(|p| p.value).collect();
+    assert_eq!(values, [0.0, 5.0, 0.0, 0.0]);
+
+    assert!(anomaly::fill_hourly_gaps(Vec::new(), &window, "%Y-%m-%d %H:00").is_empty());
+}
+
+#[test]
+fn traffic_stopping_is_flagged_as_a_drop() {
+    let series = run(TRAFFIC_GAP, || get_ingestion_over_time(12, None, None)).expect("series");
+
+    assert_filled_series(
+        &series.datapoints,
+        12,
+        &[
+            ("2025-06-02 00:00:00", 90.0),
+            ("2025-06-02 01:00:00", 110.0),
+            ("2025-06-02 02:00:00", 90.0),
+            ("2025-06-02 03:00:00", 110.0),
+            ("2025-06-02 04:00:00", 90.0),
+            ("2025-06-02 05:00:00", 110.0),
+            ("2025-06-02 06:00:00", 90.0),
+            ("2025-06-02 07:00:00", 110.0),
+            ("2025-06-02 08:00:00", 90.0),
+        ],
+    );
+    // The first empty hour is the outage; later ones fold into the baseline
+    assert_eq!(series.anomalies.len(), 1);
+    assert_eq!(series.anomalies[0].timestamp, "2025-06-02 09:00:00");
+    assert_eq!(series.anomalies[0].direction, AnomalyDirection::Drop);
+}
+
+#[test]
+fn seasonal_baseline_reads_weeks_before_the_window() {
+    let series = run(SEASONAL_SPIKE, || {
+        get_search_performance_over_time(24, Some(SEASONAL), None)
+    })
+    .expect("series");
+
+    // Only today's two hours are charted; the baseline comes from earlier weeks
+    assert_eq!(series.datapoints.len(), 2);
+    assert_eq!(series.anomalies.len(), 1);
+    let marker = &series.anomalies[0];
+    assert_eq!(marker.timestamp, "2025-06-02 10:00");
+    assert_eq!(marker.direction, AnomalyDirection::Spike);
+    assert_close(marker.expected, 100.0);
+    assert_close(marker.score, 400.0 / 50f64.sqrt());
+}
+
+#[test]
+fn raised_alerts_are_kept_in_the_feed() {
+    let (alerts, feed) = run(SEASONAL_SPIKE, || async {
+        let store = get_store_pool().await?;
+        sqlx::query("DELETE FROM anomaly_alerts")
+            .execute(&store)
+            .await?;
+
+        let alerts = get_anomaly_alerts(24, Some(SEASONAL)).await?;
+        // Rescoring later updates the entry instead of adding another
+        clock::freeze(Some(
+            "2025-06-02T12:30:00Z".parse().expect("test timestamp"),
+        ));
+        get_anomaly_alerts(24, Some(SEASONAL)).await?;
+
+        Ok::<_, ForgeCommandError>((
+            alerts,
+            get_alert_feed(10, Some("dataforge".to_string())).await?,
+        ))
+    })
+    .expect("alerts");
+
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(feed.len(), 1);
+    let entry = &feed[0];
+    assert_eq!(entry.series, "Search latency");
+    assert_eq!(entry.detected_at, "2025-06-02T12:00:00Z");
+    assert_eq!(entry.alert.service, "dataforge");
+    assert_eq!(entry.alert.timestamp, "2025-06-02 10:00");
+    assert_eq!(entry.alert.severity, "critical");
+    assert_close(entry.alert.metrics.actual_value, 500.0);
+}
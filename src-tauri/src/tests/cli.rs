@@ -0,0 +1,55 @@
+use crate::cli::{launch, CliCommand, Launch};
+
+fn launch_with(args: &[&str]) -> Launch {
+    launch(std::iter::once("forge-command").chain(args.iter().copied()))
+}
+
+#[test]
+fn plain_launches_open_the_window() {
+    for args in [
+        &[][..],
+        &["-psn_0_12345"],
+        &["/Users/me/Downloads/report.json"],
+        &["forge-command://dashboard"],
+        &["--unknown-flag"],
+        &["--output", "json"],
+    ] {
+        assert!(
+            matches!(launch_with(args), Launch::Window),
+            "{:?} should open the window",
+            args
+        );
+    }
+}
+
+#[test]
+fn serve_and_subcommands_run_headless() {
+    match launch_with(&["--serve", "--bind", "127.0.0.1:9000"]) {
+        Launch::Headless(cli) => {
+            assert!(cli.serve);
+            assert_eq!(cli.bind.as_deref(), Some("127.0.0.1:9000"));
+        }
+        other => panic!("expected headless, got {:?}", other),
+    }
+
+    match launch_with(&["status", "--output", "json"]) {
+        Launch::Headless(cli) => assert!(matches!(cli.command, Some(CliCommand::Status))),
+        other => panic!("expected headless, got {:?}", other),
+    }
+}
+
+#[test]
+fn headless_typos_and_help_are_reported() {
+    for args in [
+        &["status", "--bogus"][..],
+        &["--serve", "--bind"],
+        &["--help"],
+        &["--version"],
+    ] {
+        assert!(
+            matches!(launch_with(args), Launch::Usage(_)),
+            "{:?} should be a usage error",
+            args
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use super::harness::{assert_close, assert_series, run};
+use super::ECOSYSTEM;
+use crate::period::ComparisonWindow;
+use crate::{get_dataforge_metrics, get_search_performance_over_time};
+
+#[test]
+fn dataforge_metrics_cover_the_last_day() {
+    let metrics = run(ECOSYSTEM, || get_dataforge_metrics(Some(24), None)).expect("metrics");
+
+    assert_eq!(metrics.total_searches, 3);
+    assert_close(metrics.avg_search_duration, 60.0);
+    assert_close(metrics.avg_similarity, 0.7);
+    // One failed search out of four
+    assert_close(metrics.error_rate, 25.0);
+    assert!(metrics.comparison.is_none());
+}
+
+#[test]
+fn dataforge_metrics_without_hours_cover_all_history() {
+    let metrics = run(ECOSYSTEM, || get_dataforge_metrics(None, None)).expect("metrics");
+
+    assert_eq!(metrics.total_searches, 4);
+    assert_close(metrics.avg_search_duration, 295.0);
+    assert_close(metrics.error_rate, 20.0);
+}
+
+#[test]
+fn dataforge_metrics_compare_with_the_previous_day() {
+    let metrics = run(ECOSYSTEM, || {
+        get_dataforge_metrics(Some(24), Some(ComparisonWindow::PreviousPeriod))
+    })
+    .expect("metrics");
+
+    let comparison = metrics.comparison.expect("comparison");
+    assert_eq!(comparison.range.start, "2025-05-31 12:00:00");
+    assert_eq!(comparison.range.end, "2025-06-01 12:00:00");
+    assert_eq!(comparison.metrics.total_searches, 1);
+    assert_close(comparison.metrics.avg_search_duration, 1000.0);
+
+    let searches = &comparison.deltas["total_searches"];
+    assert_close(searches.absolute, 2.0);
+    assert_eq!(searches.percent, Some(200.0));
+    let error_rate = &comparison.deltas["error_rate"];
+    assert_close(error_rate.previous, 0.0);
+    assert_eq!(error_rate.percent, None);
+}
+
+#[test]
+fn search_performance_is_average_latency_per_hour() {
+    let series = run(ECOSYSTEM, || {
+        get_search_performance_over_time(24, None, None)
+    })
+    .expect("series");
+
+    assert_series(
+        &series.datapoints,
+        &[
+            ("2025-06-02 09:00", 80.0),
+            ("2025-06-02 10:00", 60.0),
+            ("2025-06-02 11:00", 40.0),
+        ],
+    );
+    assert!(series.comparison_datapoints.is_empty());
+    assert!(series.anomalies.is_empty());
+}
+
+#[test]
+fn search_performance_overlays_the_previous_day() {
+    let series = run(ECOSYSTEM, || {
+        get_search_performance_over_time(24, None, Some(ComparisonWindow::PreviousPeriod))
+    })
+    .expect("series");
+
+    // 2025-06-01 06:00 shifted forward a day
+    assert_series(
+        &series.comparison_datapoints,
+        &[("2025-06-02 06:00", 1000.0)],
+    );
+}
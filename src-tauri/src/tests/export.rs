@@ -0,0 +1,247 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::RowAccessor;
+use serde_json::{json, Value};
+
+use super::harness::run;
+use super::ECOSYSTEM;
+use crate::breakdown::{JsonColumn, JsonField};
+use crate::export::{
+    request_cancel, write_events, write_series, ExportEventsRequest, ExportFormat, ExportProgress,
+    ExportSeriesRequest, ExportStatus, SeriesAggregate, SeriesBucket,
+};
+use crate::get_db_pool;
+
+/// A fresh output path per test, so parallel test binaries never share files.
+fn output_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("forge-command-exports-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("export directory should be writable");
+    let path = dir.join(name);
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn partial(path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.partial", path.display()))
+}
+
+fn dataforge_events(path: &Path, format: ExportFormat) -> ExportEventsRequest {
+    ExportEventsRequest {
+        path: path.display().to_string(),
+        format,
+        service: Some("dataforge".to_string()),
+        event_type: None,
+        severity: None,
+        hours: Some(24),
+        export_id: None,
+    }
+}
+
+#[test]
+fn csv_export_streams_matching_events_in_time_order() {
+    let path = output_path("events.csv");
+    let updates = Mutex::new(Vec::<ExportProgress>::new());
+
+    let result = run(ECOSYSTEM, || {
+        write_events(dataforge_events(&path, ExportFormat::Csv), |progress| {
+            updates.lock().unwrap().push(progress)
+        })
+    })
+    .expect("export");
+
+    assert_eq!(result.status, ExportStatus::Completed);
+    assert_eq!(result.rows_written, 4);
+    assert!(!partial(&path).exists());
+
+    let mut reader = csv::Reader::from_path(&path).expect("csv");
+    let headers: Vec<String> = reader
+        .headers()
+        .expect("headers")
+        .iter()
+        .map(String::from)
+        .collect();
+    assert_eq!(
+        headers,
+        [
+            "event_id",
+            "timestamp",
+            "service",
+            "event_type",
+            "severity",
+            "metrics",
+            "metadata"
+        ]
+    );
+    let rows: Vec<(String, String, String)> = reader
+        .records()
+        .map(|record| {
+            let record = record.expect("record");
+            (
+                record[1].to_string(),
+                record[3].to_string(),
+                record[4].to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            ("2025-06-02T09:50:00Z".into(), "query".into(), "info".into()),
+            ("2025-06-02T10:50:00Z".into(), "query".into(), "info".into()),
+            (
+                "2025-06-02T11:30:00Z".into(),
+                "query_error".into(),
+                "error".into()
+            ),
+            ("2025-06-02T11:50:00Z".into(), "query".into(), "info".into()),
+        ]
+    );
+
+    let updates = updates.into_inner().unwrap();
+    let first = updates.first().expect("progress");
+    let last = updates.last().expect("progress");
+    assert_eq!(
+        (first.rows_written, first.total_rows, first.finished),
+        (0, 4, false)
+    );
+    assert_eq!(
+        (last.rows_written, last.total_rows, last.finished),
+        (4, 4, true)
+    );
+}
+
+#[test]
+fn ndjson_export_embeds_json_columns() {
+    let path = output_path("events.ndjson");
+
+    let result = run(ECOSYSTEM, || {
+        write_events(dataforge_events(&path, ExportFormat::Ndjson), |_| {})
+    })
+    .expect("export");
+
+    assert_eq!(result.rows_written, 4);
+    let text = std::fs::read_to_string(&path).expect("ndjson");
+    let lines: Vec<Value> = text
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("json line"))
+        .collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[2]["event_type"], "query_error");
+    assert_eq!(lines[2]["metadata"], json!({ "error": "timeout" }));
+    assert_eq!(
+        lines[3]["metrics"],
+        json!({ "duration_ms": 40, "avg_similarity": 0.8 })
+    );
+}
+
+#[test]
+fn parquet_series_export_sums_each_hour() {
+    let path = output_path("cost.parquet");
+    let request = ExportSeriesRequest {
+        path: path.display().to_string(),
+        format: ExportFormat::Parquet,
+        service: "neuroforge".to_string(),
+        event_type: None,
+        value: Some(JsonField {
+            column: JsonColumn::Metrics,
+            key: "cost_usd".to_string(),
+        }),
+        aggregate: SeriesAggregate::Sum,
+        bucket: SeriesBucket::Hour,
+        hours: Some(24),
+        export_id: None,
+    };
+
+    let result = run(ECOSYSTEM, || write_series(request, |_| {})).expect("export");
+
+    assert_eq!(result.rows_written, 2);
+    let reader =
+        SerializedFileReader::new(std::fs::File::open(&path).expect("parquet")).expect("reader");
+    assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+    let rows: Vec<(String, i64, f64)> = reader
+        .get_row_iter(None)
+        .expect("rows")
+        .map(|row| {
+            let row = row.expect("row");
+            (
+                row.get_string(0).expect("timestamp").clone(),
+                row.get_long(1).expect("count"),
+                row.get_double(2).expect("value"),
+            )
+        })
+        .collect();
+    assert_eq!(rows[0].0, "2025-06-02 10:00:00");
+    assert_eq!(rows[0].1, 2);
+    assert!((rows[0].2 - 0.07).abs() < 1e-9);
+    assert_eq!(rows[1].0, "2025-06-02 11:00:00");
+    assert_eq!(rows[1].1, 1);
+    assert!((rows[1].2 - 0.01).abs() < 1e-9);
+}
+
+#[test]
+fn series_export_needs_a_value_to_sum() {
+    let path = output_path("missing-value.csv");
+    let request = ExportSeriesRequest {
+        path: path.display().to_string(),
+        format: ExportFormat::Csv,
+        service: "neuroforge".to_string(),
+        event_type: None,
+        value: None,
+        aggregate: SeriesAggregate::Avg,
+        bucket: SeriesBucket::Day,
+        hours: None,
+        export_id: None,
+    };
+
+    let error = run(ECOSYSTEM, || write_series(request, |_| {})).expect_err("no value field");
+
+    assert_eq!(error.code(), "invalid_input");
+    assert!(!path.exists());
+}
+
+#[test]
+fn cancelled_export_leaves_no_file_behind() {
+    let path = output_path("cancelled.csv");
+    let mut request = dataforge_events(&path, ExportFormat::Csv);
+    request.export_id = Some("cancel-me".to_string());
+
+    let result = run(ECOSYSTEM, || {
+        write_events(request, |progress| {
+            if !progress.finished {
+                assert!(request_cancel(&progress.export_id).expect("cancel"));
+            }
+        })
+    })
+    .expect("export");
+
+    assert_eq!(result.status, ExportStatus::Cancelled);
+    assert_eq!(result.rows_written, 0);
+    assert!(!path.exists());
+    assert!(!partial(&path).exists());
+    // The export is no longer registered once it stops
+    assert!(!request_cancel("cancel-me").expect("cancel"));
+}
+
+#[test]
+fn unexpected_column_types_fail_the_export_cleanly() {
+    let path = output_path("corrupt.ndjson");
+
+    let error = run(ECOSYSTEM, || async {
+        let pool = get_db_pool().await?;
+        sqlx::query(
+            "INSERT INTO events (event_id, timestamp, service, event_type, severity, metrics)
+             VALUES ('corrupt', '2025-06-02T11:55:00Z', 'dataforge', 'query', 'info', X'00')",
+        )
+        .execute(&pool)
+        .await?;
+        write_events(dataforge_events(&path, ExportFormat::Ndjson), |_| {}).await
+    })
+    .expect_err("binary metrics column");
+
+    assert_eq!(error.code(), "schema_mismatch");
+    assert!(error.to_string().contains("metrics"));
+    assert!(!path.exists());
+    assert!(!partial(&path).exists());
+}
@@ -0,0 +1,212 @@
+use chrono::{Duration, NaiveDateTime};
+
+use super::harness::{assert_close, assert_filled_series, assert_series, run};
+use super::{AGENT_TASKS, ECOSYSTEM, HOUR_BOUNDARY};
+use crate::period::TimeWindow;
+use crate::{
+    clock, forgeagents, get_agent_activity_over_time, get_agent_latency_over_time, get_db_pool,
+    get_forgeagents_metrics,
+};
+
+#[test]
+fn forgeagents_metrics_count_tasks_not_events() {
+    let metrics =
+        run(ECOSYSTEM, || get_forgeagents_metrics(None, Some(24), None)).expect("metrics");
+
+    assert_eq!(metrics.active_agents, 2);
+    // t1 (no start event), t2 and t3
+    assert_eq!(metrics.total_tasks, 3);
+    assert_eq!(metrics.completed_tasks, 2);
+    assert_eq!(metrics.failed_tasks, 1);
+    assert_close(metrics.avg_latency_ms, 3000.0);
+    // t2 completed with a failed status
+    assert_close(metrics.success_rate, 200.0 / 3.0);
+}
+
+#[test]
+fn forgeagents_agents_are_ranked_by_finished_tasks() {
+    let metrics =
+        run(ECOSYSTEM, || get_forgeagents_metrics(None, Some(24), None)).expect("metrics");
+
+    let agents: Vec<(&str, &str, &str, i64, i64)> = metrics
+        .recent_agents
+        .iter()
+        .map(|a| {
+            (
+                a.agent_id.as_str(),
+                a.agent_name.as_str(),
+                a.status.as_str(),
+                a.tasks_completed,
+                a.tasks_failed,
+            )
+        })
+        .collect();
+    assert_eq!(
+        agents,
+        [
+            ("a1", "Alpha", "idle", 2, 0),
+            ("a2", "Beta", "failed", 0, 1)
+        ]
+    );
+    assert_close(metrics.recent_agents[0].avg_latency_ms, 2000.0);
+    assert_close(metrics.recent_agents[1].avg_latency_ms, 5000.0);
+    assert_eq!(metrics.recent_agents[0].last_seen, "2025-06-02T11:45:00Z");
+}
+
+#[test]
+fn forgeagents_tasks_pair_starts_with_outcomes() {
+    let metrics = run(AGENT_TASKS, || {
+        get_forgeagents_metrics(None, Some(24), None)
+    })
+    .expect("metrics");
+
+    // t6 started before the window but finished in it; the start without a
+    // task id can't be tracked
+    assert_eq!(metrics.completed_tasks, 3);
+    assert_eq!(metrics.failed_tasks, 1);
+    assert_close(metrics.success_rate, 75.0);
+    // t2 started 10 minutes ago; t3 has had no outcome for 90 minutes, and
+    // t7, started before the window, for 30 hours
+    assert_eq!(metrics.in_flight_tasks, 1);
+    assert_eq!(metrics.orphaned_tasks, 2);
+    assert_eq!(metrics.total_tasks, 7);
+    assert_eq!(metrics.active_agents, 4);
+    // Reported durations, or start-to-outcome for t5 which reports none
+    assert_close(
+        metrics.avg_latency_ms,
+        (290_000.0 + 1000.0 + 600_000.0 + 600_000.0) / 4.0,
+    );
+}
+
+#[test]
+fn forgeagents_agent_status_follows_the_latest_event() {
+    let metrics = run(AGENT_TASKS, || {
+        get_forgeagents_metrics(None, Some(24), None)
+    })
+    .expect("metrics");
+
+    let agents: Vec<(&str, &str, i64, i64, i64, i64, &str)> = metrics
+        .recent_agents
+        .iter()
+        .map(|a| {
+            (
+                a.agent_id.as_str(),
+                a.status.as_str(),
+                a.tasks_completed,
+                a.tasks_failed,
+                a.tasks_in_flight,
+                a.tasks_orphaned,
+                a.last_seen.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        agents,
+        [
+            ("a1", "active", 2, 0, 1, 0, "2025-06-02T11:50:00Z"),
+            ("a2", "stalled", 0, 1, 0, 1, "2025-06-02T10:30:00Z"),
+            ("a3", "idle", 1, 0, 0, 0, "2025-06-02T11:30:00Z"),
+            ("a4", "stalled", 0, 0, 0, 1, "2025-06-01T06:00:00Z"),
+        ]
+    );
+    assert_close(metrics.recent_agents[0].success_rate, 100.0);
+    assert_close(metrics.recent_agents[1].success_rate, 0.0);
+}
+
+#[test]
+fn forgeagents_tasks_started_before_the_window_stay_open() {
+    let metrics = run(AGENT_TASKS, || {
+        get_forgeagents_metrics(Some(1), Some(1), None)
+    })
+    .expect("metrics");
+
+    // t1 and t5 finish in the last hour and t2 starts in it; t3 started
+    // before it and is still without an outcome
+    assert_eq!(metrics.completed_tasks, 2);
+    assert_eq!(metrics.failed_tasks, 0);
+    assert_eq!(metrics.in_flight_tasks, 1);
+    assert_eq!(metrics.orphaned_tasks, 1);
+    assert_eq!(metrics.active_agents, 3);
+    assert_eq!(metrics.recent_agents.len(), 1);
+    assert_eq!(metrics.recent_agents[0].agent_id, "a1");
+}
+
+#[test]
+fn forgeagents_tasks_running_into_the_window_are_in_flight() {
+    let agents = run(AGENT_TASKS, || async {
+        let pool = get_db_pool().await?;
+        let end = clock::now_naive();
+        let window = TimeWindow {
+            start: end - Duration::minutes(5),
+            end,
+        };
+        forgeagents::fetch_agent_stats(&pool, &window).await
+    })
+    .expect("agent stats");
+
+    let at = |raw: &str| NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M:%S").expect("time");
+    let agents: Vec<(&str, &str, i64, i64, NaiveDateTime)> = agents
+        .iter()
+        .map(|a| {
+            (
+                a.agent_id.as_str(),
+                a.status.as_str(),
+                a.in_flight,
+                a.orphaned,
+                a.last_seen,
+            )
+        })
+        .collect();
+    // Nothing starts or finishes in the last five minutes, but t2 is still
+    // running and t3 is overdue
+    assert_eq!(
+        agents,
+        [
+            ("a1", "active", 1, 0, at("2025-06-02 11:50:00")),
+            ("a2", "stalled", 0, 1, at("2025-06-02 10:30:00")),
+        ]
+    );
+}
+
+#[test]
+fn agent_activity_counts_completions_per_hour() {
+    let series = run(ECOSYSTEM, || get_agent_activity_over_time(24, None, None)).expect("series");
+
+    assert_filled_series(
+        &series.datapoints,
+        24,
+        &[
+            ("2025-06-02 09:00", 1.0),
+            ("2025-06-02 10:00", 1.0),
+            ("2025-06-02 11:00", 1.0),
+        ],
+    );
+}
+
+#[test]
+fn agent_latency_averages_completions_per_hour() {
+    let series = run(ECOSYSTEM, || get_agent_latency_over_time(24, None, None)).expect("series");
+
+    assert_series(
+        &series.datapoints,
+        &[
+            ("2025-06-02 09:00", 1000.0),
+            ("2025-06-02 10:00", 5000.0),
+            ("2025-06-02 11:00", 3000.0),
+        ],
+    );
+}
+
+#[test]
+fn agent_activity_counts_completions_on_the_first_hour_boundary() {
+    let series = run(HOUR_BOUNDARY, || {
+        get_agent_activity_over_time(24, None, None)
+    })
+    .expect("series");
+
+    assert_filled_series(
+        &series.datapoints,
+        25,
+        &[("2025-06-01 12:00", 1.0), ("2025-06-02 12:00", 1.0)],
+    );
+}
@@ -0,0 +1,289 @@
+// ===========================================================================
+// Fixture Harness
+// ===========================================================================
+//
+// A fixture is a frozen "now" plus the events DataForge would have written
+// around it, with times given relative to that now:
+//
+//   {
+//     "now": "2025-06-02T12:00:00Z",
+//     "events": [
+//       { "at": "-10m", "service": "dataforge", "event_type": "query",
+//         "metrics": { "duration_ms": 40 } },
+//       { "at": "-6h", "repeat": 6, "every": "1h", "service": "rake", ... }
+//     ]
+//   }
+//
+// `run` writes the fixture to a fresh events database, points the commands
+// at it, freezes the clock at `now` and awaits the command. The rollup store
+// is emptied first, so every series is computed from the raw events unless
+// the command refreshes the rollups itself.
+
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+use tokio::runtime::Runtime;
+
+use crate::store::get_store_pool;
+use crate::{advisor, clock, event_store, TimeSeriesPoint};
+
+#[derive(Debug, Deserialize)]
+struct Fixture {
+    now: DateTime<Utc>,
+    events: Vec<FixtureEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FixtureEvent {
+    /// Offset from `now`, e.g. `-90m` or `-2h30m`.
+    at: String,
+    /// Writes the event this many times, `every` apart starting at `at`.
+    #[serde(default = "one")]
+    repeat: usize,
+    #[serde(default)]
+    every: Option<String>,
+    #[serde(default)]
+    event_id: Option<String>,
+    service: String,
+    event_type: String,
+    #[serde(default = "info")]
+    severity: String,
+    #[serde(default)]
+    metrics: Map<String, Value>,
+    #[serde(default)]
+    metadata: Map<String, Value>,
+}
+
+fn one() -> usize {
+    1
+}
+
+fn info() -> String {
+    "info".to_string()
+}
+
+/// Parses an offset such as `-90m`, `-2h30m` or `1d` (`s`, `m`, `h`, `d`).
+fn parse_offset(raw: &str) -> Duration {
+    let (sign, rest) = match raw.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, raw),
+    };
+    assert!(!rest.is_empty(), "empty fixture offset {:?}", raw);
+
+    let mut total = Duration::zero();
+    let mut digits = String::new();
+    for c in rest.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let amount: i64 = digits
+            .parse()
+            .unwrap_or_else(|_| panic!("invalid fixture offset {:?}", raw));
+        total += match c {
+            's' => Duration::seconds(amount),
+            'm' => Duration::minutes(amount),
+            'h' => Duration::hours(amount),
+            'd' => Duration::days(amount),
+            _ => panic!("unknown unit {:?} in fixture offset {:?}", c, raw),
+        };
+        digits.clear();
+    }
+    assert!(
+        digits.is_empty(),
+        "fixture offset {:?} is missing a unit",
+        raw
+    );
+
+    total * sign
+}
+
+/// Commands share process-wide state (environment, clock, store pool), so
+/// fixtures run one at a time on a single runtime the store pool can outlive
+/// individual tests on.
+static LOCK: Mutex<()> = Mutex::new(());
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+static SCRATCH: OnceLock<PathBuf> = OnceLock::new();
+static DATABASES: AtomicUsize = AtomicUsize::new(0);
+
+fn runtime() -> &'static Runtime {
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .expect("test runtime should start")
+    })
+}
+
+/// Per-process scratch directory holding the store and fixture databases.
+pub fn scratch_dir() -> &'static PathBuf {
+    SCRATCH.get_or_init(|| {
+        let dir = std::env::temp_dir().join(format!("forge-command-tests-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("scratch directory should be writable");
+
+        std::env::set_var("XDG_DATA_HOME", &dir);
+        std::env::set_var("FORGE_COMMAND_DB", dir.join("forge-command.db"));
+        std::env::set_var("FORGE_COMMAND_EVENT_SOURCE", "dataforge");
+        dir
+    })
+}
+
+async fn write_fixture(path: &PathBuf, fixture: &Fixture) {
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true);
+    let pool = SqlitePool::connect_with(options)
+        .await
+        .expect("fixture database should open");
+
+    for statement in
+        std::iter::once(event_store::SCHEMA.to_string()).chain(advisor::recommended_index_ddl())
+    {
+        sqlx::query(&statement)
+            .execute(&pool)
+            .await
+            .expect("fixture schema should apply");
+    }
+
+    let mut written = 0;
+    for event in &fixture.events {
+        let start = fixture.now + parse_offset(&event.at);
+        let every = event
+            .every
+            .as_deref()
+            .map_or(Duration::zero(), parse_offset);
+        assert!(
+            event.repeat == 1 || event.every.is_some(),
+            "fixture event at {:?} repeats without `every`",
+            event.at
+        );
+
+        for i in 0..event.repeat {
+            written += 1;
+            let event_id = match (&event.event_id, event.repeat) {
+                (Some(id), 1) => id.clone(),
+                (Some(id), _) => format!("{}-{}", id, i + 1),
+                (None, _) => format!("fixture-{}", written),
+            };
+            let at = start + every * i as i32;
+
+            sqlx::query(
+                "INSERT INTO events
+                    (event_id, timestamp, service, event_type, severity, metrics, metadata)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(event_id)
+            // DataForge's layout
+            .bind(at.to_rfc3339_opts(SecondsFormat::Secs, true))
+            .bind(&event.service)
+            .bind(&event.event_type)
+            .bind(&event.severity)
+            .bind(Value::Object(event.metrics.clone()).to_string())
+            .bind(Value::Object(event.metadata.clone()).to_string())
+            .execute(&pool)
+            .await
+            .expect("fixture event should insert");
+        }
+    }
+
+    pool.close().await;
+}
+
+async fn clear_rollups() {
+    let store = get_store_pool().await.expect("store should open");
+    for table in ["rollup_counts", "rollup_values", "rollup_state"] {
+        sqlx::query(&format!("DELETE FROM {}", table))
+            .execute(&store)
+            .await
+            .expect("rollups should clear");
+    }
+}
+
+/// Loads `fixture` (JSON) into a fresh events database and awaits `command`
+/// against it with the clock frozen at the fixture's `now`.
+pub fn run<T, Fut>(fixture: &str, command: impl FnOnce() -> Fut) -> T
+where
+    Fut: Future<Output = T>,
+{
+    let _guard = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+    let fixture: Fixture = serde_json::from_str(fixture).expect("fixture should parse");
+    let path = scratch_dir().join(format!(
+        "events-{}.db",
+        DATABASES.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    runtime().block_on(async {
+        write_fixture(&path, &fixture).await;
+        clear_rollups().await;
+        std::env::set_var("DATABASE_URL", &path);
+        clock::freeze(Some(fixture.now));
+
+        let result = command().await;
+
+        clock::freeze(None);
+        result
+    })
+}
+
+/// Asserts two floats agree to within rounding of the SQL arithmetic.
+#[track_caller]
+pub fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "expected {}, got {}",
+        expected,
+        actual
+    );
+}
+
+/// Asserts a series has exactly the `(timestamp, value)` points given.
+#[track_caller]
+pub fn assert_series(actual: &[TimeSeriesPoint], expected: &[(&str, f64)]) {
+    let timestamps: Vec<&str> = actual
+        .iter()
+        .map(|point| point.timestamp.as_str())
+        .collect();
+    let expected_timestamps: Vec<&str> = expected.iter().map(|(timestamp, _)| *timestamp).collect();
+    assert_eq!(timestamps, expected_timestamps);
+
+    for (point, (_, value)) in actual.iter().zip(expected) {
+        assert_close(point.value, *value);
+    }
+}
+
+/// Asserts a zero-filled hourly series has `hours` points: the `(timestamp,
+/// value)` points given, and zero at every other hour.
+#[track_caller]
+pub fn assert_filled_series(actual: &[TimeSeriesPoint], hours: usize, expected: &[(&str, f64)]) {
+    assert_eq!(actual.len(), hours, "expected {} hourly points", hours);
+    assert!(
+        actual
+            .windows(2)
+            .all(|pair| pair[0].timestamp < pair[1].timestamp),
+        "points should be in ascending hour order"
+    );
+
+    for (timestamp, value) in expected {
+        let point = actual
+            .iter()
+            .find(|point| point.timestamp == *timestamp)
+            .unwrap_or_else(|| panic!("no point at {}", timestamp));
+        assert_close(point.value, *value);
+    }
+    for point in actual {
+        if !expected
+            .iter()
+            .any(|(timestamp, _)| *timestamp == point.timestamp)
+        {
+            assert_close(point.value, 0.0);
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use serde_json::{json, Value};
+
+use super::harness::{assert_close, run};
+use super::ECOSYSTEM;
+use crate::error::{CommandResult, ForgeCommandError};
+use crate::event_store;
+use crate::get_neuroforge_metrics;
+use crate::ingest::{ingest_events, IngestResult};
+
+/// `(event_id, timestamp, severity, metrics)` stored for `service`, in
+/// insertion order.
+async fn stored(service: &str) -> Vec<(String, String, String, String)> {
+    let pool = event_store::get_event_store_pool()
+        .await
+        .expect("local event store");
+    sqlx::query_as(
+        "SELECT event_id, timestamp, severity, metrics FROM events
+         WHERE service = ? ORDER BY rowid",
+    )
+    .bind(service)
+    .fetch_all(&pool)
+    .await
+    .expect("stored events")
+}
+
+/// Posts `body`, expecting the whole batch to be rejected; returns the
+/// message and what was stored for `service`.
+fn rejected(body: Value, service: &str) -> (String, usize) {
+    run(ECOSYSTEM, || async {
+        let error = ingest_events(body)
+            .await
+            .expect_err("batch should be rejected");
+        assert_eq!(error.code(), "invalid_input");
+        (error.message(), stored(service).await.len())
+    })
+}
+
+#[test]
+fn single_and_batched_events_are_stored_with_their_ids() {
+    let (single, batch, single_rows, batch_rows) = run(ECOSYSTEM, || async {
+        let single = ingest_events(json!({
+            "event_id": "deploy-1",
+            "timestamp": "2025-06-02 11:30:00",
+            "service": "ingest-single",
+            "event_type": "deploy",
+            "severity": "warning",
+            "metrics": { "duration_ms": 420 },
+        }))
+        .await?;
+        let batch = ingest_events(json!([
+            {
+                "event_id": "batch-1",
+                "timestamp": "2025-06-02T13:30:00+02:00",
+                "service": "ingest-batch",
+                "event_type": "job",
+            },
+            { "service": "ingest-batch", "event_type": "job" },
+        ]))
+        .await?;
+
+        Ok::<_, ForgeCommandError>((
+            single,
+            batch,
+            stored("ingest-single").await,
+            stored("ingest-batch").await,
+        ))
+    })
+    .expect("ingest");
+
+    assert_eq!(
+        (single.received, single.inserted, single.duplicates),
+        (1, 1, 0)
+    );
+    assert_eq!(single.event_ids, ["deploy-1"]);
+    assert_eq!(
+        single_rows,
+        [(
+            "deploy-1".to_string(),
+            "2025-06-02T11:30:00Z".to_string(),
+            "warning".to_string(),
+            r#"{"duration_ms":420}"#.to_string()
+        )]
+    );
+
+    assert_eq!(
+        (batch.received, batch.inserted, batch.duplicates),
+        (2, 2, 0)
+    );
+    assert_eq!(batch.event_ids[0], "batch-1");
+    // Ids are assigned to events posted without one
+    let assigned = &batch.event_ids[1];
+    assert_eq!(assigned.len(), 32);
+    assert!(assigned.chars().all(|c| c.is_ascii_hexdigit()));
+
+    let rows: Vec<(&str, &str, &str)> = batch_rows
+        .iter()
+        .map(|(id, timestamp, severity, _)| (id.as_str(), timestamp.as_str(), severity.as_str()))
+        .collect();
+    // Offsets are stored in UTC; a missing timestamp is now
+    assert_eq!(
+        rows,
+        [
+            ("batch-1", "2025-06-02T11:30:00Z", "info"),
+            (assigned.as_str(), "2025-06-02T12:00:00Z", "info")
+        ]
+    );
+}
+
+#[test]
+fn duplicate_event_ids_are_reported_and_stored_once() {
+    let (first, retried, rows) = run(ECOSYSTEM, || async {
+        let event = |severity: &str| {
+            json!({
+                "event_id": "dup-1",
+                "service": "ingest-duplicates",
+                "event_type": "job",
+                "severity": severity,
+            })
+        };
+        let first = ingest_events(json!([event("info"), event("error")])).await?;
+        let retried = ingest_events(event("warning")).await?;
+
+        Ok::<_, ForgeCommandError>((first, retried, stored("ingest-duplicates").await))
+    })
+    .expect("ingest");
+
+    assert_eq!(
+        (first.received, first.inserted, first.duplicates),
+        (2, 1, 1)
+    );
+    assert_eq!(first.event_ids, ["dup-1", "dup-1"]);
+    assert_eq!(
+        (retried.received, retried.inserted, retried.duplicates),
+        (1, 0, 1)
+    );
+
+    // The first delivery wins
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].2, "info");
+}
+
+#[test]
+fn a_bad_severity_rejects_the_whole_batch() {
+    let (message, stored) = rejected(
+        json!([
+            { "service": "ingest-bad-severity", "event_type": "job" },
+            { "service": "ingest-bad-severity", "event_type": "job", "severity": "fatal" },
+        ]),
+        "ingest-bad-severity",
+    );
+
+    assert!(message.starts_with("Rejected batch, nothing stored: event 1: severity \"fatal\""));
+    assert_eq!(stored, 0);
+}
+
+#[test]
+fn a_bad_timestamp_rejects_the_whole_batch() {
+    let (message, stored) = rejected(
+        json!([
+            { "service": "ingest-bad-timestamp", "event_type": "job", "timestamp": "yesterday" },
+            { "service": "ingest-bad-timestamp", "event_type": "job" },
+        ]),
+        "ingest-bad-timestamp",
+    );
+
+    assert!(
+        message.contains("event 0: timestamp \"yesterday\""),
+        "{}",
+        message
+    );
+    assert_eq!(stored, 0);
+}
+
+#[test]
+fn a_mistyped_declared_field_rejects_the_whole_batch() {
+    let (message, stored) = rejected(
+        json!([
+            { "service": "ingest-mistyped", "event_type": "job" },
+            {
+                "service": "neuroforge",
+                "event_type": "model_request",
+                "metrics": { "tokens_total": "lots", "cost_usd": 0.1 },
+                "metadata": { "model": "ingest-mistyped" },
+            },
+        ]),
+        "ingest-mistyped",
+    );
+
+    assert!(
+        message.contains("event 1: metrics.tokens_total: expected integer, found string"),
+        "{}",
+        message
+    );
+    assert_eq!(stored, 0);
+}
+
+#[test]
+fn batches_are_limited_to_a_thousand_events() {
+    let batch = |size: usize| {
+        Value::Array(
+            (0..size)
+                .map(|_| json!({ "service": "ingest-limit", "event_type": "job" }))
+                .collect(),
+        )
+    };
+
+    let (over, at_limit, rows) = run(ECOSYSTEM, || async {
+        let over = ingest_events(batch(1001)).await;
+        let at_limit = ingest_events(batch(1000)).await;
+        (over, at_limit, stored("ingest-limit").await.len())
+    });
+
+    let error = over.expect_err("batch over the limit");
+    assert_eq!(error.code(), "invalid_input");
+    assert_eq!(
+        error.message(),
+        "Batch of 1001 events exceeds the limit of 1000"
+    );
+    assert_eq!(at_limit.expect("batch at the limit").inserted, 1000);
+    assert_eq!(rows, 1000);
+}
+
+#[test]
+fn local_source_shows_ingested_events_on_the_dashboards() {
+    let metrics = run(ECOSYSTEM, || async {
+        let result: CommandResult<IngestResult> = ingest_events(json!([
+            {
+                "timestamp": "2025-06-02T11:00:00Z",
+                "service": "neuroforge",
+                "event_type": "model_request",
+                "metrics": {
+                    "tokens_prompt": 300, "tokens_completion": 100, "tokens_total": 400,
+                    "cost_usd": 0.04, "duration_ms": 900,
+                },
+                "metadata": { "model": "ingested-model" },
+            },
+            {
+                "timestamp": "2025-06-02T11:45:00Z",
+                "service": "neuroforge",
+                "event_type": "model_request",
+                "metrics": {
+                    "tokens_prompt": 100, "tokens_completion": 100, "tokens_total": 200,
+                    "cost_usd": 0.02, "duration_ms": 1100,
+                },
+                "metadata": { "model": "ingested-model" },
+            },
+        ]))
+        .await;
+        result.expect("ingest");
+
+        // What `--source local` selects for the dashboards
+        std::env::set_var("FORGE_COMMAND_EVENT_SOURCE", "local");
+        let metrics = get_neuroforge_metrics(None, Some(24), None).await;
+        std::env::set_var("FORGE_COMMAND_EVENT_SOURCE", "dataforge");
+        metrics
+    })
+    .expect("metrics");
+
+    let model = metrics
+        .top_models
+        .iter()
+        .find(|model| model.model == "ingested-model")
+        .expect("ingested model");
+    assert_eq!(model.requests, 2);
+    assert_eq!(model.tokens, 600);
+    assert_close(model.cost, 0.06);
+    assert_close(model.avg_latency, 1000.0);
+}
@@ -0,0 +1,129 @@
+use super::harness::{assert_close, run};
+use super::{DAILY_SPEND, INSIGHTS};
+use crate::clock;
+use crate::error::ForgeCommandError;
+use crate::insights::{
+    generate_insights, generate_insights_now, get_cost_projection, get_daily_insights,
+    get_insights_history,
+};
+use crate::store::get_store_pool;
+
+#[test]
+fn insights_summarise_burn_rate_and_week_over_week_change() {
+    let insights = run(INSIGHTS, generate_insights).expect("insights");
+
+    assert_eq!(insights.generated_at, "2025-06-02T12:00:00Z");
+    let metrics = &insights.metrics;
+    // 3600 tokens and $2.40 in the last day
+    assert_close(metrics.token_burn_rate.value, 150.0);
+    assert_close(metrics.cost_burn_rate.value, 0.1);
+    assert_close(metrics.cost_projection_30d.value, 72.0);
+    // $3.00 this week against $2.00 the week before
+    assert_close(metrics.cost_change_vs_last_week.value, 50.0);
+    assert_eq!(
+        metrics.cost_change_vs_last_week.direction.as_deref(),
+        Some("up")
+    );
+
+    // Cost per 1k tokens fell from $2.00 to $0.65
+    assert_eq!(insights.efficiency.status, "improving");
+    assert_close(
+        insights.efficiency.change_vs_baseline,
+        100.0 - 3.0 / 4.6 / 2.0 * 100.0,
+    );
+    assert!(!insights.efficiency.investigation_needed);
+}
+
+#[test]
+fn insights_rank_models_by_spend_and_operations_by_volume() {
+    let insights = run(INSIGHTS, generate_insights).expect("insights");
+
+    let drivers: Vec<(&str, &str)> = insights
+        .cost_drivers
+        .iter()
+        .map(|driver| (driver.category.as_str(), driver.name.as_str()))
+        .collect();
+    assert_eq!(
+        drivers,
+        [
+            ("model", "gpt-4o"),
+            ("model", "claude-3-5-sonnet"),
+            ("operation", "chat"),
+            ("operation", "summarize"),
+        ]
+    );
+    let shares: Vec<f64> = insights
+        .cost_drivers
+        .iter()
+        .map(|driver| driver.percentage)
+        .collect();
+    for (share, expected) in shares.iter().zip([60.0, 40.0, 200.0 / 3.0, 100.0 / 3.0]) {
+        assert_close(*share, expected);
+    }
+
+    assert_eq!(insights.recommendations.len(), 2);
+    assert!(insights.recommendations[0].starts_with("Spend is up 50.0% week over week"));
+    assert!(insights.recommendations[1].starts_with("gpt-4o accounts for 60% of spend"));
+}
+
+#[test]
+fn daily_insights_are_generated_once_and_kept_in_history() {
+    let (first, again, latest, history) = run(INSIGHTS, || async {
+        let store = get_store_pool().await?;
+        sqlx::query("DELETE FROM daily_insights")
+            .execute(&store)
+            .await?;
+
+        // Nothing stored yet, so the first read generates a document
+        let first = get_daily_insights().await?;
+        clock::freeze(Some(
+            "2025-06-02T13:00:00Z".parse().expect("test timestamp"),
+        ));
+        let again = get_daily_insights().await?;
+
+        clock::freeze(Some(
+            "2025-06-03T08:00:00Z".parse().expect("test timestamp"),
+        ));
+        generate_insights_now().await?;
+        let latest = get_daily_insights().await?;
+
+        Ok::<_, ForgeCommandError>((first, again, latest, get_insights_history(10).await?))
+    })
+    .expect("insights");
+
+    assert_eq!(first.generated_at, "2025-06-02T12:00:00Z");
+    assert_eq!(again.generated_at, first.generated_at);
+    assert_eq!(latest.generated_at, "2025-06-03T08:00:00Z");
+    assert_eq!(latest.insights.generated_at, latest.generated_at);
+
+    let generated: Vec<&str> = history
+        .iter()
+        .map(|insights| insights.generated_at.as_str())
+        .collect();
+    assert_eq!(generated, ["2025-06-03T08:00:00Z", "2025-06-02T12:00:00Z"]);
+}
+
+#[test]
+fn cost_projection_covers_fourteen_whole_days() {
+    let projection = run(DAILY_SPEND, || get_cost_projection(30)).expect("projection");
+
+    // $1 and $3 days alternate, so a $2 mean with a $1 standard deviation;
+    // the $50 days on either side of the fortnight are left out
+    assert_eq!(projection.days_ahead, 30);
+    assert_close(projection.projected_cost, 60.0);
+    assert_close(projection.confidence, 0.5);
+}
+
+#[test]
+fn cost_projection_is_unsure_without_spend() {
+    let projection = run(INSIGHTS, || async {
+        clock::freeze(Some(
+            "2025-08-01T12:00:00Z".parse().expect("test timestamp"),
+        ));
+        get_cost_projection(7).await
+    })
+    .expect("projection");
+
+    assert_close(projection.projected_cost, 0.0);
+    assert_close(projection.confidence, 0.0);
+}
@@ -0,0 +1,170 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+
+use super::harness::{run, scratch_dir};
+use super::ECOSYSTEM;
+use crate::event_store;
+use crate::logtail::{FileRule, Tail};
+
+/// A log file in the scratch directory, with a rule writing its lines as
+/// `service` events.
+fn log_file(service: &str) -> (PathBuf, FileRule) {
+    let path = scratch_dir().join(format!("{}.log", service));
+    let _ = std::fs::remove_file(&path);
+    let rule =
+        serde_json::from_value(json!({ "path": path, "service": service })).expect("file rule");
+    (path, rule)
+}
+
+fn line(message: &str) -> String {
+    format!(
+        "{}\n",
+        json!({ "timestamp": "2025-06-02T11:58:00Z", "message": message })
+    )
+}
+
+fn append(path: &Path, contents: &str) {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .expect("log file")
+        .write_all(contents.as_bytes())
+        .expect("log write");
+}
+
+/// Messages stored for `service`, in file order.
+async fn messages(service: &str) -> Vec<String> {
+    let pool = event_store::get_event_store_pool()
+        .await
+        .expect("local event store");
+    sqlx::query_scalar::<_, String>(
+        "SELECT json_extract(metadata, '$.message') FROM events
+         WHERE service = ? ORDER BY rowid",
+    )
+    .bind(service)
+    .fetch_all(&pool)
+    .await
+    .expect("stored events")
+}
+
+#[test]
+fn resumes_from_the_saved_offset() {
+    let (stored, status) = run(ECOSYSTEM, || async {
+        let (path, rule) = log_file("logtail-resume");
+        append(&path, &(line("resume one") + &line("resume two")));
+
+        let mut tail = Tail::new(rule.clone());
+        tail.poll().await.expect("first poll");
+        assert_eq!(tail.status.events, 2);
+        drop(tail);
+
+        // A new collector picks up where the last one saved its offset
+        append(&path, &line("resume three"));
+        let mut tail = Tail::new(rule);
+        tail.poll().await.expect("resumed poll");
+
+        (messages("logtail-resume").await, tail.status)
+    });
+
+    assert_eq!(stored, ["resume one", "resume two", "resume three"]);
+    assert_eq!(status.events, 1);
+    assert_eq!(status.last_read_at.as_deref(), Some("2025-06-02T12:00:00Z"));
+    let written = ["resume one", "resume two", "resume three"].map(|message| line(message).len());
+    assert_eq!(status.offset, written.iter().sum::<usize>() as u64);
+}
+
+#[test]
+fn starts_over_after_truncation() {
+    let (stored, status) = run(ECOSYSTEM, || async {
+        let (path, rule) = log_file("logtail-truncate");
+        append(
+            &path,
+            &(line("truncate one") + &line("truncate two") + &line("truncate three")),
+        );
+
+        let mut tail = Tail::new(rule);
+        tail.poll().await.expect("first poll");
+
+        // copytruncate: same file, emptied and written again
+        std::fs::write(&path, line("after truncate")).expect("truncate");
+        tail.poll().await.expect("poll after truncation");
+
+        (messages("logtail-truncate").await, tail.status)
+    });
+
+    assert_eq!(
+        stored,
+        [
+            "truncate one",
+            "truncate two",
+            "truncate three",
+            "after truncate"
+        ]
+    );
+    assert_eq!(status.rotations, 1);
+    assert_eq!(status.events, 4);
+}
+
+#[cfg(unix)]
+#[test]
+fn follows_a_renamed_file_to_its_replacement() {
+    let (stored, status) = run(ECOSYSTEM, || async {
+        let (path, rule) = log_file("logtail-rename");
+        let header = line("rename header");
+        append(&path, &(header.clone() + &line("old one")));
+
+        let mut tail = Tail::new(rule);
+        tail.poll().await.expect("first poll");
+
+        // Rotated by rename; the new file starts with the same line and is
+        // already longer than the old offset, so only its identity differs
+        std::fs::rename(&path, path.with_extension("log.1")).expect("rename");
+        append(&path.with_extension("log.1"), &line("old two"));
+        append(
+            &path,
+            &(header + &line("new one") + &line("new two") + &line("new three")),
+        );
+        tail.poll().await.expect("poll after rotation");
+
+        (messages("logtail-rename").await, tail.status)
+    });
+
+    assert_eq!(
+        stored,
+        [
+            "rename header",
+            "old one",
+            "old two",
+            "rename header",
+            "new one",
+            "new two",
+            "new three"
+        ]
+    );
+    assert_eq!(status.rotations, 1);
+}
+
+#[test]
+fn skips_lines_longer_than_a_read() {
+    let (stored, status) = run(ECOSYSTEM, || async {
+        let (path, rule) = log_file("logtail-oversized");
+        append(&path, &line("before"));
+        append(&path, &line(&"x".repeat(3 * 1024 * 1024)));
+        append(&path, &line("after"));
+
+        let mut tail = Tail::new(rule);
+        for _ in 0..5 {
+            tail.poll().await.expect("poll");
+        }
+
+        (messages("logtail-oversized").await, tail.status)
+    });
+
+    assert_eq!(stored, ["before", "after"]);
+    assert_eq!(status.skipped, 1);
+    assert_eq!(status.lines, 3);
+}
@@ -0,0 +1,76 @@
+// ===========================================================================
+// IPC Command Tests
+// ===========================================================================
+//
+// Each test loads a fixture from `fixtures/` into a fresh events database,
+// freezes the clock at the fixture's "now" and calls a command function
+// directly, asserting the exact metrics and series the dashboard would get.
+
+mod advisor;
+mod anomaly;
+mod cli;
+mod dataforge;
+mod export;
+mod forgeagents;
+mod harness;
+mod ingest;
+mod insights;
+mod logtail;
+mod neuroforge;
+mod otlp;
+mod period;
+mod prometheus;
+mod rake;
+mod report;
+mod rollup;
+mod scheduler;
+mod scrape;
+mod server;
+mod system;
+
+/// Model requests from five models, one of them actually named "other", one
+/// stamped exactly at now and one an hour after it.
+const BREAKDOWN: &str = include_str!("fixtures/breakdown.json");
+
+/// A few hours of traffic from all four services, plus one event per service
+/// a day earlier for period comparisons.
+const ECOSYSTEM: &str = include_str!("fixtures/ecosystem.json");
+
+/// ForgeAgents tasks paired by `task_id`: finished, failed, in flight and
+/// orphaned, across four agents, with one task started before the day and
+/// never finished.
+const AGENT_TASKS: &str = include_str!("fixtures/agent_tasks.json");
+
+/// Rake runs reported as started/outcome pairs sharing a `run_id`, one of
+/// them still in flight.
+const RAKE_RUNS: &str = include_str!("fixtures/rake_runs.json");
+
+/// Rake jobs going through queued/started/outcome, with two still queued,
+/// one in flight and one queued so long ago it counts as abandoned.
+const RAKE_QUEUE: &str = include_str!("fixtures/rake_queue.json");
+
+/// Steady DataForge latency with one slow hour at the end.
+const LATENCY_SPIKE: &str = include_str!("fixtures/latency_spike.json");
+
+/// Rake ingesting steadily until 08:30, then nothing for the last three hours.
+const TRAFFIC_GAP: &str = include_str!("fixtures/traffic_gap.json");
+
+/// Search latency at 10:30 on the four previous Mondays, then a spike at that
+/// hour today.
+const SEASONAL_SPIKE: &str = include_str!("fixtures/seasonal_spike.json");
+
+/// DataForge queries with known latencies, NeuroForge requests across three
+/// models and Rake runs, for scraping the Prometheus exporter.
+const EXPORTER: &str = include_str!("fixtures/exporter.json");
+
+/// Requests and completed agent tasks exactly on the hour a 24h window starts
+/// in, and a second before it, with the clock half past the hour.
+const HOUR_BOUNDARY: &str = include_str!("fixtures/hour_boundary.json");
+
+/// A week of NeuroForge spend against the week before it, across two models
+/// and two operations.
+const INSIGHTS: &str = include_str!("fixtures/insights.json");
+
+/// Two weeks of NeuroForge spend alternating $1 and $3 a day, with $50 days
+/// on either side of them.
+const DAILY_SPEND: &str = include_str!("fixtures/daily_spend.json");
@@ -0,0 +1,175 @@
+use super::harness::{assert_close, assert_filled_series, run};
+use super::{ECOSYSTEM, HOUR_BOUNDARY};
+use crate::period::ComparisonWindow;
+use crate::{
+    get_cost_over_time, get_neuroforge_metrics, get_token_usage_by_model_over_time,
+    get_token_usage_over_time,
+};
+
+#[test]
+fn neuroforge_metrics_total_tokens_and_cost() {
+    let metrics = run(ECOSYSTEM, || get_neuroforge_metrics(None, Some(24), None)).expect("metrics");
+
+    assert_eq!(metrics.total_requests, 3);
+    assert_eq!(metrics.total_tokens, 850);
+    assert_eq!(metrics.total_prompt_tokens, 600);
+    assert_eq!(metrics.total_completion_tokens, 250);
+    assert_close(metrics.total_cost, 0.08);
+    assert_close(metrics.avg_evaluation_score, 0.8);
+}
+
+#[test]
+fn neuroforge_models_are_ranked_by_cost() {
+    let metrics = run(ECOSYSTEM, || get_neuroforge_metrics(None, Some(24), None)).expect("metrics");
+
+    let models: Vec<&str> = metrics
+        .top_models
+        .iter()
+        .map(|m| m.model.as_str())
+        .collect();
+    assert_eq!(models, ["claude-3-5-sonnet", "gpt-4o"]);
+
+    let claude = &metrics.top_models[0];
+    assert_eq!(claude.requests, 1);
+    assert_eq!(claude.tokens, 400);
+    assert_close(claude.cost, 0.05);
+    assert_close(claude.avg_latency, 800.0);
+    assert_close(claude.cost_per_1k_tokens, 0.125);
+    assert_close(claude.cost_per_score_point, 0.0625);
+
+    let gpt = &metrics.top_models[1];
+    assert_eq!(gpt.requests, 2);
+    assert_eq!(gpt.tokens, 450);
+    assert_eq!(gpt.prompt_tokens, 300);
+    assert_eq!(gpt.completion_tokens, 150);
+    assert_close(gpt.cost, 0.03);
+    assert_close(gpt.avg_latency, 1100.0);
+    assert_close(gpt.avg_evaluation_score, 0.8);
+    assert_close(gpt.tokens_per_request, 225.0);
+    assert_close(gpt.cost_per_1k_tokens, 0.03 * 1000.0 / 450.0);
+    assert_close(gpt.cost_per_score_point, 0.01875);
+}
+
+#[test]
+fn neuroforge_model_limit_keeps_the_most_expensive() {
+    let metrics = run(ECOSYSTEM, || {
+        get_neuroforge_metrics(Some(1), Some(24), None)
+    })
+    .expect("metrics");
+
+    assert_eq!(metrics.top_models.len(), 1);
+    assert_eq!(metrics.top_models[0].model, "claude-3-5-sonnet");
+    // The limit only trims the model list
+    assert_eq!(metrics.total_requests, 3);
+}
+
+#[test]
+fn cost_over_time_sums_per_hour() {
+    let series = run(ECOSYSTEM, || get_cost_over_time(24, None, None)).expect("series");
+
+    assert_filled_series(
+        &series.datapoints,
+        24,
+        &[("2025-06-02 10:00", 0.07), ("2025-06-02 11:00", 0.01)],
+    );
+}
+
+#[test]
+fn cost_over_time_overlays_the_previous_day() {
+    let series = run(ECOSYSTEM, || {
+        get_cost_over_time(24, None, Some(ComparisonWindow::PreviousPeriod))
+    })
+    .expect("series");
+
+    assert_filled_series(
+        &series.comparison_datapoints,
+        24,
+        &[("2025-06-02 10:00", 0.1)],
+    );
+}
+
+#[test]
+fn token_usage_splits_prompt_and_completion() {
+    let series = run(ECOSYSTEM, || get_token_usage_over_time(24, None, None)).expect("series");
+
+    assert_filled_series(
+        &series.datapoints,
+        24,
+        &[("2025-06-02 10:00", 700.0), ("2025-06-02 11:00", 150.0)],
+    );
+    assert_filled_series(
+        &series.prompt_datapoints,
+        24,
+        &[("2025-06-02 10:00", 500.0), ("2025-06-02 11:00", 100.0)],
+    );
+    assert_filled_series(
+        &series.completion_datapoints,
+        24,
+        &[("2025-06-02 10:00", 200.0), ("2025-06-02 11:00", 50.0)],
+    );
+}
+
+#[test]
+fn token_usage_by_model_fills_every_hour() {
+    let usage = run(ECOSYSTEM, || {
+        get_token_usage_by_model_over_time(24, None, None)
+    })
+    .expect("usage");
+
+    assert_eq!(usage.hours.len(), 24);
+    assert_eq!(
+        usage.hours.first().map(String::as_str),
+        Some("2025-06-01 12:00")
+    );
+    assert_eq!(
+        usage.hours.last().map(String::as_str),
+        Some("2025-06-02 11:00")
+    );
+    let models: Vec<&str> = usage.models.iter().map(|m| m.model.as_str()).collect();
+    assert_eq!(models, ["claude-3-5-sonnet", "gpt-4o"]);
+    assert_filled_series(
+        &usage.models[0].datapoints,
+        24,
+        &[("2025-06-02 10:00", 400.0)],
+    );
+    assert_filled_series(
+        &usage.models[1].datapoints,
+        24,
+        &[("2025-06-02 10:00", 300.0), ("2025-06-02 11:00", 150.0)],
+    );
+}
+
+#[test]
+fn token_usage_by_model_overlays_each_model_on_the_previous_day() {
+    let usage = run(ECOSYSTEM, || {
+        get_token_usage_by_model_over_time(24, None, Some(ComparisonWindow::PreviousPeriod))
+    })
+    .expect("usage");
+
+    let models: Vec<&str> = usage.models.iter().map(|m| m.model.as_str()).collect();
+    assert_eq!(models, ["claude-3-5-sonnet", "gpt-4o"]);
+    // Only gpt-4o was used the day before
+    assert!(usage.models[0].comparison_datapoints.is_empty());
+    assert_filled_series(
+        &usage.models[1].comparison_datapoints,
+        24,
+        &[("2025-06-02 10:00", 1000.0)],
+    );
+}
+
+#[test]
+fn token_usage_counts_requests_on_the_first_hour_boundary() {
+    let series = run(HOUR_BOUNDARY, || get_token_usage_over_time(24, None, None)).expect("series");
+
+    // The window starts at 12:30, so its first bucket is the whole of 12:00
+    assert_filled_series(
+        &series.datapoints,
+        25,
+        &[("2025-06-01 12:00", 100.0), ("2025-06-02 12:00", 50.0)],
+    );
+    assert_filled_series(
+        &series.prompt_datapoints,
+        25,
+        &[("2025-06-01 12:00", 80.0), ("2025-06-02 12:00", 40.0)],
+    );
+}
@@ -0,0 +1,453 @@
+use std::io::Write;
+
+use opentelemetry_proto::tonic::collector::logs::v1::{
+    logs_service_server::LogsService, ExportLogsServiceRequest, ExportLogsServiceResponse,
+};
+use opentelemetry_proto::tonic::collector::metrics::v1::{
+    metrics_service_server::MetricsService, ExportMetricsServiceRequest,
+};
+use opentelemetry_proto::tonic::collector::trace::v1::{
+    trace_service_server::TraceService, ExportTraceServiceRequest, ExportTraceServiceResponse,
+};
+use opentelemetry_proto::tonic::common::v1::{any_value, AnyValue, KeyValue};
+use opentelemetry_proto::tonic::logs::v1::{LogRecord, ResourceLogs, ScopeLogs};
+use opentelemetry_proto::tonic::metrics::v1::{
+    metric, number_data_point, Gauge, Histogram, HistogramDataPoint, Metric, NumberDataPoint,
+    ResourceMetrics, ScopeMetrics,
+};
+use opentelemetry_proto::tonic::resource::v1::Resource;
+use opentelemetry_proto::tonic::trace::v1::{status, ResourceSpans, ScopeSpans, Span, Status};
+use prost::Message;
+use serde_json::{json, Value};
+
+use super::harness::run;
+use super::ECOSYSTEM;
+use crate::event_store;
+use crate::otlp::{http_router, GrpcReceiver};
+
+/// Stored row: event_type, severity, timestamp, metrics, metadata.
+type Row = (String, String, String, Value, Value);
+
+/// 2025-06-02T11:58:00Z
+const AT_NANOS: u64 = 1_748_865_480_000_000_000;
+
+fn attribute(key: &str, value: any_value::Value) -> KeyValue {
+    KeyValue {
+        key: key.to_string(),
+        value: Some(AnyValue { value: Some(value) }),
+    }
+}
+
+fn text(key: &str, value: &str) -> KeyValue {
+    attribute(key, any_value::Value::StringValue(value.to_string()))
+}
+
+fn resource(service: Option<&str>) -> Option<Resource> {
+    Some(Resource {
+        attributes: service
+            .map(|service| vec![text("service.name", service)])
+            .unwrap_or_default(),
+        ..Default::default()
+    })
+}
+
+fn logs(service: Option<&str>, messages: &[&str]) -> ResourceLogs {
+    ResourceLogs {
+        resource: resource(service),
+        scope_logs: vec![ScopeLogs {
+            log_records: messages
+                .iter()
+                .map(|message| LogRecord {
+                    time_unix_nano: AT_NANOS,
+                    severity_number: 13,
+                    severity_text: "WARN".to_string(),
+                    body: Some(AnyValue {
+                        value: Some(any_value::Value::StringValue(message.to_string())),
+                    }),
+                    attributes: vec![
+                        text("event.name", "cache_miss"),
+                        attribute("metrics.retries", any_value::Value::IntValue(2)),
+                        text("region", "eu-west"),
+                    ],
+                    trace_id: vec![0xab; 16],
+                    span_id: vec![0x01; 8],
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+fn gzip(body: &[u8]) -> Vec<u8> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(body).expect("gzip");
+    encoder.finish().expect("gzip")
+}
+
+/// Posts `body` to the OTLP/HTTP router on a local listener; returns the
+/// status, content type and response body.
+async fn post(
+    path: &str,
+    body: Vec<u8>,
+    content_type: &str,
+    encoding: Option<&str>,
+) -> (u16, String, Vec<u8>) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("listener");
+    let address = listener.local_addr().expect("address");
+    let server = tokio::spawn(async move { axum::serve(listener, http_router()).await });
+
+    let mut request = reqwest::Client::new()
+        .post(format!("http://{}{}", address, path))
+        .header(reqwest::header::CONTENT_TYPE, content_type)
+        .body(body);
+    if let Some(encoding) = encoding {
+        request = request.header(reqwest::header::CONTENT_ENCODING, encoding);
+    }
+    let response = request.send().await.expect("request");
+    let status = response.status().as_u16();
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    let body = response.bytes().await.expect("body").to_vec();
+
+    server.abort();
+    (status, content_type, body)
+}
+
+/// Events the receiver wrote to the local store for `service`, oldest first.
+async fn stored(service: &str) -> Vec<Row> {
+    let pool = event_store::get_event_store_pool()
+        .await
+        .expect("local event store");
+    sqlx::query_as::<_, (String, String, String, String, String)>(
+        "SELECT event_type, severity, timestamp, metrics, metadata
+         FROM events WHERE service = ? ORDER BY timestamp, event_type, severity",
+    )
+    .bind(service)
+    .fetch_all(&pool)
+    .await
+    .expect("stored events")
+    .into_iter()
+    .map(|(event_type, severity, timestamp, metrics, metadata)| {
+        (
+            event_type,
+            severity,
+            timestamp,
+            serde_json::from_str(&metrics).expect("metrics json"),
+            serde_json::from_str(&metadata).expect("metadata json"),
+        )
+    })
+    .collect()
+}
+
+#[track_caller]
+fn assert_log(rows: &[Row], message: &str) {
+    let expected = (
+        "cache_miss".to_string(),
+        "warning".to_string(),
+        "2025-06-02T11:58:00Z".to_string(),
+        json!({"retries": 2}),
+        json!({
+            "message": message,
+            "region": "eu-west",
+            "trace_id": "abababababababababababababababab",
+            "span_id": "0101010101010101",
+        }),
+    );
+    assert_eq!(rows, [expected]);
+}
+
+#[test]
+fn http_logs_accept_protobuf_json_and_gzip() {
+    let (protobuf, json_body, gzipped) = run(ECOSYSTEM, || async {
+        let request = |service: &str, message: &str| ExportLogsServiceRequest {
+            resource_logs: vec![logs(Some(service), &[message])],
+        };
+
+        let protobuf = post(
+            "/v1/logs",
+            request("OTLP-Protobuf", "from protobuf").encode_to_vec(),
+            "application/x-protobuf",
+            None,
+        )
+        .await;
+        let json_body = post(
+            "/v1/logs",
+            serde_json::to_vec(&request("otlp-json", "from json")).expect("json"),
+            "application/json",
+            None,
+        )
+        .await;
+        let gzipped = post(
+            "/v1/logs",
+            gzip(&request("otlp-gzip", "from gzip").encode_to_vec()),
+            "application/x-protobuf",
+            Some("gzip"),
+        )
+        .await;
+
+        assert_log(&stored("otlp-protobuf").await, "from protobuf");
+        assert_log(&stored("otlp-json").await, "from json");
+        assert_log(&stored("otlp-gzip").await, "from gzip");
+        (protobuf, json_body, gzipped)
+    });
+
+    for (status, content_type, body) in [&protobuf, &gzipped] {
+        assert_eq!(*status, 200);
+        assert_eq!(content_type, "application/x-protobuf");
+        let response = ExportLogsServiceResponse::decode(&body[..]).expect("protobuf response");
+        assert_eq!(response.partial_success, None);
+    }
+    assert_eq!(json_body.0, 200);
+    assert_eq!(json_body.1, "application/json");
+    let response: ExportLogsServiceResponse =
+        serde_json::from_slice(&json_body.2).expect("json response");
+    assert_eq!(response.partial_success, None);
+}
+
+#[test]
+fn http_counts_resources_without_service_name_as_rejected() {
+    let (response, stored_rows) = run(ECOSYSTEM, || async {
+        let request = ExportLogsServiceRequest {
+            resource_logs: vec![
+                logs(Some("otlp-partial"), &["kept"]),
+                logs(None, &["dropped", "also dropped"]),
+            ],
+        };
+        let (status, _, body) = post(
+            "/v1/logs",
+            serde_json::to_vec(&request).expect("json"),
+            "application/json",
+            None,
+        )
+        .await;
+        assert_eq!(status, 200);
+
+        (
+            serde_json::from_slice::<ExportLogsServiceResponse>(&body).expect("json response"),
+            stored("otlp-partial").await,
+        )
+    });
+
+    assert_log(&stored_rows, "kept");
+    let partial = response.partial_success.expect("partial success");
+    assert_eq!(partial.rejected_log_records, 2);
+    assert!(
+        partial.error_message.contains("service.name"),
+        "{}",
+        partial.error_message
+    );
+}
+
+#[test]
+fn http_rejects_bad_bodies() {
+    let (invalid_gzip, unknown_encoding, invalid_payload) = run(ECOSYSTEM, || async {
+        (
+            post(
+                "/v1/traces",
+                b"not gzip".to_vec(),
+                "application/x-protobuf",
+                Some("gzip"),
+            )
+            .await,
+            post(
+                "/v1/traces",
+                Vec::new(),
+                "application/x-protobuf",
+                Some("br"),
+            )
+            .await,
+            post("/v1/metrics", b"{".to_vec(), "application/json", None).await,
+        )
+    });
+
+    assert_eq!(invalid_gzip.0, 400);
+    assert_eq!(unknown_encoding.0, 415);
+    assert_eq!(invalid_payload.0, 400);
+}
+
+#[test]
+fn grpc_traces_store_spans_and_reject_unnamed_resources() {
+    let (response, rows) = run(ECOSYSTEM, || async {
+        let span = |name: &str, failed: bool| Span {
+            trace_id: vec![0x0f; 16],
+            span_id: vec![if failed { 0x02 } else { 0x03 }; 8],
+            parent_span_id: if failed { vec![0x03; 8] } else { Vec::new() },
+            name: name.to_string(),
+            start_time_unix_nano: AT_NANOS - 250_000_000,
+            end_time_unix_nano: AT_NANOS,
+            attributes: vec![text("event_type", "request"), text("route", "/search")],
+            status: failed.then(|| Status {
+                message: "upstream timeout".to_string(),
+                code: status::StatusCode::Error as i32,
+            }),
+            ..Default::default()
+        };
+        let spans = |service: Option<&str>, spans: Vec<Span>| ResourceSpans {
+            resource: resource(service),
+            scope_spans: vec![ScopeSpans {
+                spans,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let request = ExportTraceServiceRequest {
+            resource_spans: vec![
+                spans(
+                    Some("otlp-grpc-traces"),
+                    vec![span("GET /search", false), span("fetch", true)],
+                ),
+                spans(None, vec![span("orphan", false)]),
+            ],
+        };
+
+        let response: ExportTraceServiceResponse =
+            TraceService::export(&GrpcReceiver, tonic::Request::new(request))
+                .await
+                .expect("export")
+                .into_inner();
+        (response, stored("otlp-grpc-traces").await)
+    });
+
+    assert_eq!(
+        response
+            .partial_success
+            .map(|partial| partial.rejected_spans),
+        Some(1)
+    );
+    let trace_id = "0f".repeat(16);
+    assert_eq!(
+        rows,
+        [
+            (
+                "request".to_string(),
+                "error".to_string(),
+                "2025-06-02T11:58:00Z".to_string(),
+                json!({"duration_ms": 250.0}),
+                json!({
+                    "route": "/search",
+                    "trace_id": trace_id,
+                    "span_id": "0202020202020202",
+                    "parent_span_id": "0303030303030303",
+                    "status_message": "upstream timeout",
+                }),
+            ),
+            (
+                "request".to_string(),
+                "info".to_string(),
+                "2025-06-02T11:58:00Z".to_string(),
+                json!({"duration_ms": 250.0}),
+                json!({
+                    "route": "/search",
+                    "trace_id": trace_id,
+                    "span_id": "0303030303030303",
+                }),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn grpc_metrics_store_one_event_per_point() {
+    let (rejected, rows) = run(ECOSYSTEM, || async {
+        let gauge = Metric {
+            name: "queue.depth".to_string(),
+            unit: "1".to_string(),
+            data: Some(metric::Data::Gauge(Gauge {
+                data_points: vec![NumberDataPoint {
+                    time_unix_nano: AT_NANOS,
+                    attributes: vec![text("queue", "ingest")],
+                    value: Some(number_data_point::Value::AsInt(7)),
+                    ..Default::default()
+                }],
+            })),
+            ..Default::default()
+        };
+        let histogram = Metric {
+            name: "request.duration".to_string(),
+            unit: "ms".to_string(),
+            data: Some(metric::Data::Histogram(Histogram {
+                data_points: vec![HistogramDataPoint {
+                    time_unix_nano: AT_NANOS + 60_000_000_000,
+                    count: 4,
+                    sum: Some(100.0),
+                    min: Some(10.0),
+                    max: Some(40.0),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        let metrics = |service: Option<&str>, metrics: Vec<Metric>| ResourceMetrics {
+            resource: resource(service),
+            scope_metrics: vec![ScopeMetrics {
+                metrics,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let request = ExportMetricsServiceRequest {
+            resource_metrics: vec![
+                metrics(Some("otlp-grpc-metrics"), vec![gauge.clone(), histogram]),
+                metrics(None, vec![gauge]),
+            ],
+        };
+
+        let response = MetricsService::export(&GrpcReceiver, tonic::Request::new(request))
+            .await
+            .expect("export")
+            .into_inner();
+        (
+            response
+                .partial_success
+                .map(|partial| partial.rejected_data_points),
+            stored("otlp-grpc-metrics").await,
+        )
+    });
+
+    assert_eq!(rejected, Some(1));
+    assert_eq!(
+        rows,
+        [
+            (
+                "queue.depth".to_string(),
+                "info".to_string(),
+                "2025-06-02T11:58:00Z".to_string(),
+                json!({"value": 7}),
+                json!({"queue": "ingest", "metric": "queue.depth", "unit": "1"}),
+            ),
+            (
+                "request.duration".to_string(),
+                "info".to_string(),
+                "2025-06-02T11:59:00Z".to_string(),
+                json!({"count": 4, "sum": 100.0, "min": 10.0, "max": 40.0}),
+                json!({"metric": "request.duration", "unit": "ms"}),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn grpc_logs_match_http() {
+    let rows = run(ECOSYSTEM, || async {
+        let request = ExportLogsServiceRequest {
+            resource_logs: vec![logs(Some("otlp-grpc-logs"), &["over grpc"])],
+        };
+        let response = LogsService::export(&GrpcReceiver, tonic::Request::new(request))
+            .await
+            .expect("export")
+            .into_inner();
+        assert_eq!(response.partial_success, None);
+        stored("otlp-grpc-logs").await
+    });
+
+    assert_log(&rows, "over grpc");
+}
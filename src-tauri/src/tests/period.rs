@@ -0,0 +1,225 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use super::harness::assert_close;
+use crate::period::{self, ComparisonWindow, MetricDelta, PeriodComparison, TimeRange, TimeWindow};
+use crate::TimeSeriesPoint;
+
+fn at(timestamp: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S").expect("timestamp")
+}
+
+fn window(start: &str, end: &str) -> TimeWindow {
+    TimeWindow {
+        start: at(start),
+        end: at(end),
+    }
+}
+
+fn range(start: &str, end: &str) -> TimeRange {
+    TimeRange {
+        start: start.to_string(),
+        end: end.to_string(),
+    }
+}
+
+fn custom(start: &str, end: &str) -> ComparisonWindow {
+    ComparisonWindow::Custom {
+        range: range(start, end),
+    }
+}
+
+#[test]
+fn previous_period_is_the_same_length_right_before() {
+    let current = window("2025-06-02 06:00:00", "2025-06-02 12:00:00");
+
+    let previous = current
+        .comparison(&ComparisonWindow::PreviousPeriod)
+        .expect("comparison");
+    assert_eq!(
+        previous,
+        window("2025-06-02 00:00:00", "2025-06-02 06:00:00")
+    );
+}
+
+#[test]
+fn same_period_last_week_shifts_both_bounds_a_week() {
+    let current = window("2025-06-02 06:00:00", "2025-06-02 12:00:00");
+
+    let previous = current
+        .comparison(&ComparisonWindow::SamePeriodLastWeek)
+        .expect("comparison");
+    assert_eq!(
+        previous,
+        window("2025-05-26 06:00:00", "2025-05-26 12:00:00")
+    );
+}
+
+#[test]
+fn custom_comparison_takes_the_given_range() {
+    let current = window("2025-06-02 06:00:00", "2025-06-02 12:00:00");
+
+    let previous = current
+        .comparison(&custom("2025-05-01 00:00:00", "2025-05-01T03:30:00Z"))
+        .expect("comparison");
+    assert_eq!(
+        previous,
+        window("2025-05-01 00:00:00", "2025-05-01 03:30:00")
+    );
+}
+
+#[test]
+fn custom_comparison_rejects_bad_ranges() {
+    let current = window("2025-06-02 06:00:00", "2025-06-02 12:00:00");
+
+    for comparison in [
+        custom("yesterday", "2025-06-01 00:00:00"),
+        // Inverted
+        custom("2025-06-01 12:00:00", "2025-06-01 06:00:00"),
+        // Empty
+        custom("2025-06-01 12:00:00", "2025-06-01 12:00:00"),
+    ] {
+        let error = current.comparison(&comparison).expect_err("invalid range");
+        assert_eq!(error.code(), "invalid_input");
+    }
+}
+
+#[test]
+fn unbounded_windows_only_compare_against_custom_ranges() {
+    let all_time = TimeWindow {
+        start: NaiveDateTime::MIN,
+        end: at("2025-06-02 12:00:00"),
+    };
+    assert!(!all_time.is_bounded());
+    assert_eq!(all_time.start_sql(), "0000-01-01 00:00:00");
+
+    assert!(all_time
+        .comparison(&ComparisonWindow::PreviousPeriod)
+        .is_err());
+    assert!(all_time
+        .comparison(&ComparisonWindow::SamePeriodLastWeek)
+        .is_err());
+    assert_eq!(
+        all_time
+            .comparison(&custom("2025-05-01 00:00:00", "2025-05-02 00:00:00"))
+            .expect("comparison"),
+        window("2025-05-01 00:00:00", "2025-05-02 00:00:00")
+    );
+}
+
+#[test]
+fn delta_against_zero_has_no_percentage() {
+    let delta = MetricDelta::new(5.0, 0.0);
+    assert_close(delta.absolute, 5.0);
+    assert_eq!(delta.percent, None);
+
+    let unchanged = MetricDelta::new(0.0, 0.0);
+    assert_close(unchanged.absolute, 0.0);
+    assert_eq!(unchanged.percent, None);
+}
+
+#[test]
+fn delta_percentage_is_relative_to_the_previous_magnitude() {
+    assert_eq!(MetricDelta::new(150.0, 100.0).percent, Some(50.0));
+    assert_eq!(MetricDelta::new(50.0, 100.0).percent, Some(-50.0));
+    // A negative baseline still reads as an improvement when the value rises
+    assert_eq!(MetricDelta::new(-5.0, -10.0).percent, Some(50.0));
+}
+
+#[derive(Serialize)]
+struct Snapshot {
+    requests: i64,
+    cost: f64,
+    label: String,
+    only_current: i64,
+}
+
+#[derive(Serialize)]
+struct PreviousSnapshot {
+    requests: i64,
+    cost: f64,
+    label: String,
+}
+
+#[test]
+fn comparison_has_deltas_for_shared_numeric_fields() {
+    let current = Snapshot {
+        requests: 30,
+        cost: 1.5,
+        label: "now".to_string(),
+        only_current: 7,
+    };
+    let previous = PreviousSnapshot {
+        requests: 20,
+        cost: 0.0,
+        label: "then".to_string(),
+    };
+
+    let comparison = PeriodComparison::new(
+        &current,
+        previous,
+        window("2025-06-01 12:00:00", "2025-06-02 12:00:00"),
+    );
+
+    assert_eq!(
+        comparison.range,
+        range("2025-06-01 12:00:00", "2025-06-02 12:00:00")
+    );
+    let fields: Vec<&str> = comparison.deltas.keys().map(String::as_str).collect();
+    assert_eq!(fields, ["cost", "requests"]);
+    assert_eq!(comparison.deltas["requests"].percent, Some(50.0));
+    assert_close(comparison.deltas["cost"].absolute, 1.5);
+    assert_eq!(comparison.deltas["cost"].percent, None);
+}
+
+#[test]
+fn shifted_series_keep_their_bucket_format() {
+    let current = window("2025-06-02 12:00:00", "2025-06-03 12:00:00");
+    let previous = current
+        .comparison(&ComparisonWindow::SamePeriodLastWeek)
+        .expect("comparison");
+    let points = vec![
+        TimeSeriesPoint {
+            timestamp: "2025-05-26 13:00".to_string(),
+            value: 1.0,
+        },
+        TimeSeriesPoint {
+            timestamp: "2025-05-27 09:00:00".to_string(),
+            value: 2.0,
+        },
+        TimeSeriesPoint {
+            timestamp: "2025-05-27T10:00:00".to_string(),
+            value: 3.0,
+        },
+        TimeSeriesPoint {
+            timestamp: "not a bucket".to_string(),
+            value: 4.0,
+        },
+    ];
+
+    let shifted = period::shift_series(points, &previous, &current);
+
+    let timestamps: Vec<&str> = shifted.iter().map(|p| p.timestamp.as_str()).collect();
+    assert_eq!(
+        timestamps,
+        [
+            "2025-06-02 13:00",
+            "2025-06-03 09:00:00",
+            "2025-06-03T10:00:00"
+        ]
+    );
+    assert_close(shifted[1].value, 2.0);
+}
+
+#[test]
+fn shifts_round_to_whole_hours() {
+    let current = window("2025-06-02 12:20:00", "2025-06-02 13:20:00");
+    let previous = window("2025-06-02 11:00:00", "2025-06-02 12:00:00");
+    let points = vec![TimeSeriesPoint {
+        timestamp: "2025-06-02 11:00:00".to_string(),
+        value: 1.0,
+    }];
+
+    let shifted = period::shift_series(points, &previous, &current);
+    assert_eq!(shifted[0].timestamp, "2025-06-02 12:00:00");
+}
@@ -0,0 +1,310 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use super::harness::{assert_close, run};
+use super::EXPORTER;
+use crate::error::CommandResult;
+use crate::prometheus::{Exporter, ExporterConfig};
+use crate::{clock, get_db_pool, server};
+
+/// Samples of one scrape, keyed by series name with labels as written.
+fn samples(exposition: &str) -> BTreeMap<String, f64> {
+    exposition
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .map(|line| {
+            let (series, value) = line.rsplit_once(' ').expect("sample line");
+            (series.to_string(), value.parse().expect("sample value"))
+        })
+        .collect()
+}
+
+#[track_caller]
+fn assert_sample(samples: &BTreeMap<String, f64>, series: &str, expected: f64) {
+    let actual = samples
+        .get(series)
+        .unwrap_or_else(|| panic!("no sample {} in {:#?}", series, samples));
+    assert_close(*actual, expected);
+}
+
+async fn insert(timestamp: &str, service: &str, event_type: &str, metrics: &str, metadata: &str) {
+    let pool = get_db_pool().await.expect("events database");
+    sqlx::query(
+        "INSERT INTO events (event_id, timestamp, service, event_type, severity, metrics, metadata)
+         VALUES (?, ?, ?, ?, 'info', ?, ?)",
+    )
+    .bind(format!("{}-{}", service, timestamp))
+    .bind(timestamp)
+    .bind(service)
+    .bind(event_type)
+    .bind(metrics)
+    .bind(metadata)
+    .execute(&pool)
+    .await
+    .expect("event should insert");
+}
+
+/// Scrapes once, writes a few more events, moves the clock on and scrapes again.
+fn scrape_twice(labels: &str, max_label_values: usize) -> (String, String) {
+    let exporter = Exporter::new(ExporterConfig::new(labels, max_label_values).expect("config"));
+
+    run(EXPORTER, || async {
+        let first = exporter.render().await?;
+
+        insert(
+            "2025-06-02T11:59:50Z",
+            "dataforge",
+            "query",
+            r#"{"duration_ms": 60}"#,
+            "{}",
+        )
+        .await;
+        insert(
+            "2025-06-02T11:59:45Z",
+            "neuroforge",
+            "model_request",
+            r#"{"tokens_prompt": 40, "tokens_completion": 4, "cost_usd": 0.002, "duration_ms": 300}"#,
+            r#"{"model": "mistral-large"}"#,
+        )
+        .await;
+        clock::freeze(Some("2025-06-02T12:01:00Z".parse().expect("timestamp")));
+
+        let second = exporter.render().await?;
+        Ok::<_, crate::error::ForgeCommandError>((first, second))
+    })
+    .expect("scrapes")
+}
+
+#[test]
+fn exposition_declares_every_family() {
+    let (first, _) = scrape_twice("none", 50);
+
+    for (name, kind) in [
+        ("forge_service_up", "gauge"),
+        ("forge_service_error_ratio", "gauge"),
+        ("forge_events_total", "counter"),
+        ("forge_latency_ms", "summary"),
+        ("forge_tokens_total", "counter"),
+        ("forge_cost_usd_total", "counter"),
+        ("forge_pipeline_runs_total", "counter"),
+        ("forge_pipeline_records_total", "counter"),
+    ] {
+        let line = format!("# TYPE {} {}\n", name, kind);
+        assert_eq!(first.matches(&line).count(), 1, "{}", line.trim_end());
+    }
+
+    let first = samples(&first);
+    assert_sample(&first, r#"forge_service_up{service="dataforge"}"#, 1.0);
+    assert_sample(&first, r#"forge_service_up{service="rake"}"#, 0.0);
+    assert_sample(&first, r#"forge_service_up{service="forgeagents"}"#, 0.0);
+    assert_sample(
+        &first,
+        r#"forge_service_error_ratio{service="dataforge"}"#,
+        1.0 / 6.0,
+    );
+    assert_sample(
+        &first,
+        r#"forge_events_total{service="dataforge",event_type="query",severity="info"}"#,
+        6.0,
+    );
+    assert_sample(
+        &first,
+        r#"forge_events_total{service="dataforge",event_type="query_error",severity="error"}"#,
+        1.0,
+    );
+    assert_sample(&first, "forge_pipeline_runs_total", 3.0);
+    assert_sample(&first, "forge_pipeline_records_total", 300.0);
+    assert_sample(&first, r#"forge_tokens_total{kind="prompt"}"#, 540.0);
+}
+
+#[test]
+fn counters_only_grow_between_scrapes() {
+    let (first, second) = scrape_twice("model", 50);
+    let (first, second) = (samples(&first), samples(&second));
+
+    let counters = first.iter().filter(|(series, _)| {
+        series.starts_with("forge_events_total")
+            || series.starts_with("forge_tokens_total")
+            || series.starts_with("forge_cost_usd_total")
+            || series.starts_with("forge_pipeline_")
+            || series.starts_with("forge_latency_ms_sum")
+            || series.starts_with("forge_latency_ms_count")
+    });
+    for (series, before) in counters {
+        let after = second
+            .get(series)
+            .unwrap_or_else(|| panic!("{} disappeared", series));
+        assert!(
+            after >= before,
+            "{} went from {} to {}",
+            series,
+            before,
+            after
+        );
+    }
+
+    assert_sample(
+        &second,
+        r#"forge_events_total{service="dataforge",event_type="query",severity="info"}"#,
+        7.0,
+    );
+    assert_sample(
+        &second,
+        r#"forge_latency_ms_count{service="dataforge"}"#,
+        7.0,
+    );
+    assert_sample(
+        &second,
+        r#"forge_latency_ms_sum{service="dataforge"}"#,
+        1260.0,
+    );
+    assert_sample(
+        &second,
+        r#"forge_tokens_total{kind="prompt",model="mistral-large"}"#,
+        40.0,
+    );
+    assert_sample(
+        &second,
+        r#"forge_tokens_total{kind="prompt",model="gpt-4o"}"#,
+        200.0,
+    );
+}
+
+#[test]
+fn capped_labels_fold_into_other() {
+    let (first, second) = scrape_twice("model", 2);
+    let (first, second) = (samples(&first), samples(&second));
+
+    // The two busiest models keep their labels
+    assert_sample(
+        &first,
+        r#"forge_tokens_total{kind="prompt",model="gpt-4o"}"#,
+        200.0,
+    );
+    assert_sample(
+        &first,
+        r#"forge_tokens_total{kind="prompt",model="claude-3-5-sonnet"}"#,
+        300.0,
+    );
+    assert_sample(
+        &first,
+        r#"forge_tokens_total{kind="prompt",model="other"}"#,
+        40.0,
+    );
+    assert_sample(&first, r#"forge_cost_usd_total{model="other"}"#, 0.002);
+    assert_sample(
+        &first,
+        r#"forge_latency_ms_count{service="neuroforge",model="other"}"#,
+        1.0,
+    );
+
+    // A model first seen later joins "other" instead of a new series
+    assert_sample(
+        &second,
+        r#"forge_tokens_total{kind="prompt",model="other"}"#,
+        80.0,
+    );
+    assert!(!second.keys().any(|series| series.contains("mistral-large")));
+    assert!(!second.keys().any(|series| series.contains("llama-3")));
+}
+
+#[test]
+fn latency_summary_reports_last_hour_quantiles() {
+    let (first, _) = scrape_twice("none", 50);
+    let first = samples(&first);
+
+    // Only the last hour's queries (10, 20, 30, 40, 100) feed the quantiles
+    assert_sample(
+        &first,
+        r#"forge_latency_ms{service="dataforge",quantile="0.5"}"#,
+        30.0,
+    );
+    assert_sample(
+        &first,
+        r#"forge_latency_ms{service="dataforge",quantile="0.9"}"#,
+        100.0,
+    );
+    assert_sample(
+        &first,
+        r#"forge_latency_ms{service="dataforge",quantile="0.99"}"#,
+        100.0,
+    );
+    // Sum and count cover everything scraped so far
+    assert_sample(
+        &first,
+        r#"forge_latency_ms_sum{service="dataforge"}"#,
+        1200.0,
+    );
+    assert_sample(
+        &first,
+        r#"forge_latency_ms_count{service="dataforge"}"#,
+        6.0,
+    );
+    // NeuroForge had no requests in the last hour, so no quantiles
+    assert!(!first
+        .keys()
+        .any(|series| series.starts_with(r#"forge_latency_ms{service="neuroforge""#)));
+    assert_sample(
+        &first,
+        r#"forge_latency_ms_count{service="neuroforge"}"#,
+        4.0,
+    );
+}
+
+/// Serves the API on a local port and requests `path` with an optional token.
+async fn get(
+    exporter: Option<Exporter>,
+    path: &str,
+    token: Option<&str>,
+) -> CommandResult<(u16, String, String)> {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    let app = server::router("scrape-secret".to_string(), exporter.map(Arc::new));
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let mut request = reqwest::Client::new().get(format!("http://{}{}", address, path));
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let response = request.send().await.expect("request");
+    let status = response.status().as_u16();
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    let body = response.text().await.expect("body");
+
+    server.abort();
+    Ok((status, content_type, body))
+}
+
+#[test]
+fn metrics_endpoint_requires_the_bearer_token() {
+    let exporter = || {
+        Some(Exporter::new(
+            ExporterConfig::new("none", 50).expect("config"),
+        ))
+    };
+
+    let (missing, wrong, scraped, disabled) = run(EXPORTER, || async {
+        Ok::<_, crate::error::ForgeCommandError>((
+            get(exporter(), "/metrics", None).await?,
+            get(exporter(), "/metrics", Some("guess")).await?,
+            get(exporter(), "/metrics", Some("scrape-secret")).await?,
+            get(None, "/metrics", Some("scrape-secret")).await?,
+        ))
+    })
+    .expect("requests");
+
+    assert_eq!(missing.0, 401);
+    assert_eq!(wrong.0, 401);
+    assert!(!missing.2.contains("forge_"));
+
+    assert_eq!(scraped.0, 200);
+    assert_eq!(scraped.1, "text/plain; version=0.0.4; charset=utf-8");
+    assert!(scraped.2.contains("# TYPE forge_events_total counter\n"));
+
+    assert_eq!(disabled.0, 404);
+}
@@ -0,0 +1,465 @@
+use super::harness::{assert_close, assert_filled_series, assert_series, run};
+use super::{ECOSYSTEM, RAKE_QUEUE, RAKE_RUNS};
+use crate::error::ForgeCommandError;
+use crate::period::TimeRange;
+use crate::rake::{
+    get_pipeline_detail, get_pipeline_runs, get_queue_depth_over_time, get_queue_wait_over_time,
+};
+use crate::{get_error_rate_over_time, get_ingestion_over_time, get_rake_metrics};
+
+#[test]
+fn rake_metrics_sum_records_per_run() {
+    let metrics = run(ECOSYSTEM, || get_rake_metrics(None, Some(24), None)).expect("metrics");
+
+    assert_eq!(metrics.total_pipelines, 2);
+    // Only p1 ran in the last hour
+    assert_eq!(metrics.active_pipelines, 1);
+    assert_eq!(metrics.total_runs, 5);
+    assert_eq!(metrics.successful_runs, 4);
+    assert_eq!(metrics.failed_runs, 1);
+    assert_close(metrics.success_rate, 80.0);
+    assert_eq!(metrics.records_ingested, 800);
+    assert_close(metrics.ingestion_rate, 800.0 / 24.0);
+    // Without start events, the reported durations; the failed run has none
+    assert_close(metrics.avg_run_duration_ms, 3750.0);
+    assert_close(metrics.error_rate, 20.0);
+}
+
+#[test]
+fn rake_pipelines_report_runs_records_and_status() {
+    let metrics = run(ECOSYSTEM, || get_rake_metrics(None, Some(24), None)).expect("metrics");
+
+    let pipelines: Vec<(&str, &str, &str, i64, i64, i64, &str)> = metrics
+        .recent_pipelines
+        .iter()
+        .map(|p| {
+            (
+                p.pipeline_id.as_str(),
+                p.pipeline_name.as_str(),
+                p.status.as_str(),
+                p.runs,
+                p.failed_runs,
+                p.records_processed,
+                p.last_run.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        pipelines,
+        [
+            (
+                "p2",
+                "Pipeline Two",
+                "idle",
+                2,
+                1,
+                500,
+                "2025-06-02T10:10:00Z"
+            ),
+            (
+                "p1",
+                "Pipeline One",
+                "active",
+                3,
+                0,
+                300,
+                "2025-06-02T11:56:00Z"
+            ),
+        ]
+    );
+    assert_close(metrics.recent_pipelines[0].avg_run_duration_ms, 9000.0);
+    assert_close(metrics.recent_pipelines[1].avg_run_duration_ms, 2000.0);
+}
+
+#[test]
+fn rake_run_durations_come_from_start_and_outcome_pairs() {
+    let metrics = run(RAKE_RUNS, || get_rake_metrics(None, Some(24), None)).expect("metrics");
+
+    // r4 started before the window but finished inside it; r5 is still running
+    assert_eq!(metrics.total_runs, 4);
+    assert_eq!(metrics.successful_runs, 3);
+    assert_eq!(metrics.failed_runs, 1);
+    assert_close(metrics.success_rate, 75.0);
+    assert_eq!(metrics.records_ingested, 450);
+    assert_close(metrics.ingestion_rate, 18.75);
+    // 10, 5 and 10 minutes for p1, 20 minutes for p2; r1's reported 999 ms is ignored
+    assert_close(metrics.avg_run_duration_ms, 675_000.0);
+    assert_eq!(metrics.active_pipelines, 2);
+
+    let p1 = &metrics.recent_pipelines[0];
+    assert_eq!(
+        (
+            p1.pipeline_id.as_str(),
+            p1.runs,
+            p1.successful_runs,
+            p1.failed_runs
+        ),
+        ("p1", 3, 2, 1)
+    );
+    assert_eq!(p1.records_processed, 400);
+    assert_close(p1.avg_run_duration_ms, 500_000.0);
+
+    let p2 = &metrics.recent_pipelines[1];
+    assert_eq!(p2.status, "active");
+    assert_eq!(p2.last_run, "2025-06-01T12:10:00Z");
+    assert_close(p2.avg_run_duration_ms, 1_200_000.0);
+}
+
+#[test]
+fn rake_ingestion_rate_over_all_history_starts_at_the_first_run() {
+    let metrics = run(RAKE_RUNS, || get_rake_metrics(None, None, None)).expect("metrics");
+
+    assert_eq!(metrics.records_ingested, 450);
+    // First run finished 23h50m before now
+    assert_close(metrics.ingestion_rate, 450.0 / (23.0 + 50.0 / 60.0));
+}
+
+#[test]
+fn rake_pipeline_limit_keeps_totals() {
+    let metrics = run(RAKE_RUNS, || get_rake_metrics(Some(1), Some(24), None)).expect("metrics");
+
+    assert_eq!(metrics.recent_pipelines.len(), 1);
+    assert_eq!(metrics.recent_pipelines[0].pipeline_id, "p1");
+    assert_eq!(metrics.total_pipelines, 2);
+    assert_eq!(metrics.records_ingested, 450);
+}
+
+#[test]
+fn rake_records_field_is_configurable() {
+    let (metrics, series) = run(RAKE_RUNS, || async {
+        std::env::set_var("FORGE_COMMAND_RAKE_RECORDS_FIELD", "rows");
+        let metrics = get_rake_metrics(None, Some(24), None).await;
+        let series = get_ingestion_over_time(24, None, None).await;
+        std::env::remove_var("FORGE_COMMAND_RAKE_RECORDS_FIELD");
+        (metrics, series)
+    });
+
+    assert_eq!(metrics.expect("metrics").records_ingested, 45);
+    assert_filled_series(
+        &series.expect("series").datapoints,
+        24,
+        &[
+            ("2025-06-01 12:00:00", 5.0),
+            ("2025-06-02 10:00:00", 10.0),
+            ("2025-06-02 11:00:00", 30.0),
+        ],
+    );
+}
+
+#[test]
+fn rake_records_field_rejects_json_paths() {
+    let result = run(RAKE_RUNS, || async {
+        std::env::set_var("FORGE_COMMAND_RAKE_RECORDS_FIELD", "stats.records");
+        let result = get_rake_metrics(None, Some(24), None).await;
+        std::env::remove_var("FORGE_COMMAND_RAKE_RECORDS_FIELD");
+        result
+    });
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn ingestion_sums_records_per_hour() {
+    let series = run(ECOSYSTEM, || get_ingestion_over_time(24, None, None)).expect("series");
+
+    assert_filled_series(
+        &series.datapoints,
+        24,
+        &[
+            ("2025-06-02 09:00:00", 500.0),
+            ("2025-06-02 11:00:00", 300.0),
+        ],
+    );
+}
+
+#[test]
+fn error_rate_is_failed_share_per_hour() {
+    let series = run(ECOSYSTEM, || get_error_rate_over_time(24, None, None)).expect("series");
+
+    assert_series(
+        &series.datapoints,
+        &[
+            ("2025-06-02 09:00:00", 0.0),
+            ("2025-06-02 10:00:00", 100.0),
+            ("2025-06-02 11:00:00", 0.0),
+        ],
+    );
+}
+
+#[test]
+fn pipeline_runs_list_each_run_newest_first() {
+    let runs = run(RAKE_RUNS, || {
+        get_pipeline_runs("p1".to_string(), None, None)
+    })
+    .expect("runs");
+
+    let summary: Vec<(Option<&str>, &str, Option<f64>)> = runs
+        .iter()
+        .map(|r| (r.run_id.as_deref(), r.status.as_str(), r.duration_ms))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (Some("r3"), "success", Some(600_000.0)),
+            (Some("r2"), "failed", Some(300_000.0)),
+            (Some("r1"), "success", Some(600_000.0)),
+        ]
+    );
+
+    // Rejected records are reported by r1 and inferred from records_in for r3
+    let r3 = &runs[0];
+    assert_eq!(r3.started_at.as_deref(), Some("2025-06-02T11:40:00Z"));
+    assert_eq!(r3.finished_at.as_deref(), Some("2025-06-02T11:50:00Z"));
+    assert_eq!(
+        (r3.records_in, r3.records_out, r3.records_rejected),
+        (Some(320), Some(300), Some(20))
+    );
+    assert_eq!(
+        (
+            runs[2].records_in,
+            runs[2].records_out,
+            runs[2].records_rejected
+        ),
+        (Some(104), Some(100), Some(4))
+    );
+
+    let r2 = &runs[1];
+    assert_eq!(r2.failure_reason.as_deref(), Some("source returned 503"));
+    assert_eq!(r2.records_out, None);
+    assert_eq!(r3.failure_reason, None);
+}
+
+#[test]
+fn pipeline_runs_include_runs_in_flight() {
+    let runs = run(RAKE_RUNS, || {
+        get_pipeline_runs("p2".to_string(), None, None)
+    })
+    .expect("runs");
+
+    let summary: Vec<(Option<&str>, &str, Option<&str>)> = runs
+        .iter()
+        .map(|r| {
+            (
+                r.run_id.as_deref(),
+                r.status.as_str(),
+                r.finished_at.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (Some("r5"), "running", None),
+            (Some("r4"), "success", Some("2025-06-01T12:10:00Z")),
+        ]
+    );
+    assert_eq!(runs[0].started_at.as_deref(), Some("2025-06-02T11:55:00Z"));
+    assert_eq!(runs[0].duration_ms, None);
+}
+
+#[test]
+fn pipeline_runs_honour_range_and_limit() {
+    let range = TimeRange {
+        start: "2025-06-02T11:00:00Z".to_string(),
+        end: "2025-06-02T11:30:00Z".to_string(),
+    };
+    let runs = run(RAKE_RUNS, || {
+        get_pipeline_runs("p1".to_string(), Some(range), None)
+    })
+    .expect("runs");
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].run_id.as_deref(), Some("r2"));
+
+    let runs = run(RAKE_RUNS, || {
+        get_pipeline_runs("p1".to_string(), None, Some(1))
+    })
+    .expect("runs");
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].run_id.as_deref(), Some("r3"));
+}
+
+#[test]
+fn pipeline_runs_reject_unknown_pipelines() {
+    let result = run(RAKE_RUNS, || {
+        get_pipeline_runs("nope".to_string(), None, None)
+    });
+
+    assert!(matches!(
+        result.map(|_| ()).unwrap_err().kind(),
+        ForgeCommandError::NotFound(_)
+    ));
+}
+
+#[test]
+fn pipeline_detail_reports_trends_and_failures() {
+    let detail = run(RAKE_RUNS, || {
+        get_pipeline_detail("p1".to_string(), Some(24))
+    })
+    .expect("detail");
+
+    assert_eq!(detail.pipeline_name, "Pipeline One");
+    assert_eq!(
+        (
+            detail.runs,
+            detail.successful_runs,
+            detail.failed_runs,
+            detail.running_runs
+        ),
+        (3, 2, 1, 0)
+    );
+    assert_close(detail.success_rate, 200.0 / 3.0);
+    assert_eq!(detail.records_ingested, 400);
+    assert_close(detail.avg_run_duration_ms, 500_000.0);
+    assert_eq!(detail.last_run.as_deref(), Some("2025-06-02T11:50:00Z"));
+    assert_eq!(
+        detail
+            .last_failure
+            .as_ref()
+            .and_then(|run| run.run_id.as_deref()),
+        Some("r2")
+    );
+
+    assert_series(
+        &detail.runs_over_time,
+        &[("2025-06-02 10:00:00", 1.0), ("2025-06-02 11:00:00", 2.0)],
+    );
+    assert_series(
+        &detail.success_rate_over_time,
+        &[
+            ("2025-06-02 10:00:00", 100.0),
+            ("2025-06-02 11:00:00", 50.0),
+        ],
+    );
+    assert_series(
+        &detail.records_over_time,
+        &[
+            ("2025-06-02 10:00:00", 100.0),
+            ("2025-06-02 11:00:00", 300.0),
+        ],
+    );
+    assert_series(
+        &detail.duration_over_time,
+        &[
+            ("2025-06-02 10:00:00", 600_000.0),
+            ("2025-06-02 11:00:00", 450_000.0),
+        ],
+    );
+
+    let reasons: Vec<(&str, i64, &str)> = detail
+        .failure_reasons
+        .iter()
+        .map(|r| (r.reason.as_str(), r.count, r.last_seen.as_str()))
+        .collect();
+    assert_eq!(
+        reasons,
+        [("source returned 503", 1, "2025-06-02T11:25:00Z")]
+    );
+}
+
+#[test]
+fn pipeline_detail_counts_runs_in_flight() {
+    let detail = run(RAKE_RUNS, || {
+        get_pipeline_detail("p2".to_string(), Some(24))
+    })
+    .expect("detail");
+
+    assert_eq!((detail.runs, detail.running_runs), (1, 1));
+    assert!(detail.failure_reasons.is_empty());
+    assert!(detail.last_failure.is_none());
+}
+
+#[test]
+fn rake_metrics_report_queue_depth_and_waits() {
+    let metrics = run(RAKE_QUEUE, || get_rake_metrics(None, Some(24), None)).expect("metrics");
+
+    // q5 and q6 are waiting, q4 is running; q7 was queued over a day ago
+    assert_eq!(metrics.queued_jobs, 2);
+    assert_eq!(metrics.running_jobs, 1);
+    // Waits of 5, 10, 10 and 35 minutes
+    assert_close(metrics.avg_queue_wait_ms, 900_000.0);
+    assert_close(metrics.queue_wait_p50_ms, 600_000.0);
+    assert_close(metrics.queue_wait_p95_ms, 2_100_000.0);
+    assert_close(metrics.queue_wait_p99_ms, 2_100_000.0);
+}
+
+#[test]
+fn rake_freshness_lists_every_source_stalest_first() {
+    let metrics = run(RAKE_QUEUE, || get_rake_metrics(None, Some(24), None)).expect("metrics");
+
+    let freshness: Vec<(&str, &str, f64)> = metrics
+        .freshness
+        .iter()
+        .map(|f| {
+            (
+                f.pipeline_id.as_str(),
+                f.newest_record_at.as_str(),
+                f.lag_seconds,
+            )
+        })
+        .collect();
+    // p3 last ran two days ago; p2 doesn't report its newest record, so its
+    // last successful run stands in
+    assert_eq!(
+        freshness,
+        [
+            ("p3", "2025-05-31T11:00:00Z", 176_400.0),
+            ("p1", "2025-06-02T09:20:00Z", 9_600.0),
+            ("p2", "2025-06-02T10:50:00Z", 4_200.0),
+        ]
+    );
+}
+
+#[test]
+fn queue_depth_is_the_hourly_peak() {
+    let series = run(RAKE_QUEUE, || get_queue_depth_over_time(4, None, None)).expect("series");
+
+    // q7 is abandoned a day after it was queued, at 09:30
+    assert_series(
+        &series.datapoints,
+        &[
+            ("2025-06-02 08:00:00", 1.0),
+            ("2025-06-02 09:00:00", 3.0),
+            ("2025-06-02 10:00:00", 1.0),
+            ("2025-06-02 11:00:00", 2.0),
+        ],
+    );
+    assert_series(
+        &series.running_datapoints,
+        &[
+            ("2025-06-02 08:00:00", 0.0),
+            ("2025-06-02 09:00:00", 1.0),
+            ("2025-06-02 10:00:00", 1.0),
+            ("2025-06-02 11:00:00", 1.0),
+        ],
+    );
+}
+
+#[test]
+fn queue_depth_is_empty_without_lifecycle_events() {
+    let series = run(ECOSYSTEM, || get_queue_depth_over_time(24, None, None)).expect("series");
+
+    assert!(series.datapoints.is_empty());
+    assert!(series.running_datapoints.is_empty());
+}
+
+#[test]
+fn queue_wait_percentiles_per_start_hour() {
+    let series = run(RAKE_QUEUE, || get_queue_wait_over_time(24, None, None)).expect("series");
+
+    assert_series(
+        &series.datapoints,
+        &[
+            ("2025-06-02 09:00:00", 2_100_000.0),
+            ("2025-06-02 10:00:00", 300_000.0),
+            ("2025-06-02 11:00:00", 600_000.0),
+        ],
+    );
+    assert_series(
+        &series.median_datapoints,
+        &[
+            ("2025-06-02 09:00:00", 600_000.0),
+            ("2025-06-02 10:00:00", 300_000.0),
+            ("2025-06-02 11:00:00", 600_000.0),
+        ],
+    );
+}
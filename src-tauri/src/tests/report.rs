@@ -0,0 +1,87 @@
+use super::harness::run;
+use super::ECOSYSTEM;
+use crate::report::{self, ReportFormat, ReportType};
+
+fn render(report_type: ReportType, format: ReportFormat) -> String {
+    let document = run(ECOSYSTEM, || report::build_report(report_type)).expect("report");
+    String::from_utf8_lossy(&report::render(&document, format)).into_owned()
+}
+
+#[test]
+fn markdown_cost_report_covers_the_report_week() {
+    let markdown = render(ReportType::Cost, ReportFormat::Markdown);
+
+    assert!(markdown.starts_with("# Forge Cost Report\n"));
+    assert!(markdown.contains("_Period: 2025-05-26 12:00:00 to 2025-06-02 12:00:00 UTC_"));
+    // The request from a day earlier is inside the week
+    assert!(markdown.contains("- **LLM spend:** $0.18\n"));
+    assert!(markdown.contains("- **Cost per user (estimated):** $0.06 across 3 users\n"));
+    assert!(markdown.contains("| gpt-4o | 3 | 1300 | 150 | $0.13 | 0.0619 |\n"));
+    // The chart spans the same week as the totals
+    assert!(
+        markdown.contains("2025-05-26 12:00 to 2025-06-02 11:00 · min 0.00 · max 0.10 · last 0.01")
+    );
+}
+
+#[test]
+fn html_ops_report_has_the_weeks_errors_and_incidents() {
+    let html = render(ReportType::Ops, ReportFormat::Html);
+
+    assert!(html.contains("<title>Forge Ops Report</title>"));
+    assert!(html.contains("Period: 2025-05-26 12:00:00 to 2025-06-02 12:00:00 UTC"));
+    assert!(html.contains(
+        "<tr><td>rake</td><td>ingestion_failed</td><td>1</td><td>2025-06-02T10:10:00Z</td></tr>"
+    ));
+    assert!(html.contains("<dt>Total spend</dt><dd>$0.18</dd>"));
+    assert!(html.contains("Token usage spiked above expected range"));
+    assert!(html.contains(">2025-05-26 12:00</text>"));
+}
+
+#[test]
+fn pdf_health_report_renders_a_document() {
+    let document = run(ECOSYSTEM, || report::build_report(ReportType::Health)).expect("report");
+    let pdf = report::render(&document, ReportFormat::Pdf);
+
+    assert!(pdf.starts_with(b"%PDF-"));
+    assert!(pdf.trim_ascii_end().ends_with(b"%%EOF"));
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(text.contains("Forge Health Report"));
+    assert!(text.contains("Period: 2025-05-26 12:00:00 to 2025-06-02 12:00:00 UTC"));
+    assert!(text.contains("Rake failure rate is above 5%"));
+}
+
+#[test]
+fn generated_reports_are_written_and_listed() {
+    let (generated, latest) = run(ECOSYSTEM, || async {
+        let dir =
+            std::env::temp_dir().join(format!("forge-command-reports-{}", std::process::id()));
+        std::env::set_var("FORGE_COMMAND_REPORTS_DIR", &dir);
+        let generated = report::generate_report(ReportType::Health, &ReportFormat::ALL).await;
+        let latest = report::get_latest_reports(Some(3)).await;
+        std::env::remove_var("FORGE_COMMAND_REPORTS_DIR");
+        (generated, latest)
+    });
+
+    let generated = generated.expect("report");
+    let names: Vec<&str> = generated.files.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "health_report_20250602_120000.pdf",
+            "health_report_20250602_120000.html",
+            "health_report_20250602_120000.md",
+        ]
+    );
+    assert!(generated.report_path.ends_with(".pdf"));
+    for file in &generated.files {
+        let bytes = std::fs::read(&file.path).expect("report file");
+        assert!(!bytes.is_empty());
+    }
+
+    let latest = latest.expect("latest");
+    assert_eq!(latest.reports.len(), 3);
+    assert!(latest
+        .reports
+        .iter()
+        .all(|r| r.report_type == "health" && r.date == "2025-06-02T12:00:00Z"));
+}
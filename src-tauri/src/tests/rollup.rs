@@ -0,0 +1,227 @@
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::SqlitePool;
+use sqlx::Row;
+
+use super::harness::{assert_close, run, scratch_dir};
+use super::ECOSYSTEM;
+use crate::error::CommandResult;
+use crate::period::TimeWindow;
+use crate::rollup::{self, Resolution, RollupSeries};
+use crate::store::get_store_pool;
+use crate::{
+    clock, fetch_hourly_series, get_db_pool, AGENT_LATENCY_QUERY, AGENT_LATENCY_SERIES, COST_QUERY,
+    COST_SERIES, RAKE_ERROR_RATE_QUERY, RAKE_ERROR_RATE_SERIES, SEARCH_LATENCY_QUERY,
+    SEARCH_LATENCY_SERIES, TOKENS_QUERY, TOKENS_SERIES,
+};
+
+const SERIES: [(&RollupSeries<'static>, &str); 5] = [
+    (&COST_SERIES, COST_QUERY),
+    (&TOKENS_SERIES, TOKENS_QUERY),
+    (&SEARCH_LATENCY_SERIES, SEARCH_LATENCY_QUERY),
+    (&AGENT_LATENCY_SERIES, AGENT_LATENCY_QUERY),
+    (&RAKE_ERROR_RATE_SERIES, RAKE_ERROR_RATE_QUERY),
+];
+
+fn at(raw: &str) -> DateTime<Utc> {
+    raw.parse().expect("test timestamp")
+}
+
+/// Writes a NeuroForge request the way DataForge would, after the fixture.
+async fn insert_request(pool: &SqlitePool, event_id: &str, timestamp: &str, cost_usd: f64) {
+    sqlx::query(
+        "INSERT INTO events (event_id, timestamp, service, event_type, severity, metrics, metadata)
+         VALUES (?, ?, 'neuroforge', 'model_request', 'info', ?, '{\"model\": \"gpt-4o\"}')",
+    )
+    .bind(event_id)
+    .bind(timestamp)
+    .bind(format!(
+        "{{\"cost_usd\": {}, \"tokens_total\": 100}}",
+        cost_usd
+    ))
+    .execute(pool)
+    .await
+    .expect("event should insert");
+}
+
+/// Asserts every chart series served from rollups equals the raw query.
+async fn assert_rollups_match_raw(pool: &SqlitePool, window: &TimeWindow) -> CommandResult<()> {
+    for (series, query) in SERIES {
+        let rolled_up = rollup::fetch_series(series, window)
+            .await?
+            .expect("rollups should cover the window");
+        let raw = fetch_hourly_series(pool, query, window).await?;
+
+        let hours = |points: &[crate::TimeSeriesPoint]| -> Vec<String> {
+            points.iter().map(|p| p.timestamp.clone()).collect()
+        };
+        assert_eq!(
+            hours(&rolled_up),
+            hours(&raw),
+            "{} {:?}",
+            series.service,
+            series.aggregate
+        );
+        for (rolled_up, raw) in rolled_up.iter().zip(&raw) {
+            assert_close(rolled_up.value, raw.value);
+        }
+    }
+    Ok(())
+}
+
+async fn rollup_cost(window: &TimeWindow, hour: &str) -> CommandResult<Option<f64>> {
+    let points = rollup::fetch_series(&COST_SERIES, window)
+        .await?
+        .expect("rollups should cover the window");
+    Ok(points
+        .into_iter()
+        .find(|p| p.timestamp == hour)
+        .map(|p| p.value))
+}
+
+#[test]
+fn backfill_matches_raw_series_from_a_partial_first_hour() {
+    let first_hour = run(ECOSYSTEM, || async {
+        let pool = get_db_pool().await?;
+        insert_request(&pool, "early", "2025-06-01T12:10:00Z", 0.5).await;
+        clock::freeze(Some(at("2025-06-02T12:30:00Z")));
+
+        rollup::refresh_rollups().await?;
+
+        // The window starts at 12:30, part-way through the first hour
+        let window = TimeWindow::last_hours(24);
+        assert_rollups_match_raw(&pool, &window).await?;
+        rollup_cost(&window, "2025-06-01 12:00").await
+    })
+    .expect("rollups");
+
+    assert_close(first_hour.expect("first hour"), 0.5);
+}
+
+#[test]
+fn late_and_new_events_are_rolled_up_on_the_next_refresh() {
+    let (late, new) = run(ECOSYSTEM, || async {
+        let pool = get_db_pool().await?;
+        rollup::refresh_rollups().await?;
+
+        // Backfilled hours behind the watermark, and one just now
+        insert_request(&pool, "late", "2025-06-02T09:15:00Z", 0.3).await;
+        insert_request(&pool, "new", "2025-06-02T12:00:30Z", 0.2).await;
+        clock::freeze(Some(at("2025-06-02T12:01:00Z")));
+        rollup::refresh_rollups().await?;
+
+        let window = TimeWindow::last_hours(24);
+        assert_rollups_match_raw(&pool, &window).await?;
+
+        // A refresh with nothing new keeps the buckets as they are
+        clock::freeze(Some(at("2025-06-02T12:02:00Z")));
+        rollup::refresh_rollups().await?;
+        let window = TimeWindow::last_hours(24);
+        assert_rollups_match_raw(&pool, &window).await?;
+
+        Ok::<_, crate::error::ForgeCommandError>((
+            rollup_cost(&window, "2025-06-02 09:00").await?,
+            rollup_cost(&window, "2025-06-02 12:00").await?,
+        ))
+    })
+    .expect("rollups");
+
+    assert_close(late.expect("late hour"), 0.3);
+    assert_close(new.expect("new hour"), 0.2);
+}
+
+#[test]
+fn pruned_minutes_are_rebuilt_from_raw_events() {
+    let (statuses, oldest_minute) = run(ECOSYSTEM, || async {
+        let pool = get_db_pool().await?;
+        rollup::refresh_rollups().await?;
+        rollup::set_rollup_retention(Resolution::Minute, 2).await?;
+
+        // Older than the minute retention, so its hour can't be downsampled
+        insert_request(&pool, "late", "2025-06-02T08:20:00Z", 0.4).await;
+        clock::freeze(Some(at("2025-06-02T12:01:00Z")));
+        rollup::refresh_rollups().await?;
+
+        let window = TimeWindow::last_hours(24);
+        assert_rollups_match_raw(&pool, &window).await?;
+
+        let store = get_store_pool().await?;
+        let oldest_minute: Option<String> = sqlx::query(
+            "SELECT MIN(bucket) AS oldest FROM rollup_counts WHERE resolution = 'minute'",
+        )
+        .fetch_one(&store)
+        .await?
+        .get("oldest");
+
+        Ok::<_, crate::error::ForgeCommandError>((
+            rollup::get_rollup_status().await?,
+            oldest_minute,
+        ))
+    })
+    .expect("rollups");
+
+    let minute = &statuses[0];
+    assert_eq!(minute.resolution, Resolution::Minute);
+    assert_eq!(minute.retention_hours, 2);
+    assert_eq!(minute.covered_from.as_deref(), Some("2025-06-02 10:01:00"));
+    assert_eq!(minute.watermark.as_deref(), Some("2025-06-02 12:01:00"));
+    assert!(oldest_minute.expect("minute buckets").as_str() >= "2025-06-02 10:01:00");
+}
+
+#[test]
+fn another_events_database_is_rolled_up_from_scratch() {
+    let (stale, cost) = run(ECOSYSTEM, || async {
+        rollup::refresh_rollups().await?;
+
+        // Another database whose rowids run past the last refresh's, so
+        // they can't tell it apart from new events
+        let path = scratch_dir().join("rollup-other-source.db");
+        std::fs::copy(std::env::var("DATABASE_URL").expect("fixture path"), &path)
+            .expect("copy events database");
+        std::env::set_var("DATABASE_URL", &path);
+        let pool = get_db_pool().await?;
+        sqlx::query("DELETE FROM events").execute(&pool).await?;
+        insert_request(&pool, "other", "2025-06-02T11:30:00Z", 0.9).await;
+        sqlx::query("UPDATE events SET rowid = 100000")
+            .execute(&pool)
+            .await?;
+
+        let window = TimeWindow::last_hours(24);
+        let stale = rollup::fetch_series(&COST_SERIES, &window).await?;
+
+        rollup::refresh_rollups().await?;
+        assert_rollups_match_raw(&pool, &window).await?;
+
+        Ok::<_, crate::error::ForgeCommandError>((
+            stale,
+            rollup::fetch_series(&COST_SERIES, &window).await?,
+        ))
+    })
+    .expect("rollups");
+
+    assert!(stale.is_none());
+    let cost = cost.expect("rebuilt rollups");
+    assert_eq!(cost.len(), 1);
+    assert_eq!(cost[0].timestamp, "2025-06-02 11:00");
+    assert_close(cost[0].value, 0.9);
+}
+
+#[test]
+fn past_windows_are_left_to_raw_events() {
+    let (past, current) = run(ECOSYSTEM, || async {
+        rollup::refresh_rollups().await?;
+
+        let current = TimeWindow::last_hours(24);
+        let past = TimeWindow {
+            start: current.start,
+            end: current.end - chrono::Duration::minutes(30),
+        };
+        Ok::<_, crate::error::ForgeCommandError>((
+            rollup::fetch_series(&COST_SERIES, &past).await?,
+            rollup::fetch_series(&COST_SERIES, &current).await?,
+        ))
+    })
+    .expect("rollups");
+
+    assert!(past.is_none());
+    assert!(current.is_some());
+}
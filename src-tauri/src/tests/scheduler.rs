@@ -0,0 +1,232 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::sqlite::SqlitePool;
+
+use super::harness::run;
+use super::{ECOSYSTEM, LATENCY_SPIKE};
+use crate::clock;
+use crate::error::{CommandResult, ForgeCommandError};
+use crate::scheduler::{
+    execute_action, get_job_runs, list_jobs, parse_schedule, pause_job, resume_job, run_due_jobs,
+    run_job_now, JobAction,
+};
+use crate::store::get_store_pool;
+
+/// Adds (or replaces) a quarter-hourly diagnostics job due at `next_run_at`.
+async fn insert_job(store: &SqlitePool, job_id: &str, next_run_at: &str) -> CommandResult<()> {
+    sqlx::query(
+        "INSERT OR REPLACE INTO jobs (job_id, name, action, schedule, paused, next_run_at)
+         VALUES (?, ?, ?, '*/15 * * * *', 0, ?)",
+    )
+    .bind(job_id)
+    .bind(job_id)
+    .bind(serde_json::to_string(&JobAction::Diagnostics)?)
+    .bind(next_run_at)
+    .execute(store)
+    .await?;
+    Ok(())
+}
+
+fn upcoming(expression: &str) -> Vec<DateTime<Utc>> {
+    let after: DateTime<Utc> = "2025-06-02T12:07:00Z".parse().expect("test timestamp");
+    parse_schedule(expression)
+        .expect("schedule")
+        .after(&after)
+        .take(3)
+        .collect()
+}
+
+fn diagnostics(fixture: &str) -> Value {
+    let output = run(fixture, || execute_action(JobAction::Diagnostics)).expect("diagnostics");
+    serde_json::from_str(&output).expect("diagnostics output")
+}
+
+#[test]
+fn diagnostics_count_alerts_scored_against_a_day_of_history() {
+    let output = diagnostics(LATENCY_SPIKE);
+
+    // The 11:00 bucket is the hour that just closed
+    assert_eq!(output["alerts_last_hour"], 1);
+    assert_eq!(output["critical_alerts"], 1);
+}
+
+#[test]
+fn diagnostics_are_quiet_without_alerts() {
+    let output = diagnostics(ECOSYSTEM);
+
+    assert_eq!(output["alerts_last_hour"], 0);
+    assert_eq!(output["critical_alerts"], 0);
+}
+
+#[test]
+fn job_runs_past_retention_are_pruned() {
+    let runs = run(ECOSYSTEM, || async {
+        let store = get_store_pool().await?;
+        sqlx::query(
+            "INSERT OR REPLACE INTO jobs (job_id, name, action, schedule, paused)
+             VALUES ('retention_check', 'Retention check', ?, '*/15 * * * *', 0)",
+        )
+        .bind(serde_json::to_string(&JobAction::Diagnostics)?)
+        .execute(&store)
+        .await?;
+        for started_at in ["2025-04-01T08:00:00Z", "2025-05-30T08:00:00Z"] {
+            sqlx::query(
+                "INSERT INTO job_runs (job_id, trigger, started_at, finished_at, status)
+                 VALUES ('retention_check', 'schedule', ?, ?, 'success')",
+            )
+            .bind(started_at)
+            .bind(started_at)
+            .execute(&store)
+            .await?;
+        }
+
+        run_job_now("retention_check".to_string()).await?;
+        get_job_runs(Some("retention_check".to_string()), Some(10)).await
+    })
+    .expect("runs");
+
+    let kept: Vec<(&str, &str)> = runs
+        .iter()
+        .map(|run| (run.trigger.as_str(), run.started_at.as_str()))
+        .collect();
+    assert_eq!(kept.len(), 2);
+    assert_eq!(kept[0].0, "manual");
+    assert_eq!(kept[1], ("schedule", "2025-05-30T08:00:00Z"));
+}
+
+#[test]
+fn missed_runs_are_caught_up_once() {
+    let (on_time, jobs, runs) = run(ECOSYSTEM, || async {
+        let store = get_store_pool().await?;
+        insert_job(&store, "on_time", "2025-06-02T12:00:00Z").await?;
+        run_due_jobs(&store).await?;
+        let on_time = get_job_runs(Some("on_time".to_string()), Some(10)).await?;
+
+        // The laptop sleeps through eight quarter hours
+        clock::freeze(Some(
+            "2025-06-02T14:07:00Z".parse().expect("test timestamp"),
+        ));
+        run_due_jobs(&store).await?;
+        // Nothing is due again until the next quarter hour
+        run_due_jobs(&store).await?;
+
+        Ok::<_, ForgeCommandError>((
+            on_time,
+            list_jobs().await?,
+            get_job_runs(Some("on_time".to_string()), Some(10)).await?,
+        ))
+    })
+    .expect("scheduler");
+
+    assert_eq!(on_time.len(), 1);
+    assert_eq!(on_time[0].trigger, "schedule");
+    assert_eq!(on_time[0].started_at, "2025-06-02T12:00:00Z");
+
+    let triggers: Vec<(&str, &str)> = runs
+        .iter()
+        .map(|run| (run.trigger.as_str(), run.started_at.as_str()))
+        .collect();
+    assert_eq!(
+        triggers,
+        [
+            ("catch_up", "2025-06-02T14:07:00Z"),
+            ("schedule", "2025-06-02T12:00:00Z")
+        ]
+    );
+
+    let job = jobs
+        .iter()
+        .find(|job| job.job_id == "on_time")
+        .expect("on_time job");
+    assert_eq!(job.last_run_at.as_deref(), Some("2025-06-02T14:07:00Z"));
+    assert_eq!(job.last_status.as_deref(), Some("success"));
+    assert_eq!(job.next_run_at.as_deref(), Some("2025-06-02T14:15:00Z"));
+}
+
+#[test]
+fn paused_jobs_are_skipped_until_resumed() {
+    let (paused, resumed, runs) = run(ECOSYSTEM, || async {
+        let store = get_store_pool().await?;
+        insert_job(&store, "paused_job", "2025-06-02T11:00:00Z").await?;
+
+        let paused = pause_job("paused_job".to_string()).await?;
+        run_due_jobs(&store).await?;
+        let resumed = resume_job("paused_job".to_string()).await?;
+        run_due_jobs(&store).await?;
+
+        Ok::<_, ForgeCommandError>((
+            paused,
+            resumed,
+            get_job_runs(Some("paused_job".to_string()), Some(10)).await?,
+        ))
+    })
+    .expect("scheduler");
+
+    assert!(paused.paused);
+    assert!(!resumed.paused);
+    // Runs missed while paused are dropped, not caught up
+    assert_eq!(resumed.next_run_at.as_deref(), Some("2025-06-02T12:15:00Z"));
+    assert!(runs.is_empty());
+}
+
+#[test]
+fn listing_jobs_seeds_the_defaults_once() {
+    let (first, second) = run(ECOSYSTEM, || async {
+        let first = list_jobs().await?;
+        let second = list_jobs().await?;
+        Ok::<_, ForgeCommandError>((first, second))
+    })
+    .expect("jobs");
+
+    for job_id in ["daily_insights", "weekly_reports", "diagnostics"] {
+        let seeded: Vec<_> = second.iter().filter(|job| job.job_id == job_id).collect();
+        assert_eq!(seeded.len(), 1, "{}", job_id);
+        assert!(first.iter().any(|job| job.job_id == job_id));
+    }
+
+    let diagnostics = second
+        .iter()
+        .find(|job| job.job_id == "diagnostics")
+        .expect("diagnostics job");
+    assert_eq!(diagnostics.schedule, "*/15 * * * *");
+    assert_eq!(diagnostics.action, JobAction::Diagnostics);
+    assert!(!diagnostics.paused);
+    // Other tests may have run it since, so only its slot is fixed
+    let next_run_at = diagnostics.next_run_at.as_deref().expect("next run");
+    assert!(next_run_at > "2025-06-02T12:00:00Z", "{}", next_run_at);
+    assert!(
+        [":00:00Z", ":15:00Z", ":30:00Z", ":45:00Z"]
+            .iter()
+            .any(|slot| next_run_at.ends_with(slot)),
+        "{}",
+        next_run_at
+    );
+}
+
+#[test]
+fn five_field_schedules_fire_at_second_zero() {
+    assert_eq!(upcoming("*/15 * * * *"), upcoming("0 */15 * * * *"));
+    assert_eq!(
+        upcoming("*/15 * * * *")
+            .iter()
+            .map(|at| at.to_rfc3339())
+            .collect::<Vec<_>>(),
+        [
+            "2025-06-02T12:15:00+00:00",
+            "2025-06-02T12:30:00+00:00",
+            "2025-06-02T12:45:00+00:00"
+        ]
+    );
+    // Six fields keep their own seconds
+    assert_eq!(
+        upcoming("30 */15 * * * *")[0].to_rfc3339(),
+        "2025-06-02T12:15:30+00:00"
+    );
+
+    match parse_schedule("every morning") {
+        Err(ForgeCommandError::InvalidInput(message)) => {
+            assert!(message.contains("\"every morning\""), "{}", message)
+        }
+        other => panic!("expected invalid input, got {:?}", other.map(|_| ())),
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use axum::routing::get;
+use axum::Router;
+use serde_json::json;
+
+use super::harness::{assert_close, run};
+use super::ECOSYSTEM;
+use crate::scrape::{histogram_quantile, parse_exposition, Converter, MetricKind, Scraper};
+use crate::store::get_store_pool;
+
+const FIRST: &str = r#"
+# TYPE jobs_processed counter
+jobs_processed_total{queue="ingest"} 100
+jobs_processed_created{queue="ingest"} 1.7e9
+# TYPE queue_depth gauge
+queue_depth{queue="ingest"} 4
+# TYPE request_seconds histogram
+request_seconds_bucket{le="0.1"} 10
+request_seconds_bucket{le="1"} 20
+request_seconds_bucket{le="+Inf"} 20
+request_seconds_sum 5
+request_seconds_count 20
+"#;
+
+const SECOND: &str = r#"
+# TYPE jobs_processed counter
+jobs_processed_total{queue="ingest"} 130
+jobs_processed_created{queue="ingest"} 1.7e9
+# TYPE queue_depth gauge
+queue_depth{queue="ingest"} 6
+# TYPE request_seconds histogram
+request_seconds_bucket{le="0.1"} 14
+request_seconds_bucket{le="1"} 28
+request_seconds_bucket{le="+Inf"} 30
+request_seconds_sum 9
+request_seconds_count 30
+"#;
+
+/// The process restarted: the counter starts again from zero.
+const THIRD: &str = r#"
+# TYPE jobs_processed counter
+jobs_processed_total{queue="ingest"} 12
+jobs_processed_created{queue="ingest"} 1.7e9
+# TYPE queue_depth gauge
+queue_depth{queue="ingest"} 5
+# TYPE request_seconds histogram
+request_seconds_bucket{le="0.1"} 14
+request_seconds_bucket{le="1"} 28
+request_seconds_bucket{le="+Inf"} 30
+request_seconds_sum 9
+request_seconds_count 30
+"#;
+
+/// Converts each exposition in turn, carrying counter values over; returns
+/// the `(metric, dimension, value)` samples of each scrape.
+fn convert(expositions: &[&str]) -> Vec<Vec<(String, String, f64)>> {
+    let mut previous = HashMap::new();
+    expositions
+        .iter()
+        .map(|text| {
+            let mut converter = Converter::new(&previous);
+            for family in parse_exposition(text).expect("exposition") {
+                converter.family(&family);
+            }
+            let samples = converter
+                .samples
+                .into_iter()
+                .map(|sample| (sample.metric, sample.dimension, sample.value))
+                .collect();
+            previous = converter.current;
+            samples
+        })
+        .collect()
+}
+
+#[track_caller]
+fn assert_samples(actual: &[(String, String, f64)], expected: &[(&str, &str, f64)]) {
+    let names: Vec<(&str, &str)> = actual
+        .iter()
+        .map(|(metric, dimension, _)| (metric.as_str(), dimension.as_str()))
+        .collect();
+    let expected_names: Vec<(&str, &str)> = expected
+        .iter()
+        .map(|(metric, dimension, _)| (*metric, *dimension))
+        .collect();
+    assert_eq!(names, expected_names);
+
+    for ((_, _, value), (_, _, expected)) in actual.iter().zip(expected) {
+        assert_close(*value, *expected);
+    }
+}
+
+#[test]
+fn exposition_unescapes_label_values_and_help() {
+    let families = parse_exposition(concat!(
+        "# HELP http_requests Requests served.\\nPer path.\n",
+        "# TYPE http_requests counter\n",
+        r#"http_requests_total{path="C:\\logs\\app", msg="say \"hi\"\nbye,}", method="GET"} 12 1718000000000"#,
+        "\n",
+        r#"http_requests_created{method="GET"} 1.7e9"#,
+        "\n",
+        "temperature 21.5\n",
+    ))
+    .expect("exposition");
+
+    assert_eq!(families.len(), 2);
+    let requests = &families[0];
+    assert_eq!(requests.name, "http_requests");
+    assert_eq!(requests.kind, MetricKind::Counter);
+    assert_eq!(
+        requests.help.as_deref(),
+        Some("Requests served.\nPer path.")
+    );
+    assert_eq!(requests.samples.len(), 2);
+    assert_eq!(requests.samples[0].name, "http_requests_total");
+    assert_eq!(
+        requests.samples[0].labels,
+        [
+            ("method".to_string(), "GET".to_string()),
+            ("msg".to_string(), "say \"hi\"\nbye,}".to_string()),
+            ("path".to_string(), r"C:\logs\app".to_string()),
+        ]
+    );
+    assert_close(requests.samples[0].value, 12.0);
+    assert_eq!(requests.samples[1].name, "http_requests_created");
+
+    assert_eq!(families[1].name, "temperature");
+    assert_eq!(families[1].kind, MetricKind::Untyped);
+}
+
+#[test]
+fn exposition_reports_the_bad_line() {
+    let error = parse_exposition("up 1\nup{job=\"api} 1\n").expect_err("unterminated label");
+    assert!(error.starts_with("line 2:"), "{}", error);
+}
+
+#[test]
+fn histogram_quantile_interpolates_and_stops_at_inf() {
+    let buckets = [(0.1, 2.0), (0.5, 6.0), (f64::INFINITY, 10.0)];
+
+    assert_close(histogram_quantile(0.5, &buckets).expect("p50"), 0.4);
+    assert_close(histogram_quantile(0.1, &buckets).expect("p10"), 0.05);
+    // Past the highest finite bound: that bound, not infinity
+    assert_close(histogram_quantile(0.99, &buckets).expect("p99"), 0.5);
+
+    assert_eq!(histogram_quantile(0.5, &[(f64::INFINITY, 3.0)]), None);
+    assert_eq!(
+        histogram_quantile(0.5, &[(0.1, 0.0), (f64::INFINITY, 0.0)]),
+        None
+    );
+    assert_eq!(histogram_quantile(0.5, &[]), None);
+}
+
+#[test]
+fn counters_store_increases_and_survive_resets() {
+    let scrapes = convert(&[FIRST, SECOND, THIRD]);
+
+    // The first scrape only sets the baseline; `_created` is never stored
+    assert_samples(&scrapes[0], &[("queue_depth", r#"queue="ingest""#, 4.0)]);
+    assert_samples(
+        &scrapes[1],
+        &[
+            ("jobs_processed_total", r#"queue="ingest""#, 30.0),
+            ("queue_depth", r#"queue="ingest""#, 6.0),
+            ("request_seconds_sum", "", 4.0),
+            ("request_seconds_count", "", 10.0),
+            ("request_seconds:p50", "", 0.325),
+            ("request_seconds:p90", "", 1.0),
+            ("request_seconds:p99", "", 1.0),
+        ],
+    );
+    // The counter dropped from 130 to 12, so it counted 12 since the restart;
+    // unchanged buckets give no quantiles
+    assert_samples(
+        &scrapes[2],
+        &[
+            ("jobs_processed_total", r#"queue="ingest""#, 12.0),
+            ("queue_depth", r#"queue="ingest""#, 5.0),
+            ("request_seconds_sum", "", 0.0),
+            ("request_seconds_count", "", 0.0),
+        ],
+    );
+}
+
+#[test]
+fn scraper_records_a_served_exposition() {
+    let (rows, status) = run(ECOSYSTEM, || async {
+        let body = Arc::new(Mutex::new(FIRST.to_string()));
+        let served = body.clone();
+        let app = Router::new().route(
+            "/metrics",
+            get(move || async move { served.lock().expect("body").clone() }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener");
+        let address = listener.local_addr().expect("address");
+        let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let target = serde_json::from_value(json!({
+            "service": "scrape-test",
+            "url": format!("http://{}/metrics", address),
+        }))
+        .expect("target");
+        let mut scraper = Scraper::new(target);
+        let client = reqwest::Client::new();
+        for exposition in [FIRST, SECOND, THIRD] {
+            *body.lock().expect("body") = exposition.to_string();
+            scraper.scrape(&client).await.expect("scrape");
+        }
+        server.abort();
+
+        let store = get_store_pool().await.expect("store");
+        let rows = sqlx::query_as::<_, (String, String, i64, f64)>(
+            "SELECT metric, dimension, SUM(samples), SUM(total) FROM rollup_values
+             WHERE resolution = 'minute' AND service = 'scrape-test'
+               AND bucket = '2025-06-02 12:00:00'
+             GROUP BY metric, dimension ORDER BY metric, dimension",
+        )
+        .fetch_all(&store)
+        .await
+        .expect("rollup rows");
+        (rows, scraper.status)
+    });
+
+    let expected = [
+        ("jobs_processed_total", r#"queue="ingest""#, 2, 42.0),
+        ("queue_depth", r#"queue="ingest""#, 3, 15.0),
+        ("request_seconds:p50", "", 1, 0.325),
+        ("request_seconds:p90", "", 1, 1.0),
+        ("request_seconds:p99", "", 1, 1.0),
+        ("request_seconds_count", "", 2, 10.0),
+        ("request_seconds_sum", "", 2, 4.0),
+    ];
+    assert_eq!(rows.len(), expected.len(), "{:?}", rows);
+    for ((metric, dimension, samples, total), expected) in rows.iter().zip(expected) {
+        assert_eq!(
+            (metric.as_str(), dimension.as_str(), *samples),
+            (expected.0, expected.1, expected.2)
+        );
+        assert_close(*total, expected.3);
+    }
+
+    assert!(status.up);
+    assert_eq!(
+        status.last_scrape_at.as_deref(),
+        Some("2025-06-02T12:00:00Z")
+    );
+    assert_eq!(status.series, 4);
+    assert_eq!(status.dropped_series, 0);
+}
@@ -0,0 +1,104 @@
+use reqwest::StatusCode;
+use serde_json::Value;
+
+use super::harness::run;
+use super::ECOSYSTEM;
+use crate::server;
+
+const TOKEN: &str = "api-secret";
+
+/// Serves the REST API on a loopback port and GETs each `(path, token)`,
+/// returning the status and JSON body of every response.
+fn get_all(requests: &[(&str, Option<&str>)]) -> Vec<(StatusCode, Value)> {
+    run(ECOSYSTEM, || async {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener");
+        let address = listener.local_addr().expect("listener address");
+        let app = server::router(TOKEN.to_string(), None);
+        let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let client = reqwest::Client::new();
+        let mut responses = Vec::new();
+        for (path, token) in requests {
+            let mut request = client.get(format!("http://{}{}", address, path));
+            if let Some(token) = token {
+                request = request.bearer_auth(token);
+            }
+            let response = request.send().await.expect("response");
+            let status = response.status();
+            let body = response.text().await.expect("body");
+            responses.push((status, serde_json::from_str(&body).unwrap_or(Value::Null)));
+        }
+
+        server.abort();
+        responses
+    })
+}
+
+#[test]
+fn requests_without_the_token_are_refused() {
+    let responses = get_all(&[
+        ("/api/health", None),
+        ("/api/health", Some("wrong-secret")),
+        ("/api/metrics/neuroforge", None),
+    ]);
+
+    for (status, _) in responses {
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
+
+#[test]
+fn health_and_service_metrics_are_served() {
+    let responses = get_all(&[
+        ("/api/health", Some(TOKEN)),
+        ("/api/metrics/neuroforge?hours=24", Some(TOKEN)),
+    ]);
+
+    let (status, health) = &responses[0];
+    assert_eq!(*status, StatusCode::OK);
+    assert_eq!(health["neuroforge_status"], "UP");
+    assert_eq!(health["dataforge_status"], "DOWN");
+
+    let (status, metrics) = &responses[1];
+    assert_eq!(*status, StatusCode::OK);
+    assert_eq!(metrics["total_requests"], 3);
+    assert_eq!(metrics["total_tokens"], 850);
+}
+
+#[test]
+fn unknown_services_and_series_are_not_found() {
+    let responses = get_all(&[
+        ("/api/metrics/unknown", Some(TOKEN)),
+        ("/api/series/unknown", Some(TOKEN)),
+    ]);
+
+    for (status, error) in responses {
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(error["code"], "not_found");
+    }
+}
+
+#[test]
+fn an_unknown_comparison_is_a_bad_request() {
+    let responses = get_all(&[
+        ("/api/metrics/neuroforge?comparison=bogus", Some(TOKEN)),
+        ("/api/series/cost?comparison=bogus", Some(TOKEN)),
+    ]);
+
+    for (status, error) in responses {
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error["code"], "invalid_input");
+    }
+}
+
+#[test]
+fn openapi_document_is_served_without_the_token() {
+    let responses = get_all(&[("/api/openapi.json", None)]);
+
+    let (status, document) = &responses[0];
+    assert_eq!(*status, StatusCode::OK);
+    assert!(document["openapi"].is_string(), "{}", document);
+    assert!(document["paths"]["/api/health"].is_object());
+}
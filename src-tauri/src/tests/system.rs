@@ -0,0 +1,329 @@
+use super::harness::{assert_close, run};
+use super::{BREAKDOWN, ECOSYSTEM, LATENCY_SPIKE};
+use crate::breakdown::{
+    get_breakdown, Breakdown, BreakdownRequest, BreakdownSort, JsonColumn, JsonField, SortDirection,
+};
+use crate::schema::{get_schema_report, FieldIssueKind};
+use crate::{get_anomaly_alerts, get_recent_events, get_system_health};
+
+#[test]
+fn system_health_reports_recent_activity_and_uptime() {
+    let health = run(ECOSYSTEM, get_system_health).expect("health");
+
+    // Up means an event in the last five minutes
+    assert_eq!(health.dataforge_status, "DOWN");
+    assert_eq!(health.neuroforge_status, "UP");
+    assert_eq!(health.forgeagents_status, "DOWN");
+    assert_eq!(health.rake_status, "UP");
+
+    assert_close(health.dataforge_uptime, 75.0);
+    assert_close(health.neuroforge_uptime, 100.0);
+    assert_close(health.forgeagents_uptime, 80.0);
+    assert_close(health.rake_uptime, 80.0);
+}
+
+#[test]
+fn system_health_without_rake_events_reports_not_deployed() {
+    let fixture = r#"{ "now": "2025-06-02T12:00:00Z", "events": [] }"#;
+    let health = run(fixture, get_system_health).expect("health");
+
+    assert_eq!(health.rake_status, "NOT_DEPLOYED");
+    assert_eq!(health.dataforge_status, "DOWN");
+    assert_close(health.dataforge_uptime, 100.0);
+}
+
+#[test]
+fn recent_events_are_newest_first() {
+    let events = run(ECOSYSTEM, || get_recent_events(3, None)).expect("events");
+
+    let summary: Vec<(&str, &str, &str)> = events
+        .iter()
+        .map(|e| {
+            (
+                e.timestamp.as_str(),
+                e.service.as_str(),
+                e.event_type.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("2025-06-02T11:58:00Z", "neuroforge", "model_request"),
+            ("2025-06-02T11:56:00Z", "rake", "ingestion_complete"),
+            ("2025-06-02T11:50:00Z", "dataforge", "query"),
+        ]
+    );
+}
+
+#[test]
+fn recent_events_filter_by_service() {
+    let events = run(ECOSYSTEM, || {
+        get_recent_events(10, Some("forgeagents".to_string()))
+    })
+    .expect("events");
+
+    assert_eq!(events.len(), 5);
+    assert!(events.iter().all(|e| e.service == "forgeagents"));
+    assert_eq!(events[0].timestamp, "2025-06-02T11:45:00Z");
+    assert_eq!(events[0].event_type, "agent_task_completed");
+    assert_eq!(events[1].event_type, "agent_task_started");
+}
+
+#[test]
+fn anomaly_alerts_flag_a_latency_spike() {
+    let alerts = run(LATENCY_SPIKE, || get_anomaly_alerts(24, None)).expect("alerts");
+
+    assert_eq!(alerts.len(), 1);
+    let alert = &alerts[0];
+    assert_eq!(alert.service, "dataforge");
+    assert_eq!(alert.severity, "critical");
+    assert_eq!(alert.timestamp, "2025-06-02 11:00");
+    assert_eq!(
+        alert.message,
+        "Search latency spiked above expected range (1000.00 vs 105.00 expected)"
+    );
+    assert_close(alert.metrics.actual_value, 1000.0);
+    assert_close(alert.metrics.expected_value, 105.0);
+    assert_close(alert.metrics.threshold, 120.0);
+    assert_close(alert.metrics.score, 179.0);
+}
+
+#[test]
+fn anomaly_alerts_are_quiet_without_history() {
+    let alerts = run(ECOSYSTEM, || get_anomaly_alerts(24, None)).expect("alerts");
+
+    assert!(alerts.is_empty());
+}
+
+#[test]
+fn breakdown_groups_model_requests_by_model() {
+    let request = BreakdownRequest {
+        service: "neuroforge".to_string(),
+        event_type: Some("model_request".to_string()),
+        group_by: JsonField {
+            column: JsonColumn::Metadata,
+            key: "model".to_string(),
+        },
+        value: Some(JsonField {
+            column: JsonColumn::Metrics,
+            key: "cost_usd".to_string(),
+        }),
+        sort_by: BreakdownSort::Sum,
+        direction: SortDirection::Desc,
+        limit: Some(1),
+        offset: None,
+        include_other: true,
+        hours: Some(24),
+    };
+    let breakdown = run(ECOSYSTEM, || get_breakdown(request)).expect("breakdown");
+
+    assert_eq!(breakdown.total_groups, 2);
+    assert_eq!(breakdown.rows.len(), 1);
+    assert_eq!(breakdown.rows[0].key, "claude-3-5-sonnet");
+    assert_eq!(breakdown.rows[0].count, 1);
+    assert_close(breakdown.rows[0].sum, 0.05);
+
+    // The earlier gpt-4o request falls outside the 24 hours
+    let other = breakdown.other.expect("other bucket");
+    assert_eq!(other.key, "other");
+    assert!(other.is_other);
+    assert_eq!(other.count, 2);
+    assert_close(other.sum, 0.03);
+    assert_close(other.avg, 0.015);
+}
+
+/// Model requests from the breakdown fixture grouped by model, with cost as
+/// the value.
+fn model_breakdown(
+    sort_by: BreakdownSort,
+    direction: SortDirection,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    include_other: bool,
+) -> Breakdown {
+    let request = BreakdownRequest {
+        service: "neuroforge".to_string(),
+        event_type: Some("model_request".to_string()),
+        group_by: JsonField {
+            column: JsonColumn::Metadata,
+            key: "model".to_string(),
+        },
+        value: Some(JsonField {
+            column: JsonColumn::Metrics,
+            key: "cost_usd".to_string(),
+        }),
+        sort_by,
+        direction,
+        limit,
+        offset,
+        include_other,
+        hours: Some(24),
+    };
+    run(BREAKDOWN, || get_breakdown(request)).expect("breakdown")
+}
+
+fn keys(breakdown: &Breakdown) -> Vec<&str> {
+    breakdown.rows.iter().map(|row| row.key.as_str()).collect()
+}
+
+#[test]
+fn breakdown_pages_from_an_offset() {
+    let breakdown = model_breakdown(
+        BreakdownSort::Count,
+        SortDirection::Desc,
+        Some(2),
+        Some(1),
+        false,
+    );
+
+    assert_eq!(breakdown.total_groups, 5);
+    assert_eq!(keys(&breakdown), ["gpt-4o", "claude"]);
+    assert!(breakdown.other.is_none());
+}
+
+#[test]
+fn breakdown_sorts_by_key_within_the_window() {
+    let breakdown = model_breakdown(BreakdownSort::Key, SortDirection::Asc, None, None, false);
+
+    // llama's request at now is in; gpt-4o's an hour later isn't
+    assert_eq!(
+        keys(&breakdown),
+        ["claude", "gpt-4o", "llama", "mistral", "other"]
+    );
+    assert_eq!(breakdown.rows[1].count, 3);
+    assert_close(breakdown.rows[1].sum, 0.03);
+    assert!(breakdown.rows.iter().all(|row| !row.is_other));
+}
+
+#[test]
+fn breakdown_sorts_by_average_ascending() {
+    let breakdown = model_breakdown(BreakdownSort::Avg, SortDirection::Asc, None, None, false);
+
+    assert_eq!(
+        keys(&breakdown),
+        ["mistral", "gpt-4o", "llama", "claude", "other"]
+    );
+    assert_close(breakdown.rows[0].avg, 0.004);
+}
+
+#[test]
+fn other_bucket_rolls_up_groups_before_and_after_the_page() {
+    let breakdown = model_breakdown(
+        BreakdownSort::Sum,
+        SortDirection::Desc,
+        Some(2),
+        Some(1),
+        true,
+    );
+
+    assert_eq!(keys(&breakdown), ["claude", "gpt-4o"]);
+    // The "other" model ranked first, plus llama and mistral after the page
+    let other = breakdown.other.expect("other bucket");
+    assert!(other.is_other);
+    assert_eq!(other.count, 6);
+    assert_close(other.sum, 0.236);
+    assert_close(other.avg, 0.236 / 6.0);
+}
+
+#[test]
+fn other_bucket_is_marked_apart_from_a_group_named_other() {
+    let breakdown = model_breakdown(BreakdownSort::Sum, SortDirection::Desc, Some(1), None, true);
+
+    assert_eq!(keys(&breakdown), ["other"]);
+    assert!(!breakdown.rows[0].is_other);
+    assert_eq!(breakdown.rows[0].count, 1);
+
+    let other = breakdown.other.expect("other bucket");
+    assert_eq!(other.key, "other");
+    assert!(other.is_other);
+    assert_eq!(other.count, 10);
+}
+
+#[test]
+fn schema_report_passes_well_formed_events() {
+    let report = run(ECOSYSTEM, || get_schema_report(Some(true))).expect("report");
+
+    assert_eq!(report.checked_at, "2025-06-02T12:00:00Z");
+    assert!(report.columns.missing.is_empty());
+    assert!(report.silent_event_types.is_empty());
+    assert_eq!(report.issue_count, 0);
+}
+
+#[test]
+fn schema_report_flags_mistyped_fields() {
+    let fixture = r#"{
+        "now": "2025-06-02T12:00:00Z",
+        "events": [
+            { "at": "-5m", "service": "dataforge", "event_type": "query",
+              "metrics": { "duration_ms": "40ms" } }
+        ]
+    }"#;
+    let report = run(fixture, || get_schema_report(Some(true))).expect("report");
+
+    let dataforge = report
+        .event_types
+        .iter()
+        .find(|r| r.service == "dataforge" && r.event_type == "query")
+        .expect("dataforge query report");
+    assert_eq!(dataforge.sampled, 1);
+    assert!(dataforge
+        .issues
+        .iter()
+        .any(|issue| issue.field == "metrics.duration_ms"));
+}
+
+#[test]
+fn schema_report_suggests_the_new_name_of_a_renamed_field() {
+    let fixture = r#"{
+        "now": "2025-06-02T12:00:00Z",
+        "events": [
+            { "at": "-5m", "repeat": 2, "every": "1m", "service": "dataforge",
+              "event_type": "query", "metrics": { "latency_ms": 40, "avg_similarity": 0.9 } }
+        ]
+    }"#;
+    let report = run(fixture, || get_schema_report(Some(true))).expect("report");
+
+    let dataforge = report
+        .event_types
+        .iter()
+        .find(|r| r.service == "dataforge" && r.event_type == "query")
+        .expect("dataforge query report");
+    assert_eq!(dataforge.undeclared_fields, ["metrics.latency_ms"]);
+    assert_eq!(dataforge.issues.len(), 1);
+    let issue = &dataforge.issues[0];
+    assert_eq!(issue.field, "metrics.duration_ms");
+    assert_eq!(
+        issue.issue,
+        FieldIssueKind::PossiblyRenamed {
+            candidate: "metrics.latency_ms".to_string()
+        }
+    );
+    assert_close(issue.affected_ratio, 1.0);
+}
+
+#[test]
+fn schema_report_flags_sparse_optional_fields() {
+    let fixture = r#"{
+        "now": "2025-06-02T12:00:00Z",
+        "events": [
+            { "at": "-10m", "service": "dataforge", "event_type": "query",
+              "metrics": { "duration_ms": 40, "avg_similarity": 0.9 } },
+            { "at": "-8m", "repeat": 3, "every": "1m", "service": "dataforge",
+              "event_type": "query", "metrics": { "duration_ms": 50 } }
+        ]
+    }"#;
+    let report = run(fixture, || get_schema_report(Some(true))).expect("report");
+
+    let dataforge = report
+        .event_types
+        .iter()
+        .find(|r| r.service == "dataforge" && r.event_type == "query")
+        .expect("dataforge query report");
+    assert_eq!(dataforge.sampled, 4);
+    assert_eq!(dataforge.issues.len(), 1);
+    let issue = &dataforge.issues[0];
+    assert_eq!(issue.field, "metrics.avg_similarity");
+    assert_eq!(issue.issue, FieldIssueKind::Sparse);
+    assert_close(issue.affected_ratio, 0.75);
+}
@@ -0,0 +1,43 @@
+// Type definitions for errors returned by Forge Command's IPC commands
+export type ForgeCommandErrorCode =
+  | 'database_missing'
+  | 'database_unavailable'
+  | 'database_busy'
+  | 'query_timeout'
+  | 'schema_mismatch'
+  | 'query_failed'
+  | 'invalid_input'
+  | 'not_found'
+  | 'io_error'
+  | 'internal';
+
+export interface ForgeCommandError {
+  code: ForgeCommandErrorCode;
+  message: string;
+  retryable: boolean;
+  context: {
+    command: string | null;
+    service: string | null;
+    query: string | null;
+  };
+}
+
+/**
+ * Narrows a rejected `invoke` value to a ForgeCommandError
+ */
+export function isForgeCommandError(error: unknown): error is ForgeCommandError {
+  return typeof error === 'object' && error !== null && 'code' in error && 'message' in error;
+}
+
+/**
+ * Turns any rejected `invoke` value into a message for display
+ * @param error - Value caught from `invoke`
+ */
+export function describeCommandError(error: unknown): string {
+  if (!isForgeCommandError(error)) {
+    return String(error);
+  }
+
+  const hint = error.retryable ? ' Retrying on the next refresh.' : '';
+  return `${error.message}${hint}`;
+}
@@ -1,5 +1,7 @@
 import jsPDF from 'jspdf';
 import html2canvas from 'html2canvas';
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 
 // Type definitions for export functions
 export interface ExportData {
@@ -352,3 +354,73 @@ export async function exportComprehensivePDF(
     alert('Failed to generate PDF report. Please try again.');
   }
 }
+
+// ============================================================================
+// Backend exports (streamed to disk by the Tauri backend)
+// ============================================================================
+
+export type ExportFormat = 'csv' | 'ndjson' | 'parquet';
+
+export interface ExportEventsRequest {
+  path: string;
+  format: ExportFormat;
+  service?: string;
+  event_type?: string;
+  severity?: string;
+  hours?: number;
+  export_id?: string;
+}
+
+export interface ExportSeriesRequest {
+  path: string;
+  format: ExportFormat;
+  service: string;
+  event_type?: string;
+  value?: { column: 'metadata' | 'metrics'; key: string };
+  aggregate?: 'count' | 'sum' | 'avg';
+  bucket?: 'minute' | 'hour' | 'day';
+  hours?: number;
+  export_id?: string;
+}
+
+export interface ExportProgress {
+  export_id: string;
+  rows_written: number;
+  total_rows: number;
+  finished: boolean;
+}
+
+export interface ExportResult {
+  export_id: string;
+  status: 'completed' | 'cancelled';
+  path: string;
+  rows_written: number;
+}
+
+/**
+ * Export raw events from the events table without loading them into the page
+ */
+export function exportEvents(request: ExportEventsRequest): Promise<ExportResult> {
+  return invoke<ExportResult>('export_events', { request });
+}
+
+/**
+ * Export an aggregated time series for one service
+ */
+export function exportSeries(request: ExportSeriesRequest): Promise<ExportResult> {
+  return invoke<ExportResult>('export_series', { request });
+}
+
+/**
+ * Cancel a running backend export; resolves false if it already finished
+ */
+export function cancelExport(exportId: string): Promise<boolean> {
+  return invoke<boolean>('cancel_export', { exportId });
+}
+
+/**
+ * Subscribe to progress updates for backend exports
+ */
+export function onExportProgress(handler: (progress: ExportProgress) => void): Promise<UnlistenFn> {
+  return listen<ExportProgress>('export-progress', (event) => handler(event.payload));
+}